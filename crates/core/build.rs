@@ -0,0 +1,20 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Write include/kacemon.h from the `extern "C"` items in src/ffi.rs
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    cbindgen::Builder::new()
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("KACEMON_H")
+        .with_autogen_warning("/* Generated from crates/core/src/ffi.rs by build.rs; do not edit */")
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .generate()
+        .expect("src/ffi.rs should parse")
+        .write_to_file(format!("{}/include/kacemon.h", crate_dir));
+}
@@ -0,0 +1,56 @@
+#ifndef KACEMON_H
+#define KACEMON_H
+
+/* Generated from crates/core/src/ffi.rs by build.rs; do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Opaque handle to a metrics collector
+ */
+typedef struct KacemonCollector KacemonCollector;
+
+/**
+ * Create a collector with the default collectors and take its baseline measurements.
+ * Returns NULL on failure; see `kacemon_last_error`.
+ */
+struct KacemonCollector *kacemon_collector_new(void);
+
+/**
+ * Take a snapshot and return it as a JSON object, in the same shape as the HTTP API's
+ * /snapshot. Returns NULL on failure; free the result with `kacemon_string_free`.
+ *
+ * # Safety
+ *
+ * `collector` must come from `kacemon_collector_new` and not have been freed.
+ */
+char *kacemon_collect_json(struct KacemonCollector *collector);
+
+/**
+ * Free a collector. NULL is ignored.
+ *
+ * # Safety
+ *
+ * `collector` must come from `kacemon_collector_new` and must not be used afterwards.
+ */
+void kacemon_collector_free(struct KacemonCollector *collector);
+
+/**
+ * Free a string returned by the library. NULL is ignored.
+ *
+ * # Safety
+ *
+ * `text` must come from this library and must not be used afterwards.
+ */
+void kacemon_string_free(char *text);
+
+/**
+ * Why the last call on this thread returned NULL, or NULL if it succeeded. The message
+ * belongs to the library and stays valid until the next call on this thread.
+ */
+const char *kacemon_last_error(void);
+
+#endif /* KACEMON_H */
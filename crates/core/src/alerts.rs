@@ -0,0 +1,648 @@
+use crate::model::{ProcessInfo, ProcessState, SystemSnapshot};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::Write,
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Threshold rules; a rule set to null in the config is switched off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertThresholds {
+    pub enabled: bool,
+    /// Average across all cores
+    pub cpu_percent: Option<f32>,
+    /// Used share of physical memory
+    pub memory_percent: Option<f32>,
+    /// Any sensor, in Celsius
+    pub temperature_celsius: Option<f32>,
+    /// Used share of any mounted filesystem
+    pub disk_percent: Option<f32>,
+    /// NVMe "percentage used" life estimate
+    pub nvme_wear_percent: Option<f32>,
+    /// A single process, flagged in the process table
+    pub process_cpu_percent: Option<f32>,
+    pub process_memory_percent: Option<f32>,
+    /// Zombie processes at once; a burst usually means a parent stopped reaping
+    pub zombie_count: Option<u32>,
+    /// Processes in uninterruptible sleep at once, for at least `disk_sleep_secs`
+    pub disk_sleep_count: Option<u32>,
+    pub disk_sleep_secs: u64,
+    /// Restarts of one program (same name and command line) within the last hour
+    pub restart_count: Option<u32>,
+    /// Processes created per second, from the kernel's fork counter (Linux); catches fork
+    /// bombs and runaway respawn loops even when the children die straight away
+    pub fork_rate: Option<f32>,
+    /// Net processes added per second
+    pub process_growth: Option<f32>,
+    /// What to run when an alert fires
+    pub actions: AlertActions,
+}
+
+/// Side effects of a firing alert; both are off unless configured
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertActions {
+    /// Run through `sh -c` with KACEMON_ALERT_* set in the environment
+    pub command: Option<String>,
+    /// POST the alert as JSON (sent with curl)
+    pub webhook_url: Option<String>,
+    /// Write the alert to the Windows Application event log as source "kacemon" (sent with
+    /// eventcreate, which needs an administrator the first time to register the source)
+    pub event_log: bool,
+    /// Also run the actions when a process watched in the TUI exits
+    pub watched_exits: bool,
+    /// A condition must hold this long before actions run, to ride out spikes
+    pub debounce_secs: u64,
+    /// Minimum time between actions for the same condition
+    pub cooldown_secs: u64,
+}
+
+impl Default for AlertActions {
+    fn default() -> Self {
+        Self {
+            command: None,
+            webhook_url: None,
+            event_log: false,
+            watched_exits: false,
+            debounce_secs: 10,
+            cooldown_secs: 300,
+        }
+    }
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cpu_percent: Some(90.0),
+            memory_percent: Some(85.0),
+            temperature_celsius: Some(80.0),
+            disk_percent: Some(95.0),
+            nvme_wear_percent: Some(90.0),
+            process_cpu_percent: Some(90.0),
+            process_memory_percent: Some(50.0),
+            zombie_count: Some(50),
+            disk_sleep_count: Some(5),
+            disk_sleep_secs: 30,
+            restart_count: Some(5),
+            fork_rate: None,
+            process_growth: None,
+            actions: AlertActions::default(),
+        }
+    }
+}
+
+/// Which rule an alert came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AlertKind {
+    Cpu,
+    Memory,
+    Temperature,
+    Disk,
+    NvmeWear,
+    ProcessCpu,
+    ProcessMemory,
+    Zombies,
+    DiskSleep,
+    /// Fork rate or process count climbing too fast; the subject says which
+    Spawning,
+    /// A program that keeps exiting and coming back
+    CrashLoop,
+    /// A process watched in the TUI is gone; fired once rather than evaluated
+    ProcessExit,
+}
+
+/// A threshold currently being exceeded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    /// What is over the limit: sensor label, mount point, device or process name
+    pub subject: String,
+    pub pid: Option<u32>,
+    pub value: f32,
+    pub threshold: f32,
+    /// Needs attention now: sensor at its critical trip point, memory close to OOM, zombie burst
+    #[serde(default)]
+    pub critical: bool,
+}
+
+impl Alert {
+    /// Same rule and subject, i.e. the same ongoing condition
+    pub fn same_condition(&self, other: &Alert) -> bool {
+        self.kind == other.kind && self.subject == other.subject && self.pid == other.pid
+    }
+
+    fn key(&self) -> AlertKey {
+        (self.kind, self.subject.clone(), self.pid)
+    }
+
+    pub(crate) fn kind_name(&self) -> &'static str {
+        match self.kind {
+            AlertKind::Cpu => "cpu",
+            AlertKind::Memory => "memory",
+            AlertKind::Temperature => "temperature",
+            AlertKind::Disk => "disk",
+            AlertKind::NvmeWear => "nvme_wear",
+            AlertKind::ProcessCpu => "process_cpu",
+            AlertKind::ProcessMemory => "process_memory",
+            AlertKind::Zombies => "zombies",
+            AlertKind::DiskSleep => "disk_sleep",
+            AlertKind::Spawning => "spawning",
+            AlertKind::CrashLoop => "crash_loop",
+            AlertKind::ProcessExit => "process_exit",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self.kind {
+            AlertKind::Cpu => format!("CPU at {:.0}% (limit {:.0}%)", self.value, self.threshold),
+            AlertKind::Memory => format!("Memory at {:.0}% (limit {:.0}%)", self.value, self.threshold),
+            AlertKind::Temperature => format!(
+                "{} at {:.0}°C (limit {:.0}°C)",
+                self.subject, self.value, self.threshold
+            ),
+            AlertKind::Disk => format!("{} {:.0}% full (limit {:.0}%)", self.subject, self.value, self.threshold),
+            AlertKind::NvmeWear => format!(
+                "{} {:.0}% worn (limit {:.0}%)",
+                self.subject, self.value, self.threshold
+            ),
+            AlertKind::ProcessCpu | AlertKind::ProcessMemory => format!(
+                "{} ({}) {} at {:.0}% (limit {:.0}%)",
+                self.subject,
+                self.pid.unwrap_or_default(),
+                if self.kind == AlertKind::ProcessCpu { "CPU" } else { "memory" },
+                self.value,
+                self.threshold
+            ),
+            AlertKind::Zombies => format!("{:.0} zombie processes (limit {:.0})", self.value, self.threshold),
+            AlertKind::DiskSleep => format!(
+                "{:.0} processes stuck in uninterruptible sleep (limit {:.0})",
+                self.value, self.threshold
+            ),
+            AlertKind::Spawning if self.subject == FORKS => {
+                format!("{:.0} processes forked/s (limit {:.0}/s)", self.value, self.threshold)
+            },
+            AlertKind::CrashLoop => format!(
+                "{} restarted {:.0} times in the last hour (limit {:.0})",
+                self.subject, self.value, self.threshold
+            ),
+            AlertKind::Spawning => format!("Process count growing {:.0}/s (limit {:.0}/s)", self.value, self.threshold),
+            AlertKind::ProcessExit => format!("{} ({}) exited", self.subject, self.pid.unwrap_or_default()),
+        }
+    }
+}
+
+impl AlertThresholds {
+    /// Every rule the snapshot currently breaks
+    pub fn evaluate(&self, snapshot: &SystemSnapshot) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        if !self.enabled {
+            return alerts;
+        }
+
+        let mut check = |kind, subject: &str, pid, value: f32, threshold: Option<f32>, critical: bool| {
+            if let Some(threshold) = threshold.filter(|&t| value > t) {
+                alerts.push(Alert {
+                    kind,
+                    subject: subject.to_string(),
+                    pid,
+                    value,
+                    threshold,
+                    critical,
+                });
+            }
+        };
+
+        if !snapshot.cpu_cores.is_empty() {
+            check(AlertKind::Cpu, "CPU", None, snapshot.cpu_average(), self.cpu_percent, false);
+        }
+
+        let memory = &snapshot.memory;
+        if memory.total > 0 {
+            let used = snapshot.memory_percent() as f32;
+            // Nearly out of RAM with no swap left to fall back on
+            let swap_exhausted = memory.swap_total == 0 || memory.swap_used as f32 >= memory.swap_total as f32 * 0.9;
+            let oom_risk = used >= 95.0 && swap_exhausted;
+            check(AlertKind::Memory, "Memory", None, used, self.memory_percent, oom_risk);
+        }
+
+        for sensor in &snapshot.temperatures {
+            let critical = sensor.critical.is_some_and(|c| c > 0.0 && sensor.temperature >= c);
+            check(AlertKind::Temperature, &sensor.label, None, sensor.temperature, self.temperature_celsius, critical);
+        }
+
+        for disk in &snapshot.disks {
+            if disk.total_space > 0 {
+                let used = disk.used_space as f32 / disk.total_space as f32 * 100.0;
+                check(AlertKind::Disk, &disk.mount_point, None, used, self.disk_percent, false);
+            }
+        }
+
+        // Several partitions share one controller; report it once
+        let mut controllers: Vec<&str> = Vec::new();
+        for health in snapshot.disks.iter().filter_map(|d| d.nvme_health.as_ref()) {
+            if !controllers.contains(&health.controller.as_str()) {
+                controllers.push(&health.controller);
+                check(
+                    AlertKind::NvmeWear,
+                    &health.controller,
+                    None,
+                    health.percentage_used as f32,
+                    self.nvme_wear_percent,
+                    false,
+                );
+            }
+        }
+
+        for process in &snapshot.processes {
+            check(AlertKind::ProcessCpu, &process.name, Some(process.pid), process.cpu_percent, self.process_cpu_percent, false);
+            check(
+                AlertKind::ProcessMemory,
+                &process.name,
+                Some(process.pid),
+                process.memory_percent,
+                self.process_memory_percent,
+                false,
+            );
+        }
+
+        // Every instance of a program shares its count, and each restart brings a new PID,
+        // so the alert is per program rather than per process
+        let mut restarting: Vec<(&str, &[String])> = Vec::new();
+        for process in snapshot.processes.iter().filter(|p| p.restarts > 0) {
+            if !restarting.contains(&(process.name.as_str(), process.cmd.as_slice())) {
+                restarting.push((&process.name, &process.cmd));
+                let limit = self.restart_count.map(|c| c as f32);
+                check(AlertKind::CrashLoop, &process.name, None, process.restarts as f32, limit, false);
+            }
+        }
+
+        let zombies = snapshot.processes.iter().filter(|p| p.state == ProcessState::Zombie).count();
+        check(AlertKind::Zombies, "zombies", None, zombies as f32, self.zombie_count.map(|c| c as f32), true);
+
+        alerts
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let percentages = [
+            ("cpu_percent", self.cpu_percent),
+            ("memory_percent", self.memory_percent),
+            ("disk_percent", self.disk_percent),
+            ("process_memory_percent", self.process_memory_percent),
+        ];
+        for (name, value) in percentages {
+            if value.is_some_and(|v| !(0.0..=100.0).contains(&v)) {
+                return Err(format!("alerts.{} must be between 0 and 100", name));
+            }
+        }
+
+        // Per-process CPU can exceed 100% on multi-core machines, NVMe wear can pass 100
+        let non_negative = [
+            ("process_cpu_percent", self.process_cpu_percent),
+            ("nvme_wear_percent", self.nvme_wear_percent),
+            ("temperature_celsius", self.temperature_celsius),
+            ("fork_rate", self.fork_rate),
+            ("process_growth", self.process_growth),
+        ];
+        for (name, value) in non_negative {
+            if value.is_some_and(|v| v < 0.0 || !v.is_finite()) {
+                return Err(format!("alerts.{} must be a positive number", name));
+            }
+        }
+
+        if let Some(url) = &self.actions.webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("alerts.actions.webhook_url must be an http:// or https:// URL".to_string());
+            }
+        }
+        if self.actions.command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err("alerts.actions.command must not be empty".to_string());
+        }
+        if self.actions.event_log && !cfg!(windows) {
+            return Err("alerts.actions.event_log is only available on Windows".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Subjects of the two `Spawning` rules
+const FORKS: &str = "forks";
+const PROCESS_GROWTH: &str = "process growth";
+
+/// Spawn rates are measured over at least this long; the process list is refreshed less
+/// often than the fork counter, so shorter windows would see it jump
+const SPAWN_WINDOW: Duration = Duration::from_secs(2);
+
+/// Spawning parents listed while a `Spawning` alert is active
+const TOP_SPAWNERS: usize = 5;
+
+/// A parent that gained children over the last spawn window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Spawner {
+    pub pid: u32,
+    pub name: String,
+    pub children: usize,
+    /// Change in its child count over the window
+    pub new_children: usize,
+}
+
+/// Counts at the start of the current spawn window
+#[derive(Debug)]
+struct SpawnBaseline {
+    timestamp: SystemTime,
+    forks: Option<u64>,
+    processes: usize,
+    children: HashMap<u32, usize>,
+}
+
+/// Rules that only fire once they have held for a while, so they need state across snapshots
+#[derive(Debug, Default)]
+pub struct AlertTracker {
+    disk_sleep_since: Option<SystemTime>,
+    spawn_baseline: Option<SpawnBaseline>,
+    spawn_alerts: Vec<Alert>,
+    spawners: Vec<Spawner>,
+}
+
+impl AlertTracker {
+    /// Everything `AlertThresholds::evaluate` reports, plus sustained rules that have held long enough
+    pub fn evaluate(&mut self, thresholds: &AlertThresholds, snapshot: &SystemSnapshot) -> Vec<Alert> {
+        let mut alerts = thresholds.evaluate(snapshot);
+
+        // A few D-state tasks come and go with ordinary I/O; a pile that stays is storage trouble
+        let blocked = snapshot.processes.iter().filter(|p| p.state == ProcessState::DiskSleep).count() as u32;
+        match thresholds.disk_sleep_count.filter(|&limit| thresholds.enabled && blocked > limit) {
+            Some(limit) => {
+                let since = *self.disk_sleep_since.get_or_insert(snapshot.timestamp);
+                let held = snapshot.timestamp.duration_since(since).unwrap_or_default();
+                if held >= Duration::from_secs(thresholds.disk_sleep_secs) {
+                    alerts.push(Alert {
+                        kind: AlertKind::DiskSleep,
+                        subject: "uninterruptible sleep".to_string(),
+                        pid: None,
+                        value: blocked as f32,
+                        threshold: limit as f32,
+                        critical: true,
+                    });
+                }
+            },
+            None => self.disk_sleep_since = None,
+        }
+
+        self.evaluate_spawning(thresholds, snapshot);
+        alerts.extend(self.spawn_alerts.iter().cloned());
+        alerts
+    }
+
+    /// Parents gaining the most children while a `Spawning` alert is active, busiest first
+    pub fn spawners(&self) -> &[Spawner] {
+        &self.spawners
+    }
+
+    /// Compare fork and process counts with the start of the window once it is long enough;
+    /// in between, the last verdict stands
+    fn evaluate_spawning(&mut self, thresholds: &AlertThresholds, snapshot: &SystemSnapshot) {
+        let elapsed = self
+            .spawn_baseline
+            .as_ref()
+            .and_then(|baseline| snapshot.timestamp.duration_since(baseline.timestamp).ok());
+        if elapsed.is_some_and(|elapsed| elapsed < SPAWN_WINDOW) {
+            return;
+        }
+
+        let children = child_counts(&snapshot.processes);
+        self.spawn_alerts.clear();
+        if let (Some(baseline), Some(elapsed)) = (&self.spawn_baseline, elapsed) {
+            let secs = elapsed.as_secs_f32();
+            let forks = snapshot.system.processes_created.zip(baseline.forks).map(|(now, then)| now.saturating_sub(then));
+            let growth = snapshot.processes.len() as f32 - baseline.processes as f32;
+            let rules = [
+                (FORKS, forks.map(|forks| forks as f32 / secs), thresholds.fork_rate),
+                (PROCESS_GROWTH, Some(growth / secs), thresholds.process_growth),
+            ];
+            for (subject, value, limit) in rules {
+                if let (Some(value), Some(threshold)) = (value, limit.filter(|_| thresholds.enabled)) {
+                    if value > threshold {
+                        self.spawn_alerts.push(Alert {
+                            kind: AlertKind::Spawning,
+                            subject: subject.to_string(),
+                            pid: None,
+                            value,
+                            threshold,
+                            critical: true,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.spawners = if self.spawn_alerts.is_empty() {
+            Vec::new()
+        } else {
+            let before = self.spawn_baseline.as_ref().map(|baseline| &baseline.children);
+            top_spawners(&snapshot.processes, &children, before)
+        };
+        self.spawn_baseline = Some(SpawnBaseline {
+            timestamp: snapshot.timestamp,
+            forks: snapshot.system.processes_created,
+            processes: snapshot.processes.len(),
+            children,
+        });
+    }
+}
+
+/// Live children per parent PID
+fn child_counts(processes: &[ProcessInfo]) -> HashMap<u32, usize> {
+    let mut counts = HashMap::new();
+    for parent in processes.iter().filter_map(|p| p.parent_pid) {
+        *counts.entry(parent).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Parents ranked by how many children they gained since `before`
+fn top_spawners(
+    processes: &[ProcessInfo],
+    children: &HashMap<u32, usize>,
+    before: Option<&HashMap<u32, usize>>,
+) -> Vec<Spawner> {
+    let mut spawners: Vec<Spawner> = children
+        .iter()
+        .filter_map(|(&pid, &count)| {
+            let new_children = count.saturating_sub(before.and_then(|b| b.get(&pid)).copied().unwrap_or(0));
+            if new_children == 0 {
+                return None;
+            }
+            let parent = processes.iter().find(|p| p.pid == pid)?;
+            Some(Spawner {
+                pid,
+                name: parent.name.clone(),
+                children: count,
+                new_children,
+            })
+        })
+        .collect();
+    spawners.sort_by(|a, b| b.new_children.cmp(&a.new_children).then(a.pid.cmp(&b.pid)));
+    spawners.truncate(TOP_SPAWNERS);
+    spawners
+}
+
+type AlertKey = (AlertKind, String, Option<u32>);
+
+/// Body of the webhook POST
+#[derive(Serialize)]
+struct AlertPayload<'a> {
+    hostname: &'a str,
+    fired_at: SystemTime,
+    message: String,
+    #[serde(flatten)]
+    alert: &'a Alert,
+}
+
+/// Runs the configured actions for alerts, with per-condition debounce and cooldown
+pub struct AlertDispatcher {
+    actions: AlertActions,
+    first_seen: HashMap<AlertKey, Instant>,
+    last_fired: HashMap<AlertKey, Instant>,
+    // Actions run on their own threads and report failures back here
+    failures_tx: Sender<String>,
+    failures_rx: Receiver<String>,
+}
+
+impl AlertDispatcher {
+    pub fn new(actions: AlertActions) -> Self {
+        let (failures_tx, failures_rx) = mpsc::channel();
+        Self {
+            actions,
+            first_seen: HashMap::new(),
+            last_fired: HashMap::new(),
+            failures_tx,
+            failures_rx,
+        }
+    }
+
+    /// Whether any action is configured at all
+    pub fn is_active(&self) -> bool {
+        self.actions.command.is_some() || self.actions.webhook_url.is_some() || self.actions.event_log
+    }
+
+    /// Feed the alerts currently active; returns the ones whose actions were started
+    pub fn dispatch(&mut self, hostname: &str, alerts: &[Alert]) -> Vec<Alert> {
+        self.dispatch_at(Instant::now(), hostname, alerts)
+    }
+
+    pub(crate) fn dispatch_at(&mut self, now: Instant, hostname: &str, alerts: &[Alert]) -> Vec<Alert> {
+        if !self.is_active() {
+            return Vec::new();
+        }
+
+        // A condition that clears has to be debounced again when it comes back
+        self.first_seen.retain(|key, _| alerts.iter().any(|a| &a.key() == key));
+        let debounce = Duration::from_secs(self.actions.debounce_secs);
+        let cooldown = Duration::from_secs(self.actions.cooldown_secs);
+        self.last_fired.retain(|_, at| now.duration_since(*at) < cooldown);
+
+        let mut fired = Vec::new();
+        for alert in alerts {
+            let key = alert.key();
+            let since = *self.first_seen.entry(key.clone()).or_insert(now);
+            if now.duration_since(since) < debounce || self.last_fired.contains_key(&key) {
+                continue;
+            }
+            self.last_fired.insert(key, now);
+            self.run_actions(hostname, alert);
+            fired.push(alert.clone());
+        }
+        fired
+    }
+
+    /// Run the actions for a one-off event such as a watched process exiting, right away:
+    /// there is no condition to debounce or cool down
+    pub fn fire(&self, hostname: &str, alert: &Alert) {
+        if self.is_active() {
+            self.run_actions(hostname, alert);
+        }
+    }
+
+    /// Failures reported by actions since the last call
+    pub fn failures(&self) -> Vec<String> {
+        self.failures_rx.try_iter().collect()
+    }
+
+    fn run_actions(&self, hostname: &str, alert: &Alert) {
+        if let Some(command) = &self.actions.command {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg(command)
+                .env("KACEMON_ALERT_KIND", alert.kind_name())
+                .env("KACEMON_ALERT_SUBJECT", &alert.subject)
+                .env("KACEMON_ALERT_PID", alert.pid.map(|p| p.to_string()).unwrap_or_default())
+                .env("KACEMON_ALERT_VALUE", format!("{:.1}", alert.value))
+                .env("KACEMON_ALERT_THRESHOLD", format!("{:.1}", alert.threshold))
+                .env("KACEMON_ALERT_MESSAGE", alert.message())
+                .env("KACEMON_HOSTNAME", hostname)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            self.spawn("command", cmd, None);
+        }
+
+        if let Some(url) = &self.actions.webhook_url {
+            let payload = AlertPayload {
+                hostname,
+                fired_at: SystemTime::now(),
+                message: alert.message(),
+                alert,
+            };
+            let Ok(body) = serde_json::to_vec(&payload) else {
+                return;
+            };
+            let mut cmd = Command::new("curl");
+            cmd.args(["--silent", "--show-error", "--fail", "--max-time", "10", "--request", "POST"])
+                .args(["--header", "Content-Type: application/json", "--data-binary", "@-"])
+                .arg(url)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            self.spawn("webhook", cmd, Some(body));
+        }
+
+        if self.actions.event_log {
+            // Event IDs only tell the alert kinds apart; the message carries the rest
+            let id = alert.kind as u8 as u32 + 1;
+            let mut cmd = Command::new("eventcreate");
+            cmd.args(["/L", "APPLICATION", "/SO", "kacemon"])
+                .args(["/T", if alert.critical { "ERROR" } else { "WARNING" }])
+                .args(["/ID", &id.to_string()])
+                .args(["/D", &format!("{}: {}", hostname, alert.message())])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            self.spawn("event log", cmd, None);
+        }
+    }
+
+    /// Run `cmd` to completion on a background thread so slow actions never stall collection
+    fn spawn(&self, what: &'static str, mut cmd: Command, stdin: Option<Vec<u8>>) {
+        let failures = self.failures_tx.clone();
+        std::thread::spawn(move || {
+            let result = cmd.spawn().and_then(|mut child| {
+                if let (Some(body), Some(mut pipe)) = (stdin, child.stdin.take()) {
+                    pipe.write_all(&body)?;
+                }
+                child.wait()
+            });
+            let failure = match result {
+                Ok(status) if status.success() => return,
+                Ok(status) => format!("alert {} exited with {}", what, status),
+                Err(e) => format!("alert {} failed to start: {}", what, e),
+            };
+            let _ = failures.send(failure);
+        });
+    }
+}
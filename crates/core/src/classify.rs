@@ -0,0 +1,99 @@
+use crate::{
+    error::{CoreError, Result},
+    model::{PaletteColor, ProcessInfo},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// One `categories` entry: processes whose name or command line match `pattern` get `name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryRule {
+    pub name: String,
+    pub pattern: String,
+    /// Badge color in the process table; the theme accent if unset
+    #[serde(default)]
+    pub color: Option<PaletteColor>,
+}
+
+/// Compiled category rules, in config order
+#[derive(Debug, Clone, Default)]
+pub struct Classifier {
+    rules: Vec<(CategoryRule, Regex)>,
+}
+
+impl Classifier {
+    pub fn new(rules: &[CategoryRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                if rule.name.trim().is_empty() {
+                    return Err(CoreError::config("Category names must not be empty".to_string()));
+                }
+                let regex = Regex::new(&rule.pattern).map_err(|e| {
+                    CoreError::config(format!("Category '{}' has an invalid pattern: {}", rule.name, e))
+                })?;
+                Ok((rule.clone(), regex))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &CategoryRule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    /// Name of the first rule matching the process name or its full command line
+    pub fn classify(&self, process: &ProcessInfo) -> Option<&str> {
+        let cmdline = process.cmd.join(" ");
+        self.rules
+            .iter()
+            .find(|(_, regex)| regex.is_match(&process.name) || regex.is_match(&cmdline))
+            .map(|(rule, _)| rule.name.as_str())
+    }
+
+    /// Set `category` on every process
+    pub fn tag(&self, processes: &mut [ProcessInfo]) {
+        if self.is_empty() {
+            return;
+        }
+        for process in processes {
+            process.category = self.classify(process).map(str::to_string);
+        }
+    }
+
+    /// Totals per category, in rule order, for already tagged processes
+    pub fn aggregate(&self, processes: &[ProcessInfo]) -> Vec<CategoryUsage> {
+        let mut usage: Vec<CategoryUsage> = self
+            .rules()
+            .map(|rule| CategoryUsage {
+                name: rule.name.clone(),
+                ..CategoryUsage::default()
+            })
+            .collect();
+        for process in processes {
+            let Some(entry) = process.category.as_ref().and_then(|c| usage.iter_mut().find(|u| &u.name == c)) else {
+                continue;
+            };
+            entry.processes += 1;
+            entry.cpu_percent += process.cpu_percent;
+            entry.memory_percent += process.memory_percent;
+            entry.memory_rss += process.memory_rss;
+        }
+        usage
+    }
+}
+
+/// Combined usage of all processes in one category
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CategoryUsage {
+    pub name: String,
+    pub processes: usize,
+    pub cpu_percent: f32,
+    pub memory_percent: f32,
+    pub memory_rss: u64,
+}
@@ -1,10 +1,132 @@
-use crate::{error::Result, model::{ProcessColumns, SortKey, Theme}};
+use crate::{alerts::AlertThresholds, classify::{CategoryRule, Classifier}, daemon::{LogConfig, LogFormat}, error::Result, filter::ProcessFilter, remote::RemoteHost, model::{GpuExportMode, NameCollation, PressureWeights, ProcessColumns, SortKey, Theme, ThemePalette}};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf, time::Duration};
+use std::{collections::HashMap, fs, ops::RangeInclusive, path::{Path, PathBuf}, time::Duration};
+
+/// Refresh intervals offered by the interactive rate controls
+pub const REFRESH_PRESETS_MS: [u64; 5] = [250, 500, 1000, 2000, 5000];
+
+/// Further presets offered in slow-poll mode
+pub const SLOW_REFRESH_PRESETS_MS: [u64; 4] = [10_000, 30_000, 60_000, 300_000];
+
+/// Longest refresh interval for the TUI, unless slow-poll mode is on
+pub const MAX_TUI_REFRESH_MS: u64 = 10_000;
+
+/// Longest refresh interval headless or in slow-poll mode
+pub const MAX_REFRESH_MS: u64 = 3_600_000;
+
+/// Shortest refresh interval for the TUI, below which collecting costs more than the screen shows
+pub const MIN_TUI_REFRESH_MS: u64 = 100;
+
+/// Shortest refresh interval headless
+pub const MIN_REFRESH_MS: u64 = 50;
+
+/// Refresh intervals allowed in a mode: headless (--daemon, --serve) or the TUI, with or
+/// without slow-poll
+pub fn refresh_bounds(headless: bool, slow_poll: bool) -> RangeInclusive<u64> {
+    if headless {
+        MIN_REFRESH_MS..=MAX_REFRESH_MS
+    } else if slow_poll {
+        MIN_TUI_REFRESH_MS..=MAX_REFRESH_MS
+    } else {
+        MIN_TUI_REFRESH_MS..=MAX_TUI_REFRESH_MS
+    }
+}
+
+/// Lay `layer` over `base`: objects merge key by key, anything else (arrays included) replaces
+/// what was there, so a layer only changes the settings it names
+fn merge_json(base: &mut serde_json::Value, layer: serde_json::Value) {
+    match (base, layer) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    },
+                }
+            }
+        },
+        (base, layer) => *base = layer,
+    }
+}
+
+/// A setting `Config::load` adjusted instead of refusing, for the caller to report
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigWarning {
+    /// --refresh was outside what the mode allows and the nearest bound was used instead
+    RefreshClamped { requested: u64, used: u64 },
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RefreshClamped { requested, used } if used > requested => {
+                write!(f, "Refresh interval {}ms is too short, using {}ms", requested, used)
+            },
+            Self::RefreshClamped { requested, used } => {
+                write!(f, "Refresh interval {}ms is too long, using {}ms", requested, used)
+            },
+        }
+    }
+}
+
+/// A named process table view, picked with `p` or F1-F12 in config order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViewPreset {
+    pub name: String,
+    /// Filter query, e.g. "user:postgres" or "re:^(mysqld|postgres)"; empty shows everything
+    #[serde(default)]
+    pub filter: String,
+    /// Sort column; the current one is kept if unset
+    #[serde(default)]
+    pub sort: Option<SortKey>,
+    /// Biggest first; the sort column's usual direction if unset
+    #[serde(default)]
+    pub descending: Option<bool>,
+    /// Column headers in order, e.g. ["PID", "USER", "NAME", "RSS"]; the current ones if unset
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+}
+
+impl ViewPreset {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Preset names must not be empty".to_string());
+        }
+        self.filter
+            .parse::<ProcessFilter>()
+            .map_err(|e| format!("Preset '{}' filter: {}", self.name, e))?;
+        for column in self.columns.iter().flatten() {
+            if !ProcessColumns::HEADERS.contains(&column.as_str()) {
+                return Err(format!(
+                    "Preset '{}' has unknown column '{}': use {}",
+                    self.name,
+                    column,
+                    ProcessColumns::HEADERS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Sort column and direction after switching to this preset from `current`, sorted
+    /// biggest first if `descending`
+    pub fn sort_order(&self, current: SortKey, descending: bool) -> (SortKey, bool) {
+        let sort = self.sort.unwrap_or(current);
+        let default = if self.sort.is_some() { sort.default_descending() } else { descending };
+        (sort, self.descending.unwrap_or(default))
+    }
+}
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
+    /// Other config files merged underneath this one, in order; relative to this file
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<PathBuf>,
+    
     /// Refresh interval in milliseconds
     pub refresh_ms: u64,
     
@@ -23,20 +145,136 @@ pub struct Config {
     /// Tree view enabled by default
     pub tree_view: bool,
     
+    /// CPU percent a process has to stay above to count as busy in the BUSY column
+    pub cpu_hog_percent: f32,
+    
+    /// Leave kernel threads out of the process table until toggled back in
+    pub hide_kernel_threads: bool,
+    
+    /// Show at most this many matching processes in the TUI, keeping the busiest by CPU and
+    /// memory, so sorting and drawing stay quick on hosts with tens of thousands
+    pub process_display_limit: Option<usize>,
+    
+    /// Read only this many of the busiest processes in full each refresh; the rest are listed
+    /// without wchan, cgroup or children's CPU, which are read when one is opened
+    pub process_collect_limit: Option<usize>,
+    
+    /// Let the TUI refresh as rarely as every hour, with 10s to 5min rate presets
+    pub slow_poll: bool,
+    
     /// Enable Linux procfs features (if available)
     pub use_procfs: bool,
+    
+    /// Whether the CSV file, Telegraf output and the API's /gpus combine all GPUs
+    /// ("aggregate") or give each its own values ("per_device")
+    pub gpu_export: GpuExportMode,
+    
+    /// Upper bound on TUI redraws per second, independent of refresh_ms
+    pub max_fps: u32,
+    
+    /// Animate CPU and memory gauges from the previous sample to the new one
+    pub smooth_gauges: bool,
+    
+    /// Keep the terminal title set to a live "host cpu 42% mem 71%" summary
+    pub terminal_title: bool,
+    
+    /// Emit OSC 9 notification / progress sequences on alerts
+    pub osc_notifications: bool,
+    
+    /// Pop a desktop notification for critical alerts (builds with the desktop-notifications feature)
+    pub desktop_notifications: bool,
+    
+    /// User-defined color palettes, selected by name through `theme`
+    pub themes: HashMap<String, ThemePalette>,
+    
+    /// Process categories, first matching rule wins
+    pub categories: Vec<CategoryRule>,
+    
+    /// Where process list exports are written (current directory if unset)
+    pub export_dir: Option<PathBuf>,
+    
+    /// Draw with plain ASCII instead of box-drawing, block and emoji glyphs
+    pub ascii: bool,
+    
+    /// Weights of CPU, memory, PSI, swap and disk in the pressure score
+    pub pressure_weights: PressureWeights,
+    
+    /// Thresholds that raise alerts in the TUI
+    pub alerts: AlertThresholds,
+    
+    /// Snapshot log written in daemon mode
+    pub log: LogConfig,
+    
+    /// Append a row of system-level metrics per refresh to this CSV file
+    pub csv_path: Option<PathBuf>,
+    
+    /// Address for the read-only HTTP JSON API in daemon mode, e.g. "127.0.0.1:9100"
+    pub serve_addr: Option<String>,
+    
+    /// Web origins, e.g. "http://localhost:3000", whose pages may read the API and open its
+    /// WebSocket; none by default, since the API has no authentication
+    pub serve_allowed_origins: Vec<String>,
+    
+    /// Other machines running `kacemon --serve`, shown in the fleet view and host switcher
+    pub hosts: Vec<RemoteHost>,
+    
+    /// Named filter, sort and column combinations for the process table
+    pub presets: Vec<ViewPreset>,
+    
+    /// Temperature sensors listed first, by label or part of one, e.g. "Package id 0"; the
+    /// rest follow hottest first
+    pub temperature_priority: Vec<String>,
+    
+    /// Count virtual interfaces (docker0, veth*, bridges) in the network totals; off by
+    /// default since their traffic is already counted on the physical interface
+    pub count_virtual_interfaces: bool,
+    
+    /// How sorting by name or user orders them: "natural" (case-insensitive, numbers by
+    /// value) or "bytes"
+    pub name_collation: NameCollation,
+    
+    /// Show the average core clock next to the CPU gauge, against the base and max clocks
+    pub cpu_frequency: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            include: Vec::new(),
             refresh_ms: 2000,
             theme: Theme::Dark,
             no_color: false,
             initial_sort: SortKey::Cpu,
             process_columns: ProcessColumns::default(),
             tree_view: false,
+            cpu_hog_percent: 80.0,
+            hide_kernel_threads: true,
+            process_display_limit: None,
+            process_collect_limit: None,
+            slow_poll: false,
             use_procfs: cfg!(feature = "linux_procfs"),
+            gpu_export: GpuExportMode::Aggregate,
+            max_fps: 30,
+            smooth_gauges: false,
+            terminal_title: false,
+            osc_notifications: false,
+            desktop_notifications: false,
+            themes: HashMap::new(),
+            categories: Vec::new(),
+            export_dir: None,
+            ascii: false,
+            pressure_weights: PressureWeights::default(),
+            alerts: AlertThresholds::default(),
+            log: LogConfig::default(),
+            csv_path: None,
+            serve_addr: None,
+            serve_allowed_origins: Vec::new(),
+            hosts: Vec::new(),
+            presets: Vec::new(),
+            temperature_priority: Vec::new(),
+            count_virtual_interfaces: false,
+            name_collation: NameCollation::Natural,
+            cpu_frequency: false,
         }
     }
 }
@@ -47,57 +285,140 @@ impl Config {
     /// 2. JSON config file if specified
     /// 3. Default config file locations
     /// 4. Built-in defaults
+    ///
+    /// Each file brings its `include`s with it, plus `config.d/<hostname>.json` next to it.
     pub fn load(cli_config: Option<&CliConfig>, json_path: Option<&PathBuf>) -> Result<Self> {
-        let mut config = Self::default();
-        
-        // Try to load from default config locations
-        if let Some(default_config) = Self::load_default_config()? {
-            config.merge(default_config);
+        Self::load_with_warnings(cli_config, json_path).map(|(config, _)| config)
+    }
+    
+    /// `load`, also returning what was adjusted on the way. An out-of-range --refresh is
+    /// clamped into the mode's bounds, since it was typed just now and the nearest usable
+    /// interval is what was meant; the same value in a file is still an error.
+    pub fn load_with_warnings(cli_config: Option<&CliConfig>, json_path: Option<&PathBuf>) -> Result<(Self, Vec<ConfigWarning>)> {
+        // Defaults, then the first default location found, then the file named, each one
+        // layered over the last key by key
+        let mut layers = serde_json::Value::Object(Default::default());
+        if let Some(default_config) = Self::first_config(&Self::default_config_paths())? {
+            merge_json(&mut layers, default_config);
         }
-        
-        // Override with JSON config file if specified
         if let Some(path) = json_path {
-            let file_config = Self::load_from_file(path)?;
-            config.merge(file_config);
+            merge_json(&mut layers, Self::layered_json(path, sysinfo::System::host_name().as_deref())?);
         }
+        let mut config = Self::from_layers(layers)?;
         
         // Override with CLI arguments
-        if let Some(cli) = cli_config {
-            config.apply_cli_overrides(cli);
-        }
+        let warnings = cli_config.map(|cli| config.apply_cli_overrides(cli)).unwrap_or_default();
         
-        config.validate()?;
-        Ok(config)
+        config.validate(cli_config.is_some_and(|cli| cli.headless))?;
+        Ok((config, warnings))
     }
     
     /// Load configuration from a specific JSON file
-    pub fn load_from_file(path: &PathBuf) -> Result<Self> {
-        let contents = fs::read_to_string(path)
-            .map_err(|e| crate::error::CoreError::config(format!("Failed to read config file {}: {}", path.display(), e)))?;
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let contents = Self::read_file(path)?;
+        Self::parse_file(path, &contents)
+    }
+    
+    fn read_file(path: &Path) -> Result<String> {
+        fs::read_to_string(path)
+            .map_err(|e| crate::error::CoreError::config(format!("Failed to read config file {}: {}", path.display(), e)))
+    }
+    
+    fn parse_file(path: &Path, contents: &str) -> Result<Self> {
+        // Name the offending field, e.g. "alerts.actions.webhok_url: unknown field ... at line 7 column 20"
+        let mut deserializer = serde_json::Deserializer::from_str(contents);
+        serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+            let field = e.path().to_string();
+            let field = if field == "." { String::new() } else { format!("{}: ", field) };
+            crate::error::CoreError::config(format!("Failed to parse config file {}: {}{}", path.display(), field, e.inner()))
+        })
+    }
+    
+    /// Turn merged layers into a config. Each file was already checked on its own, so this
+    /// only fails if the layers clash, e.g. a theme given as a name in one and a palette in another.
+    fn from_layers(layers: serde_json::Value) -> Result<Self> {
+        serde_path_to_error::deserialize(layers).map_err(|e| {
+            crate::error::CoreError::config(format!("Failed to merge config files: {}: {}", e.path(), e.inner()))
+        })
+    }
+    
+    /// Load and validate a config file (or the first one found in the default locations) the
+    /// way startup would, returning the file checked and any warnings
+    pub fn check_file(path: Option<&Path>) -> Result<(PathBuf, Vec<String>)> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => Self::default_config_paths().into_iter().find(|p| p.exists()).ok_or_else(|| {
+                let searched: Vec<String> = Self::default_config_paths().iter().map(|p| p.display().to_string()).collect();
+                crate::error::CoreError::config(format!("No config file found; looked in {}", searched.join(", ")))
+            })?,
+        };
         
-        let config: Self = serde_json::from_str(&contents)
-            .map_err(|e| crate::error::CoreError::config(format!("Failed to parse config file {}: {}", path.display(), e)))?;
+        let config = Self::load_layered(&path)?;
+        config.validate(true)?;
+        let mut warnings = Vec::new();
+        if let Err(crate::error::CoreError::Config(message)) = config.validate(false) {
+            warnings.push(format!("{} (fine with --daemon or --serve)", message));
+        }
+        Ok((path, warnings))
+    }
+    
+    /// Load a config file with its includes merged underneath and this host's override on top
+    pub fn load_layered(path: &Path) -> Result<Self> {
+        Self::load_for_host(path, sysinfo::System::host_name().as_deref())
+    }
+    
+    pub(crate) fn load_for_host(path: &Path, hostname: Option<&str>) -> Result<Self> {
+        Self::from_layers(Self::layered_json(path, hostname)?)
+    }
+    
+    /// The file with its includes and host override merged in, still as JSON so that only
+    /// the keys each layer actually sets replace the ones beneath
+    fn layered_json(path: &Path, hostname: Option<&str>) -> Result<serde_json::Value> {
+        let mut layers = Self::json_with_includes(path, &mut Vec::new())?;
         
-        Ok(config)
+        // Try the full name first, then the short one ("web1" for "web1.example.com")
+        let dir = path.parent().unwrap_or(Path::new(".")).join("config.d");
+        let candidates = hostname.into_iter().flat_map(|host| [host, host.split('.').next().unwrap_or(host)]);
+        if let Some(host_file) = candidates.map(|host| dir.join(format!("{}.json", host))).find(|p| p.exists()) {
+            merge_json(&mut layers, Self::json_with_includes(&host_file, &mut Vec::new())?);
+        }
+        Ok(layers)
     }
     
-    /// Load configuration from default locations
-    fn load_default_config() -> Result<Option<Self>> {
-        let config_paths = Self::default_config_paths();
+    /// Includes are merged first, in order, so the including file has the last word
+    fn json_with_includes(path: &Path, chain: &mut Vec<PathBuf>) -> Result<serde_json::Value> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&canonical) {
+            return Err(crate::error::CoreError::config(format!("Config file {} includes itself", path.display())));
+        }
+        chain.push(canonical);
         
-        for path in config_paths {
-            if path.exists() {
-                match Self::load_from_file(&path) {
-                    Ok(config) => return Ok(Some(config)),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to load config from {}: {}", path.display(), e);
-                        continue;
-                    }
-                }
-            }
+        // Parsed as a config first for the precise error, then kept as the keys it sets
+        let contents = Self::read_file(path)?;
+        let file = Self::parse_file(path, &contents)?;
+        let mut keys: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| crate::error::CoreError::config(format!("Failed to parse config file {}: {}", path.display(), e)))?;
+        if let Some(keys) = keys.as_object_mut() {
+            keys.remove("include");
+        }
+        let mut layers = serde_json::Value::Object(Default::default());
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for include in &file.include {
+            merge_json(&mut layers, Self::json_with_includes(&dir.join(include), chain)?);
         }
+        merge_json(&mut layers, keys);
         
-        Ok(None)
+        chain.pop();
+        Ok(layers)
+    }
+    
+    /// The first of `paths` that exists. A broken one is an error like any named file,
+    /// rather than quietly falling through to the next location.
+    pub(crate) fn first_config(paths: &[PathBuf]) -> Result<Option<serde_json::Value>> {
+        match paths.iter().find(|path| path.exists()) {
+            Some(path) => Self::layered_json(path, sysinfo::System::host_name().as_deref()).map(Some),
+            None => Ok(None),
+        }
     }
     
     /// Get default configuration file search paths
@@ -120,72 +441,127 @@ impl Config {
         paths
     }
     
-    /// Merge another configuration into this one
-    fn merge(&mut self, other: Self) {
-        // Only override non-default values
-        if other.refresh_ms != 250 {
-            self.refresh_ms = other.refresh_ms;
+    /// Apply CLI argument overrides
+    fn apply_cli_overrides(&mut self, cli: &CliConfig) -> Vec<ConfigWarning> {
+        if let Some(theme) = &cli.theme {
+            self.theme = theme.clone();
         }
-        if other.theme != Theme::Dark {
-            self.theme = other.theme;
+        if cli.no_color {
+            self.no_color = true;
         }
-        if other.no_color {
-            self.no_color = other.no_color;
+        if let Some(fps) = cli.max_fps {
+            self.max_fps = fps;
         }
-        if other.initial_sort != SortKey::Cpu {
-            self.initial_sort = other.initial_sort;
+        if cli.ascii {
+            self.ascii = true;
         }
-        // For process columns, merge individual fields
-        // For simplicity, we'll replace the whole struct if any field differs from default
-        let default_columns = ProcessColumns::default();
-        if other.process_columns.pid != default_columns.pid
-            || other.process_columns.name != default_columns.name
-            || other.process_columns.user != default_columns.user
-            || other.process_columns.cpu_percent != default_columns.cpu_percent
-            || other.process_columns.memory_percent != default_columns.memory_percent
-            || other.process_columns.memory_rss != default_columns.memory_rss
-            || other.process_columns.memory_vsz != default_columns.memory_vsz
-            || other.process_columns.threads != default_columns.threads
-            || other.process_columns.state != default_columns.state
-            || other.process_columns.start_time != default_columns.start_time
-        {
-            self.process_columns = other.process_columns;
+        if cli.slow_poll {
+            self.slow_poll = true;
         }
-        if other.tree_view {
-            self.tree_view = other.tree_view;
+        if let Some(path) = &cli.log_path {
+            self.log.path = Some(path.clone());
         }
-        if !other.use_procfs {
-            self.use_procfs = other.use_procfs;
+        if let Some(format) = cli.log_format {
+            self.log.format = format;
         }
-    }
-    
-    /// Apply CLI argument overrides
-    fn apply_cli_overrides(&mut self, cli: &CliConfig) {
-        if let Some(refresh) = cli.refresh_ms {
-            self.refresh_ms = refresh;
+        if let Some(path) = &cli.csv_path {
+            self.csv_path = Some(path.clone());
         }
-        if let Some(theme) = &cli.theme {
-            self.theme = theme.clone();
+        if let Some(addr) = &cli.serve_addr {
+            self.serve_addr = Some(addr.clone());
         }
-        if cli.no_color {
-            self.no_color = true;
+        
+        // Last, so a --slow-poll from either place widens the bounds
+        let mut warnings = Vec::new();
+        if let Some(requested) = cli.refresh_ms {
+            let bounds = refresh_bounds(cli.headless, self.slow_poll);
+            self.refresh_ms = requested.clamp(*bounds.start(), *bounds.end());
+            if self.refresh_ms != requested {
+                warnings.push(ConfigWarning::RefreshClamped { requested, used: self.refresh_ms });
+            }
         }
+        warnings
     }
     
-    /// Validate configuration values
-    fn validate(&self) -> Result<()> {
-        if self.refresh_ms < 50 {
+    /// Validate configuration values; `headless` allows the long intervals exporters want
+    fn validate(&self, headless: bool) -> Result<()> {
+        let bounds = refresh_bounds(headless, self.slow_poll);
+        if self.refresh_ms < *bounds.start() {
+            return Err(crate::error::CoreError::config(format!(
+                "Refresh interval must be at least {}ms{}",
+                bounds.start(),
+                if headless { "" } else { " in the TUI" }
+            )));
+        }
+        
+        if self.refresh_ms > MAX_REFRESH_MS {
             return Err(crate::error::CoreError::config(
-                "Refresh interval must be at least 50ms".to_string()
+                "Refresh interval must be at most 1 hour".to_string()
             ));
         }
         
-        if self.refresh_ms > 10000 {
+        if self.refresh_ms > *bounds.end() {
             return Err(crate::error::CoreError::config(
-                "Refresh interval must be at most 10 seconds".to_string()
+                "Refresh interval must be at most 10 seconds in the TUI; use --slow-poll or --daemon for longer".to_string()
             ));
         }
         
+        if !(1..=240).contains(&self.max_fps) {
+            return Err(crate::error::CoreError::config(
+                "max_fps must be between 1 and 240".to_string()
+            ));
+        }
+        
+        let w = &self.pressure_weights;
+        let weights = [w.cpu, w.memory, w.psi, w.swap, w.disk];
+        if weights.iter().any(|&weight| weight < 0.0 || !weight.is_finite()) || weights.iter().sum::<f32>() <= 0.0 {
+            return Err(crate::error::CoreError::config(
+                "pressure_weights must be non-negative and not all zero".to_string()
+            ));
+        }
+        
+        self.alerts.validate().map_err(crate::error::CoreError::config)?;
+        
+        if self.process_display_limit == Some(0) {
+            return Err(crate::error::CoreError::config("process_display_limit must be at least 1".to_string()));
+        }
+        if self.process_collect_limit == Some(0) {
+            return Err(crate::error::CoreError::config("process_collect_limit must be at least 1".to_string()));
+        }
+        if !(self.cpu_hog_percent >= 0.0 && self.cpu_hog_percent.is_finite()) {
+            return Err(crate::error::CoreError::config("cpu_hog_percent must be a positive number".to_string()));
+        }
+        
+        if self.log.max_size_mb == 0 {
+            return Err(crate::error::CoreError::config("log.max_size_mb must be at least 1".to_string()));
+        }
+        Classifier::new(&self.categories)?;
+        
+        for host in &self.hosts {
+            host.validate().map_err(crate::error::CoreError::config)?;
+        }
+        for preset in &self.presets {
+            preset.validate().map_err(crate::error::CoreError::config)?;
+        }
+        
+        if let Theme::Custom(name) = &self.theme {
+            if !self.themes.contains_key(name) {
+                return Err(crate::error::CoreError::config(format!(
+                    "Unknown theme '{}': use dark, light or a name defined under \"themes\"",
+                    name
+                )));
+            }
+        }
+        
+        for (name, palette) in &self.themes {
+            if matches!(palette.base, Theme::Custom(_)) {
+                return Err(crate::error::CoreError::config(format!(
+                    "Theme '{}' must use dark or light as its base",
+                    name
+                )));
+            }
+        }
+        
         Ok(())
     }
     
@@ -193,25 +569,64 @@ impl Config {
     pub fn refresh_interval(&self) -> Duration {
         Duration::from_millis(self.refresh_ms)
     }
+    
+    /// Directory for exported files
+    pub fn export_dir(&self) -> PathBuf {
+        self.export_dir.clone().unwrap_or_else(|| PathBuf::from("."))
+    }
+    
+    /// Minimum time between two TUI frames
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.max_fps.max(1)
+    }
+    
+    /// Presets the rate controls step through; slow-poll mode adds the long ones
+    fn refresh_presets(&self) -> Vec<u64> {
+        let slow: &[u64] = if self.slow_poll { &SLOW_REFRESH_PRESETS_MS } else { &[] };
+        REFRESH_PRESETS_MS.iter().chain(slow).copied().collect()
+    }
+    
+    /// Switch to the next slower preset, wrapping to the fastest
+    pub fn cycle_refresh_preset(&mut self) {
+        let presets = self.refresh_presets();
+        self.refresh_ms = presets
+            .iter()
+            .copied()
+            .find(|&ms| ms > self.refresh_ms)
+            .unwrap_or(presets[0]);
+    }
+    
+    /// Step to the adjacent faster or slower preset, stopping at either end
+    pub fn step_refresh_preset(&mut self, faster: bool) {
+        let presets = self.refresh_presets();
+        let next = if faster {
+            presets.iter().rev().copied().find(|&ms| ms < self.refresh_ms)
+        } else {
+            presets.iter().copied().find(|&ms| ms > self.refresh_ms)
+        };
+        if let Some(ms) = next {
+            self.refresh_ms = ms;
+        }
+    }
 }
 
 /// CLI configuration (temporary struct for CLI parsing)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CliConfig {
     pub refresh_ms: Option<u64>,
     pub theme: Option<Theme>,
     pub no_color: bool,
+    pub max_fps: Option<u32>,
+    pub ascii: bool,
+    pub slow_poll: bool,
+    /// Running without the TUI (--daemon, --serve), where long intervals are fine
+    pub headless: bool,
+    pub log_path: Option<PathBuf>,
+    pub log_format: Option<LogFormat>,
+    pub csv_path: Option<PathBuf>,
+    pub serve_addr: Option<String>,
 }
 
-impl Default for CliConfig {
-    fn default() -> Self {
-        Self {
-            refresh_ms: None,
-            theme: None,
-            no_color: false,
-        }
-    }
-}
 
 // Add dirs dependency for config directory discovery
 // This will be added to Cargo.toml dependencies
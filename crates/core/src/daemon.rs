@@ -0,0 +1,295 @@
+use crate::{
+    alerts::{Alert, AlertDispatcher, AlertTracker},
+    classify::Classifier,
+    config::Config,
+    error::Result,
+    export::CsvWriter,
+    metrics::MetricsCollector,
+    model::{NetworkInfo, PressureWeights, SystemSnapshot},
+    score::PressureScore,
+    serve::ApiServer,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime},
+};
+
+/// What each log line holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Headline numbers, top processes and active alerts
+    #[default]
+    Summary,
+    /// The whole snapshot, every process included
+    Full,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "summary" => Ok(Self::Summary),
+            "full" => Ok(Self::Full),
+            other => Err(format!("Unknown log format '{}': use summary or full", other)),
+        }
+    }
+}
+
+/// Where and how headless mode logs snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub path: Option<PathBuf>,
+    pub format: LogFormat,
+    /// Rotate once the file would grow past this size
+    pub max_size_mb: u64,
+    /// Rotated files kept next to the log as <path>.1 ... <path>.N
+    pub keep: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            format: LogFormat::Summary,
+            max_size_mb: 10,
+            keep: 5,
+        }
+    }
+}
+
+/// Processes listed in a summary line
+const SUMMARY_TOP_PROCESSES: usize = 5;
+
+/// One summary log line
+#[derive(Serialize)]
+struct SnapshotSummary<'a> {
+    timestamp: SystemTime,
+    hostname: &'a str,
+    cpu_percent: f32,
+    memory_percent: f32,
+    swap_percent: f32,
+    load_avg: [f64; 3],
+    pressure: f32,
+    disk_read_bytes: u64,
+    disk_write_bytes: u64,
+    net_rx_bytes: u64,
+    net_tx_bytes: u64,
+    process_count: usize,
+    top_processes: Vec<TopProcess<'a>>,
+    alerts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct TopProcess<'a> {
+    pid: u32,
+    name: &'a str,
+    cpu_percent: f32,
+    memory_percent: f32,
+}
+
+/// One full log line
+#[derive(Serialize)]
+struct SnapshotRecord<'a> {
+    #[serde(flatten)]
+    snapshot: &'a SystemSnapshot,
+    alerts: &'a [Alert],
+}
+
+/// JSON-lines snapshot log with size-based rotation
+pub struct SnapshotLog {
+    path: PathBuf,
+    format: LogFormat,
+    max_bytes: u64,
+    keep: usize,
+    file: BufWriter<File>,
+    written: u64,
+    include_virtual: bool,
+}
+
+impl SnapshotLog {
+    pub fn open(config: &LogConfig, path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            format: config.format,
+            max_bytes: config.max_size_mb.saturating_mul(1024 * 1024),
+            keep: config.keep,
+            file: BufWriter::new(file),
+            written,
+            include_virtual: false,
+        })
+    }
+
+    /// Count virtual interfaces in a summary line's network totals too
+    pub fn with_virtual_interfaces(mut self, include_virtual: bool) -> Self {
+        self.include_virtual = include_virtual;
+        self
+    }
+
+    /// Append one line for the snapshot, rotating first if it wouldn't fit
+    pub fn write(
+        &mut self,
+        snapshot: &SystemSnapshot,
+        alerts: &[Alert],
+        interval_secs: f64,
+        weights: &PressureWeights,
+    ) -> Result<()> {
+        let mut line = match self.format {
+            LogFormat::Full => serde_json::to_vec(&SnapshotRecord { snapshot, alerts })?,
+            LogFormat::Summary => {
+                serde_json::to_vec(&summarize(snapshot, alerts, interval_secs, weights, self.include_virtual))?
+            },
+        };
+        line.push(b'\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        // Flush every line so a kill or crash loses at most the current one
+        self.file.flush()?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// <path>.N-1 -> <path>.N, ..., <path> -> <path>.1, then start a fresh file
+    fn rotate(&mut self) -> Result<()> {
+        self.file.flush()?;
+        let numbered = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(numbered(self.keep));
+            for n in (1..self.keep).rev() {
+                if numbered(n).exists() {
+                    fs::rename(numbered(n), numbered(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, numbered(1))?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.file = BufWriter::new(file);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn summarize<'a>(
+    snapshot: &'a SystemSnapshot,
+    alerts: &[Alert],
+    interval_secs: f64,
+    weights: &PressureWeights,
+    include_virtual: bool,
+) -> SnapshotSummary<'a> {
+    let (net_rx_bytes, net_tx_bytes) = NetworkInfo::total_deltas(&snapshot.networks, include_virtual);
+
+    let mut top: Vec<_> = snapshot.processes.iter().collect();
+    top.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+
+    SnapshotSummary {
+        timestamp: snapshot.timestamp,
+        hostname: &snapshot.system.hostname,
+        cpu_percent: snapshot.cpu_average(),
+        memory_percent: snapshot.memory_percent() as f32,
+        swap_percent: snapshot.memory.swap_percent() as f32,
+        load_avg: [snapshot.system.load_avg_1, snapshot.system.load_avg_5, snapshot.system.load_avg_15],
+        pressure: PressureScore::compute(snapshot, weights, interval_secs).score,
+        disk_read_bytes: snapshot.disks.iter().map(|d| d.read_bytes_delta).sum(),
+        disk_write_bytes: snapshot.disks.iter().map(|d| d.write_bytes_delta).sum(),
+        net_rx_bytes,
+        net_tx_bytes,
+        process_count: snapshot.processes.len(),
+        top_processes: top
+            .into_iter()
+            .take(SUMMARY_TOP_PROCESSES)
+            .map(|p| TopProcess {
+                pid: p.pid,
+                name: &p.name,
+                cpu_percent: p.cpu_percent,
+                memory_percent: p.memory_percent,
+            })
+            .collect(),
+        alerts: alerts.iter().map(Alert::message).collect(),
+    }
+}
+
+/// Collect on the configured interval and log, export or serve every snapshot until the
+/// process is stopped. Alert actions run here too, so a headless box still gets its
+/// commands and webhooks.
+pub fn run_daemon(config: &Config) -> Result<()> {
+    if config.log.path.is_none() && config.csv_path.is_none() && config.serve_addr.is_none() {
+        return Err(crate::error::CoreError::config(
+            "Daemon mode needs a log path (--log or log.path), a CSV file (--csv or csv_path) \
+             or an API address (--serve or serve_addr)",
+        ));
+    }
+    let mut log = match &config.log.path {
+        Some(path) => Some(SnapshotLog::open(&config.log, path)?.with_virtual_interfaces(config.count_virtual_interfaces)),
+        None => None,
+    };
+    let mut csv = match &config.csv_path {
+        Some(path) => Some(
+            CsvWriter::open(path)?
+                .with_virtual_interfaces(config.count_virtual_interfaces)
+                .with_gpu_export(config.gpu_export),
+        ),
+        None => None,
+    };
+    let server = match &config.serve_addr {
+        Some(addr) => {
+            Some(ApiServer::bind_allowing(addr, config.serve_allowed_origins.clone())?.with_gpu_export(config.gpu_export))
+        },
+        None => None,
+    };
+
+    let mut collector = MetricsCollector::builder()
+        .process_limit(config.process_collect_limit)
+        .hog_threshold(config.cpu_hog_percent)
+        .build()?;
+    collector.init()?;
+    let classifier = Classifier::new(&config.categories)?;
+    let mut dispatcher = AlertDispatcher::new(config.alerts.actions.clone());
+    let mut tracker = AlertTracker::default();
+    let interval = config.refresh_interval();
+    let mut previous_timestamp: Option<SystemTime> = None;
+
+    loop {
+        let started = Instant::now();
+        let mut snapshot = collector.collect()?;
+        classifier.tag(&mut snapshot.processes);
+
+        let interval_secs = previous_timestamp
+            .and_then(|previous| snapshot.timestamp.duration_since(previous).ok())
+            .map_or(interval.as_secs_f64(), |elapsed| elapsed.as_secs_f64().max(0.001));
+        previous_timestamp = Some(snapshot.timestamp);
+
+        let alerts = tracker.evaluate(&config.alerts, &snapshot);
+        dispatcher.dispatch(&snapshot.system.hostname, &alerts);
+        for failure in dispatcher.failures() {
+            eprintln!("kacemon: {}", failure);
+        }
+
+        if let Some(log) = &mut log {
+            log.write(&snapshot, &alerts, interval_secs, &config.pressure_weights)?;
+        }
+        if let Some(csv) = &mut csv {
+            csv.write_row(&snapshot, interval_secs)?;
+        }
+        if let Some(server) = &server {
+            server.publish(&snapshot, &alerts);
+        }
+        std::thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
@@ -15,6 +15,9 @@ pub enum CoreError {
     #[error("Platform-specific error: {0}")]
     Platform(String),
 
+    #[error("Remote host error: {0}")]
+    Remote(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -55,6 +58,10 @@ impl CoreError {
         Self::Platform(msg.into())
     }
 
+    pub fn remote<S: Into<String>>(msg: S) -> Self {
+        Self::Remote(msg.into())
+    }
+
     pub fn permission_denied<S: Into<String>>(msg: S) -> Self {
         Self::PermissionDenied(msg.into())
     }
@@ -0,0 +1,358 @@
+use crate::{
+    alerts::Alert,
+    error::{CoreError, Result},
+    leaks::LeakSuspect,
+    model::{GpuAggregate, GpuExportMode, NetworkInfo, ProcessGrouping, ProcessInfo, SortKey, SystemSnapshot},
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Top-level document written by `write_processes_json`
+#[derive(Serialize)]
+struct ProcessExport<'a> {
+    exported_at: SystemTime,
+    process_count: usize,
+    processes: &'a [&'a ProcessInfo],
+}
+
+/// Write `processes` to `<dir>/kacemon-processes-<UTC timestamp>.json`, creating `dir` if needed
+pub fn write_processes_json(dir: &Path, processes: &[&ProcessInfo]) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let now = SystemTime::now();
+    let path = dir.join(format!("kacemon-processes-{}.json", timestamp_slug(now)));
+    let writer = BufWriter::new(File::create(&path)?);
+    serde_json::to_writer_pretty(
+        writer,
+        &ProcessExport {
+            exported_at: now,
+            process_count: processes.len(),
+            processes,
+        },
+    )?;
+
+    Ok(path)
+}
+
+/// Bumped when a bundle written by this version can't be read by older ones
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// How the process table was filtered and sorted when a bundle was saved
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleView {
+    /// Filter query as typed, e.g. `user:root cpu>50`
+    pub filter: String,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub category: Option<String>,
+    /// Only processes owned by this user
+    pub user: Option<String>,
+    /// Only processes on this terminal, e.g. "pts/3"
+    pub terminal: Option<String>,
+    /// Only processes with no terminal: daemons and services
+    pub detached_only: bool,
+    pub disk_sleep_only: bool,
+    pub leaks_only: bool,
+    pub hide_kernel_threads: bool,
+    pub tree_view: bool,
+    /// One row per process name; kept alongside `grouping` for bundles from before it
+    pub group_by_name: bool,
+    pub grouping: ProcessGrouping,
+    /// Process table columns, in order
+    pub columns: Vec<String>,
+    pub selected_pid: Option<u32>,
+}
+
+/// Everything needed to show someone else one screen of kacemon: the snapshot, how the
+/// table was filtered and sorted, and the alerts and leak suspects at the time. Saved as
+/// gzipped JSON with a `.kmon` extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: SystemTime,
+    pub snapshot: SystemSnapshot,
+    #[serde(default)]
+    pub view: BundleView,
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+    #[serde(default)]
+    pub leak_suspects: Vec<LeakSuspect>,
+    /// Seconds the snapshot's deltas cover, for turning them into rates
+    #[serde(default)]
+    pub interval_secs: Option<f64>,
+}
+
+impl Bundle {
+    pub fn new(snapshot: SystemSnapshot, view: BundleView, alerts: Vec<Alert>, leak_suspects: Vec<LeakSuspect>) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            exported_at: SystemTime::now(),
+            snapshot,
+            view,
+            alerts,
+            leak_suspects,
+            interval_secs: None,
+        }
+    }
+
+    pub fn with_interval(mut self, interval_secs: f64) -> Self {
+        self.interval_secs = Some(interval_secs);
+        self
+    }
+
+    /// Write to `<dir>/kacemon-<host>-<UTC timestamp>.kmon`, creating `dir` if needed
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+
+        let host: String = self
+            .snapshot
+            .system
+            .hostname
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let path = dir.join(format!("kacemon-{}-{}.kmon", host, timestamp_slug(self.exported_at)));
+        let mut encoder = GzEncoder::new(BufWriter::new(File::create(&path)?), Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?.flush()?;
+
+        Ok(path)
+    }
+
+    /// Read a bundle written by `write`
+    pub fn read(path: &Path) -> Result<Self> {
+        let decoder = GzDecoder::new(BufReader::new(File::open(path)?));
+        let bundle: Self = serde_json::from_reader(decoder)?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(CoreError::config(format!(
+                "{} is a version {} bundle; this kacemon reads up to version {}",
+                path.display(),
+                bundle.version,
+                BUNDLE_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+/// One line of a `--log-format full` snapshot log
+#[derive(Deserialize)]
+struct RecordedSnapshot {
+    #[serde(flatten)]
+    snapshot: SystemSnapshot,
+    #[serde(default)]
+    alerts: Vec<Alert>,
+}
+
+/// Read what `kacemon view` can show, as one bundle per frame in time order: a `.kmon`
+/// bundle, a snapshot as served on `/snapshot`, or a snapshot log written with
+/// `--log-format full`, one frame per line
+pub fn read_recording(path: &Path) -> Result<Vec<Bundle>> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(vec![Bundle::read(path)?]);
+    }
+
+    let frame = |recorded: RecordedSnapshot| Bundle {
+        exported_at: recorded.snapshot.timestamp,
+        ..Bundle::new(recorded.snapshot, BundleView::default(), recorded.alerts, Vec::new())
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    if let Ok(recorded) = serde_json::from_str::<RecordedSnapshot>(&text) {
+        return Ok(vec![frame(recorded)]);
+    }
+
+    let mut frames = Vec::new();
+    for (number, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let recorded = serde_json::from_str::<RecordedSnapshot>(line).map_err(|e| {
+            let hint = if line.contains("\"top_processes\"") {
+                "; summary logs only hold headline numbers, record with --log-format full to view them"
+            } else {
+                ""
+            };
+            CoreError::config(format!("{} line {}: not a snapshot ({}){}", path.display(), number + 1, e, hint))
+        })?;
+        frames.push(frame(recorded));
+    }
+    if frames.is_empty() {
+        return Err(CoreError::config(format!("{} holds no snapshots", path.display())));
+    }
+
+    // Rates need the gap to the snapshot before; the first frame borrows the next one's
+    let gaps: Vec<Option<f64>> = frames
+        .windows(2)
+        .map(|pair| pair[1].snapshot.timestamp.duration_since(pair[0].snapshot.timestamp).ok().map(|d| d.as_secs_f64()))
+        .collect();
+    for (i, frame) in frames.iter_mut().enumerate() {
+        let gap = if i == 0 { gaps.first() } else { gaps.get(i - 1) };
+        frame.interval_secs = gap.copied().flatten().filter(|&secs| secs > 0.0);
+    }
+    Ok(frames)
+}
+
+/// `YYYYMMDD-HHMMSS` in UTC, for file names that sort chronologically
+pub fn timestamp_slug(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(time);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, hour, minute, second)
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ`, which spreadsheets parse as a date
+pub fn iso8601(time: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(time);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)
+}
+
+/// `HH:MM:SS` for a time on the same UTC day as `now`, `YYYY-MM-DD` for one before it, the
+/// way ps shortens start times
+pub fn short_timestamp(time: SystemTime, now: SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(time);
+    let (today_year, today_month, today_day, ..) = civil_time(now);
+    if (year, month, day) == (today_year, today_month, today_day) {
+        format!("{:02}:{:02}:{:02}", hour, minute, second)
+    } else {
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// UTC year, month, day, hour, minute, second
+fn civil_time(time: SystemTime) -> (i64, i64, i64, u64, u64, u64) {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, day_secs) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    (year, month, day, day_secs / 3_600, day_secs % 3_600 / 60, day_secs % 60)
+}
+
+/// The UTC time `civil_time` would give back these fields for; None for a time that doesn't
+/// exist or is before 1970
+pub(crate) fn utc_time(year: i64, month: i64, day: i64, hour: u64, minute: u64, second: u64) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let year = year - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let days = era * 146_097 + yoe * 365 + yoe / 4 - yoe / 100 + doy - 719_468;
+    let secs = u64::try_from(days).ok()? * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+const CSV_HEADER: &str = "timestamp,cpu_percent,memory_used_bytes,memory_percent,swap_used_bytes,swap_percent,\
+net_rx_bytes_per_sec,net_tx_bytes_per_sec,disk_read_bytes_per_sec,disk_write_bytes_per_sec,max_temp_celsius,\
+gpu_utilization_percent,gpu_memory_used_bytes,gpu_temp_celsius";
+
+/// Appends one row of system-level metrics per snapshot
+pub struct CsvWriter {
+    file: BufWriter<File>,
+    include_virtual: bool,
+    gpu_export: GpuExportMode,
+}
+
+impl CsvWriter {
+    /// Open `path` for appending, writing the header if the file is new or empty
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let is_empty = file.metadata()?.len() == 0;
+        let mut writer = Self { file: BufWriter::new(file), include_virtual: false, gpu_export: GpuExportMode::default() };
+        if is_empty {
+            writeln!(writer.file, "{}", CSV_HEADER)?;
+            writer.file.flush()?;
+        }
+        Ok(writer)
+    }
+
+    /// Count virtual interfaces in the network columns too
+    pub fn with_virtual_interfaces(mut self, include_virtual: bool) -> Self {
+        self.include_virtual = include_virtual;
+        self
+    }
+
+    /// Combine the GPU columns across devices, or list each device's value
+    pub fn with_gpu_export(mut self, gpu_export: GpuExportMode) -> Self {
+        self.gpu_export = gpu_export;
+        self
+    }
+
+    /// `interval_secs` is the time covered by the snapshot's deltas
+    pub fn write_row(&mut self, snapshot: &SystemSnapshot, interval_secs: f64) -> Result<()> {
+        writeln!(self.file, "{}", csv_row(snapshot, interval_secs, self.include_virtual, self.gpu_export))?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// One CSV line (no newline) matching `CSV_HEADER`; the network columns leave virtual
+/// interfaces out unless `include_virtual`. The GPU columns hold the mean utilization, total
+/// memory and hottest reading, or per device each GPU's value in index order, split by ';'.
+pub fn csv_row(snapshot: &SystemSnapshot, interval_secs: f64, include_virtual: bool, gpu_export: GpuExportMode) -> String {
+    let per_sec = |bytes: u64| (bytes as f64 / interval_secs.max(0.001)).round() as u64;
+    let memory = &snapshot.memory;
+    let (rx, tx) = NetworkInfo::total_deltas(&snapshot.networks, include_virtual);
+    // No sensors leaves the cell empty rather than a misleading zero
+    let max_temp = snapshot
+        .temperatures
+        .iter()
+        .map(|t| t.temperature)
+        .reduce(f32::max)
+        .map(|t| format!("{:.1}", t))
+        .unwrap_or_default();
+    let gpus = &snapshot.gpus;
+    let (gpu_utilization, gpu_memory, gpu_temp) = match gpu_export {
+        _ if gpus.is_empty() => Default::default(),
+        GpuExportMode::Aggregate => {
+            let all = GpuAggregate::from_gpus(gpus);
+            (
+                format!("{:.1}", all.utilization_percent),
+                all.memory_used.to_string(),
+                all.max_temperature.map(|t| format!("{:.1}", t)).unwrap_or_default(),
+            )
+        },
+        GpuExportMode::PerDevice => {
+            let join = |values: Vec<String>| values.join(";");
+            (
+                join(gpus.iter().map(|g| format!("{:.1}", g.utilization_percent)).collect()),
+                join(gpus.iter().map(|g| g.memory_used.to_string()).collect()),
+                join(gpus.iter().map(|g| g.temperature.map(|t| format!("{:.1}", t)).unwrap_or_default()).collect()),
+            )
+        },
+    };
+
+    format!(
+        "{},{:.1},{},{:.1},{},{:.1},{},{},{},{},{},{},{},{}",
+        iso8601(snapshot.timestamp),
+        snapshot.cpu_average(),
+        memory.used,
+        snapshot.memory_percent(),
+        memory.swap_used,
+        memory.swap_percent(),
+        per_sec(rx),
+        per_sec(tx),
+        per_sec(snapshot.disks.iter().map(|d| d.read_bytes_delta).sum()),
+        per_sec(snapshot.disks.iter().map(|d| d.write_bytes_delta).sum()),
+        max_temp,
+        gpu_utilization,
+        gpu_memory,
+        gpu_temp
+    )
+}
@@ -0,0 +1,107 @@
+//! C ABI over the snapshot API, built with the `ffi` feature. Every call is safe to make
+//! from any thread, but one collector must not be used from two threads at once. Strings
+//! returned by the library are owned by the caller and freed with `kacemon_string_free`.
+
+use crate::{error::Result, metrics::MetricsCollector};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+/// Opaque handle to a metrics collector
+pub struct KacemonCollector {
+    inner: MetricsCollector,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, turning an error or panic into a null return and a message for `kacemon_last_error`
+fn guarded<T>(f: impl FnOnce() -> Result<*mut T>) -> *mut T {
+    let message = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            set_last_error(None);
+            return value;
+        },
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => "kacemon panicked".to_string(),
+    };
+    set_last_error(Some(message));
+    ptr::null_mut()
+}
+
+fn set_last_error(message: Option<String>) {
+    // Escape any NULs, say from a path in the message, so a failure always leaves a message
+    let message = message.and_then(|m| CString::new(m.replace('\0', "\\0")).ok());
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+fn into_c_string(text: String) -> Result<*mut c_char> {
+    CString::new(text)
+        .map(CString::into_raw)
+        .map_err(|e| crate::error::CoreError::system_info(e.to_string()))
+}
+
+/// Create a collector with the default collectors and take its baseline measurements.
+/// Returns NULL on failure; see `kacemon_last_error`.
+#[no_mangle]
+pub extern "C" fn kacemon_collector_new() -> *mut KacemonCollector {
+    guarded(|| {
+        let mut inner = MetricsCollector::new()?;
+        inner.init()?;
+        Ok(Box::into_raw(Box::new(KacemonCollector { inner })))
+    })
+}
+
+/// Take a snapshot and return it as a JSON object, in the same shape as the HTTP API's
+/// /snapshot. Returns NULL on failure; free the result with `kacemon_string_free`.
+///
+/// # Safety
+///
+/// `collector` must come from `kacemon_collector_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kacemon_collect_json(collector: *mut KacemonCollector) -> *mut c_char {
+    guarded(|| {
+        // SAFETY: the caller passes a live collector from kacemon_collector_new
+        let collector = unsafe { collector.as_mut() }
+            .ok_or_else(|| crate::error::CoreError::system_info("collector is NULL"))?;
+        let snapshot = collector.inner.collect()?;
+        into_c_string(serde_json::to_string(&snapshot)?)
+    })
+}
+
+/// Free a collector. NULL is ignored.
+///
+/// # Safety
+///
+/// `collector` must come from `kacemon_collector_new` and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kacemon_collector_free(collector: *mut KacemonCollector) {
+    if !collector.is_null() {
+        // SAFETY: ownership returns from the caller, who created it with Box::into_raw
+        drop(unsafe { Box::from_raw(collector) });
+    }
+}
+
+/// Free a string returned by the library. NULL is ignored.
+///
+/// # Safety
+///
+/// `text` must come from this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kacemon_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: the string was handed out by CString::into_raw
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+/// Why the last call on this thread returned NULL, or NULL if it succeeded. The message
+/// belongs to the library and stays valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn kacemon_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
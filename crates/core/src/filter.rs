@@ -0,0 +1,424 @@
+use crate::model::ProcessInfo;
+use regex::{Regex, RegexBuilder};
+use std::str::FromStr;
+
+/// Fields a `field>N` comparison can use
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Cpu,
+    Memory,
+    Rss,
+    Vsz,
+    Threads,
+    Pid,
+}
+
+/// Fields matched as text
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Text {
+    Name,
+    Cmd,
+    User,
+    State,
+    Category,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    /// `>`, `>=`, `<`, `<=`, or `=`/`:` for equality
+    pub(crate) fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            ">" => Some(Self::Greater),
+            ">=" => Some(Self::GreaterOrEqual),
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessOrEqual),
+            ":" | "=" | "==" => Some(Self::Equal),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn holds(self, actual: f64, value: f64) -> bool {
+        match self {
+            Self::Greater => actual > value,
+            Self::GreaterOrEqual => actual >= value,
+            Self::Less => actual < value,
+            Self::LessOrEqual => actual <= value,
+            Self::Equal => actual == value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    /// Bare word: name, command line, user or PID contains it
+    Any(String),
+    /// `field:value`, the whole value; states match by prefix, so `state:run` finds Running
+    Is(Text, String),
+    /// `field~value`, anywhere in the value
+    Contains(Text, String),
+    Compare(Number, Comparison, f64),
+    /// `re:pattern`, searched for in name, command line and user
+    Regex(Regex),
+    /// One word after `fz:`, fuzzy-matched against name or command line
+    Fuzzy(String),
+}
+
+/// Process query such as `user:root cpu>50 state:running name~nginx`. Terms are separated
+/// by spaces and must all match; text is compared case-insensitively and a leading `!`
+/// negates a term. A word without a field matches name, command line, user or PID.
+/// Starting with `re:` makes the rest, spaces and all, a case-insensitive regex instead;
+/// starting with `fz:` fuzzy-matches each following word, so `fz:chrmhlpr` finds Chrome Helper.
+///
+/// ```
+/// let filter: kacemon_core::ProcessFilter = "user:root cpu>=50 !name~kworker".parse().unwrap();
+/// assert!(!filter.is_empty());
+/// assert!("rss>lots".parse::<kacemon_core::ProcessFilter>().is_err());
+/// assert!("re:^(nginx|php-fpm)".parse::<kacemon_core::ProcessFilter>().is_ok());
+/// assert!("fz:chrmhlpr".parse::<kacemon_core::ProcessFilter>().unwrap().is_fuzzy());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProcessFilter {
+    // (negated, term)
+    terms: Vec<(bool, Term)>,
+}
+
+impl ProcessFilter {
+    /// Plain substring match on name, command line, user or PID; how the TUI treats a query
+    /// that doesn't parse yet while it is being typed
+    pub fn substring(text: &str) -> Self {
+        let text = text.trim().to_lowercase();
+        let terms = if text.is_empty() { Vec::new() } else { vec![(false, Term::Any(text))] };
+        Self { terms }
+    }
+
+    /// Read bare words as pkill does, for signalling: all digits is exactly that PID, anything
+    /// else has to be in the process name. `cmd~` still reaches the command line.
+    pub fn pkill_words(mut self) -> Self {
+        for (_, term) in &mut self.terms {
+            if let Term::Any(word) = term {
+                *term = match word.parse::<u32>() {
+                    Ok(pid) if word.bytes().all(|b| b.is_ascii_digit()) => {
+                        Term::Compare(Number::Pid, Comparison::Equal, pid as f64)
+                    },
+                    _ => Term::Contains(Text::Name, std::mem::take(word)),
+                };
+            }
+        }
+        self
+    }
+
+    /// No terms, so every process matches
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        self.terms.iter().all(|(negated, term)| term_matches(term, process) != *negated)
+    }
+
+    /// Whether this is a `fz:` query, whose matches are best ranked by `fuzzy_score`
+    pub fn is_fuzzy(&self) -> bool {
+        self.terms.iter().any(|(_, term)| matches!(term, Term::Fuzzy(_)))
+    }
+
+    /// How well the process fits the fuzzy words, higher is better; 0 without any. A word
+    /// found only in the command line counts half, so `fz:top` puts top before its shell.
+    pub fn fuzzy_score(&self, process: &ProcessInfo) -> i32 {
+        self.fuzzy_words()
+            .filter_map(|word| {
+                fuzzy_match(word, &process.name)
+                    .map(|m| m.score)
+                    .or_else(|| fuzzy_match(word, &process.cmd.join(" ")).map(|m| m.score / 2))
+            })
+            .sum()
+    }
+
+    /// Character positions in `name` the fuzzy words matched, for highlighting
+    pub fn highlights(&self, name: &str) -> Vec<usize> {
+        let mut positions: Vec<usize> =
+            self.fuzzy_words().filter_map(|word| fuzzy_match(word, name)).flat_map(|m| m.positions).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    fn fuzzy_words(&self) -> impl Iterator<Item = &str> {
+        self.terms.iter().filter_map(|(negated, term)| match term {
+            Term::Fuzzy(word) if !negated => Some(word.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// Where a fuzzy pattern lands in a piece of text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character (not byte) positions of the matched characters
+    pub positions: Vec<usize>,
+}
+
+const SCORE_MATCH: i32 = 16;
+/// Matching the first character of the text or of a word
+const BONUS_BOUNDARY: i32 = 8;
+/// Matching an uppercase letter after a lowercase one, or a digit after a letter
+const BONUS_CAMEL: i32 = 7;
+/// Matching straight after the previous pattern character
+const BONUS_CONSECUTIVE: i32 = 4;
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// Case-insensitive fzf-style match: every pattern character must appear in order, and of
+/// all the ways it can, the best scoring one wins. Matches at word starts and in runs
+/// score higher; gaps between matched characters cost a little.
+///
+/// ```
+/// let found = kacemon_core::filter::fuzzy_match("chrmhlpr", "Chrome Helper").unwrap();
+/// assert_eq!(found.positions, [0, 1, 2, 4, 7, 9, 10, 12]);
+/// assert!(kacemon_core::filter::fuzzy_match("hc", "Chrome").is_none());
+/// ```
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let (m, n) = (pattern.len(), chars.len());
+    if m == 0 {
+        return Some(FuzzyMatch { score: 0, positions: Vec::new() });
+    }
+
+    // Cheap check before the table: is the pattern a subsequence at all?
+    let mut rest = lower.iter();
+    if !pattern.iter().all(|p| rest.any(|c| c == p)) {
+        return None;
+    }
+
+    let bonus: Vec<i32> = (0..n)
+        .map(|j| match j.checked_sub(1).map(|k| chars[k]) {
+            None => BONUS_BOUNDARY,
+            Some(prev) if !prev.is_alphanumeric() => BONUS_BOUNDARY,
+            Some(prev) if prev.is_lowercase() && chars[j].is_uppercase() => BONUS_CAMEL,
+            Some(prev) if prev.is_alphabetic() && chars[j].is_numeric() => BONUS_CAMEL,
+            _ => 0,
+        })
+        .collect();
+
+    // score[i * n + j]: best alignment of pattern[..=i] with pattern[i] at text[j];
+    // from[i * n + j]: where pattern[i - 1] sits in it
+    let mut score: Vec<Option<i32>> = vec![None; m * n];
+    let mut from = vec![0; m * n];
+    for i in 0..m {
+        // Best cell of the row above that leaves a gap before j, penalty included
+        let mut gapped: Option<(i32, usize)> = None;
+        for j in i..n {
+            if i > 0 && j >= 2 {
+                let extended = gapped.map(|(s, k)| (s - PENALTY_GAP_EXTENSION, k));
+                let opened = score[(i - 1) * n + j - 2].map(|s| (s - PENALTY_GAP_START, j - 2));
+                gapped = better(extended, opened);
+            }
+            if lower[j] != pattern[i] {
+                continue;
+            }
+
+            let cell = if i == 0 {
+                // The first character's position bonus counts double, as in fzf
+                Some((SCORE_MATCH + bonus[j] * 2, 0))
+            } else {
+                let consecutive = j
+                    .checked_sub(1)
+                    .and_then(|k| score[(i - 1) * n + k])
+                    .map(|s| (s + SCORE_MATCH + bonus[j].max(BONUS_CONSECUTIVE), j - 1));
+                let gap = gapped.map(|(s, k)| (s + SCORE_MATCH + bonus[j], k));
+                better(consecutive, gap)
+            };
+            if let Some((s, k)) = cell {
+                score[i * n + j] = Some(s);
+                from[i * n + j] = k;
+            }
+        }
+    }
+
+    // Highest score, the earliest end on a tie
+    let (mut j, best) = (0..n)
+        .filter_map(|j| score[(m - 1) * n + j].map(|s| (j, s)))
+        .max_by_key(|&(j, s)| (s, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i * n + j];
+    }
+    Some(FuzzyMatch { score: best, positions })
+}
+
+/// The higher scoring of two candidate cells, the first on a tie
+fn better(a: Option<(i32, usize)>, b: Option<(i32, usize)>) -> Option<(i32, usize)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+impl FromStr for ProcessFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(words) = s.trim_start().strip_prefix("fz:") {
+            let terms = words.split_whitespace().map(|word| (false, Term::Fuzzy(word.to_string()))).collect();
+            return Ok(Self { terms });
+        }
+        if let Some(pattern) = s.trim_start().strip_prefix("re:") {
+            let regex = RegexBuilder::new(pattern).case_insensitive(true).build().map_err(|e| {
+                // The full message spans several lines to point at the spot; keep the gist
+                let detail = e.to_string();
+                let reason = detail.lines().last().unwrap_or_default().trim_start_matches("error: ").to_string();
+                format!("Invalid regex: {}", reason)
+            })?;
+            return Ok(Self { terms: vec![(false, Term::Regex(regex))] });
+        }
+        let terms = s.split_whitespace().map(parse_term).collect::<Result<_, _>>()?;
+        Ok(Self { terms })
+    }
+}
+
+fn parse_term(word: &str) -> Result<(bool, Term), String> {
+    let (negated, word) = match word.strip_prefix('!') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, word),
+    };
+
+    // A field name is the leading run of letters, straight before an operator
+    let field_end = word.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(word.len());
+    let (field, rest) = word.split_at(field_end);
+    let operator = ["<=", ">=", ":", "~", ">", "<", "="].into_iter().find(|op| rest.starts_with(op));
+    let (Some(operator), false) = (operator, field.is_empty()) else {
+        return Ok((negated, Term::Any(word.to_lowercase())));
+    };
+    let value = &rest[operator.len()..];
+    if value.is_empty() {
+        return Err(format!("'{}' needs a value after '{}'", word, operator));
+    }
+
+    let term = match (text_field(field), number_field(field)) {
+        (Some(text), _) => match operator {
+            ":" | "=" => Term::Is(text, value.to_lowercase()),
+            "~" => Term::Contains(text, value.to_lowercase()),
+            _ => return Err(format!("'{}' is text and can't be compared with '{}'", field, operator)),
+        },
+        (None, Some(number)) => {
+            let Some(comparison) = Comparison::from_operator(operator) else {
+                return Err(format!("'{}' is a number; use >, >=, <, <= or =", field));
+            };
+            Term::Compare(number, comparison, parse_number(number, value)?)
+        },
+        (None, None) => {
+            return Err(format!(
+                "Unknown field '{}': use name, cmd, user, state, cat, cpu, mem, rss, vsz, threads or pid",
+                field
+            ))
+        },
+    };
+    Ok((negated, term))
+}
+
+fn text_field(name: &str) -> Option<Text> {
+    match name.to_ascii_lowercase().as_str() {
+        "name" => Some(Text::Name),
+        "cmd" => Some(Text::Cmd),
+        "user" => Some(Text::User),
+        "state" => Some(Text::State),
+        "cat" | "category" => Some(Text::Category),
+        _ => None,
+    }
+}
+
+fn number_field(name: &str) -> Option<Number> {
+    match name.to_ascii_lowercase().as_str() {
+        "cpu" => Some(Number::Cpu),
+        "mem" => Some(Number::Memory),
+        "rss" => Some(Number::Rss),
+        "vsz" => Some(Number::Vsz),
+        "threads" | "thr" => Some(Number::Threads),
+        "pid" => Some(Number::Pid),
+        _ => None,
+    }
+}
+
+/// Sizes take a K, M, G or T suffix (powers of 1024); percentages an optional %
+fn parse_number(field: Number, value: &str) -> Result<f64, String> {
+    let lower = value.to_ascii_lowercase();
+    let number = match field {
+        Number::Rss | Number::Vsz => parse_size(&lower),
+        Number::Cpu | Number::Memory => parse_finite(lower.trim_end_matches('%')),
+        Number::Threads | Number::Pid => parse_finite(&lower),
+    };
+    number.ok_or_else(|| format!("'{}' is not a number", value))
+}
+
+/// Bytes in a size like "512", "64k", "1.5G" or "2TB", case-insensitive
+pub(crate) fn parse_size(value: &str) -> Option<f64> {
+    let lower = value.to_ascii_lowercase();
+    let digits = lower.trim_end_matches('b');
+    let (digits, scale) = match digits.chars().last() {
+        Some('k') => (&digits[..digits.len() - 1], 1024.0),
+        Some('m') => (&digits[..digits.len() - 1], 1024.0 * 1024.0),
+        Some('g') => (&digits[..digits.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        Some('t') => (&digits[..digits.len() - 1], 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        _ => (digits, 1.0),
+    };
+    parse_finite(digits).map(|n| n * scale)
+}
+
+pub(crate) fn parse_finite(digits: &str) -> Option<f64> {
+    digits.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn term_matches(term: &Term, process: &ProcessInfo) -> bool {
+    match term {
+        Term::Any(word) => {
+            process.name.to_lowercase().contains(word)
+                || process.cmd.join(" ").to_lowercase().contains(word)
+                || process.user.to_lowercase().contains(word)
+                || process.pid.to_string().contains(word)
+        },
+        Term::Is(Text::State, value) => text(process, Text::State).starts_with(value),
+        Term::Is(field, value) => text(process, *field) == *value,
+        Term::Contains(field, value) => text(process, *field).contains(value),
+        Term::Regex(regex) => {
+            regex.is_match(&process.name) || regex.is_match(&process.cmd.join(" ")) || regex.is_match(&process.user)
+        },
+        Term::Fuzzy(word) => {
+            fuzzy_match(word, &process.name).is_some() || fuzzy_match(word, &process.cmd.join(" ")).is_some()
+        },
+        Term::Compare(field, comparison, value) => {
+            comparison.holds(number(process, *field), *value)
+        },
+    }
+}
+
+fn text(process: &ProcessInfo, field: Text) -> String {
+    match field {
+        Text::Name => process.name.to_lowercase(),
+        Text::Cmd => process.cmd.join(" ").to_lowercase(),
+        Text::User => process.user.to_lowercase(),
+        Text::State => format!("{:?}", process.state).to_lowercase(),
+        Text::Category => process.category.as_deref().unwrap_or_default().to_lowercase(),
+    }
+}
+
+fn number(process: &ProcessInfo, field: Number) -> f64 {
+    match field {
+        Number::Cpu => process.cpu_percent as f64,
+        Number::Memory => process.memory_percent as f64,
+        Number::Rss => process.memory_rss as f64,
+        Number::Vsz => process.memory_vsz as f64,
+        Number::Threads => process.threads as f64,
+        Number::Pid => process.pid as f64,
+    }
+}
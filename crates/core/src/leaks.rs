@@ -0,0 +1,121 @@
+use crate::model::SystemSnapshot;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime},
+};
+
+/// RSS is sampled this often, whatever the refresh rate
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Samples kept per process, i.e. the longest stretch a trend is fitted over
+const WINDOW_SAMPLES: usize = 30;
+
+/// A trend needs this many samples before it counts
+const MIN_SAMPLES: usize = 10;
+
+/// Slower growth than this (bytes per minute, like the RSS it is fitted to) is left alone,
+/// however steady
+const MIN_GROWTH_PER_MIN: f64 = 1024.0 * 1024.0;
+
+/// Share of sample-to-sample steps that may not shrink; caches fill and drain, leaks only fill
+const MIN_RISING_STEPS: f64 = 0.9;
+
+/// A process whose RSS has grown steadily for as long as it has been watched
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LeakSuspect {
+    pub pid: u32,
+    /// Fitted growth in bytes per minute
+    pub growth_per_min: f64,
+    /// Time between the first and last sample the fit covers
+    pub span: Duration,
+}
+
+/// RSS samples of one process; the start time tells a reused PID apart
+#[derive(Debug)]
+struct RssHistory {
+    start_time: SystemTime,
+    samples: VecDeque<(SystemTime, u64)>,
+}
+
+/// Watches per-process RSS over the last half hour and flags steady growth
+#[derive(Debug, Default)]
+pub struct LeakDetector {
+    histories: HashMap<u32, RssHistory>,
+    sampled_at: Option<SystemTime>,
+    suspects: HashMap<u32, LeakSuspect>,
+}
+
+impl LeakDetector {
+    /// Sample the snapshot's processes if a minute has passed since the last sample
+    pub fn record(&mut self, snapshot: &SystemSnapshot) {
+        let due = self.sampled_at.map_or(true, |at| {
+            snapshot.timestamp.duration_since(at).map_or(true, |elapsed| elapsed >= SAMPLE_INTERVAL)
+        });
+        if !due || snapshot.processes.is_empty() {
+            return;
+        }
+        self.sampled_at = Some(snapshot.timestamp);
+
+        let mut histories = HashMap::with_capacity(snapshot.processes.len());
+        for process in &snapshot.processes {
+            let mut history = self
+                .histories
+                .remove(&process.pid)
+                .filter(|history| history.start_time == process.start_time)
+                .unwrap_or_else(|| RssHistory { start_time: process.start_time, samples: VecDeque::new() });
+            if history.samples.len() == WINDOW_SAMPLES {
+                history.samples.pop_front();
+            }
+            history.samples.push_back((snapshot.timestamp, process.memory_rss));
+            histories.insert(process.pid, history);
+        }
+        // Processes that exited go with the old map
+        self.histories = histories;
+
+        self.suspects = self
+            .histories
+            .iter()
+            .filter_map(|(&pid, history)| suspect(pid, &history.samples))
+            .map(|suspect| (suspect.pid, suspect))
+            .collect();
+    }
+
+    pub fn suspect(&self, pid: u32) -> Option<&LeakSuspect> {
+        self.suspects.get(&pid)
+    }
+
+    pub fn suspects(&self) -> impl Iterator<Item = &LeakSuspect> {
+        self.suspects.values()
+    }
+}
+
+/// Least-squares slope of RSS over time, if it is steep and steady enough to look like a leak
+fn suspect(pid: u32, samples: &VecDeque<(SystemTime, u64)>) -> Option<LeakSuspect> {
+    if samples.len() < MIN_SAMPLES {
+        return None;
+    }
+    let first = samples.front()?.0;
+    let span = samples.back()?.0.duration_since(first).ok()?;
+
+    let rising = samples.iter().zip(samples.iter().skip(1)).filter(|(a, b)| b.1 >= a.1).count();
+    if (rising as f64) < (samples.len() - 1) as f64 * MIN_RISING_STEPS {
+        return None;
+    }
+
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|&(at, rss)| (at.duration_since(first).unwrap_or_default().as_secs_f64() / 60.0, rss as f64))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if variance <= 0.0 {
+        return None;
+    }
+    let growth_per_min = covariance / variance;
+
+    (growth_per_min >= MIN_GROWTH_PER_MIN).then_some(LeakSuspect { pid, growth_per_min, span })
+}
@@ -1,51 +1,74 @@
+//! Collection engine behind kacemon: CPU, memory, disks, network, sensors, GPUs and
+//! processes gathered into one [`SystemSnapshot`] per call.
+//!
+//! ```no_run
+//! use kacemon_core::MetricsCollector;
+//!
+//! let mut collector = MetricsCollector::builder().connections(false).build()?;
+//! collector.init()?;
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! let snapshot = collector.collect()?;
+//! for process in snapshot.processes.iter().filter(|p| p.cpu_percent > 50.0) {
+//!     println!("{} ({}) {:.0}%", process.name, process.pid, process.cpu_percent);
+//! }
+//! # Ok::<(), kacemon_core::CoreError>(())
+//! ```
+//!
+//! Rates and CPU percentages are deltas since the previous collection, so the first
+//! snapshot after `init` covers the time in between.
+//!
+//! # Stability
+//!
+//! The items re-exported at the crate root (the collector, its builder, the snapshot model,
+//! `Config`, alerts and errors) follow semver from 0.3 on: within a minor series they only
+//! gain methods, though model structs may gain fields. The submodules are public for the
+//! kacemon binaries and may change in any release.
+
+pub mod alerts;
+pub mod classify;
 pub mod config;
+pub mod daemon;
 pub mod error;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+pub mod leaks;
 pub mod metrics;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod model;
 pub mod platform;
+pub mod remote;
+pub mod report;
+pub mod score;
+pub mod serve;
+pub mod telegraf;
+pub mod watch;
 
+pub use alerts::{Alert, AlertActions, AlertDispatcher, AlertKind, AlertThresholds, AlertTracker, Spawner};
+pub use classify::{CategoryRule, CategoryUsage, Classifier};
 pub use config::Config;
 pub use error::{CoreError, Result};
-pub use metrics::MetricsCollector;
+pub use filter::ProcessFilter;
+pub use leaks::{LeakDetector, LeakSuspect};
+pub use metrics::{BackgroundCollector, CollectorBuilder, MetricsCollector};
 pub use model::*;
 pub use platform::PlatformProvider;
+pub use remote::RemoteHost;
+pub use report::OutputFormat;
+pub use score::PressureScore;
+pub use telegraf::TelegrafFormat;
+pub use watch::WatchCondition;
 
 #[cfg(test)]
 mod tests {
     use super::*;
     
-    #[test]
-    fn test_config_default() {
-        let config = Config::default();
-        assert_eq!(config.refresh_ms, 2000);
-        assert_eq!(config.theme, Theme::Dark);
-        assert!(!config.no_color);
-    }
-    
-    #[test]
-    fn test_sort_key_cycle() {
-        let mut sort = SortKey::Cpu;
-        sort = sort.next();
-        assert_eq!(sort, SortKey::Memory);
-        sort = sort.next();
-        assert_eq!(sort, SortKey::Pid);
-        sort = sort.next();
-        assert_eq!(sort, SortKey::Name);
-        sort = sort.next();
-        assert_eq!(sort, SortKey::Cpu);
-    }
-    
-    #[test]
-    fn test_metrics_collector_creation() {
-        let result = MetricsCollector::new();
-        assert!(result.is_ok());
-    }
-    
-    #[test] 
-    fn test_system_snapshot_serialization() {
+    fn test_snapshot() -> SystemSnapshot {
         use std::time::SystemTime;
         
-        let snapshot = SystemSnapshot {
+        SystemSnapshot {
             timestamp: SystemTime::now(),
             system: SystemInfo {
                 hostname: "test".to_string(),
@@ -56,6 +79,7 @@ mod tests {
                 load_avg_1: 0.5,
                 load_avg_5: 0.4,
                 load_avg_15: 0.3,
+                processes_created: None,
             },
             cpu_cores: vec![],
             memory: MemoryInfo {
@@ -68,17 +92,1746 @@ mod tests {
                 swap_total: 2_000_000_000,
                 swap_used: 0,
                 swap_free: 2_000_000_000,
+                swap_in_pages: 0,
+                swap_out_pages: 0,
             },
             disks: vec![],
             networks: vec![],
             temperatures: vec![],
+            gpus: vec![],
+            connections: vec![],
+            pressure: PressureInfo::default(),
+            run_queue: None,
+            cgroups: vec![],
+            services: vec![],
+            log_entries: vec![],
             processes: vec![],
+            collector_failures: vec![],
+        }
+    }
+    
+    fn test_process(pid: u32, name: &str, cmd: &str, cpu: f32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cmd: cmd.split(' ').map(str::to_string).collect(),
+            user: "test".to_string(),
+            cpu_percent: cpu,
+            memory_percent: 1.0,
+            memory_rss: 1024,
+            memory_vsz: 2048,
+            threads: 1,
+            state: ProcessState::Running,
+            start_time: std::time::SystemTime::now(),
+            parent_pid: None,
+            pgid: None,
+            session_id: None,
+            tty: None,
+            cgroup: None,
+            container_id: None,
+            pod: None,
+            gpu_usage: Vec::new(),
+            category: None,
+            children_cpu_time: std::time::Duration::ZERO,
+            cpu_time_total: std::time::Duration::ZERO,
+            children_cpu_percent: 0.0,
+            wchan: None,
+            open_fds: None,
+            memory_pss: None,
+            memory_uss: None,
+            busy_since: None,
+            restarts: 0,
+            net_rate: None,
+            is_kernel_thread: false,
+            partial: false,
+        }
+    }
+    
+    #[test]
+    fn test_config_default() {
+        let config = Config::default();
+        assert_eq!(config.refresh_ms, 2000);
+        assert_eq!(config.theme, Theme::Dark);
+        assert!(!config.no_color);
+    }
+    
+    #[test]
+    fn test_refresh_presets() {
+        let mut config = Config { refresh_ms: 2000, ..Config::default() };
+        config.cycle_refresh_preset();
+        assert_eq!(config.refresh_ms, 5000);
+        config.cycle_refresh_preset();
+        assert_eq!(config.refresh_ms, 250);
+        
+        config.step_refresh_preset(true);
+        assert_eq!(config.refresh_ms, 250);
+        config.step_refresh_preset(false);
+        assert_eq!(config.refresh_ms, 500);
+        
+        // Off-preset values snap to the neighbouring preset
+        config.refresh_ms = 1500;
+        config.step_refresh_preset(true);
+        assert_eq!(config.refresh_ms, 1000);
+    }
+    
+    #[test]
+    fn test_custom_theme_config() {
+        let config: Config = serde_json::from_str(r##"{
+            "theme": "solar",
+            "themes": { "solar": { "base": "light", "accent": "#268bd2", "muted": "dark-grey", "border": "245" } }
+        }"##).unwrap();
+        assert_eq!(config.theme, Theme::Custom("solar".to_string()));
+        
+        let palette = &config.themes["solar"];
+        assert_eq!(palette.base, Theme::Light);
+        assert_eq!(palette.accent, Some(PaletteColor::Rgb(0x26, 0x8b, 0xd2)));
+        assert_eq!(palette.muted, Some(PaletteColor::Indexed(8)));
+        assert_eq!(palette.border, Some(PaletteColor::Indexed(245)));
+        assert_eq!(PaletteColor::try_from("#fff".to_string()), Ok(PaletteColor::Rgb(255, 255, 255)));
+        assert!(PaletteColor::try_from("#12345".to_string()).is_err());
+        assert!(PaletteColor::try_from("chartreuse".to_string()).is_err());
+        
+        // Built-in names keep their old spelling on disk
+        assert_eq!(serde_json::to_string(&Theme::Dark).unwrap(), "\"Dark\"");
+    }
+    
+    #[test]
+    fn test_kill_signal_parse() {
+        assert_eq!("TERM".parse::<KillSignal>(), Ok(KillSignal::Term));
+        assert_eq!("sigkill".parse::<KillSignal>(), Ok(KillSignal::Kill));
+        assert_eq!("SIGUSR1".parse::<KillSignal>(), Ok(KillSignal::Usr1));
+        assert_eq!("9".parse::<KillSignal>(), Ok(KillSignal::Kill));
+        assert!("SIGFOO".parse::<KillSignal>().is_err());
+        assert!("19".parse::<KillSignal>().is_err());
+    }
+    
+    #[test]
+    fn test_export_timestamp_slug() {
+        use std::time::{Duration, UNIX_EPOCH};
+        
+        assert_eq!(export::timestamp_slug(UNIX_EPOCH), "19700101-000000");
+        // 2024-02-29 13:45:07 UTC
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_214_307);
+        assert_eq!(export::timestamp_slug(leap_day), "20240229-134507");
+        assert_eq!(export::short_timestamp(leap_day, leap_day + Duration::from_secs(3600)), "13:45:07");
+        assert_eq!(export::short_timestamp(leap_day, leap_day + Duration::from_secs(86_400)), "2024-02-29");
+    }
+    
+    #[test]
+    fn test_sort_key_cycle() {
+        let mut sort = SortKey::Cpu;
+        sort = sort.next();
+        assert_eq!(sort, SortKey::Memory);
+        sort = sort.next();
+        assert_eq!(sort, SortKey::Pid);
+        sort = sort.next();
+        assert_eq!(sort, SortKey::Name);
+        sort = sort.next();
+        assert_eq!(sort, SortKey::Cpu);
+    }
+    
+    #[test]
+    fn test_sort_key_columns() {
+        for key in [SortKey::Rss, SortKey::Vsz, SortKey::Threads, SortKey::State, SortKey::StartTime, SortKey::User, SortKey::Busy, SortKey::Restarts, SortKey::Net, SortKey::Fds, SortKey::Pss, SortKey::Uss] {
+            assert_eq!(SortKey::from_column(key.column()), Some(key));
+        }
+        assert_eq!(SortKey::from_column("BOGUS"), None);
+        assert!(SortKey::Rss.default_descending());
+        assert!(!SortKey::User.default_descending());
+        
+        // Busy for longer sorts higher, idle lowest
+        let now = std::time::SystemTime::now();
+        let idle = test_process(1, "idle", "idle", 0.0);
+        let spike = ProcessInfo { busy_since: Some(now), ..test_process(2, "spike", "spike", 99.0) };
+        let stuck = ProcessInfo { busy_since: Some(now - std::time::Duration::from_secs(840)), ..test_process(3, "stuck", "stuck", 98.0) };
+        assert_eq!(SortKey::Busy.compare(&idle, &spike), std::cmp::Ordering::Less);
+        assert_eq!(SortKey::Busy.compare(&spike, &stuck), std::cmp::Ordering::Less);
+        
+        // Processes whose descriptors couldn't be counted sort below any count
+        let leaky = ProcessInfo { open_fds: Some(4096), ..test_process(4, "leaky", "leaky", 1.0) };
+        let quiet = ProcessInfo { open_fds: Some(3), ..test_process(5, "quiet", "quiet", 1.0) };
+        assert!(SortKey::Fds.default_descending());
+        assert_eq!(SortKey::Fds.compare(&idle, &quiet), std::cmp::Ordering::Less);
+        assert_eq!(SortKey::Fds.compare(&quiet, &leaky), std::cmp::Ordering::Less);
+    }
+    
+    #[test]
+    fn test_natural_name_sort() {
+        let mut names = vec!["chrome-10", "Xorg", "chrome", "chrome-2", "Chrome", "bash", "worker-007", "worker-7"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["bash", "Chrome", "chrome", "chrome-2", "chrome-10", "worker-007", "worker-7", "Xorg"]);
+        
+        let process = |name: &str| test_process(1, name, name, 0.0);
+        let (upper, lower) = (process("Zed"), process("apt"));
+        assert_eq!(SortKey::Name.compare(&upper, &lower), std::cmp::Ordering::Greater);
+        assert_eq!(SortKey::Name.compare_with(&upper, &lower, NameCollation::Bytes), std::cmp::Ordering::Less);
+        
+        let config: Config = serde_json::from_str(r#"{"name_collation": "bytes"}"#).unwrap();
+        assert_eq!(config.name_collation, NameCollation::Bytes);
+    }
+    
+    #[test]
+    fn test_metrics_collector_creation() {
+        let result = MetricsCollector::new();
+        assert!(result.is_ok());
+    }
+    
+    #[test]
+    fn test_connections_collector_disabled_by_default() {
+        let mut collector = metrics::ConnectionsCollector::new().unwrap();
+        assert!(!collector.is_enabled());
+        assert!(collector.collect().unwrap().is_empty());
+
+        collector.set_enabled(true);
+        assert!(collector.collect().is_ok());
+    }
+    
+    #[test]
+    fn test_cpu_average_and_memory_percent() {
+        let core = |id: usize, usage_percent: f32| CpuCore {
+            id,
+            name: format!("cpu{}", id),
+            usage_percent,
+            frequency: 0,
+            base_frequency: None,
+            max_frequency: None,
+        };
+        
+        let mut snapshot = test_snapshot();
+        assert_eq!(snapshot.cpu_average(), 0.0, "no cores");
+        snapshot.cpu_cores = vec![core(0, 20.0), core(1, 60.0)];
+        assert_eq!(snapshot.cpu_average(), 40.0);
+        
+        assert_eq!(snapshot.memory_percent(), 50.0);
+        assert_eq!(snapshot.memory.swap_percent(), 0.0);
+        snapshot.memory.total = 0;
+        snapshot.memory.swap_total = 0;
+        assert_eq!((snapshot.memory_percent(), snapshot.memory.swap_percent()), (0.0, 0.0));
+    }
+    
+    #[test]
+    fn test_gpu_aggregate() {
+        let gpu = |index: u32, utilization_percent: f32, temperature: Option<f32>| GpuInfo {
+            index,
+            uuid: format!("GPU-{}", index),
+            name: "test-gpu".to_string(),
+            utilization_percent,
+            memory_used: 1_000,
+            memory_total: 4_000,
+            temperature,
         };
         
+        let aggregate = GpuAggregate::from_gpus(&[gpu(0, 20.0, Some(50.0)), gpu(1, 60.0, Some(70.0)), gpu(2, 40.0, None)]);
+        assert_eq!(aggregate.count, 3);
+        assert_eq!(aggregate.utilization_percent, 40.0);
+        assert_eq!(aggregate.memory_used, 3_000);
+        assert_eq!(aggregate.memory_total, 12_000);
+        assert_eq!(aggregate.max_temperature, Some(70.0));
+        
+        assert_eq!(GpuAggregate::from_gpus(&[]).count, 0);
+        
+        // gpu_export picks combined or per-device figures in every exporter
+        let mut snapshot = test_snapshot();
+        snapshot.gpus = vec![gpu(0, 20.0, Some(50.0)), gpu(1, 60.0, Some(70.0))];
+        let gpu_cells = |mode| export::csv_row(&snapshot, 1.0, false, mode).split(',').skip(11).collect::<Vec<_>>().join(",");
+        assert_eq!(gpu_cells(GpuExportMode::Aggregate), "40.0,2000,70.0");
+        assert_eq!(gpu_cells(GpuExportMode::PerDevice), "20.0;60.0,1000;1000,50.0;70.0");
+        
+        let gpu_lines = |mode| {
+            telegraf::telegraf_metrics(&snapshot, TelegrafFormat::Influx, mode)
+                .lines()
+                .filter(|line| line.starts_with("kacemon_gpu,"))
+                .map(|line| line.split(' ').next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(gpu_lines(GpuExportMode::Aggregate), ["kacemon_gpu,host=test,gpu=all"]);
+        assert_eq!(
+            gpu_lines(GpuExportMode::PerDevice),
+            ["kacemon_gpu,host=test,gpu=0,model=test-gpu", "kacemon_gpu,host=test,gpu=1,model=test-gpu"]
+        );
+        
+        let gpus = |gpu_export| {
+            let published = serve::Published { snapshot: snapshot.clone(), alerts: vec![], gpu_export };
+            serde_json::from_str::<serde_json::Value>(&serve::respond("/gpus", Some(&published)).1).unwrap()
+        };
+        assert_eq!(gpus(GpuExportMode::Aggregate)["count"], 2);
+        assert_eq!(gpus(GpuExportMode::PerDevice).as_array().unwrap().len(), 2);
+        assert_eq!(serde_json::from_str::<GpuExportMode>("\"per_device\"").unwrap(), GpuExportMode::PerDevice);
+    }
+    
+    #[test]
+    fn test_container_id_from_cgroup() {
+        use platform::container_id_from_cgroup;
+        
+        let id = "4f1c2b3a5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708";
+        assert_eq!(container_id_from_cgroup(&format!("/docker/{}", id)).as_deref(), Some("4f1c2b3a5d6e"));
+        assert_eq!(container_id_from_cgroup(&format!("/system.slice/docker-{}.scope", id)).as_deref(), Some("4f1c2b3a5d6e"));
+        assert_eq!(
+            container_id_from_cgroup(&format!("/kubepods.slice/kubepods-burstable.slice/cri-containerd-{}.scope", id)).as_deref(),
+            Some("4f1c2b3a5d6e")
+        );
+        assert_eq!(container_id_from_cgroup("/kubepods.slice/kubepods-burstable.slice"), None);
+        assert_eq!(container_id_from_cgroup("/user.slice/user-1000.slice/session-2.scope"), None);
+        
+        let v1 = format!("12:pids:/user.slice\n4:memory:/docker/{}\n0::/\n", id);
+        assert_eq!(platform::pick_cgroup(&v1), Some(format!("/docker/{}", id)));
+        assert_eq!(platform::pick_cgroup("0::/user.slice/user-1000.slice\n").as_deref(), Some("/user.slice/user-1000.slice"));
+    }
+    
+    #[test] 
+    fn test_system_snapshot_serialization() {
+        let snapshot = test_snapshot();
+        
         let json = serde_json::to_string(&snapshot);
         assert!(json.is_ok());
         
         let deserialized: std::result::Result<SystemSnapshot, _> = serde_json::from_str(&json.unwrap());
         assert!(deserialized.is_ok());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_collector_failures() {
+        let mut snapshot = test_snapshot();
+        assert!(serde_json::to_value(&snapshot).unwrap().get("collector_failures").is_none());
+        
+        snapshot.collector_failures.push(CollectorFailure {
+            collector: "temperatures".to_string(),
+            error: "sensor went away".to_string(),
+            last_success: Some(snapshot.timestamp),
+        });
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: SystemSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.collector_failure("temperatures").unwrap().error, "sensor went away");
+        assert!(restored.collector_failure("disks").is_none());
+        
+        // Working collectors report nothing, before and after a forced slow pass
+        let mut collector = metrics::MetricsCollector::builder().gpus(false).build().unwrap();
+        collector.init().unwrap();
+        assert!(collector.collect().unwrap().collector_failures.is_empty());
+        collector.expire_slow_metrics();
+        let snapshot = collector.collect().unwrap();
+        assert!(snapshot.collector_failures.is_empty());
+        assert!(!snapshot.processes.is_empty());
+    }
+    
+    #[test]
+    fn test_pressure_score() {
+        let psi = "some avg10=12.50 avg60=3.00 avg300=1.00 total=100\nfull avg10=4.00 avg60=1.00 avg300=0.50 total=50\n";
+        let stall = metrics::pressure::parse_pressure(psi).unwrap();
+        assert_eq!(stall.some_avg10, 12.5);
+        assert_eq!(stall.full_avg10, Some(4.0));
+        
+        let mut snapshot = test_snapshot();
+        snapshot.cpu_cores = vec![CpuCore { id: 0, name: "cpu0".to_string(), usage_percent: 80.0, frequency: 0, base_frequency: None, max_frequency: None }];
+        
+        // Half the memory available, no PSI or disk busy time: mean of cpu 80, memory 50, swap 0
+        let weights = PressureWeights { cpu: 1.0, memory: 1.0, psi: 1.0, swap: 1.0, disk: 1.0 };
+        let score = PressureScore::compute(&snapshot, &weights, 1.0);
+        assert!((score.score - 130.0 / 3.0).abs() < 0.01);
+        assert_eq!(score.psi, None);
+        
+        snapshot.pressure.io = Some(stall);
+        snapshot.memory.swap_out_pages = 2048;
+        let score = PressureScore::compute(&snapshot, &weights, 1.0);
+        assert!((score.score - (80.0 + 50.0 + 12.5 + 100.0) / 4.0).abs() < 0.01);
+    }
+    
+    #[test]
+    fn test_alert_thresholds() {
+        let mut snapshot = test_snapshot();
+        snapshot.cpu_cores = vec![CpuCore { id: 0, name: "cpu0".to_string(), usage_percent: 95.0, frequency: 0, base_frequency: None, max_frequency: None }];
+        snapshot.temperatures = vec![TemperatureInfo { label: "Package".to_string(), temperature: 70.0, critical: None, max: None }];
+        
+        // CPU over 90, memory at 50 under 85, temperature under 80
+        let thresholds = AlertThresholds::default();
+        let alerts = thresholds.evaluate(&snapshot);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::Cpu);
+        assert!(alerts[0].same_condition(&thresholds.evaluate(&snapshot)[0]));
+        
+        let disabled = AlertThresholds { cpu_percent: None, ..AlertThresholds::default() };
+        assert!(disabled.evaluate(&snapshot).is_empty());
+        
+        // Past the sensor's own trip point counts as critical
+        snapshot.temperatures[0] = TemperatureInfo { label: "Package".to_string(), temperature: 101.0, critical: Some(100.0), max: None };
+        let alerts = thresholds.evaluate(&snapshot);
+        assert!(alerts.iter().any(|a| a.kind == AlertKind::Temperature && a.critical));
+        assert!(!alerts.iter().any(|a| a.kind == AlertKind::Zombies));
+        
+        let invalid = AlertThresholds { memory_percent: Some(120.0), ..AlertThresholds::default() };
+        assert!(invalid.validate().is_err());
+    }
+    
+    #[test]
+    fn test_boot_timing_parse() {
+        use metrics::boot::{parse_blame, parse_manager_timestamps};
+        use std::time::Duration;
+        
+        // firmware, loader, initrd, userspace, finish in microseconds
+        let timing = parse_manager_timestamps("t 5000000\nt 2000000\nt 1500000\nt 4000000\nt 10000000\n").unwrap();
+        assert_eq!(timing.firmware, Some(Duration::from_secs(3)));
+        assert_eq!(timing.loader, Some(Duration::from_secs(2)));
+        assert_eq!(timing.kernel, Duration::from_millis(1500));
+        assert_eq!(timing.initrd, Some(Duration::from_millis(2500)));
+        assert_eq!(timing.userspace, Some(Duration::from_secs(6)));
+        assert_eq!(timing.total(), Duration::from_secs(15));
+        
+        // No initrd and boot not finished yet
+        let timing = parse_manager_timestamps("t 0\nt 0\nt 0\nt 900000\nt 0\n").unwrap();
+        assert_eq!(timing.kernel, Duration::from_millis(900));
+        assert_eq!(timing.userspace, None);
+        assert!(parse_manager_timestamps("").is_none());
+        
+        let units = parse_blame("1min 2.500s network-online.service\n  512ms systemd-udevd.service\n40us tmp.mount\ngarbage\n");
+        assert_eq!(units.len(), 3);
+        assert_eq!(units[0].name, "network-online.service");
+        assert_eq!(units[0].duration, Duration::from_millis(62_500));
+        assert_eq!(units[1].duration, Duration::from_millis(512));
+        assert_eq!(units[2].duration, Duration::from_micros(40));
+    }
+    
+    #[test]
+    fn test_smart_report_parse() {
+        use metrics::scan::{parse_smart_report, smart_device};
+        
+        assert_eq!(smart_device("/dev/nvme0n1p2").as_deref(), Some("/dev/nvme0"));
+        assert_eq!(smart_device("overlay"), None);
+        
+        let json = serde_json::json!({
+            "model_name": "Samsung SSD 980",
+            "smart_status": { "passed": true },
+            "temperature": { "current": 41 },
+            "power_on_time": { "hours": 8123 }
+        });
+        let report = parse_smart_report("/dev/nvme0", &json);
+        assert_eq!(report.model.as_deref(), Some("Samsung SSD 980"));
+        assert_eq!((report.passed, report.temperature, report.power_on_hours), (Some(true), Some(41.0), Some(8123)));
+        assert_eq!(report.error, None);
+        
+        // A drive smartctl can't open carries its explanation instead of a verdict
+        let json = serde_json::json!({ "smartctl": { "messages": [{ "string": "Permission denied" }] } });
+        let report = parse_smart_report("/dev/sda", &json);
+        assert_eq!((report.passed, report.error.as_deref()), (None, Some("Permission denied")));
+    }
+    
+    #[test]
+    fn test_alert_debounce_and_cooldown() {
+        use std::time::{Duration, Instant};
+        
+        let actions = AlertActions { command: Some("true".to_string()), debounce_secs: 10, cooldown_secs: 60, ..AlertActions::default() };
+        let mut dispatcher = AlertDispatcher::new(actions);
+        let alerts = vec![Alert { kind: AlertKind::Cpu, subject: "CPU".to_string(), pid: None, value: 95.0, threshold: 90.0, critical: false }];
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        
+        // Has to persist through the debounce window first
+        assert!(dispatcher.dispatch_at(at(0), "test", &alerts).is_empty());
+        assert_eq!(dispatcher.dispatch_at(at(10), "test", &alerts).len(), 1);
+        
+        // Then stays quiet for the cooldown, even if it clears and comes back
+        assert!(dispatcher.dispatch_at(at(20), "test", &[]).is_empty());
+        assert!(dispatcher.dispatch_at(at(30), "test", &alerts).is_empty());
+        assert!(dispatcher.dispatch_at(at(69), "test", &alerts).is_empty());
+        assert_eq!(dispatcher.dispatch_at(at(71), "test", &alerts).len(), 1);
+        
+        // Nothing configured, nothing fires
+        let mut idle = AlertDispatcher::new(AlertActions { debounce_secs: 0, ..AlertActions::default() });
+        assert!(idle.dispatch_at(at(0), "test", &alerts).is_empty());
+    }
+    
+    #[test]
+    fn test_run_queue_latency() {
+        use metrics::schedstat::{parse_schedstat, run_queue_latency};
+        
+        assert_eq!(parse_schedstat("123456 7890 12\n"), Some((7890, 12)));
+        assert_eq!(parse_schedstat("garbage"), None);
+        
+        // 98 timeslices at 10us, 2 at 5ms: the median is quiet, the tail is not
+        let latency = run_queue_latency(&[(980_000, 98), (10_000_000, 2)]).unwrap();
+        assert_eq!(latency.p50_us, 10.0);
+        assert_eq!(latency.p99_us, 5_000.0);
+        assert!((latency.mean_us - 109.8).abs() < 0.01);
+        assert!(run_queue_latency(&[]).is_none());
+    }
+
+    #[test]
+    fn test_status_report_formats() {
+        use report::{process_list, status_report};
+        
+        let mut snapshot = test_snapshot();
+        snapshot.processes = vec![test_process(7, "make", "make all, then test", 12.34)];
+        let alerts = vec![Alert { kind: AlertKind::Memory, subject: "Memory".to_string(), pid: None, value: 95.0, threshold: 90.0, critical: true }];
+        
+        let csv = status_report(&snapshot, &alerts, OutputFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("timestamp,hostname,healthy,cpu_percent,"));
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        assert!(lines[1].ends_with(",test,false,0.0,50.0,0.0,0.50,0.40,0.30,1,,1,Memory at 95% (limit 90%)"));
+        
+        // Keys keep their declared order, so the output diffs cleanly between runs
+        let json = status_report(&snapshot, &alerts, OutputFormat::Json);
+        let position = |key: &str| json.find(&format!("\"{}\"", key)).unwrap();
+        assert!(position("timestamp") < position("healthy") && position("healthy") < position("alerts"));
+        assert!(json.contains("\"kind\": \"memory\""));
+        assert!(status_report(&snapshot, &[], OutputFormat::Table).contains("STATUS      OK"));
+        
+        // Commas in a command line get the cell quoted
+        let processes: Vec<&ProcessInfo> = snapshot.processes.iter().collect();
+        let csv = process_list(&processes, OutputFormat::Csv);
+        assert_eq!(csv.lines().nth(1), Some("7,test,make,12.3,1024,\"make all, then test\""));
+        assert_eq!("JSON".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+    
+    #[test]
+    fn test_telegraf_output() {
+        use telegraf::telegraf_metrics;
+        
+        let mut snapshot = test_snapshot();
+        snapshot.timestamp = std::time::UNIX_EPOCH + std::time::Duration::from_millis(1_700_000_000_123);
+        snapshot.system.hostname = "web 1".to_string();
+        snapshot.disks = vec![DiskInfo {
+            name: "/dev/sda1".to_string(),
+            mount_point: "/data,backup".to_string(),
+            file_system: String::new(),
+            total_space: 1000,
+            used_space: 250,
+            available_space: 750,
+            read_bytes: 4096,
+            write_bytes: 0,
+            read_bytes_delta: 0,
+            write_bytes_delta: 0,
+            latency_ms: None,
+            busy_percent: None,
+            nvme_health: None,
+        }];
+        
+        // Spaces and commas in tags escaped, empty tags left off, integers marked with i
+        let influx = telegraf_metrics(&snapshot, TelegrafFormat::Influx, GpuExportMode::default());
+        let disk = influx.lines().find(|line| line.starts_with("kacemon_disk")).unwrap();
+        assert_eq!(
+            disk,
+            "kacemon_disk,host=web\\ 1,device=/dev/sda1,mount=/data\\,backup \
+             total=1000i,used=250i,free=750i,used_percent=25,read_bytes=4096i,write_bytes=0i 1700000000123000000"
+        );
+        assert!(influx.lines().any(|line| line.starts_with("kacemon_mem,host=web\\ 1 total=8000000000i,")));
+        
+        // JSON is one line, flat objects with the measurement and time in milliseconds
+        let json = telegraf_metrics(&snapshot, TelegrafFormat::Json, GpuExportMode::default());
+        assert_eq!(json.lines().count(), 1);
+        let points: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let disk = points.as_array().unwrap().iter().find(|point| point["measurement"] == "kacemon_disk").unwrap();
+        assert_eq!(disk["mount"], "/data,backup");
+        assert_eq!(disk["time"], 1_700_000_000_123u64);
+        assert_eq!(disk["used_percent"], 25.0);
+        assert!("prometheus".parse::<TelegrafFormat>().is_err());
+    }
+    
+    #[test]
+    fn test_watch_condition() {
+        let mut snapshot = test_snapshot();
+        snapshot.processes = vec![test_process(42, "make", "make -j8", 90.0)];
+        let holds = |text: &str| text.parse::<WatchCondition>().unwrap().matches(&snapshot);
+        
+        // 4GB of 8GB available: sizes are powers of 1024, percentages of the total
+        assert!(holds("memory available < 4GB"));
+        assert!(!holds("memory available < 3GB"));
+        assert!(holds("memory used >= 50%"));
+        assert!(holds("swap used = 0 and load < 1"));
+        assert!(holds("load5 > 1 or process name:make"));
+        assert!(!holds("no process name:make"));
+        // Not the watcher itself nor the shell that started it, whose command lines hold the
+        // filter text too
+        let me = std::process::id();
+        snapshot.processes.push(ProcessInfo { parent_pid: Some(me + 1), ..test_process(me, "kacemon", "kacemon watch process qqq", 0.0) });
+        snapshot.processes.push(test_process(me + 1, "bash", "bash -c kacemon watch process qqq", 0.0));
+        let holds = |text: &str| text.parse::<WatchCondition>().unwrap().matches(&snapshot);
+        assert!(!holds("process qqq") && holds("no process qqq"));
+        let condition: WatchCondition = "process qqq".parse().unwrap();
+        assert_eq!(condition.describe(&snapshot), vec!["process qqq (now 0 matching)"]);
+        // Readings the snapshot lacks never hold, whichever way they compare
+        assert!(!holds("temperature < 200") && !holds("disk /data free < 1G"));
+        
+        assert!("memory available".parse::<WatchCondition>().is_err());
+        assert!("memory available < 1GB and".parse::<WatchCondition>().is_err());
+        assert!("uptime > 5".parse::<WatchCondition>().is_err());
+    }
+    
+    #[test]
+    fn test_cgroup_stat_parsing() {
+        use metrics::cgroup::{parse_cpu_stat_usage, parse_io_stat};
+        
+        let cpu_stat = "usage_usec 8123456\nuser_usec 6000000\nsystem_usec 2123456\nnr_periods 0\n";
+        assert_eq!(parse_cpu_stat_usage(cpu_stat), Some(8_123_456));
+        assert_eq!(parse_cpu_stat_usage(""), None);
+        
+        // Summed over every device the group touched
+        let io_stat = "8:0 rbytes=4096 wbytes=1024 rios=1 wios=1 dbytes=0 dios=0\n\
+                       259:0 rbytes=100 wbytes=0 rios=2 wios=0 dbytes=0 dios=0\n";
+        assert_eq!(parse_io_stat(io_stat), (4196, 1024));
+        assert_eq!(parse_io_stat(""), (0, 0));
+    }
+    
+    #[test]
+    fn test_pod_resolution_names() {
+        use metrics::pods::{parse_container_log_name, parse_pod_log_dir, pod_uid_from_cgroup};
+        
+        let uid = "8a4b6c2e-1f3d-4e5a-9b7c-0d1e2f3a4b5c";
+        let systemd = format!(
+            "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod{}.slice/cri-containerd-4f1c2b3a5d6e7f80.scope",
+            uid.replace('-', "_")
+        );
+        assert_eq!(pod_uid_from_cgroup(&systemd).as_deref(), Some(uid));
+        assert_eq!(pod_uid_from_cgroup(&format!("/kubepods/besteffort/pod{}/4f1c2b3a5d6e", uid)).as_deref(), Some(uid));
+        assert_eq!(pod_uid_from_cgroup("/kubepods.slice/kubepods-burstable.slice"), None);
+        
+        let id = "4f1c2b3a5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708";
+        let (short, pod) = parse_container_log_name(&format!("web-7d9f8b6c5-x2x4q_shop_nginx-{}.log", id)).unwrap();
+        assert_eq!(short, "4f1c2b3a5d6e");
+        assert_eq!(pod.to_string(), "shop/web-7d9f8b6c5-x2x4q");
+        assert_eq!(pod.container.as_deref(), Some("nginx"));
+        assert_eq!(parse_container_log_name("syslog.log"), None);
+        
+        let (pod_uid, pod) = parse_pod_log_dir(&format!("kube-system_coredns-5d78c9869d-abcde_{}", uid)).unwrap();
+        assert_eq!(pod_uid, uid);
+        assert_eq!((pod.namespace.as_str(), pod.name.as_str(), pod.container), ("kube-system", "coredns-5d78c9869d-abcde", None));
+    }
+    
+    #[test]
+    fn test_systemctl_show_parsing() {
+        let output = "Id=nginx.service\nDescription=A high performance web server\nLoadState=loaded\n\
+                      ActiveState=active\nSubState=running\nMainPID=812\nMemoryCurrent=10485760\nNRestarts=2\n\n\
+                      Id=backup.service\nDescription=Nightly backup\nLoadState=loaded\nActiveState=failed\n\
+                      SubState=failed\nMainPID=0\nMemoryCurrent=[not set]\nNRestarts=0\n\n\
+                      Id=gone.service\nLoadState=not-found\nActiveState=inactive\n";
+        let units = metrics::services::parse_show_output(output);
+        assert_eq!(units.len(), 2, "units without a unit file are left out");
+        assert_eq!(units[0].name, "nginx.service");
+        assert_eq!(units[0].main_pid, Some(812));
+        assert_eq!(units[0].memory, Some(10 * 1024 * 1024));
+        assert_eq!(units[0].restarts, 2);
+        assert!(!units[0].is_failed());
+        assert!(units[1].is_failed());
+        assert_eq!(units[1].main_pid, None);
+        assert_eq!(units[1].memory, None);
+    }
+    
+    #[test]
+    fn test_log_line_parsing() {
+        use metrics::logs::parse_log_line;
+        use std::time::{Duration, UNIX_EPOCH};
+        
+        // journalctl --output=json
+        let entry = parse_log_line(
+            r#"{"__REALTIME_TIMESTAMP":"1700000000123456","PRIORITY":"3","SYSLOG_IDENTIFIER":"sshd","_PID":"812","MESSAGE":"error: kex_exchange_identification\n"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.time, UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456));
+        assert_eq!((entry.source.as_str(), entry.pid, entry.level.as_str()), ("sshd", Some(812), "err"));
+        assert_eq!(entry.message, "error: kex_exchange_identification");
+        let binary = parse_log_line(r#"{"__REALTIME_TIMESTAMP":"1","PRIORITY":"2","_COMM":"kernel","MESSAGE":[104,105,255]}"#).unwrap();
+        assert_eq!((binary.source.as_str(), binary.level.as_str(), binary.message.as_str()), ("kernel", "crit", "hi\u{fffd}"));
+        
+        // log stream --style ndjson; the timestamp is local time with its offset
+        let entry = parse_log_line(
+            r#"{"eventType":"logEvent","messageType":"Fault","processImagePath":"\/usr\/libexec\/trustd","processID":401,"eventMessage":"SecTrustEvaluate failed","timestamp":"2023-11-14 14:13:20.500000-0800"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.time, UNIX_EPOCH + Duration::from_millis(1_700_000_000_500));
+        assert_eq!((entry.source.as_str(), entry.pid, entry.level.as_str()), ("trustd", Some(401), "fault"));
+        assert_eq!(entry.message, "SecTrustEvaluate failed");
+        let east = r#"{"eventType":"logEvent","messageType":"Error","eventMessage":"x","timestamp":"2023-11-15 07:43:20.000000+0930"}"#;
+        assert_eq!(parse_log_line(east).unwrap().time, UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        
+        // The banner and summary lines log stream prints around the entries
+        assert!(parse_log_line("Filtering the log data using \"messageType == error\"").is_none());
+        assert!(parse_log_line(r#"{"count":12,"finished":1}"#).is_none());
+    }
+    
+    #[test]
+    fn test_process_classification() {
+        let rule = |name: &str, pattern: &str| CategoryRule { name: name.to_string(), pattern: pattern.to_string(), color: None };
+        
+        let classifier = Classifier::new(&[rule("database", "^(postgres|mysqld)"), rule("build", "cargo|rustc")]).unwrap();
+        let mut processes = vec![
+            test_process(1, "postgres", "postgres -D /data", 10.0),
+            test_process(2, "sh", "sh -c cargo build", 20.0),
+            test_process(3, "rustc", "rustc --edition 2021", 30.0),
+            test_process(4, "bash", "bash", 5.0),
+        ];
+        classifier.tag(&mut processes);
+        assert_eq!(processes[0].category.as_deref(), Some("database"));
+        assert_eq!(processes[1].category.as_deref(), Some("build"), "matches on the command line");
+        assert_eq!(processes[3].category, None);
+        
+        let usage = classifier.aggregate(&processes);
+        assert_eq!(usage[1].name, "build");
+        assert_eq!(usage[1].processes, 2);
+        assert_eq!(usage[1].cpu_percent, 50.0);
+        
+        assert!(Classifier::new(&[rule("broken", "(unclosed")]).is_err());
+    }
+    
+    #[test]
+    fn test_snapshot_log_rotation() {
+        use daemon::{LogConfig, LogFormat, SnapshotLog};
+        
+        let dir = std::env::temp_dir().join(format!("kacemon-log-test-{}", std::process::id()));
+        let path = dir.join("snapshots.jsonl");
+        let config = LogConfig { path: Some(path.clone()), format: LogFormat::Full, max_size_mb: 1, keep: 2 };
+        let mut log = SnapshotLog::open(&config, &path).unwrap();
+        
+        // Enough ~1KB lines to fill the file three times over
+        let snapshot = test_snapshot();
+        for _ in 0..3_500 {
+            log.write(&snapshot, &[], 1.0, &PressureWeights::default()).unwrap();
+        }
+        
+        let rotated = |n: usize| std::path::PathBuf::from(format!("{}.{}", path.display(), n));
+        assert!(rotated(1).exists() && rotated(2).exists());
+        assert!(!rotated(3).exists(), "only `keep` rotated files survive");
+        assert!(std::fs::metadata(&path).unwrap().len() <= 1024 * 1024);
+        
+        let line = std::fs::read_to_string(&path).unwrap();
+        let first: serde_json::Value = serde_json::from_str(line.lines().next().unwrap()).unwrap();
+        assert_eq!(first["system"]["hostname"], "test");
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_children_cpu_ticks() {
+        use metrics::process::{parse_children_ticks, parse_cpu_ticks, parse_process_group, parse_tty_nr, tty_name};
+        
+        // Name with a space and a parenthesis; pgrp 40 session 38 utime 5 stime 6 cutime 700 cstime 80
+        let stat = "42 (my (odd) sh) S 1 40 38 0 -1 4194560 100 0 0 0 5 6 700 80 20 0 1 0 100 1000 10";
+        assert_eq!(parse_children_ticks(stat), Some(780));
+        assert_eq!(parse_cpu_ticks(stat), Some(11));
+        assert_eq!(parse_children_ticks("42 (truncated) S 1"), None);
+        assert_eq!(parse_cpu_ticks("42 (truncated) S 1"), None);
+        assert_eq!(parse_process_group(stat), Some((40, 38)));
+        assert_eq!(parse_process_group("42 (truncated) S 1"), None);
+        assert_eq!(parse_tty_nr(stat), Some(0));
+        assert_eq!(tty_name(0), None);
+        assert_eq!(tty_name(136 << 8 | 3).as_deref(), Some("pts/3"));
+        assert_eq!(tty_name(137 << 8 | 44).as_deref(), Some("pts/300"));
+        assert_eq!(tty_name(4 << 8 | 2).as_deref(), Some("tty2"));
+        assert_eq!(tty_name(4 << 8 | 64).as_deref(), Some("ttyS0"));
+    }
+    
+    #[test]
+    fn test_tty_from_stat() {
+        use metrics::process::{parse_tty_nr, tty_name};
+        
+        // tty_nr 34819 is major 136 minor 3
+        let stat = "4242 (bash) S 4000 4242 4242 34819 4300 4194304 900 0 0 0 3 1 0 0 20 0 1 0 500 9000 700";
+        assert_eq!(parse_tty_nr(stat), Some(34819));
+        assert_eq!(parse_tty_nr(stat).and_then(tty_name).as_deref(), Some("pts/3"));
+        assert_eq!(parse_tty_nr("1 (x) S 0"), None);
+        
+        // Our own stat parses the same way, terminal or not
+        #[cfg(target_os = "linux")]
+        {
+            let own = std::fs::read_to_string("/proc/self/stat").unwrap();
+            assert!(parse_tty_nr(&own).is_some());
+        }
+    }
+    
+    #[test]
+    fn test_csv_export() {
+        use export::{csv_row, iso8601, CsvWriter};
+        use std::time::{Duration, UNIX_EPOCH};
+        
+        assert_eq!(iso8601(UNIX_EPOCH + Duration::from_secs(1_700_000_000)), "2023-11-14T22:13:20Z");
+        
+        let mut snapshot = test_snapshot();
+        snapshot.timestamp = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        // Empty cell, not 0.0, when there are no sensors
+        assert_eq!(
+            csv_row(&snapshot, 2.0, false, GpuExportMode::default()),
+            "2023-11-14T22:13:20Z,0.0,4000000000,50.0,0,0.0,0,0,0,0,,,,"
+        );
+        
+        let path = std::env::temp_dir().join(format!("kacemon-csv-test-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        CsvWriter::open(&path).unwrap().write_row(&snapshot, 2.0).unwrap();
+        // Reopening appends without repeating the header
+        CsvWriter::open(&path).unwrap().write_row(&snapshot, 2.0).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,cpu_percent,"));
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_network_totals_skip_virtual_interfaces() {
+        use metrics::network::is_virtual_interface;
+        
+        assert!(is_virtual_interface("docker0"));
+        assert!(is_virtual_interface("veth1a2b3c"));
+        assert!(is_virtual_interface("br-5f1e2d"));
+        assert!(!is_virtual_interface("eth0"));
+        assert!(!is_virtual_interface("wlp3s0"));
+        
+        let interface = |name: &str, rx: u64, tx: u64| NetworkInfo {
+            interface_name: name.to_string(),
+            rx_bytes: 0,
+            tx_bytes: 0,
+            rx_bytes_delta: rx,
+            tx_bytes_delta: tx,
+            rx_packets: 0,
+            tx_packets: 0,
+            rx_errors: 0,
+            tx_errors: 0,
+            rx_packets_delta: 0,
+            tx_packets_delta: 0,
+            rx_errors_delta: 0,
+            tx_errors_delta: 0,
+            is_virtual: is_virtual_interface(name),
+        };
+        let networks = [interface("eth0", 1000, 200), interface("docker0", 900, 150), interface("veth1a2b3c", 900, 150)];
+        assert_eq!(NetworkInfo::total_deltas(&networks, false), (1000, 200));
+        assert_eq!(NetworkInfo::total_deltas(&networks, true), (2800, 500));
+    }
+    
+    #[test]
+    fn test_bundle_round_trip() {
+        use export::{Bundle, BundleView};
+        
+        let mut snapshot = test_snapshot();
+        snapshot.system.hostname = "db 1".to_string();
+        snapshot.processes.push(test_process(42, "postgres", "postgres -D /data", 12.5));
+        let view = BundleView {
+            filter: "user:root cpu>50".to_string(),
+            sort: SortKey::Memory,
+            reverse: true,
+            selected_pid: Some(42),
+            ..BundleView::default()
+        };
+        let alert = Alert { kind: AlertKind::Cpu, subject: "CPU".to_string(), pid: None, value: 97.0, threshold: 90.0, critical: false };
+        let suspect = LeakSuspect { pid: 42, growth_per_min: 2e6, span: std::time::Duration::from_secs(900) };
+        
+        let dir = std::env::temp_dir().join(format!("kacemon-bundle-test-{}", std::process::id()));
+        let path = Bundle::new(snapshot, view.clone(), vec![alert.clone()], vec![suspect]).write(&dir).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("kacemon-db_1-") && name.ends_with(".kmon"));
+        // Compressed, not plain JSON
+        assert_eq!(&std::fs::read(&path).unwrap()[..2], &[0x1f, 0x8b]);
+        
+        let bundle = Bundle::read(&path).unwrap();
+        assert_eq!(bundle.view, view);
+        assert_eq!(bundle.alerts, vec![alert.clone()]);
+        assert_eq!(bundle.leak_suspects, vec![suspect]);
+        assert_eq!(bundle.snapshot.processes[0].cmd, vec!["postgres", "-D", "/data"]);
+        
+        assert_eq!(export::read_recording(&path).unwrap()[0].view, view);
+        
+        std::fs::write(&path, b"not a bundle").unwrap();
+        assert!(Bundle::read(&path).is_err());
+        
+        // A full snapshot log replays line by line, a summary log doesn't
+        use daemon::{LogConfig, LogFormat, SnapshotLog};
+        let log_path = dir.join("snapshots.jsonl");
+        let record = |format: LogFormat| {
+            let _ = std::fs::remove_file(&log_path);
+            let config = LogConfig { path: Some(log_path.clone()), format, ..LogConfig::default() };
+            let mut log = SnapshotLog::open(&config, &log_path).unwrap();
+            let mut snapshot = test_snapshot();
+            log.write(&snapshot, std::slice::from_ref(&alert), 2.0, &PressureWeights::default()).unwrap();
+            snapshot.timestamp += std::time::Duration::from_secs(2);
+            log.write(&snapshot, &[], 2.0, &PressureWeights::default()).unwrap();
+            export::read_recording(&log_path)
+        };
+        let error = record(LogFormat::Summary).unwrap_err().to_string();
+        assert!(error.contains("line 1") && error.contains("--log-format full"), "{}", error);
+        let frames = record(LogFormat::Full).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].alerts, vec![alert]);
+        assert!(frames[1].alerts.is_empty());
+        assert_eq!(frames[0].interval_secs, Some(2.0));
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_fake_collector_end_to_end() {
+        use mock::{FakeCollector, MockPlatformProvider};
+        
+        // The same snapshots on every run
+        let mut collector = FakeCollector::new();
+        let first = collector.collect();
+        assert_eq!(serde_json::to_value(&first).unwrap(), serde_json::to_value(FakeCollector::new().snapshot_at(0)).unwrap());
+        assert_eq!(collector.tick(), 1);
+        // Load peaks two minutes in
+        let peak = collector.snapshot_at(120);
+        assert_eq!(peak.timestamp.duration_since(first.timestamp).unwrap(), std::time::Duration::from_secs(120));
+        
+        let mut processes = peak.processes.clone();
+        processes.sort_by(|a, b| SortKey::Cpu.compare(b, a));
+        assert_eq!(processes[0].name, "rustc");
+        processes.sort_by(|a, b| SortKey::Name.compare(a, b));
+        assert_eq!(processes[0].name, "bash");
+        
+        let filter: ProcessFilter = "user:postgres".parse().unwrap();
+        assert_eq!(peak.processes.iter().filter(|p| filter.matches(p)).count(), 3);
+        let filter: ProcessFilter = "user:dev cpu>100".parse().unwrap();
+        assert_eq!(peak.processes.iter().filter(|p| filter.matches(p)).map(|p| p.pid).collect::<Vec<_>>(), vec![1305]);
+        
+        let thresholds = AlertThresholds::default();
+        assert!(thresholds.evaluate(&first).is_empty());
+        let alerts = thresholds.evaluate(&peak);
+        assert!(alerts.iter().any(|a| a.kind == AlertKind::Cpu));
+        assert!(alerts.iter().any(|a| a.kind == AlertKind::ProcessCpu && a.pid == Some(1305)));
+        
+        // Exporters see the same machine
+        let row = export::csv_row(&peak, 1.0, false, GpuExportMode::default());
+        let cells: Vec<&str> = row.split(',').collect();
+        assert_eq!((cells[0], cells[6]), ("2023-11-14T22:15:20Z", "12500000"));
+        assert!(report::status_report(&peak, &alerts, OutputFormat::Table).contains("PROCESSES   12"));
+        assert!(telegraf::telegraf_metrics(&peak, TelegrafFormat::Influx, GpuExportMode::default()).contains("kacemon_disk,host=demo,device=/dev/nvme0n1p2,"));
+        
+        // Replay: a full log of fake snapshots reads back frame for frame
+        use daemon::{LogConfig, LogFormat, SnapshotLog};
+        let dir = std::env::temp_dir().join(format!("kacemon-fake-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("snapshots.jsonl");
+        let config = LogConfig { path: Some(log_path.clone()), format: LogFormat::Full, ..LogConfig::default() };
+        let mut log = SnapshotLog::open(&config, &log_path).unwrap();
+        let recorded: Vec<SystemSnapshot> = (0..3).map(|_| collector.collect()).collect();
+        for snapshot in &recorded {
+            log.write(snapshot, &thresholds.evaluate(snapshot), 1.0, &PressureWeights::default()).unwrap();
+        }
+        let frames = export::read_recording(&log_path).unwrap();
+        assert_eq!(frames.len(), 3);
+        for (frame, snapshot) in frames.iter().zip(&recorded) {
+            assert_eq!(frame.snapshot.timestamp, snapshot.timestamp);
+            assert_eq!(frame.snapshot.memory.used, snapshot.memory.used);
+            assert_eq!(serde_json::to_value(&frame.snapshot.processes).unwrap(), serde_json::to_value(&snapshot.processes).unwrap());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        // A slower refresh spaces snapshots out without counters going backwards
+        let before = collector.collect();
+        collector.set_interval(std::time::Duration::from_secs(5));
+        let after = collector.collect();
+        let next = collector.collect();
+        assert_eq!(after.timestamp.duration_since(before.timestamp).unwrap(), std::time::Duration::from_secs(1));
+        assert_eq!(next.timestamp.duration_since(after.timestamp).unwrap(), std::time::Duration::from_secs(5));
+        assert_eq!(after.disks[0].read_bytes, before.disks[0].read_bytes + after.disks[0].read_bytes_delta);
+        assert!(next.networks[0].rx_bytes_delta > 4 * before.networks[0].rx_bytes_delta);
+        
+        let platform = MockPlatformProvider::new().with_process(
+            530,
+            platform::PlatformProcessDetails { cwd: Some("/var/lib/postgresql".to_string()), ..Default::default() },
+        );
+        assert_eq!(platform.get_process_details(530).unwrap().cwd.as_deref(), Some("/var/lib/postgresql"));
+        assert!(platform.get_process_details(1).is_err());
+        assert_eq!(platform.get_system_metrics().unwrap().context_switches, None);
+    }
+    
+    #[test]
+    fn test_cpu_frequency_limits() {
+        use metrics::cpu::parse_khz;
+        
+        assert_eq!(parse_khz("4200000\n"), Some(4200));
+        assert_eq!(parse_khz("0"), None);
+        assert_eq!(parse_khz("<unknown>"), None);
+        
+        let mut core = CpuCore { id: 0, name: "cpu0".to_string(), usage_percent: 95.0, frequency: 1200, base_frequency: Some(2400), max_frequency: Some(4200) };
+        assert!(core.throttled());
+        // Idle cores clock down on purpose
+        core.usage_percent = 10.0;
+        assert!(!core.throttled());
+        core.usage_percent = 95.0;
+        core.frequency = 3900;
+        assert!(!core.throttled());
+        core.base_frequency = None;
+        core.frequency = 800;
+        assert!(!core.throttled());
+    }
+    
+    #[test]
+    fn test_smaps_rollup_breakdown() {
+        use metrics::process::parse_smaps_rollup;
+        
+        let rollup = "55d0c8a0e000-7ffd4a1f9000 ---p 00000000 00:00 0                          [rollup]
+Rss:              268432 kB
+Pss:               98304 kB
+Pss_Anon:          12288 kB
+Shared_Clean:     180224 kB
+Shared_Dirty:      10240 kB
+Private_Clean:      4096 kB
+Private_Dirty:     20480 kB
+Referenced:       268000 kB
+Swap:                  0 kB
+";
+        assert_eq!(parse_smaps_rollup(rollup), Some((98304 * 1024, 24576 * 1024)));
+        // Kernel threads have a header line and nothing else
+        assert_eq!(parse_smaps_rollup("00000000-00000000 ---p 00000000 00:00 0 [rollup]\n"), None);
+        assert_eq!(parse_smaps_rollup(""), None);
+        
+        // Unmeasured sorts below the smallest measured
+        let unmeasured = test_process(1, "kworker", "kworker", 0.0);
+        let forked = ProcessInfo { memory_pss: Some(96 << 20), memory_uss: Some(24 << 20), ..test_process(2, "postgres", "postgres", 1.0) };
+        assert_eq!(SortKey::Pss.compare(&unmeasured, &forked), std::cmp::Ordering::Less);
+        assert!(SortKey::Uss.default_descending());
+    }
+    
+    #[test]
+    fn test_parse_wchan() {
+        use metrics::process::parse_wchan;
+        
+        assert_eq!(parse_wchan("nfs_wait_bit_killable"), Some("nfs_wait_bit_killable".to_string()));
+        assert_eq!(parse_wchan("0"), None);
+        assert_eq!(parse_wchan(""), None);
+    }
+    
+    #[test]
+    fn test_disk_sleep_alarm() {
+        use std::time::Duration;
+        
+        let thresholds = AlertThresholds { disk_sleep_count: Some(2), disk_sleep_secs: 30, ..AlertThresholds::default() };
+        let mut tracker = AlertTracker::default();
+        let mut snapshot = test_snapshot();
+        let start = snapshot.timestamp;
+        snapshot.processes = (1..=3)
+            .map(|pid| ProcessInfo { state: ProcessState::DiskSleep, ..test_process(pid, "nfsd", "nfsd", 0.0) })
+            .collect();
+        let fired = |alerts: Vec<Alert>| alerts.iter().any(|a| a.kind == AlertKind::DiskSleep);
+        
+        // Over the count, but not for long enough yet
+        assert!(!fired(tracker.evaluate(&thresholds, &snapshot)));
+        snapshot.timestamp = start + Duration::from_secs(29);
+        assert!(!fired(tracker.evaluate(&thresholds, &snapshot)));
+        snapshot.timestamp = start + Duration::from_secs(30);
+        assert!(fired(tracker.evaluate(&thresholds, &snapshot)));
+        
+        // Dropping under the count restarts the clock
+        snapshot.processes.pop();
+        assert!(!fired(tracker.evaluate(&thresholds, &snapshot)));
+        snapshot.processes.push(ProcessInfo { state: ProcessState::DiskSleep, ..test_process(3, "nfsd", "nfsd", 0.0) });
+        snapshot.timestamp = start + Duration::from_secs(40);
+        assert!(!fired(tracker.evaluate(&thresholds, &snapshot)));
+    }
+    
+    #[test]
+    fn test_spawning_alarm_and_spawners() {
+        use std::time::Duration;
+        
+        let thresholds = AlertThresholds { fork_rate: Some(100.0), process_growth: Some(5.0), ..AlertThresholds::default() };
+        let mut tracker = AlertTracker::default();
+        let mut snapshot = test_snapshot();
+        let start = snapshot.timestamp;
+        snapshot.system.processes_created = Some(1000);
+        snapshot.processes = vec![test_process(1, "init", "init", 0.0), test_process(2, "sh", "sh", 0.0)];
+        let spawning = |alerts: &[Alert]| alerts.iter().filter(|a| a.kind == AlertKind::Spawning).count();
+        assert_eq!(spawning(&tracker.evaluate(&thresholds, &snapshot)), 0);
+        
+        // 1000 forks and 40 new children of "sh" in 4s
+        snapshot.timestamp = start + Duration::from_secs(4);
+        snapshot.system.processes_created = Some(2000);
+        snapshot.processes.extend((100..140).map(|pid| ProcessInfo { parent_pid: Some(2), ..test_process(pid, "sh", "sh", 0.0) }));
+        let alerts = tracker.evaluate(&thresholds, &snapshot);
+        assert_eq!(spawning(&alerts), 2);
+        assert!(alerts.iter().any(|a| a.message() == "250 processes forked/s (limit 100/s)"));
+        assert_eq!(tracker.spawners()[0].pid, 2);
+        assert_eq!(tracker.spawners()[0].new_children, 40);
+        
+        // The verdict holds until the next window is complete
+        snapshot.timestamp = start + Duration::from_secs(5);
+        assert_eq!(spawning(&tracker.evaluate(&thresholds, &snapshot)), 2);
+        snapshot.timestamp = start + Duration::from_secs(6);
+        assert_eq!(spawning(&tracker.evaluate(&thresholds, &snapshot)), 0);
+        assert!(tracker.spawners().is_empty());
+    }
+    
+    #[test]
+    fn test_restart_tracker_and_crash_loop_alert() {
+        use metrics::restarts::RestartTracker;
+        use std::time::Duration;
+        
+        let mut tracker = RestartTracker::default();
+        let start = std::time::SystemTime::now();
+        let worker = |pid| test_process(pid, "worker", "worker --queue jobs", 0.0);
+        let mut processes = vec![worker(10), worker(11), test_process(1, "init", "init", 0.0)];
+        tracker.update(&mut processes, start);
+        assert!(processes.iter().all(|p| p.restarts == 0));
+        
+        // Worker 10 crashes and is down for a pass, then comes back; a third worker is new
+        processes.remove(0);
+        tracker.update(&mut processes, start + Duration::from_secs(5));
+        processes.extend([worker(20), worker(21)]);
+        tracker.update(&mut processes, start + Duration::from_secs(10));
+        assert_eq!(processes.iter().map(|p| p.restarts).collect::<Vec<_>>(), vec![1, 0, 1, 1]);
+        
+        // Six crash-and-restart cycles later the alert fires, once for the program
+        for n in 0..5u64 {
+            let pid = 100 + n as u32;
+            processes[0] = worker(pid);
+            tracker.update(&mut processes, start + Duration::from_secs(20 + n));
+        }
+        assert_eq!(processes[0].restarts, 6);
+        let mut snapshot = test_snapshot();
+        snapshot.processes = processes.clone();
+        let crash_loops: Vec<Alert> = AlertThresholds::default()
+            .evaluate(&snapshot)
+            .into_iter()
+            .filter(|a| a.kind == AlertKind::CrashLoop)
+            .collect();
+        assert_eq!(crash_loops.len(), 1);
+        assert_eq!(crash_loops[0].message(), "worker restarted 6 times in the last hour (limit 5)");
+        
+        // They age out after an hour
+        tracker.update(&mut processes, start + Duration::from_secs(3700));
+        assert!(processes.iter().all(|p| p.restarts == 0));
+    }
+    
+    #[test]
+    fn test_process_filter() {
+        let nginx = ProcessInfo {
+            user: "root".to_string(),
+            memory_rss: 600 * 1024 * 1024,
+            category: Some("web".to_string()),
+            ..test_process(42, "nginx", "nginx -g daemon off;", 75.0)
+        };
+        let idle = ProcessInfo { state: ProcessState::Sleeping, ..test_process(7, "bash", "bash", 0.0) };
+        let matches = |query: &str, process: &ProcessInfo| query.parse::<ProcessFilter>().unwrap().matches(process);
+        
+        assert!(matches("", &idle));
+        assert!(matches("user:root cpu>50 state:running name~ngin", &nginx));
+        assert!(matches("NGINX daemon", &nginx));
+        assert!(matches("rss>=500M cat:web pid=42", &nginx));
+        assert!(!matches("rss>1g", &nginx));
+        assert!(matches("state:sleep !name:nginx", &idle));
+        assert!(!matches("!user:test", &idle));
+        assert!(matches("cpu<=0% threads:1", &idle));
+        
+        assert!(matches("re:^ngi.x -g", &nginx));
+        assert!(matches("re:ROOT|www-data", &nginx));
+        assert!(!matches("re:^bash$", &nginx));
+        let error = "re:(nginx".parse::<ProcessFilter>().unwrap_err();
+        assert_eq!(error, "Invalid regex: unclosed group");
+        
+        let helper = test_process(9, "Chrome Helper", "/opt/chrome/chrome --type=renderer", 3.0);
+        let chromium = test_process(10, "chromium", "chromium", 3.0);
+        assert!(matches("fz:chrmhlpr", &helper));
+        assert!(!matches("fz:chrmhlpr", &chromium));
+        assert!(matches("fz:chr rndr", &helper));
+        assert!(!"nginx".parse::<ProcessFilter>().unwrap().is_fuzzy());
+        let fuzzy: ProcessFilter = "fz:chr".parse().unwrap();
+        assert_eq!(fuzzy.highlights("Chrome Helper"), vec![0, 1, 2]);
+        let launcher = test_process(11, "bash", "bash -c chrome", 0.0);
+        assert!(fuzzy.fuzzy_score(&chromium) > fuzzy.fuzzy_score(&launcher));
+        // Word starts and runs outrank scattered letters
+        let ch = |name: &str| filter::fuzzy_match("ch", name).unwrap().score;
+        assert!(ch("chrome") > ch("cache"));
+        assert!(ch("gnome-chess") > ch("launchpad"));
+        assert_eq!(filter::fuzzy_match("ch", "launchpad").unwrap().positions, vec![4, 5]);
+        
+        for bad in ["cpu>", "cpu~5", "name>a", "rss>lots", "colour:red"] {
+            assert!(bad.parse::<ProcessFilter>().is_err(), "{}", bad);
+        }
+        // Unparsed text still works as a plain substring
+        assert!(ProcessFilter::substring("cpu>").matches(&ProcessInfo { cmd: vec!["cpu>x".to_string()], ..idle.clone() }));
+        assert!(ProcessFilter::substring("  ").is_empty());
+    }
+
+    #[test]
+    fn test_process_filter_pkill_words() {
+        let kill = |query: &str| query.parse::<ProcessFilter>().unwrap().pkill_words();
+        let init = test_process(1, "systemd", "/sbin/init splash", 0.0);
+        let worker = test_process(128, "sleep", "sleep 1", 0.0);
+        let script = test_process(300, "bash", "bash ./nginx-reload.sh", 0.0);
+        
+        assert!(kill("").is_empty());
+        assert!(kill("  ").is_empty());
+        // All digits is exactly that PID, not any PID or argument containing it
+        assert!(kill("1").matches(&init));
+        assert!(!kill("1").matches(&worker));
+        assert!(kill("128").matches(&worker));
+        // Other words only look at the name
+        assert!(kill("SLEEP").matches(&worker));
+        assert!(!kill("nginx").matches(&script));
+        assert!(!kill("init").matches(&init));
+        assert!(kill("cmd~nginx").matches(&script));
+        assert!(kill("!sleep").matches(&script));
+        assert!(kill("sleep user:test").matches(&worker));
+        // The TUI keeps the loose reading
+        assert!("1".parse::<ProcessFilter>().unwrap().matches(&worker));
+    }
+    
+    #[test]
+    fn test_kill_refusal() {
+        let init = test_process(1, "systemd", "/sbin/init", 0.0);
+        let workers: Vec<ProcessInfo> = (100..130).map(|pid| test_process(pid, "worker", "worker", 0.0)).collect();
+        let few: Vec<&ProcessInfo> = workers.iter().take(MAX_KILL_TARGETS).collect();
+        let all: Vec<&ProcessInfo> = workers.iter().collect();
+        
+        assert_eq!(kill_refusal(&few, false), None);
+        assert_eq!(kill_refusal(&all, false), Some("30 processes match, more than 20".to_string()));
+        assert!(kill_refusal(&[&init], false).unwrap().contains("PID 1"));
+        // '!name:x' or 're:.' also match the kacemon asking
+        assert!(kill_refusal(&few[..1], true).unwrap().contains("kacemon itself"));
+    }
+    
+    #[test]
+    fn test_leak_detector() {
+        use std::time::Duration;
+        
+        let mut detector = LeakDetector::default();
+        let mut snapshot = test_snapshot();
+        let start = snapshot.timestamp;
+        let mb = 1024 * 1024;
+        for minute in 0..15u64 {
+            snapshot.timestamp = start + Duration::from_secs(minute * 60);
+            snapshot.processes = vec![
+                // Steady 2MB/min
+                ProcessInfo { memory_rss: 100 * mb + minute * 2 * mb, ..test_process(1, "leaky", "leaky", 0.0) },
+                // Fills and drains like a cache
+                ProcessInfo { memory_rss: 100 * mb + (minute % 3) * 10 * mb, ..test_process(2, "cache", "cache", 0.0) },
+                // Steady but slow
+                ProcessInfo { memory_rss: 100 * mb + minute * 1024, ..test_process(3, "slow", "slow", 0.0) },
+                // Steady, just under the 1MB/min floor
+                ProcessInfo { memory_rss: 100 * mb + minute * mb * 9 / 10, ..test_process(4, "creep", "creep", 0.0) },
+            ];
+            for process in &mut snapshot.processes {
+                process.start_time = start;
+            }
+            detector.record(&snapshot);
+            // Snapshots between samples are ignored
+            detector.record(&snapshot);
+            
+            let flagged = detector.suspect(1).is_some();
+            assert_eq!(flagged, minute >= 9, "minute {}", minute);
+        }
+        
+        let leak = detector.suspect(1).unwrap();
+        assert!((leak.growth_per_min - 2.0 * mb as f64).abs() < 1.0);
+        assert_eq!(leak.span, Duration::from_secs(14 * 60));
+        assert!(detector.suspect(2).is_none() && detector.suspect(3).is_none() && detector.suspect(4).is_none());
+        
+        // A new process with the same PID starts from scratch
+        snapshot.timestamp += Duration::from_secs(60);
+        snapshot.processes[0].start_time += Duration::from_secs(1);
+        detector.record(&snapshot);
+        assert_eq!(detector.suspects().count(), 0);
+    }
+    
+    #[test]
+    fn test_api_server() {
+        use serve::{respond, ApiServer, Published};
+        use std::io::{Read, Write};
+        
+        assert_eq!(respond("/snapshot", None).0, 503);
+        assert_eq!(respond("/nope", None).0, 404);
+        assert!(respond("/", None).1.contains("/processes"));
+        
+        let published = Published { snapshot: test_snapshot(), alerts: vec![], gpu_export: GpuExportMode::default() };
+        let (status, body) = respond("/system?pretty=1", Some(&published));
+        assert_eq!(status, 200);
+        let system: SystemInfo = serde_json::from_str(&body).unwrap();
+        assert_eq!(system.hostname, "test");
+        
+        // One real round trip over a socket
+        let server = ApiServer::bind("127.0.0.1:0").unwrap();
+        server.publish(&published.snapshot, &[]);
+        let mut stream = std::net::TcpStream::connect(server.local_addr()).unwrap();
+        stream.write_all(b"GET /memory HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let memory: MemoryInfo = serde_json::from_str(body).unwrap();
+        assert_eq!(memory.total, 8_000_000_000);
+        
+        // Browsers may only hand the answer to pages from an allowed origin
+        let get = |server: &ApiServer, origin: &str| {
+            let mut stream = std::net::TcpStream::connect(server.local_addr()).unwrap();
+            write!(stream, "GET /processes HTTP/1.1\r\nHost: localhost\r\nOrigin: {}\r\n\r\n", origin).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get(&server, "http://evil.example");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+        let server = ApiServer::bind_allowing("127.0.0.1:0", vec!["http://localhost:3000".to_string()]).unwrap();
+        server.publish(&published.snapshot, &[]);
+        assert!(get(&server, "http://localhost:3000").contains("Access-Control-Allow-Origin: http://localhost:3000\r\n"));
+        assert!(!get(&server, "http://evil.example").contains("Access-Control-Allow-Origin"));
+    }
+    
+    #[test]
+    fn test_slow_poll_refresh_limits() {
+        use config::CliConfig;
+        
+        let path = std::env::temp_dir().join(format!("kacemon-slow-poll-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "refresh_ms": 300000 }"#).unwrap();
+        let load = |cli: CliConfig| Config::load(Some(&cli), Some(&path));
+        
+        // Five minutes is fine for exporters, too slow for the TUI unless asked for
+        assert!(load(CliConfig::default()).is_err());
+        assert!(load(CliConfig { headless: true, ..CliConfig::default() }).is_ok());
+        let mut config = load(CliConfig { slow_poll: true, ..CliConfig::default() }).unwrap();
+        // Too long for any mode on the command line is clamped rather than refused
+        assert_eq!(load(CliConfig { refresh_ms: Some(7_200_000), headless: true, ..CliConfig::default() }).unwrap().refresh_ms, 3_600_000);
+        
+        config.step_refresh_preset(true);
+        assert_eq!(config.refresh_ms, 60_000);
+        config.cycle_refresh_preset();
+        assert_eq!(config.refresh_ms, 300_000);
+        config.cycle_refresh_preset();
+        assert_eq!(config.refresh_ms, 250);
+        
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_cli_refresh_precedence_and_clamping() {
+        use config::{CliConfig, ConfigWarning};
+        
+        let path = std::env::temp_dir().join(format!("kacemon-refresh-{}.json", std::process::id()));
+        let load = |file: &str, cli: CliConfig| {
+            std::fs::write(&path, file).unwrap();
+            Config::load_with_warnings(Some(&cli), Some(&path))
+        };
+        let refresh = |ms: u64| CliConfig { refresh_ms: Some(ms), ..CliConfig::default() };
+        
+        // The file beats the default and --refresh beats the file
+        assert_eq!(Config::default().refresh_ms, 2000);
+        assert_eq!(load(r#"{ "refresh_ms": 1000 }"#, CliConfig::default()).unwrap().0.refresh_ms, 1000);
+        let (config, warnings) = load(r#"{ "refresh_ms": 1000 }"#, refresh(500)).unwrap();
+        assert_eq!((config.refresh_ms, warnings), (500, vec![]));
+        // so a good --refresh rescues a file value out of range
+        assert_eq!(load(r#"{ "refresh_ms": 20 }"#, refresh(500)).unwrap().0.refresh_ms, 500);
+        assert!(load(r#"{ "refresh_ms": 20 }"#, CliConfig::default()).is_err());
+        
+        // Out of range on the command line: the nearest bound for the mode, with a warning
+        let (config, warnings) = load("{}", refresh(20)).unwrap();
+        assert_eq!(config.refresh_ms, 100);
+        assert_eq!(warnings, vec![ConfigWarning::RefreshClamped { requested: 20, used: 100 }]);
+        assert_eq!(warnings[0].to_string(), "Refresh interval 20ms is too short, using 100ms");
+        let headless = |ms: u64| CliConfig { refresh_ms: Some(ms), headless: true, ..CliConfig::default() };
+        assert_eq!(load("{}", headless(20)).unwrap().0.refresh_ms, 50);
+        let (config, warnings) = load("{}", headless(60_000)).unwrap();
+        assert_eq!((config.refresh_ms, warnings.len()), (60_000, 0));
+        assert_eq!(load("{}", refresh(60_000)).unwrap().0.refresh_ms, 10_000);
+        // Slow-poll from the file widens the TUI's bounds for --refresh too
+        let (config, warnings) = load(r#"{ "slow_poll": true }"#, refresh(60_000)).unwrap();
+        assert_eq!((config.refresh_ms, warnings.len()), (60_000, 0));
+        
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_fake_collector_replays_the_same_run() {
+        use mock::FakeCollector;
+        use std::time::{Duration, UNIX_EPOCH};
+        let json = |snapshot: &SystemSnapshot| serde_json::to_value(snapshot).unwrap();
+        
+        // Taking snapshots one by one gives the same ones as jumping straight to them
+        let start = UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let mut demo = FakeCollector::new().starting_at(start).with_interval(Duration::from_secs(2));
+        let jumped = FakeCollector::new().starting_at(start).with_interval(Duration::from_secs(2));
+        for tick in 0..5 {
+            assert_eq!(json(&demo.collect()), json(&jumped.snapshot_at(tick)), "tick {}", tick);
+        }
+        assert_eq!(jumped.snapshot_at(4).timestamp, start + Duration::from_secs(8));
+        
+        // Memory leaks with time rather than per snapshot: a minute in is a minute in
+        let slow = FakeCollector::new().with_interval(Duration::from_secs(2)).snapshot_at(30);
+        let fast = FakeCollector::new().snapshot_at(60);
+        assert_eq!(slow.memory.used, fast.memory.used);
+    }
+    
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_process_memory_in_bytes() {
+        let snapshot = MetricsCollector::new().unwrap().collect().unwrap();
+        let me = snapshot.processes.iter().find(|p| p.pid == std::process::id()).unwrap();
+        
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        let vm_rss_kb: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+            .unwrap();
+        // Read at slightly different moments, so allow for the test's own allocations
+        let vm_rss = vm_rss_kb * 1024;
+        assert!(me.memory_rss >= vm_rss / 2 && me.memory_rss <= vm_rss * 2, "{} vs {}", me.memory_rss, vm_rss);
+        assert!(me.memory_vsz >= me.memory_rss);
+    }
+    
+    #[test]
+    #[cfg(unix)]
+    fn test_slow_metrics_reused_between_fast_refreshes() {
+        let mut collector = MetricsCollector::new().unwrap();
+        let first = collector.collect().unwrap();
+        let second = collector.collect().unwrap();
+        
+        // Too soon for another process scan: the same list comes back
+        let pids = |snapshot: &SystemSnapshot| snapshot.processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+        assert_eq!(pids(&first), pids(&second));
+        assert!(second.timestamp > first.timestamp);
+        
+        // A spawned child shows up once the cache is expired
+        let mut child = std::process::Command::new("sleep").arg("5").spawn().unwrap();
+        collector.expire_slow_metrics();
+        let third = collector.collect().unwrap();
+        assert!(third.processes.iter().any(|p| p.pid == child.id()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+    
+    #[test]
+    fn test_websocket_stream() {
+        use serve::{websocket_accept, websocket_frame, ApiServer};
+        use std::io::{BufRead, BufReader, Read, Write};
+        
+        // The example handshake from RFC 6455
+        assert_eq!(websocket_accept("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+        assert_eq!(websocket_frame(0x1, b"hi"), vec![0x81, 2, b'h', b'i']);
+        assert_eq!(&websocket_frame(0x1, &[0; 300])[..4], &[0x81, 126, 1, 44]);
+        
+        let server = ApiServer::bind("127.0.0.1:0").unwrap();
+        let mut stream = std::net::TcpStream::connect(server.local_addr()).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        stream
+            .write_all(b"GET /ws?sections=memory,system HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n")
+            .unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut status = String::new();
+        reader.read_line(&mut status).unwrap();
+        assert!(status.starts_with("HTTP/1.1 101"));
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        
+        // Nothing published before the client joined, so the first frame is this snapshot
+        server.publish(&test_snapshot(), &[]);
+        let mut head = [0u8; 4];
+        reader.read_exact(&mut head[..2]).unwrap();
+        assert_eq!(head[0], 0x81);
+        let len = match head[1] {
+            126 => {
+                reader.read_exact(&mut head[2..]).unwrap();
+                u16::from_be_bytes([head[2], head[3]]) as usize
+            },
+            len => len as usize,
+        };
+        let mut payload = vec![0u8; len];
+        reader.read_exact(&mut payload).unwrap();
+        let message: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(message["system"]["hostname"], "test");
+        assert_eq!(message["memory"]["total"], 8_000_000_000u64);
+        assert!(message.get("processes").is_none());
+    }
+    
+    #[test]
+    fn test_websocket_origin_and_close() {
+        use serve::ApiServer;
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::time::Duration;
+        
+        let upgrade = |server: &ApiServer, origin: Option<&str>| {
+            let mut stream = std::net::TcpStream::connect(server.local_addr()).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            let origin = origin.map(|origin| format!("Origin: {}\r\n", origin)).unwrap_or_default();
+            write!(
+                stream,
+                "GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n{}\
+                 Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+                origin
+            )
+            .unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut status = String::new();
+            reader.read_line(&mut status).unwrap();
+            (status, reader)
+        };
+        
+        // A page from another site can't subscribe; one from an allowed origin can
+        let server = ApiServer::bind_allowing("127.0.0.1:0", vec!["http://localhost:3000".to_string()]).unwrap();
+        assert!(upgrade(&server, Some("http://evil.example")).0.starts_with("HTTP/1.1 403"));
+        assert!(upgrade(&server, Some("http://localhost:3000")).0.starts_with("HTTP/1.1 101"));
+        
+        // Once the sending side stops, here because the server goes away, the connection is
+        // closed rather than left to a reader thread waiting on the client for good
+        let (status, mut reader) = upgrade(&server, None);
+        assert!(status.starts_with("HTTP/1.1 101"));
+        server.publish(&test_snapshot(), &[]);
+        drop(server);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        // The rest of the handshake, then the frame that was already on its way
+        assert!(rest.windows(5).any(|w| w == b"\r\n\r\n\x81"));
+    }
+    
+    #[test]
+    fn test_config_includes_and_host_override() {
+        let dir = std::env::temp_dir().join(format!("kacemon-include-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        std::fs::create_dir_all(dir.join("config.d")).unwrap();
+        std::fs::write(dir.join("shared/base.json"), r#"{ "max_fps": 10, "ascii": true, "theme": "light", "refresh_ms": 500 }"#).unwrap();
+        std::fs::write(
+            dir.join("config.json"),
+            r#"{ "include": ["shared/base.json"], "max_fps": 20, "alerts": { "enabled": true, "cpu_percent": 90 } }"#,
+        ).unwrap();
+        std::fs::write(dir.join("config.d/web1.json"), r#"{ "theme": "solar", "max_fps": 30, "alerts": { "memory_percent": 95 } }"#).unwrap();
+        
+        // The including file wins over its includes; the host file wins over both
+        let config = Config::load_for_host(&dir.join("config.json"), Some("web1.example.com")).unwrap();
+        assert_eq!(config.max_fps, 30);
+        assert!(config.ascii);
+        assert_eq!(config.theme, Theme::Custom("solar".to_string()));
+        // Settings a layer leaves out come from below, even in nested sections; a layer can
+        // also set something back to its default
+        assert_eq!(config.refresh_ms, 500);
+        assert!(config.alerts.enabled);
+        assert_eq!(config.alerts.cpu_percent, Some(90.0));
+        assert_eq!(config.alerts.memory_percent, Some(95.0));
+        
+        let other_host = Config::load_for_host(&dir.join("config.json"), Some("db1")).unwrap();
+        assert_eq!(other_host.theme, Theme::Light);
+        
+        std::fs::write(dir.join("shared/base.json"), r#"{ "include": ["../config.json"] }"#).unwrap();
+        let err = Config::load_for_host(&dir.join("config.json"), None).unwrap_err();
+        assert!(err.to_string().contains("includes itself"));
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_config_errors_name_the_field() {
+        let dir = std::env::temp_dir().join(format!("kacemon-check-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let check = |contents: &str| {
+            let path = dir.join("config.json");
+            std::fs::write(&path, contents).unwrap();
+            Config::check_file(Some(&path)).map(|(_, warnings)| warnings).map_err(|e| e.to_string())
+        };
+        
+        let typo = check("{\n  \"alerts\": { \"actions\": { \"webhok_url\": \"http://x\" } }\n}").unwrap_err();
+        assert!(typo.contains("alerts.actions.webhok_url: unknown field"), "{}", typo);
+        assert!(typo.contains("line 2"), "{}", typo);
+        
+        let mismatch = check(r#"{ "max_fps": "fast" }"#).unwrap_err();
+        assert!(mismatch.contains("max_fps: invalid type"), "{}", mismatch);
+        assert!(check(r#"{ "themes": { "t": { "accent": "chartreuse" } } }"#).unwrap_err().contains("themes.t.accent"));
+        assert!(check(r#"{ "categories": [{ "name": "db", "pattern": "(postgres" }] }"#).unwrap_err().contains("'db'"));
+        assert_eq!(check(r#"{ "alerts": { "actions": { "event_log": true } } }"#).is_ok(), cfg!(windows));
+        let preset = |body: &str| check(&format!(r#"{{ "presets": [{{ "name": "db", {} }}] }}"#, body));
+        assert!(preset(r#""filter": "user:postgres", "sort": "Memory", "columns": ["PID", "RSS", "NAME"]"#).is_ok());
+        assert!(preset(r#""filter": "cpu>""#).unwrap_err().contains("Preset 'db' filter"));
+        assert!(preset(r#""columns": ["PID", "COLOUR"]"#).unwrap_err().contains("unknown column 'COLOUR'"));
+        
+        // A preset's sort takes that column's usual direction unless it says otherwise, and
+        // one without a sort keeps the current column
+        let view = |body: &str| serde_json::from_str::<config::ViewPreset>(&format!(r#"{{ "name": "v", {} }}"#, body)).unwrap();
+        assert_eq!(view(r#""sort": "Memory""#).sort_order(SortKey::Name, false), (SortKey::Memory, true));
+        assert_eq!(view(r#""sort": "Name""#).sort_order(SortKey::Cpu, true), (SortKey::Name, false));
+        assert_eq!(view(r#""sort": "Memory", "descending": false"#).sort_order(SortKey::Cpu, true), (SortKey::Memory, false));
+        assert_eq!(view(r#""descending": true"#).sort_order(SortKey::Name, false), (SortKey::Name, true));
+        assert_eq!(view(r#""filter": "nginx""#).sort_order(SortKey::Cpu, false), (SortKey::Cpu, false));
+        
+        // Too slow for the TUI is only a warning; headless modes take it
+        assert_eq!(check(r#"{ "refresh_ms": 60000 }"#).unwrap().len(), 1);
+        assert!(check(r#"{ "refresh_ms": 1000 }"#).unwrap().is_empty());
+        
+        // A broken file in a default location fails the load instead of being skipped
+        let (missing, found) = (dir.join("missing.json"), dir.join("config.json"));
+        let first = |contents: &str| {
+            std::fs::write(&found, contents).unwrap();
+            Config::first_config(&[missing.clone(), found.clone(), missing.clone()]).map_err(|e| e.to_string())
+        };
+        assert_eq!(first(r#"{ "max_fps": 30 }"#).unwrap().unwrap()["max_fps"], 30);
+        assert!(first(r#"{ "max_fps": "fast" }"#).unwrap_err().contains("max_fps: invalid type"));
+        assert!(Config::first_config(&[missing]).unwrap().is_none());
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_remote_host_fetch() {
+        use serve::ApiServer;
+        
+        let server = ApiServer::bind("127.0.0.1:0").unwrap();
+        let host = RemoteHost {
+            name: "web1".to_string(),
+            url: format!("http://{}/", server.local_addr()),
+        };
+        assert!(host.validate().is_ok());
+        
+        // The server answers 503 until its first snapshot
+        let error = host.fetch().unwrap_err().to_string();
+        assert!(error.contains("No snapshot collected yet"), "{}", error);
+        
+        let mut alerts = AlertTracker::default();
+        let snapshot = test_snapshot();
+        server.publish(&snapshot, &alerts.evaluate(&AlertThresholds::default(), &snapshot));
+        let (fetched, fetched_alerts) = host.fetch().unwrap();
+        assert_eq!(fetched.system.hostname, "test");
+        assert_eq!(fetched.memory.total, 8_000_000_000);
+        assert!(fetched_alerts.is_empty());
+        
+        let bad = |url: &str| RemoteHost { name: "x".to_string(), url: url.to_string() }.validate().is_err();
+        assert!(bad("https://example.com"));
+        assert!(bad("http://"));
+        assert!(RemoteHost { name: " ".to_string(), url: "http://a:1".to_string() }.validate().is_err());
+    }
+    
+    #[test]
+    fn test_fast_and_slow_collected_together() {
+        // The first snapshot already carries the slow pass, run beside the fast collectors
+        let mut collector = MetricsCollector::builder().gpus(false).build().unwrap();
+        collector.init().unwrap();
+        let snapshot = collector.collect().unwrap();
+        assert!(!snapshot.cpu_cores.is_empty() && snapshot.memory.total > 0);
+        assert!(!snapshot.processes.is_empty());
+        
+        // Slow collectors switched off leave their sections empty and the rest still fill in
+        let mut collector = MetricsCollector::builder()
+            .processes(false)
+            .temperatures(false)
+            .run_queue(false)
+            .cgroups(false)
+            .gpus(false)
+            .build()
+            .unwrap();
+        collector.init().unwrap();
+        let snapshot = collector.collect().unwrap();
+        assert!(snapshot.processes.is_empty() && snapshot.temperatures.is_empty() && snapshot.cgroups.is_empty());
+        assert!(snapshot.run_queue.is_none());
+        assert!(!snapshot.cpu_cores.is_empty() && snapshot.memory.total > 0);
+        assert!(snapshot.collector_failures.is_empty());
+    }
+    
+    #[test]
+    fn test_collector_builder_and_background() {
+        use std::time::Duration;
+        
+        let mut collector = MetricsCollector::builder().processes(false).disks(false).gpus(false).build().unwrap();
+        collector.init().unwrap();
+        let snapshot = collector.collect().unwrap();
+        assert!(snapshot.processes.is_empty() && snapshot.disks.is_empty() && snapshot.gpus.is_empty());
+        assert!(snapshot.memory.total > 0);
+        
+        let background = BackgroundCollector::spawn(collector, Duration::from_millis(50));
+        let first = background.collect_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        // Only the newest of the queued snapshots comes back, then nothing until the next one
+        let latest = background.try_collect().unwrap().unwrap();
+        assert!(latest.timestamp > first.timestamp);
+        background.set_interval(Duration::from_secs(60));
+        std::thread::sleep(Duration::from_millis(100));
+        let _ = background.try_collect();
+        assert!(background.try_collect().is_none());
+    }
+    
+    #[test]
+    fn test_process_collect_limit() {
+        let mut collector = MetricsCollector::builder().process_limit(Some(2)).gpus(false).build().unwrap();
+        collector.init().unwrap();
+        let snapshot = collector.collect().unwrap();
+        assert!(snapshot.processes.len() > 2);
+        assert_eq!(snapshot.processes.iter().filter(|p| !p.partial).count(), 2);
+        
+        // Partial processes stay in the list and can be finished on demand
+        let mut process = snapshot.processes.iter().find(|p| p.partial).unwrap().clone();
+        let json = serde_json::to_value(&process).unwrap();
+        assert_eq!(json["partial"], true);
+        metrics::process::complete_process(&mut process);
+        assert!(!process.partial);
+        assert!(serde_json::to_value(&process).unwrap().get("partial").is_none());
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_thread_counts_and_thread_collector() {
+        use metrics::threads::{parse_thread_stat, ThreadCollector};
+        
+        let stat = "4242 (tokio (worker)) R 1 4242 4242 0 -1 4194304 100 0 0 0 250 50 0 0 20 0 8 0";
+        let (name, state, ticks) = parse_thread_stat(stat).unwrap();
+        assert_eq!((name.as_str(), state, ticks), ("tokio (worker)", ProcessState::Running, 300));
+        
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        let worker = std::thread::spawn(move || stopped.recv());
+        let pid = std::process::id();
+        
+        let mut collector = metrics::ProcessCollector::new().unwrap();
+        let processes = collector.collect().unwrap();
+        assert!(processes.iter().find(|p| p.pid == pid).unwrap().threads >= 2);
+        assert!(!processes.iter().find(|p| p.pid == pid).unwrap().is_kernel_thread);
+        assert!(processes.iter().find(|p| p.pid == pid).unwrap().open_fds >= Some(3));
+        assert!(processes.iter().filter(|p| p.is_kernel_thread).all(|p| p.cmd.is_empty()));
+        
+        let mut threads = ThreadCollector::new(pid);
+        let listed = threads.collect().unwrap();
+        assert!(listed.len() >= 2 && listed.iter().any(|t| t.tid == pid));
+        // Threads are counted, not listed as processes of their own
+        assert!(listed.iter().filter(|t| t.tid != pid).all(|t| processes.iter().all(|p| p.pid != t.tid)));
+        assert!(ThreadCollector::new(u32::MAX).collect().is_err());
+        
+        stop.send(()).unwrap();
+        worker.join().unwrap().unwrap();
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sock_diag_parse() {
+        use metrics::process_net::sock_diag::parse_messages;
+        
+        // One inet_diag_msg for inode 777 carrying a tcp_info attribute, then NLMSG_DONE
+        let mut tcp_info = vec![0u8; 136];
+        tcp_info[120..128].copy_from_slice(&2048u64.to_ne_bytes());
+        tcp_info[128..136].copy_from_slice(&4096u64.to_ne_bytes());
+        let mut diag = vec![0u8; 72];
+        diag[68..72].copy_from_slice(&777u32.to_ne_bytes());
+        diag.extend_from_slice(&(4 + tcp_info.len() as u16).to_ne_bytes());
+        diag.extend_from_slice(&2u16.to_ne_bytes());
+        diag.extend_from_slice(&tcp_info);
+        let mut batch = Vec::new();
+        for (kind, payload) in [(20u16, diag), (3u16, vec![0u8; 4])] {
+            batch.extend_from_slice(&(16 + payload.len() as u32).to_ne_bytes());
+            batch.extend_from_slice(&kind.to_ne_bytes());
+            batch.extend_from_slice(&[0u8; 10]);
+            batch.extend_from_slice(&payload);
+        }
+        
+        let mut sockets = std::collections::HashMap::new();
+        assert!(parse_messages(&batch, &mut sockets));
+        assert_eq!(sockets.get(&777), Some(&(4096, 2048)));
+        // Cut off mid-message: nothing more to read from it
+        sockets.clear();
+        assert!(parse_messages(&batch[..40], &mut sockets));
+        assert!(sockets.is_empty());
+    }
+    
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi_collect_json() {
+        use ffi::*;
+        use std::ffi::CStr;
+        
+        let collector = kacemon_collector_new();
+        assert!(!collector.is_null());
+        unsafe {
+            let json = kacemon_collect_json(collector);
+            assert!(!json.is_null() && kacemon_last_error().is_null());
+            let snapshot: SystemSnapshot = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert!(snapshot.memory.total > 0);
+            kacemon_string_free(json);
+            kacemon_collector_free(collector);
+            
+            assert!(kacemon_collect_json(std::ptr::null_mut()).is_null());
+            assert!(CStr::from_ptr(kacemon_last_error()).to_str().unwrap().contains("NULL"));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{error::Result, metrics::MetricsCollector, model::SystemSnapshot};
+use std::{
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A `MetricsCollector` running on a thread of its own, for callers with a loop of their own
+/// (a UI, an event loop) that must not wait on a collection. The thread stops when this is
+/// dropped.
+///
+/// ```no_run
+/// use kacemon_core::{BackgroundCollector, MetricsCollector};
+/// use std::time::Duration;
+///
+/// let mut collector = MetricsCollector::new()?;
+/// collector.init()?;
+/// let background = BackgroundCollector::spawn(collector, Duration::from_secs(1));
+/// loop {
+///     if let Some(snapshot) = background.try_collect() {
+///         println!("{} processes", snapshot?.processes.len());
+///     }
+///     // ... the caller's own work ...
+///     # break;
+/// }
+/// # Ok::<(), kacemon_core::CoreError>(())
+/// ```
+pub struct BackgroundCollector {
+    snapshots: Receiver<Result<SystemSnapshot>>,
+    intervals: Sender<Duration>,
+}
+
+impl BackgroundCollector {
+    /// Collect right away, then every `interval`
+    pub fn spawn(mut collector: MetricsCollector, interval: Duration) -> Self {
+        let (snapshot_tx, snapshots) = mpsc::channel();
+        let (intervals, interval_rx) = mpsc::channel();
+        thread::spawn(move || run(&mut collector, interval, &interval_rx, &snapshot_tx));
+        Self { snapshots, intervals }
+    }
+
+    /// The newest snapshot collected since the last call, without waiting; None when there
+    /// is nothing new yet. Older snapshots still queued are skipped.
+    pub fn try_collect(&self) -> Option<Result<SystemSnapshot>> {
+        self.snapshots.try_iter().last()
+    }
+
+    /// Wait up to `timeout` for the next snapshot
+    pub fn collect_timeout(&self, timeout: Duration) -> Option<Result<SystemSnapshot>> {
+        self.snapshots.recv_timeout(timeout).ok()
+    }
+
+    /// Change how often snapshots are taken, starting with the next one
+    pub fn set_interval(&self, interval: Duration) {
+        // Only fails once the thread is gone, and then there is nothing to steer
+        let _ = self.intervals.send(interval);
+    }
+}
+
+fn run(
+    collector: &mut MetricsCollector,
+    mut interval: Duration,
+    intervals: &Receiver<Duration>,
+    snapshots: &Sender<Result<SystemSnapshot>>,
+) {
+    loop {
+        let started = Instant::now();
+        if snapshots.send(collector.collect()).is_err() {
+            return;
+        }
+        loop {
+            match intervals.recv_timeout((started + interval).saturating_duration_since(Instant::now())) {
+                Ok(new_interval) => interval = new_interval,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::model::{BootTiming, UnitTiming};
+use std::time::Duration;
+
+/// Units kept from `systemd-analyze blame`; the rest are too fast to matter
+const MAX_UNITS: usize = 50;
+
+/// Boot phases from systemd's manager timestamps over D-Bus, plus the slowest units.
+/// None when systemd (or its bus) isn't available.
+#[cfg(target_os = "linux")]
+pub fn boot_timing() -> Option<BootTiming> {
+    use std::process::Command;
+
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.systemd1",
+            "/org/freedesktop/systemd1",
+            "org.freedesktop.systemd1.Manager",
+            "FirmwareTimestampMonotonic",
+            "LoaderTimestampMonotonic",
+            "InitRDTimestampMonotonic",
+            "UserspaceTimestampMonotonic",
+            "FinishTimestampMonotonic",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut timing = parse_manager_timestamps(&String::from_utf8_lossy(&output.stdout))?;
+
+    // blame asks the same bus for every unit's activation times
+    if let Ok(output) = Command::new("systemd-analyze").args(["blame", "--no-pager"]).output() {
+        timing.slowest_units = parse_blame(&String::from_utf8_lossy(&output.stdout));
+    }
+
+    Some(timing)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn boot_timing() -> Option<BootTiming> {
+    None
+}
+
+/// Turn busctl's "t <usec>" lines (firmware, loader, initrd, userspace, finish) into phases,
+/// the same way `systemd-analyze time` does
+pub fn parse_manager_timestamps(output: &str) -> Option<BootTiming> {
+    let values: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("t ")?.trim().parse().ok())
+        .collect();
+    let [firmware, loader, initrd, userspace, finish] = values[..] else {
+        return None;
+    };
+    if userspace == 0 {
+        return None;
+    }
+
+    // Firmware and loader timestamps count backwards from kernel start
+    let us = Duration::from_micros;
+    Some(BootTiming {
+        firmware: (firmware > 0).then(|| us(firmware.saturating_sub(loader))),
+        loader: (loader > 0).then(|| us(loader)),
+        kernel: us(if initrd > 0 { initrd } else { userspace }),
+        initrd: (initrd > 0).then(|| us(userspace.saturating_sub(initrd))),
+        userspace: (finish > 0).then(|| us(finish.saturating_sub(userspace))),
+        slowest_units: Vec::new(),
+    })
+}
+
+/// Parse `systemd-analyze blame` lines such as "1min 2.345s foo.service" or "512ms bar.mount"
+pub fn parse_blame(output: &str) -> Vec<UnitTiming> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields: Vec<&str> = line.split_whitespace().collect();
+            let name = fields.pop()?;
+            let duration = fields
+                .iter()
+                .map(|field| parse_timespan(field))
+                .sum::<Option<Duration>>()?;
+            (!fields.is_empty()).then(|| UnitTiming {
+                name: name.to_string(),
+                duration,
+            })
+        })
+        .take(MAX_UNITS)
+        .collect()
+}
+
+/// One component of a systemd timespan: "2.345s", "1min", "512ms", "40us"
+fn parse_timespan(field: &str) -> Option<Duration> {
+    let split = field.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = field.split_at(split);
+    let value: f64 = number.parse().ok()?;
+    let seconds = match unit {
+        "us" | "µs" => value / 1_000_000.0,
+        "ms" => value / 1_000.0,
+        "s" => value,
+        "min" => value * 60.0,
+        "h" => value * 3_600.0,
+        "d" => value * 86_400.0,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(seconds))
+}
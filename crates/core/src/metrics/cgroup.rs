@@ -0,0 +1,138 @@
+use crate::{error::Result, model::CgroupUsage};
+use std::{collections::HashMap, time::Instant};
+
+/// Levels below the root that are read: slices, then the services and scopes in them
+const MAX_DEPTH: usize = 2;
+
+/// Per-cgroup CPU, memory and I/O from the cgroup v2 hierarchy (cpu.stat, memory.current,
+/// io.stat), so usage can be seen per slice or service rather than per process. Empty
+/// where there is no cgroup2 mount, including on pure cgroup v1 hosts.
+pub struct CgroupCollector {
+    // cgroup path -> (usage_usec, bytes read, bytes written) at the last pass
+    previous: HashMap<String, (u64, u64, u64)>,
+    previous_at: Option<Instant>,
+}
+
+impl CgroupCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            previous: HashMap::new(),
+            previous_at: None,
+        })
+    }
+
+    pub fn init(&mut self) -> Result<()> {
+        self.collect()?;
+        Ok(())
+    }
+
+    /// Groups sorted by path; rates are zero on the first pass
+    pub fn collect(&mut self) -> Result<Vec<CgroupUsage>> {
+        let Some(root) = cgroup2_mount() else {
+            return Ok(Vec::new());
+        };
+        let now = Instant::now();
+        let elapsed = self.previous_at.map(|at| now.duration_since(at).as_secs_f64().max(0.001));
+
+        let mut groups = Vec::new();
+        let mut counters = HashMap::new();
+        for (path, dir) in walk(&root) {
+            let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+            let cpu_usage_usec = read("cpu.stat").as_deref().and_then(parse_cpu_stat_usage).unwrap_or(0);
+            let (read_bytes, write_bytes) = read("io.stat").as_deref().map(parse_io_stat).unwrap_or_default();
+            let mut usage = CgroupUsage {
+                path: path.clone(),
+                cpu_usage_usec,
+                memory_current: read("memory.current").and_then(|s| s.trim().parse().ok()).unwrap_or(0),
+                memory_max: read("memory.max").as_deref().and_then(parse_memory_max),
+                ..CgroupUsage::default()
+            };
+            // Groups created since the last pass have no baseline yet
+            if let (Some(elapsed), Some(&(previous_cpu, previous_read, previous_write))) = (elapsed, self.previous.get(&path)) {
+                let rate = |now: u64, before: u64| (now.saturating_sub(before) as f64 / elapsed).round() as u64;
+                usage.cpu_percent = (cpu_usage_usec.saturating_sub(previous_cpu) as f64 / 1e6 / elapsed * 100.0) as f32;
+                usage.io_read_bytes_per_sec = rate(read_bytes, previous_read);
+                usage.io_write_bytes_per_sec = rate(write_bytes, previous_write);
+            }
+            counters.insert(path, (cpu_usage_usec, read_bytes, write_bytes));
+            groups.push(usage);
+        }
+
+        groups.sort_by(|a, b| a.path.cmp(&b.path));
+        self.previous = counters;
+        self.previous_at = Some(now);
+        Ok(groups)
+    }
+}
+
+/// Where the cgroup2 hierarchy is mounted: /sys/fs/cgroup on unified hosts,
+/// /sys/fs/cgroup/unified on hybrid ones
+#[cfg(target_os = "linux")]
+fn cgroup2_mount() -> Option<std::path::PathBuf> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let mount_point = fields.nth(1)?;
+        (fields.next()? == "cgroup2").then(|| mount_point.into())
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cgroup2_mount() -> Option<std::path::PathBuf> {
+    None
+}
+
+/// Every group down to `MAX_DEPTH`, as (path below the mount, directory)
+fn walk(root: &std::path::Path) -> Vec<(String, std::path::PathBuf)> {
+    let mut groups = Vec::new();
+    let mut pending = vec![(String::new(), root.to_path_buf(), 0)];
+    while let Some((path, dir, depth)) = pending.pop() {
+        if depth == MAX_DEPTH {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // Groups are the directories; everything else is a control file
+            if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                continue;
+            }
+            let child = format!("{}/{}", path, entry.file_name().to_string_lossy());
+            groups.push((child.clone(), entry.path()));
+            pending.push((child, entry.path(), depth + 1));
+        }
+    }
+    groups
+}
+
+/// `usage_usec` out of cpu.stat
+pub fn parse_cpu_stat_usage(contents: &str) -> Option<u64> {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Bytes read and written, summed over the devices in io.stat
+/// ("8:0 rbytes=1024 wbytes=0 rios=1 wios=0 dbytes=0 dios=0")
+pub fn parse_io_stat(contents: &str) -> (u64, u64) {
+    let mut totals = (0, 0);
+    for field in contents.split_whitespace() {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        let value: u64 = value.parse().unwrap_or(0);
+        match key {
+            "rbytes" => totals.0 += value,
+            "wbytes" => totals.1 += value,
+            _ => {},
+        }
+    }
+    totals
+}
+
+/// memory.max holds "max" when the group has no limit
+fn parse_memory_max(contents: &str) -> Option<u64> {
+    contents.trim().parse().ok()
+}
@@ -0,0 +1,190 @@
+use crate::{error::Result, model::{ConnectionInfo, SocketProtocol, SocketState}};
+
+/// Network socket collector (TCP/UDP, IPv4/IPv6)
+///
+/// Enumerating sockets and mapping them to owning processes walks every
+/// process' file descriptors, so the collector is disabled by default and
+/// only runs while something (e.g. the connections panel) needs the data.
+pub struct ConnectionsCollector {
+    enabled: bool,
+}
+
+impl ConnectionsCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self { enabled: false })
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn collect(&mut self) -> Result<Vec<ConnectionInfo>> {
+        if !self.enabled {
+            return Ok(Vec::new());
+        }
+
+        self.collect_sockets()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn collect_sockets(&self) -> Result<Vec<ConnectionInfo>> {
+        let inode_owners = linux::socket_inode_owners();
+        let mut connections = Vec::new();
+
+        for (path, protocol) in [
+            ("/proc/net/tcp", SocketProtocol::Tcp),
+            ("/proc/net/tcp6", SocketProtocol::Tcp6),
+            ("/proc/net/udp", SocketProtocol::Udp),
+            ("/proc/net/udp6", SocketProtocol::Udp6),
+        ] {
+            // Missing tables (e.g. IPv6 disabled) are not an error
+            let Ok(contents) = std::fs::read_to_string(path) else {
+                continue;
+            };
+
+            for line in contents.lines().skip(1) {
+                if let Some((mut connection, inode)) = linux::parse_socket_line(line, protocol) {
+                    connection.pid = inode_owners.get(&inode).copied();
+                    connections.push(connection);
+                }
+            }
+        }
+
+        Ok(connections)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn collect_sockets(&self) -> Result<Vec<ConnectionInfo>> {
+        // macOS would use libproc's proc_pidfdinfo, Windows GetExtendedTcpTable
+        // For now, report no sockets on these platforms
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) mod linux {
+    use super::{ConnectionInfo, SocketProtocol, SocketState};
+    use std::{
+        collections::HashMap,
+        fs,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    };
+
+    /// Map socket inodes to the PID holding them open
+    pub(crate) fn socket_inode_owners() -> HashMap<u64, u32> {
+        let mut owners = HashMap::new();
+
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return owners;
+        };
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            // Other users' fd directories are unreadable without privileges
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let target = target.to_string_lossy();
+                if let Some(inode) = target
+                    .strip_prefix("socket:[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|inode| inode.parse::<u64>().ok())
+                {
+                    owners.entry(inode).or_insert(pid);
+                }
+            }
+        }
+
+        owners
+    }
+
+    /// Parse one row of /proc/net/{tcp,tcp6,udp,udp6}, returning the socket inode alongside
+    pub(super) fn parse_socket_line(line: &str, protocol: SocketProtocol) -> Option<(ConnectionInfo, u64)> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            return None;
+        }
+
+        let local_address = parse_address(fields[1])?;
+        let remote_address = parse_address(fields[2])?;
+        let state_code = u8::from_str_radix(fields[3], 16).ok()?;
+        let inode = fields[9].parse::<u64>().ok()?;
+
+        let state = match protocol {
+            SocketProtocol::Tcp | SocketProtocol::Tcp6 => tcp_state(state_code),
+            // UDP sockets only report "established" (connected) or "close" (unconnected)
+            SocketProtocol::Udp | SocketProtocol::Udp6 => {
+                if state_code == 0x01 {
+                    SocketState::Established
+                } else {
+                    SocketState::Unconnected
+                }
+            }
+        };
+
+        Some((
+            ConnectionInfo {
+                protocol,
+                local_address,
+                remote_address,
+                state,
+                pid: None,
+            },
+            inode,
+        ))
+    }
+
+    /// Parse the kernel's "ADDR:PORT" hex notation
+    fn parse_address(field: &str) -> Option<SocketAddr> {
+        let (addr_hex, port_hex) = field.split_once(':')?;
+        let port = u16::from_str_radix(port_hex, 16).ok()?;
+
+        // Addresses are printed as native-endian 32-bit words
+        let ip = match addr_hex.len() {
+            8 => {
+                let word = u32::from_str_radix(addr_hex, 16).ok()?;
+                IpAddr::V4(Ipv4Addr::from(word.to_ne_bytes()))
+            }
+            32 => {
+                let mut bytes = [0u8; 16];
+                for (i, chunk) in bytes.chunks_mut(4).enumerate() {
+                    let word = u32::from_str_radix(&addr_hex[i * 8..i * 8 + 8], 16).ok()?;
+                    chunk.copy_from_slice(&word.to_ne_bytes());
+                }
+                IpAddr::V6(Ipv6Addr::from(bytes))
+            }
+            _ => return None,
+        };
+
+        Some(SocketAddr::new(ip, port))
+    }
+
+    fn tcp_state(code: u8) -> SocketState {
+        match code {
+            0x01 => SocketState::Established,
+            0x02 => SocketState::SynSent,
+            0x03 => SocketState::SynRecv,
+            0x04 => SocketState::FinWait1,
+            0x05 => SocketState::FinWait2,
+            0x06 => SocketState::TimeWait,
+            0x07 => SocketState::Close,
+            0x08 => SocketState::CloseWait,
+            0x09 => SocketState::LastAck,
+            0x0A => SocketState::Listen,
+            0x0B => SocketState::Closing,
+            _ => SocketState::Unknown,
+        }
+    }
+}
@@ -5,6 +5,8 @@ use sysinfo::System;
 pub struct CpuCollector {
     sys: System,
     previous_usage: HashMap<usize, f32>,
+    // Base and max clock per core, which don't change while running
+    limits: Vec<(Option<u64>, Option<u64>)>,
 }
 
 impl CpuCollector {
@@ -15,6 +17,7 @@ impl CpuCollector {
         Ok(Self {
             sys,
             previous_usage: HashMap::new(),
+            limits: Vec::new(),
         })
     }
 
@@ -34,6 +37,9 @@ impl CpuCollector {
         self.sys.refresh_cpu();
         
         let mut cores = Vec::new();
+        if self.limits.len() != self.sys.cpus().len() {
+            self.limits = (0..self.sys.cpus().len()).map(frequency_limits).collect();
+        }
         
         for (id, cpu) in self.sys.cpus().iter().enumerate() {
             let usage_percent = cpu.cpu_usage();
@@ -45,6 +51,8 @@ impl CpuCollector {
                 name,
                 usage_percent,
                 frequency,
+                base_frequency: self.limits[id].0,
+                max_frequency: self.limits[id].1,
             });
             
             // Update previous usage for next calculation
@@ -64,3 +72,25 @@ impl CpuCollector {
         total / self.sys.cpus().len() as f32
     }
 }
+
+/// Base and max clock of core `id` from cpufreq. Only some drivers (intel_pstate,
+/// amd-pstate) report a base clock; VMs usually have neither.
+#[cfg(target_os = "linux")]
+fn frequency_limits(id: usize) -> (Option<u64>, Option<u64>) {
+    let read = |name: &str| {
+        std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/cpufreq/{}", id, name))
+            .ok()
+            .and_then(|text| parse_khz(&text))
+    };
+    (read("base_frequency"), read("cpuinfo_max_freq"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn frequency_limits(_id: usize) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// MHz out of a cpufreq file, which holds kHz
+pub fn parse_khz(text: &str) -> Option<u64> {
+    text.trim().parse::<u64>().ok().filter(|&khz| khz > 0).map(|khz| khz / 1000)
+}
@@ -1,10 +1,27 @@
-use crate::{error::Result, model::DiskInfo};
-use std::collections::HashMap;
+use crate::{error::Result, model::{DiskInfo, NvmeHealth}};
+use std::{collections::HashMap, time::{Duration, Instant}};
 use sysinfo::{Disks};
 
+/// How often NVMe health logs are re-read (they change slowly and smartctl is costly)
+const NVME_HEALTH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Cumulative I/O counters for one block device
+#[derive(Debug, Clone, Copy, Default)]
+struct DiskIoCounters {
+    read_bytes: u64,
+    write_bytes: u64,
+    read_ops: u64,
+    write_ops: u64,
+    read_time_ms: u64,
+    write_time_ms: u64,
+    io_time_ms: u64,  // time the device had I/O in flight
+}
+
 pub struct DiskCollector {
     disks: Disks,
-    previous_stats: HashMap<String, (u64, u64)>, // (read_bytes, write_bytes)
+    previous_stats: HashMap<String, DiskIoCounters>,
+    previous_collect: Option<Instant>,
+    nvme_health: NvmeHealthCache,
 }
 
 impl DiskCollector {
@@ -14,6 +31,8 @@ impl DiskCollector {
         Ok(Self {
             disks,
             previous_stats: HashMap::new(),
+            previous_collect: None,
+            nvme_health: NvmeHealthCache::default(),
         })
     }
 
@@ -22,13 +41,14 @@ impl DiskCollector {
         self.disks.refresh();
         
         // Store initial I/O stats for delta calculation
+        // Note: sysinfo doesn't provide disk I/O stats, so these come from platform-specific sources
+        let io_stats = read_disk_io_stats();
         for disk in &self.disks {
             let name = disk.name().to_string_lossy().to_string();
-            // Note: sysinfo doesn't provide disk I/O stats on all platforms
-            // We'll implement platform-specific collection where possible
-            let (read_bytes, write_bytes) = self.get_disk_io_stats(&name);
-            self.previous_stats.insert(name, (read_bytes, write_bytes));
+            let counters = io_stats.get(device_name(&name)).copied().unwrap_or_default();
+            self.previous_stats.insert(name, counters);
         }
+        self.previous_collect = Some(Instant::now());
         
         Ok(())
     }
@@ -36,6 +56,13 @@ impl DiskCollector {
     pub fn collect(&mut self) -> Result<Vec<DiskInfo>> {
         self.disks.refresh();
         
+        let io_stats = read_disk_io_stats();
+        let now = Instant::now();
+        let elapsed_ms = self.previous_collect
+            .map(|previous| now.duration_since(previous).as_millis() as u64)
+            .unwrap_or(0);
+        self.previous_collect = Some(now);
+        
         let mut disks = Vec::new();
         
         for disk in &self.disks {
@@ -47,20 +74,32 @@ impl DiskCollector {
             let used_space = total_space.saturating_sub(available_space);
             
             // Get current I/O stats
-            let (read_bytes, write_bytes) = self.get_disk_io_stats(&name);
+            let counters = io_stats.get(device_name(&name)).copied().unwrap_or_default();
+            let previous = self.previous_stats.get(&name).copied();
             
             // Calculate deltas
-            let (read_bytes_delta, write_bytes_delta) = if let Some((prev_read, prev_write)) = self.previous_stats.get(&name) {
-                (
-                    read_bytes.saturating_sub(*prev_read),
-                    write_bytes.saturating_sub(*prev_write),
-                )
-            } else {
-                (0, 0)
+            let (read_bytes_delta, write_bytes_delta, latency_ms, busy_percent) = match previous {
+                Some(prev) => {
+                    let ops = (counters.read_ops + counters.write_ops)
+                        .saturating_sub(prev.read_ops + prev.write_ops);
+                    let io_wait_ms = (counters.read_time_ms + counters.write_time_ms)
+                        .saturating_sub(prev.read_time_ms + prev.write_time_ms);
+                    let busy_ms = counters.io_time_ms.saturating_sub(prev.io_time_ms);
+                    (
+                        counters.read_bytes.saturating_sub(prev.read_bytes),
+                        counters.write_bytes.saturating_sub(prev.write_bytes),
+                        (ops > 0).then(|| io_wait_ms as f32 / ops as f32),
+                        (elapsed_ms > 0 && io_stats.contains_key(device_name(&name)))
+                            .then(|| (busy_ms as f32 / elapsed_ms as f32 * 100.0).min(100.0)),
+                    )
+                }
+                None => (0, 0, None, None),
             };
             
             // Update previous stats
-            self.previous_stats.insert(name.clone(), (read_bytes, write_bytes));
+            self.previous_stats.insert(name.clone(), counters);
+            
+            let nvme_health = self.nvme_health.get(&name);
             
             disks.push(DiskInfo {
                 name,
@@ -69,57 +108,207 @@ impl DiskCollector {
                 total_space,
                 used_space,
                 available_space,
-                read_bytes,
-                write_bytes,
+                read_bytes: counters.read_bytes,
+                write_bytes: counters.write_bytes,
                 read_bytes_delta,
                 write_bytes_delta,
+                latency_ms,
+                busy_percent,
+                nvme_health,
             });
         }
         
         Ok(disks)
     }
+}
 
-    #[cfg(target_os = "linux")]
-    fn get_disk_io_stats(&self, disk_name: &str) -> (u64, u64) {
-        #[cfg(feature = "linux_procfs")]
-        {
-            if let Ok(diskstats) = procfs::diskstats() {
-                // Extract device name from path (e.g., "/dev/sda1" -> "sda1")
-                let device_name = disk_name
-                    .strip_prefix("/dev/")
-                    .unwrap_or(disk_name);
-                
-                for stat in diskstats {
-                    if stat.name == device_name {
-                        // Convert sectors to bytes (assuming 512 bytes per sector)
-                        let read_bytes = stat.sectors_read * 512;
-                        let write_bytes = stat.sectors_written * 512;
-                        return (read_bytes, write_bytes);
-                    }
-                }
+/// Strip the /dev/ prefix from a disk name (e.g., "/dev/sda1" -> "sda1")
+fn device_name(disk_name: &str) -> &str {
+    disk_name.strip_prefix("/dev/").unwrap_or(disk_name)
+}
+
+/// Get the whole-disk device a partition belongs to (e.g. "sda1" -> "sda", "nvme0n1p2" -> "nvme0n1")
+#[cfg(target_os = "linux")]
+pub fn parent_device(disk_name: &str) -> String {
+    let device = device_name(disk_name);
+    
+    // sysfs knows the answer: /sys/class/block/<part> links into its parent's directory
+    if let Ok(target) = std::fs::canonicalize(format!("/sys/class/block/{}", device)) {
+        if std::path::Path::new(&format!("{}/partition", target.display())).exists() {
+            if let Some(parent) = target.parent().and_then(|p| p.file_name()) {
+                return parent.to_string_lossy().into_owned();
             }
         }
+    }
+    
+    device.to_string()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn parent_device(disk_name: &str) -> String {
+    device_name(disk_name).to_string()
+}
+
+/// List the partitions of a whole-disk device
+#[cfg(target_os = "linux")]
+pub fn device_partitions(device: &str) -> Vec<String> {
+    let mut partitions: Vec<String> = std::fs::read_dir(format!("/sys/block/{}", device))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("partition").exists())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    partitions.sort();
+    partitions
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn device_partitions(_device: &str) -> Vec<String> {
+    Vec::new()
+}
+
+/// Read cumulative I/O counters for all block devices, keyed by device name
+#[cfg(target_os = "linux")]
+fn read_disk_io_stats() -> HashMap<String, DiskIoCounters> {
+    let mut stats = HashMap::new();
+    
+    let Ok(contents) = std::fs::read_to_string("/proc/diskstats") else {
+        return stats;
+    };
+    
+    // major minor name reads merged sectors_read ms_reading writes merged sectors_written ms_writing in_flight ms_io ...
+    for line in contents.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 13 {
+            continue;
+        }
+        let field = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
         
-        // Fallback: return zeros if procfs is not available
-        (0, 0)
+        // Sectors are always 512 bytes in diskstats regardless of the device's block size
+        stats.insert(fields[2].to_string(), DiskIoCounters {
+            read_ops: field(3),
+            read_bytes: field(5) * 512,
+            read_time_ms: field(6),
+            write_ops: field(7),
+            write_bytes: field(9) * 512,
+            write_time_ms: field(10),
+            io_time_ms: field(12),
+        });
     }
+    
+    stats
+}
 
-    #[cfg(target_os = "macos")]
-    fn get_disk_io_stats(&self, _disk_name: &str) -> (u64, u64) {
-        // macOS implementation would use IOKit
-        // For now, return zeros as a placeholder
-        (0, 0)
+#[cfg(target_os = "macos")]
+fn read_disk_io_stats() -> HashMap<String, DiskIoCounters> {
+    // macOS implementation would use IOKit
+    // For now, return no counters as a placeholder
+    HashMap::new()
+}
+
+#[cfg(target_os = "windows")]
+fn read_disk_io_stats() -> HashMap<String, DiskIoCounters> {
+    // Windows implementation would use Performance Counters or WMI
+    // For now, return no counters as a placeholder
+    HashMap::new()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read_disk_io_stats() -> HashMap<String, DiskIoCounters> {
+    HashMap::new()
+}
+
+/// Per-controller cache of NVMe health logs read through `smartctl --json`
+#[derive(Default)]
+struct NvmeHealthCache {
+    entries: HashMap<String, (Instant, Option<NvmeHealth>)>,
+    smartctl_missing: bool,
+}
+
+impl NvmeHealthCache {
+    /// Get health for the controller backing a disk, if it is an NVMe device
+    fn get(&mut self, disk_name: &str) -> Option<NvmeHealth> {
+        let controller = nvme_controller(disk_name)?;
+        
+        if let Some((read_at, health)) = self.entries.get(&controller) {
+            if read_at.elapsed() < NVME_HEALTH_INTERVAL {
+                return health.clone();
+            }
+        }
+        
+        let health = if self.smartctl_missing {
+            None
+        } else {
+            self.read_health(&controller)
+        };
+        self.entries.insert(controller, (Instant::now(), health.clone()));
+        health
     }
+    
+    fn read_health(&mut self, controller: &str) -> Option<NvmeHealth> {
+        let output = match std::process::Command::new("smartctl")
+            .args(["--json", "-A", &format!("/dev/{}", controller)])
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => {
+                // Not installed; don't keep trying every minute
+                self.smartctl_missing = true;
+                return None;
+            }
+        };
+        
+        // smartctl uses non-zero exit bits for drive warnings, so parse regardless
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+        parse_nvme_health(controller, &json)
+    }
+}
 
-    #[cfg(target_os = "windows")]
-    fn get_disk_io_stats(&self, _disk_name: &str) -> (u64, u64) {
-        // Windows implementation would use Performance Counters or WMI
-        // For now, return zeros as a placeholder
-        (0, 0)
+/// Map a disk device name to its NVMe controller (e.g. "/dev/nvme0n1p2" -> "nvme0")
+pub(crate) fn nvme_controller(disk_name: &str) -> Option<String> {
+    let device = disk_name.strip_prefix("/dev/").unwrap_or(disk_name);
+    let rest = device.strip_prefix("nvme")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
     }
+    Some(format!("nvme{}", digits))
+}
 
-    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
-    fn get_disk_io_stats(&self, _disk_name: &str) -> (u64, u64) {
-        (0, 0)
+/// Extract the NVMe health information log from smartctl JSON output
+fn parse_nvme_health(controller: &str, json: &serde_json::Value) -> Option<NvmeHealth> {
+    let log = json.get("nvme_smart_health_information_log")?;
+    let field = |name: &str| log.get(name).and_then(|v| v.as_u64());
+    
+    Some(NvmeHealth {
+        controller: controller.to_string(),
+        temperature: field("temperature").map(|t| t as f32),
+        percentage_used: field("percentage_used").unwrap_or(0).min(255) as u8,
+        available_spare: field("available_spare").unwrap_or(100).min(100) as u8,
+        media_errors: field("media_errors").unwrap_or(0),
+        critical_warning: field("critical_warning").unwrap_or(0) as u8,
+    })
+}
+
+/// Get wear status based on the drive's life-used estimate
+impl NvmeHealth {
+    pub fn get_status(&self) -> NvmeWearStatus {
+        if self.critical_warning != 0 || self.percentage_used >= 95 {
+            NvmeWearStatus::Critical
+        } else if self.percentage_used >= 80 || self.media_errors > 0 {
+            NvmeWearStatus::Warning
+        } else {
+            NvmeWearStatus::Healthy
+        }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NvmeWearStatus {
+    Healthy,  // < 80% life used, no errors
+    Warning,  // >= 80% life used or media errors logged
+    Critical, // >= 95% life used or controller critical warning
+}
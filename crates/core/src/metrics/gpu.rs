@@ -0,0 +1,119 @@
+use crate::{error::Result, model::{GpuInfo, ProcessGpuUsage}};
+use std::{collections::HashMap, process::Command};
+
+const MIB: u64 = 1024 * 1024;
+
+/// GPU collector backed by `nvidia-smi` query output.
+///
+/// Hosts without an NVIDIA driver simply report no devices; the collector
+/// stops probing after the first failed invocation so it costs nothing on
+/// machines without GPUs.
+pub struct GpuCollector {
+    available: bool,
+    uuid_to_index: HashMap<String, u32>,
+}
+
+impl GpuCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            available: true,
+            uuid_to_index: HashMap::new(),
+        })
+    }
+
+    pub fn collect(&mut self) -> Result<Vec<GpuInfo>> {
+        if !self.available {
+            return Ok(Vec::new());
+        }
+
+        let output = match run_nvidia_smi(&[
+            "--query-gpu=index,uuid,name,utilization.gpu,memory.used,memory.total,temperature.gpu",
+            "--format=csv,noheader,nounits",
+        ]) {
+            Some(output) => output,
+            None => {
+                self.available = false;
+                return Ok(Vec::new());
+            }
+        };
+
+        let gpus: Vec<GpuInfo> = output.lines().filter_map(parse_gpu_line).collect();
+
+        self.uuid_to_index = gpus.iter().map(|gpu| (gpu.uuid.clone(), gpu.index)).collect();
+
+        Ok(gpus)
+    }
+
+    /// Get per-process VRAM usage keyed by PID
+    ///
+    /// Must be called after `collect` so GPU UUIDs can be mapped to indices.
+    pub fn collect_process_usage(&mut self) -> Result<HashMap<u32, Vec<ProcessGpuUsage>>> {
+        let mut usage: HashMap<u32, Vec<ProcessGpuUsage>> = HashMap::new();
+
+        if !self.available || self.uuid_to_index.is_empty() {
+            return Ok(usage);
+        }
+
+        let output = match run_nvidia_smi(&[
+            "--query-compute-apps=pid,gpu_uuid,used_memory",
+            "--format=csv,noheader,nounits",
+        ]) {
+            Some(output) => output,
+            None => return Ok(usage),
+        };
+
+        for line in output.lines() {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() < 3 {
+                continue;
+            }
+
+            let Ok(pid) = fields[0].parse::<u32>() else {
+                continue;
+            };
+            let Some(&gpu_index) = self.uuid_to_index.get(fields[1]) else {
+                continue;
+            };
+            let memory_used = parse_number(fields[2]).map(|mib| mib as u64 * MIB).unwrap_or(0);
+
+            usage.entry(pid).or_default().push(ProcessGpuUsage {
+                gpu_index,
+                memory_used,
+            });
+        }
+
+        Ok(usage)
+    }
+}
+
+/// Run `nvidia-smi` with the given arguments, returning stdout on success
+fn run_nvidia_smi(args: &[&str]) -> Option<String> {
+    let output = Command::new("nvidia-smi").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Parse one line of `--query-gpu` CSV output
+fn parse_gpu_line(line: &str) -> Option<GpuInfo> {
+    let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+    if fields.len() < 7 {
+        return None;
+    }
+
+    Some(GpuInfo {
+        index: fields[0].parse().ok()?,
+        uuid: fields[1].to_string(),
+        name: fields[2].to_string(),
+        utilization_percent: parse_number(fields[3]).unwrap_or(0.0),
+        memory_used: parse_number(fields[4]).map(|mib| mib as u64 * MIB).unwrap_or(0),
+        memory_total: parse_number(fields[5]).map(|mib| mib as u64 * MIB).unwrap_or(0),
+        temperature: parse_number(fields[6]),
+    })
+}
+
+/// Parse a numeric CSV field, treating "[N/A]" and "[Not Supported]" as missing
+fn parse_number(field: &str) -> Option<f32> {
+    field.parse::<f32>().ok()
+}
@@ -0,0 +1,216 @@
+use crate::{
+    error::{CoreError, Result},
+    export::utc_time,
+    model::LogEntry,
+};
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Entries kept; older ones drop off the front
+const MAX_ENTRIES: usize = 500;
+
+/// Error and fault messages only; the unified log has no syslog-style priority filter
+#[cfg(target_os = "macos")]
+const PREDICATE: &str = "messageType == error OR messageType == fault";
+
+/// The tool that follows the log, started with the last few dozen errors where it can
+#[cfg(target_os = "linux")]
+const FOLLOW: &[&str] = &["journalctl", "--follow", "--priority=err", "--lines=50", "--output=json", "--no-pager"];
+#[cfg(target_os = "macos")]
+const FOLLOW: &[&str] = &["log", "stream", "--style", "ndjson", "--predicate", PREDICATE];
+
+/// Run once before following, for a tool that can't look back by itself
+#[cfg(target_os = "linux")]
+const BACKLOG: Option<&[&str]> = None;
+#[cfg(target_os = "macos")]
+const BACKLOG: Option<&[&str]> = Some(&["log", "show", "--last", "5m", "--style", "ndjson", "--predicate", PREDICATE]);
+
+/// Error-level entries from the system log as they are written: `journalctl --follow` on
+/// Linux, `log show` then `log stream` on macOS. The tool runs in the background only while
+/// the collector is enabled (by the log view) and `collect` copies what it has read so far.
+/// Elsewhere it reports nothing.
+pub struct LogCollector {
+    enabled: bool,
+    tail: Option<LogTail>,
+}
+
+/// A running follow command and what its reader thread has made of it
+struct LogTail {
+    child: Child,
+    feed: Arc<Mutex<Feed>>,
+}
+
+#[derive(Default)]
+struct Feed {
+    entries: VecDeque<LogEntry>,
+    /// Why the tool stopped, once it has
+    error: Option<String>,
+}
+
+impl LogCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self { enabled: false, tail: None })
+    }
+
+    /// Disabling stops the tool; enabling starts it again on the next `collect`
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.tail = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Entries read so far, oldest first; empty while disabled
+    pub fn collect(&mut self) -> Result<Vec<LogEntry>> {
+        if !self.enabled || !cfg!(any(target_os = "linux", target_os = "macos")) {
+            return Ok(Vec::new());
+        }
+
+        let tail = match &mut self.tail {
+            Some(tail) => tail,
+            None => self.tail.insert(LogTail::start()?),
+        };
+        let feed = lock(&tail.feed);
+        match &feed.error {
+            Some(error) => Err(CoreError::system_info(error.clone())),
+            None => Ok(feed.entries.iter().cloned().collect()),
+        }
+    }
+}
+
+impl LogTail {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn start() -> Result<Self> {
+        let mut child = Command::new(FOLLOW[0])
+            .args(&FOLLOW[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CoreError::unsupported_platform(format!("{}: {}", FOLLOW[0], e)))?;
+        let feed = Arc::new(Mutex::new(Feed::default()));
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let shared = Arc::clone(&feed);
+        thread::spawn(move || {
+            if let Some(backlog) = BACKLOG {
+                if let Ok(output) = Command::new(backlog[0]).args(&backlog[1..]).stdin(Stdio::null()).output() {
+                    read_entries(&output.stdout[..], &shared);
+                }
+            }
+            if let Some(stdout) = stdout {
+                read_entries(stdout, &shared);
+            }
+            // Only reached once the tool exits, which it doesn't unless it can't read the log
+            let mut complaint = String::new();
+            if let Some(mut stderr) = stderr {
+                let _ = stderr.read_to_string(&mut complaint);
+            }
+            let reason = complaint.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("exited").to_string();
+            lock(&shared).error = Some(format!("{}: {}", FOLLOW[0], reason));
+        });
+        Ok(Self { child, feed })
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    fn start() -> Result<Self> {
+        Err(CoreError::unsupported_platform("no system log to follow on this platform"))
+    }
+}
+
+impl Drop for LogTail {
+    fn drop(&mut self) {
+        // The reader thread sees the end of the pipe and finishes on its own
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn lock(feed: &Mutex<Feed>) -> std::sync::MutexGuard<'_, Feed> {
+    feed.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Parse lines until the end of `output`, keeping the newest `MAX_ENTRIES`
+fn read_entries(output: impl Read, feed: &Mutex<Feed>) {
+    for line in BufReader::new(output).lines().map_while(std::io::Result::ok) {
+        if let Some(entry) = parse_log_line(&line) {
+            let mut feed = lock(feed);
+            if feed.entries.len() == MAX_ENTRIES {
+                feed.entries.pop_front();
+            }
+            feed.entries.push_back(entry);
+        }
+    }
+}
+
+/// An entry out of one line of `journalctl --output=json` or `log stream --style ndjson`.
+/// Anything else, like the banner `log stream` starts with, is None.
+pub fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let fields: serde_json::Value = serde_json::from_str(line).ok()?;
+    let text = |key: &str| fields.get(key).and_then(|value| value.as_str());
+
+    if let Some(micros) = text("__REALTIME_TIMESTAMP") {
+        const PRIORITIES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+        let message = match fields.get("MESSAGE")? {
+            serde_json::Value::String(message) => message.clone(),
+            // Messages that aren't valid UTF-8 come as an array of bytes
+            serde_json::Value::Array(bytes) => {
+                let bytes: Vec<u8> = bytes.iter().filter_map(|byte| byte.as_u64()).map(|byte| byte as u8).collect();
+                String::from_utf8_lossy(&bytes).into_owned()
+            },
+            _ => return None,
+        };
+        return Some(LogEntry {
+            time: UNIX_EPOCH + Duration::from_micros(micros.parse().ok()?),
+            source: text("SYSLOG_IDENTIFIER").or_else(|| text("_COMM")).unwrap_or_default().to_string(),
+            pid: text("_PID").or_else(|| text("SYSLOG_PID")).and_then(|pid| pid.parse().ok()),
+            level: text("PRIORITY").and_then(|p| PRIORITIES.get(p.parse::<usize>().ok()?)).unwrap_or(&"err").to_string(),
+            message: message.trim_end().to_string(),
+        });
+    }
+
+    if text("eventType") != Some("logEvent") {
+        return None;
+    }
+    let image = text("processImagePath").unwrap_or_default();
+    Some(LogEntry {
+        time: parse_unified_timestamp(text("timestamp")?)?,
+        source: image.rsplit('/').next().unwrap_or(image).to_string(),
+        pid: fields.get("processID").and_then(|pid| pid.as_u64()).and_then(|pid| u32::try_from(pid).ok()),
+        level: text("messageType").unwrap_or("error").to_lowercase(),
+        message: text("eventMessage").unwrap_or_default().trim_end().to_string(),
+    })
+}
+
+/// `2024-01-02 10:11:12.345678-0800`, the unified log's local time with its UTC offset
+fn parse_unified_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, rest) = timestamp.split_once(' ')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+
+    let offset_at = rest.rfind(['+', '-'])?;
+    let (clock, offset) = rest.split_at(offset_at);
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let mut clock = clock.splitn(3, ':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    let nanos = format!("{:0<9}", fraction.get(..9).unwrap_or(fraction)).parse::<u32>().ok()?;
+
+    let offset_minutes = offset.get(1..3)?.parse::<u64>().ok()? * 60 + offset.get(3..5)?.parse::<u64>().ok()?;
+    let local = utc_time(year, month, day, hour, minute, second)? + Duration::from_nanos(nanos as u64);
+    // Local time is UTC plus the offset, so UTC is local minus it
+    let shift = Duration::from_secs(offset_minutes * 60);
+    if offset.starts_with('+') {
+        local.checked_sub(shift)
+    } else {
+        local.checked_add(shift)
+    }
+}
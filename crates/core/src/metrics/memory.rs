@@ -3,13 +3,22 @@ use sysinfo::System;
 
 pub struct MemoryCollector {
     sys: System,
+    previous_swap_pages: Option<(u64, u64)>,  // cumulative pages swapped in / out
 }
 
 impl MemoryCollector {
     pub fn new() -> Result<Self> {
         let sys = System::new();
         
-        Ok(Self { sys })
+        Ok(Self {
+            sys,
+            previous_swap_pages: None,
+        })
+    }
+
+    pub fn init(&mut self) -> Result<()> {
+        self.previous_swap_pages = read_swap_pages();
+        Ok(())
     }
 
     pub fn collect(&mut self) -> Result<MemoryInfo> {
@@ -27,6 +36,15 @@ impl MemoryCollector {
         let swap_total = self.sys.total_swap();
         let swap_used = self.sys.used_swap();
         let swap_free = self.sys.free_swap();
+        
+        let swap_pages = read_swap_pages();
+        let (swap_in_pages, swap_out_pages) = match (swap_pages, self.previous_swap_pages) {
+            (Some((now_in, now_out)), Some((prev_in, prev_out))) => {
+                (now_in.saturating_sub(prev_in), now_out.saturating_sub(prev_out))
+            }
+            _ => (0, 0),
+        };
+        self.previous_swap_pages = swap_pages;
 
         Ok(MemoryInfo {
             total,
@@ -38,6 +56,8 @@ impl MemoryCollector {
             swap_total,
             swap_used,
             swap_free,
+            swap_in_pages,
+            swap_out_pages,
         })
     }
 
@@ -55,13 +75,12 @@ impl MemoryCollector {
         }
         
         // Fallback estimation
-        let total = self.sys.total_memory();
         let used = self.sys.used_memory();
         let free = self.sys.free_memory();
         let available = self.sys.available_memory();
         
         // Rough estimation: cached = available - free, buffers = small portion of used
-        let cached = if available > free { available - free } else { 0 };
+        let cached = available.saturating_sub(free);
         let buffers = (used as f64 * 0.05) as u64; // Rough estimate: 5% of used memory
         
         (buffers, cached)
@@ -74,3 +93,20 @@ impl MemoryCollector {
         (0, 0)
     }
 }
+
+/// Cumulative pswpin / pswpout from /proc/vmstat
+#[cfg(target_os = "linux")]
+fn read_swap_pages() -> Option<(u64, u64)> {
+    let vmstat = std::fs::read_to_string("/proc/vmstat").ok()?;
+    let counter = |name: &str| {
+        vmstat
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.trim().parse::<u64>().ok())
+    };
+    Some((counter("pswpin")?, counter("pswpout")?))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_swap_pages() -> Option<(u64, u64)> {
+    None
+}
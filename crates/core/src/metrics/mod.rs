@@ -1,21 +1,397 @@
+pub mod background;
+pub mod boot;
+pub mod cgroup;
+pub mod connections;
 pub mod cpu;
 pub mod disk;
+pub mod gpu;
+pub mod logs;
 pub mod memory;
 pub mod network;
+pub mod pressure;
 pub mod process;
+pub mod pods;
+pub mod process_net;
+pub mod scan;
+pub(crate) mod restarts;
+pub mod schedstat;
+pub mod services;
 pub mod system;
 pub mod temperature;
+pub mod threads;
 
+pub use background::BackgroundCollector;
+pub use cgroup::CgroupCollector;
+pub use connections::ConnectionsCollector;
 pub use cpu::CpuCollector;
 pub use disk::DiskCollector;
+pub use gpu::GpuCollector;
+pub use logs::LogCollector;
 pub use memory::MemoryCollector;
 pub use network::NetworkCollector;
+pub use pressure::PressureCollector;
 pub use process::ProcessCollector;
+pub use process_net::ProcessNetCollector;
+pub use schedstat::SchedLatencyCollector;
+pub use services::ServicesCollector;
 pub use system::SystemCollector;
 pub use temperature::TemperatureCollector;
+pub use threads::ThreadCollector;
 
-use crate::{error::Result, model::SystemSnapshot};
-use std::time::SystemTime;
+use crate::{
+    error::Result,
+    model::{
+        CgroupUsage, CollectorFailure, ConnectionInfo, DiskInfo, GpuInfo, KillSignal, LogEntry, NetworkInfo, PressureInfo,
+        ProcessInfo, RunQueueLatency, ServiceInfo, SystemSnapshot, TemperatureInfo,
+    },
+};
+use std::{
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Collectors that walk every process or shell out to tools are held to about once a second,
+/// however fast the refresh; in between, their last results are reused. Just under a second
+/// so a 1s refresh never skips a pass.
+const SLOW_COLLECTION_INTERVAL: Duration = Duration::from_millis(900);
+
+/// One optional collector's last good output. A collector that starts failing (a sensor
+/// unplugged, nvidia-smi gone) keeps its previous output in the snapshot and reports the
+/// error alongside, instead of failing the whole snapshot.
+#[derive(Default)]
+struct Section<T> {
+    value: T,
+    succeeded_at: Option<SystemTime>,
+    error: Option<String>,
+}
+
+impl<T> Section<T> {
+    fn update(&mut self, result: Result<T>, now: SystemTime) {
+        match result {
+            Ok(value) => {
+                self.value = value;
+                self.succeeded_at = Some(now);
+                self.error = None;
+            },
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn failure(&self, collector: &str) -> Option<CollectorFailure> {
+        self.error.as_ref().map(|error| CollectorFailure {
+            collector: collector.to_string(),
+            error: error.clone(),
+            last_success: self.succeeded_at,
+        })
+    }
+}
+
+/// Results of the fast optional collectors
+#[derive(Default)]
+struct FastMetrics {
+    disks: Section<Vec<DiskInfo>>,
+    networks: Section<Vec<NetworkInfo>>,
+    pressure: Section<PressureInfo>,
+}
+
+/// Results of the slow collectors, kept between passes
+#[derive(Default)]
+struct SlowMetrics {
+    /// None until the first pass and after `expire_slow_metrics`
+    collected_at: Option<Instant>,
+    temperatures: Section<Vec<TemperatureInfo>>,
+    gpus: Section<Vec<GpuInfo>>,
+    connections: Section<Vec<ConnectionInfo>>,
+    services: Section<Vec<ServiceInfo>>,
+    log_entries: Section<Vec<LogEntry>>,
+    run_queue: Section<Option<RunQueueLatency>>,
+    cgroups: Section<Vec<CgroupUsage>>,
+    processes: Section<Vec<ProcessInfo>>,
+    /// Nothing kept; per-process rates are attached to `processes`
+    process_network: Section<()>,
+}
+
+/// Optional collectors and whether each one runs; CPU, memory and system info always do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EnabledCollectors {
+    disks: bool,
+    networks: bool,
+    pressure: bool,
+    temperatures: bool,
+    gpus: bool,
+    run_queue: bool,
+    cgroups: bool,
+    processes: bool,
+}
+
+/// Chooses which collectors a `MetricsCollector` runs. Everything but socket enumeration, the
+/// systemd service list and the system log feed is on by default; a disabled collector leaves
+/// its part of the snapshot empty.
+///
+/// ```no_run
+/// let mut collector = kacemon_core::MetricsCollector::builder()
+///     .processes(false)
+///     .gpus(false)
+///     .build()?;
+/// collector.init()?;
+/// let snapshot = collector.collect()?;
+/// println!("{} cores, {} bytes used", snapshot.cpu_cores.len(), snapshot.memory.used);
+/// # Ok::<(), kacemon_core::CoreError>(())
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct CollectorBuilder {
+    enabled: EnabledCollectors,
+    connections: bool,
+    services: bool,
+    log_feed: bool,
+    process_network: bool,
+    memory_breakdown: bool,
+    process_limit: Option<usize>,
+    hog_threshold: f32,
+}
+
+impl Default for CollectorBuilder {
+    fn default() -> Self {
+        Self {
+            enabled: EnabledCollectors {
+                disks: true,
+                networks: true,
+                pressure: true,
+                temperatures: true,
+                gpus: true,
+                run_queue: true,
+                cgroups: true,
+                processes: true,
+            },
+            connections: false,
+            services: false,
+            log_feed: false,
+            process_network: false,
+            memory_breakdown: false,
+            process_limit: None,
+            hog_threshold: process::DEFAULT_HOG_THRESHOLD,
+        }
+    }
+}
+
+impl CollectorBuilder {
+    /// Per-disk usage, I/O rates and NVMe health
+    pub fn disks(mut self, enabled: bool) -> Self {
+        self.enabled.disks = enabled;
+        self
+    }
+
+    /// Per-interface traffic
+    pub fn networks(mut self, enabled: bool) -> Self {
+        self.enabled.networks = enabled;
+        self
+    }
+
+    /// Linux pressure stall information
+    pub fn pressure(mut self, enabled: bool) -> Self {
+        self.enabled.pressure = enabled;
+        self
+    }
+
+    /// Hardware sensors
+    pub fn temperatures(mut self, enabled: bool) -> Self {
+        self.enabled.temperatures = enabled;
+        self
+    }
+
+    /// GPUs and their per-process memory, through nvidia-smi
+    pub fn gpus(mut self, enabled: bool) -> Self {
+        self.enabled.gpus = enabled;
+        self
+    }
+
+    /// Run-queue latency from per-thread schedstat
+    pub fn run_queue(mut self, enabled: bool) -> Self {
+        self.enabled.run_queue = enabled;
+        self
+    }
+
+    /// Per-slice and per-service usage from the cgroup v2 hierarchy
+    pub fn cgroups(mut self, enabled: bool) -> Self {
+        self.enabled.cgroups = enabled;
+        self
+    }
+
+    /// The process list, usually the most expensive collector
+    pub fn processes(mut self, enabled: bool) -> Self {
+        self.enabled.processes = enabled;
+        self
+    }
+
+    /// Read only the `limit` busiest processes by CPU in full. The rest are still listed, so
+    /// counts stay right, but marked `partial`; `metrics::process::complete_process` reads
+    /// the missing fields for one of them when needed.
+    pub fn process_limit(mut self, limit: Option<usize>) -> Self {
+        self.process_limit = limit;
+        self
+    }
+
+    /// CPU percent a process has to stay above for its `busy_since` to be set
+    pub fn hog_threshold(mut self, percent: f32) -> Self {
+        self.hog_threshold = percent;
+        self
+    }
+
+    /// Open sockets per process; can be switched later with `set_connections_enabled`
+    pub fn connections(mut self, enabled: bool) -> Self {
+        self.connections = enabled;
+        self
+    }
+
+    /// systemd service units; can be switched later with `set_services_enabled`
+    pub fn services(mut self, enabled: bool) -> Self {
+        self.services = enabled;
+        self
+    }
+
+    /// Error-level system log entries; can be switched later with `set_log_feed_enabled`
+    pub fn log_feed(mut self, enabled: bool) -> Self {
+        self.log_feed = enabled;
+        self
+    }
+
+    /// TCP throughput per process; can be switched later with `set_process_network_enabled`
+    pub fn process_network(mut self, enabled: bool) -> Self {
+        self.process_network = enabled;
+        self
+    }
+
+    /// PSS and USS per process; can be switched later with `set_memory_breakdown_enabled`
+    pub fn memory_breakdown(mut self, enabled: bool) -> Self {
+        self.memory_breakdown = enabled;
+        self
+    }
+
+    /// Create the collector; call `init` on it before the first `collect` for rate baselines
+    pub fn build(self) -> Result<MetricsCollector> {
+        let mut connections = ConnectionsCollector::new()?;
+        connections.set_enabled(self.connections);
+        let mut services = ServicesCollector::new()?;
+        services.set_enabled(self.services);
+        let mut log_feed = LogCollector::new()?;
+        log_feed.set_enabled(self.log_feed);
+        let mut process_network = ProcessNetCollector::new()?;
+        process_network.set_enabled(self.process_network);
+        let mut process = ProcessCollector::new()?;
+        process.set_limit(self.process_limit);
+        process.set_hog_threshold(self.hog_threshold);
+        process.set_memory_breakdown(self.memory_breakdown);
+        Ok(MetricsCollector {
+            system: SystemCollector::new()?,
+            cpu: CpuCollector::new()?,
+            memory: MemoryCollector::new()?,
+            disk: DiskCollector::new()?,
+            network: NetworkCollector::new()?,
+            pressure: PressureCollector::new()?,
+            slow_collectors: SlowCollectors {
+                enabled: self.enabled,
+                temperature: TemperatureCollector::new()?,
+                gpu: GpuCollector::new()?,
+                connections,
+                services,
+                log_feed,
+                process_network,
+                sched_latency: SchedLatencyCollector::new()?,
+                cgroup: CgroupCollector::new()?,
+                process,
+            },
+            enabled: self.enabled,
+            fast: FastMetrics::default(),
+            slow: SlowMetrics::default(),
+        })
+    }
+}
+
+/// The collectors behind `SlowMetrics`
+struct SlowCollectors {
+    enabled: EnabledCollectors,
+    temperature: TemperatureCollector,
+    gpu: GpuCollector,
+    connections: ConnectionsCollector,
+    services: ServicesCollector,
+    log_feed: LogCollector,
+    process_network: ProcessNetCollector,
+    sched_latency: SchedLatencyCollector,
+    cgroup: CgroupCollector,
+    process: ProcessCollector,
+}
+
+impl SlowCollectors {
+    /// Run every slow collector at once; the pass takes as long as the slowest one,
+    /// usually process enumeration, rather than the sum of them all
+    fn collect(&mut self, slow: &mut SlowMetrics) {
+        let collected_at = Instant::now();
+        let now = SystemTime::now();
+        let Self { enabled, temperature, gpu, connections, services, log_feed, process_network, sched_latency, cgroup, process } =
+            self;
+        let enabled = *enabled;
+
+        let (processes, temperatures, connections, services, net_rates, run_queue, cgroups, gpus) = thread::scope(|scope| {
+            let processes = scope.spawn(|| if enabled.processes { process.collect() } else { Ok(Vec::new()) });
+            let temperatures = scope.spawn(|| if enabled.temperatures { temperature.collect() } else { Ok(Vec::new()) });
+            let connections = scope.spawn(|| connections.collect());
+            let services = scope.spawn(|| services.collect());
+            let net_rates = scope.spawn(|| process_network.collect());
+            let run_queue = scope.spawn(|| if enabled.run_queue { sched_latency.collect() } else { Ok(None) });
+            let cgroups = scope.spawn(|| if enabled.cgroups { cgroup.collect() } else { Ok(Vec::new()) });
+            let gpus = if enabled.gpus { gpu.collect() } else { Ok(Vec::new()) }.and_then(|gpus| {
+                let usage = if gpus.is_empty() { Default::default() } else { gpu.collect_process_usage()? };
+                Ok((gpus, usage))
+            });
+            (join(processes), join(temperatures), join(connections), join(services), join(net_rates), join(run_queue), join(cgroups), gpus)
+        });
+        let (gpus, mut gpu_usage) = match gpus {
+            Ok((gpus, usage)) => (Ok(gpus), usage),
+            Err(e) => (Err(e), Default::default()),
+        };
+        let mut net_rates = match net_rates {
+            Ok(rates) => {
+                slow.process_network.update(Ok(()), now);
+                rates
+            },
+            Err(e) => {
+                slow.process_network.update(Err(e), now);
+                Default::default()
+            },
+        };
+        let net_enabled = process_network.is_enabled() && slow.process_network.error.is_none();
+        let processes = processes.map(|mut processes| {
+            // Attach per-process VRAM usage reported by the GPU driver
+            for process in &mut processes {
+                if let Some(usage) = gpu_usage.remove(&process.pid) {
+                    process.gpu_usage = usage;
+                }
+                // Processes without TCP traffic show zero rather than "not measured"
+                if net_enabled {
+                    process.net_rate = Some(net_rates.remove(&process.pid).unwrap_or_default());
+                }
+            }
+            processes
+        });
+
+        slow.collected_at = Some(collected_at);
+        slow.temperatures.update(temperatures, now);
+        slow.gpus.update(gpus, now);
+        slow.connections.update(connections, now);
+        slow.services.update(services, now);
+        // Read on its own thread as it comes in; this only copies it
+        slow.log_entries.update(log_feed.collect(), now);
+        slow.run_queue.update(run_queue, now);
+        slow.cgroups.update(cgroups, now);
+        slow.processes.update(processes, now);
+    }
+}
+
+/// Result of a scoped collector thread; a panic there carries on here
+fn join<T>(handle: thread::ScopedJoinHandle<'_, T>) -> T {
+    handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
 
 /// Main metrics collector that coordinates all sub-collectors
 pub struct MetricsCollector {
@@ -24,53 +400,150 @@ pub struct MetricsCollector {
     memory: MemoryCollector,
     disk: DiskCollector,
     network: NetworkCollector,
-    temperature: TemperatureCollector,
-    process: ProcessCollector,
+    pressure: PressureCollector,
+    slow_collectors: SlowCollectors,
+    enabled: EnabledCollectors,
+    fast: FastMetrics,
+    slow: SlowMetrics,
 }
 
 impl MetricsCollector {
+    /// A collector with the default set of collectors
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            system: SystemCollector::new()?,
-            cpu: CpuCollector::new()?,
-            memory: MemoryCollector::new()?,
-            disk: DiskCollector::new()?,
-            network: NetworkCollector::new()?,
-            temperature: TemperatureCollector::new()?,
-            process: ProcessCollector::new()?,
-        })
+        Self::builder().build()
     }
 
+    /// Choose which collectors run
+    pub fn builder() -> CollectorBuilder {
+        CollectorBuilder::default()
+    }
+
+    /// Take one snapshot. Blocks for as long as the collectors take, which with processes
+    /// enabled can be tens of milliseconds; see `BackgroundCollector` for a non-blocking way.
+    ///
+    /// Only the system, CPU and memory collectors fail the snapshot. When any other one
+    /// fails, its section keeps what it returned last and the error is listed in
+    /// `collector_failures`.
     pub fn collect(&mut self) -> Result<SystemSnapshot> {
         let timestamp = SystemTime::now();
+        let slow_due = self.slow.collected_at.map_or(true, |at| at.elapsed() >= SLOW_COLLECTION_INTERVAL);
         
-        let system = self.system.collect()?;
-        let cpu_cores = self.cpu.collect()?;
-        let memory = self.memory.collect()?;
-        let disks = self.disk.collect()?;
-        let networks = self.network.collect()?;
-        let temperatures = self.temperature.collect()?;
-        let processes = self.process.collect()?;
+        // The slow pass runs on its own thread alongside the fast collectors
+        let Self { system, cpu, memory, disk, network, pressure, slow_collectors, enabled, fast, slow } = self;
+        let required = thread::scope(|scope| {
+            let slow_pass = slow_due.then(|| scope.spawn(|| slow_collectors.collect(slow)));
+            let required = (|| -> Result<_> { Ok((system.collect()?, cpu.collect()?, memory.collect()?)) })();
+            fast.disks.update(if enabled.disks { disk.collect() } else { Ok(Vec::new()) }, timestamp);
+            fast.networks.update(if enabled.networks { network.collect() } else { Ok(Vec::new()) }, timestamp);
+            fast.pressure.update(if enabled.pressure { pressure.collect() } else { Ok(PressureInfo::default()) }, timestamp);
+            slow_pass.map(join);
+            required
+        });
+        let (system, cpu_cores, memory) = required?;
+
+        let (fast, slow) = (&self.fast, &self.slow);
+        let collector_failures = [
+            fast.disks.failure("disks"),
+            fast.networks.failure("networks"),
+            fast.pressure.failure("pressure"),
+            slow.temperatures.failure("temperatures"),
+            slow.gpus.failure("gpus"),
+            slow.connections.failure("connections"),
+            slow.services.failure("services"),
+            slow.log_entries.failure("log_feed"),
+            slow.run_queue.failure("run_queue"),
+            slow.cgroups.failure("cgroups"),
+            slow.processes.failure("processes"),
+            slow.process_network.failure("process_network"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
 
         Ok(SystemSnapshot {
             timestamp,
             system,
             cpu_cores,
             memory,
-            disks,
-            networks,
-            temperatures,
-            processes,
+            disks: fast.disks.value.clone(),
+            networks: fast.networks.value.clone(),
+            temperatures: slow.temperatures.value.clone(),
+            gpus: slow.gpus.value.clone(),
+            connections: slow.connections.value.clone(),
+            services: slow.services.value.clone(),
+            log_entries: slow.log_entries.value.clone(),
+            pressure: fast.pressure.value.clone(),
+            run_queue: slow.run_queue.value,
+            cgroups: slow.cgroups.value.clone(),
+            processes: slow.processes.value.clone(),
+            collector_failures,
         })
     }
 
+    /// Make the next `collect` run every collector, e.g. right after killing a process
+    pub fn expire_slow_metrics(&mut self) {
+        self.slow.collected_at = None;
+    }
+
+    /// Send a signal to a process
+    pub fn kill_process(&self, pid: u32, signal: KillSignal) -> Result<()> {
+        self.slow_collectors.process.kill_process(pid, signal)
+    }
+
+    /// Enable or disable socket enumeration (expensive, off by default)
+    pub fn set_connections_enabled(&mut self, enabled: bool) {
+        self.slow_collectors.connections.set_enabled(enabled);
+        self.expire_slow_metrics();
+    }
+
+    /// Enable or disable listing systemd services (runs systemctl each pass, off by default)
+    pub fn set_services_enabled(&mut self, enabled: bool) {
+        self.slow_collectors.services.set_enabled(enabled);
+        self.expire_slow_metrics();
+    }
+
+    /// Enable or disable following the system log's errors (keeps journalctl or `log stream`
+    /// running, off by default)
+    pub fn set_log_feed_enabled(&mut self, enabled: bool) {
+        self.slow_collectors.log_feed.set_enabled(enabled);
+        self.expire_slow_metrics();
+    }
+
+    /// Enable or disable per-process TCP throughput (walks every process' sockets, off by default)
+    pub fn set_process_network_enabled(&mut self, enabled: bool) {
+        self.slow_collectors.process_network.set_enabled(enabled);
+        self.expire_slow_metrics();
+    }
+
+    /// Enable or disable per-process PSS and USS (reads smaps_rollup for each process, off by default)
+    pub fn set_memory_breakdown_enabled(&mut self, enabled: bool) {
+        self.slow_collectors.process.set_memory_breakdown(enabled);
+        self.expire_slow_metrics();
+    }
+
     /// Initialize the collectors (useful for taking initial baseline measurements)
     pub fn init(&mut self) -> Result<()> {
+        let enabled = self.enabled;
         self.cpu.init()?;
-        self.disk.init()?;
-        self.network.init()?;
-        self.temperature.init()?;
-        self.process.init()?;
+        self.memory.init()?;
+        if enabled.disks {
+            self.disk.init()?;
+        }
+        if enabled.networks {
+            self.network.init()?;
+        }
+        if enabled.temperatures {
+            self.slow_collectors.temperature.init()?;
+        }
+        if enabled.run_queue {
+            self.slow_collectors.sched_latency.init()?;
+        }
+        if enabled.cgroups {
+            self.slow_collectors.cgroup.init()?;
+        }
+        if enabled.processes {
+            self.slow_collectors.process.init()?;
+        }
         Ok(())
     }
 }
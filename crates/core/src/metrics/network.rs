@@ -1,10 +1,34 @@
-use crate::{error::Result, model::NetworkInfo};
+use crate::{error::Result, model::{InterfaceDetails, NetworkInfo}};
 use std::collections::HashMap;
-use sysinfo::Networks;
+use sysinfo::{NetworkData, Networks};
+
+/// Cumulative counters for one interface
+#[derive(Debug, Clone, Copy, Default)]
+struct NetworkCounters {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+}
+
+impl NetworkCounters {
+    fn from_data(data: &NetworkData) -> Self {
+        Self {
+            rx_bytes: data.total_received(),
+            tx_bytes: data.total_transmitted(),
+            rx_packets: data.total_packets_received(),
+            tx_packets: data.total_packets_transmitted(),
+            rx_errors: data.total_errors_on_received(),
+            tx_errors: data.total_errors_on_transmitted(),
+        }
+    }
+}
 
 pub struct NetworkCollector {
     networks: Networks,
-    previous_stats: HashMap<String, (u64, u64, u64, u64)>, // (rx_bytes, tx_bytes, rx_packets, tx_packets)
+    previous_stats: HashMap<String, NetworkCounters>,
 }
 
 impl NetworkCollector {
@@ -23,15 +47,7 @@ impl NetworkCollector {
         
         // Store initial network stats for delta calculation
         for (interface_name, data) in &self.networks {
-            let rx_bytes = data.total_received();
-            let tx_bytes = data.total_transmitted();
-            let rx_packets = data.total_packets_received();
-            let tx_packets = data.total_packets_transmitted();
-            
-            self.previous_stats.insert(
-                interface_name.clone(),
-                (rx_bytes, tx_bytes, rx_packets, tx_packets),
-            );
+            self.previous_stats.insert(interface_name.clone(), NetworkCounters::from_data(data));
         }
         
         Ok(())
@@ -43,29 +59,14 @@ impl NetworkCollector {
         let mut networks = Vec::new();
         
         for (interface_name, data) in &self.networks {
-            let rx_bytes = data.total_received();
-            let tx_bytes = data.total_transmitted();
-            let rx_packets = data.total_packets_received();
-            let tx_packets = data.total_packets_transmitted();
-            let rx_errors = data.total_errors_on_received();
-            let tx_errors = data.total_errors_on_transmitted();
+            let counters = NetworkCounters::from_data(data);
+            let NetworkCounters { rx_bytes, tx_bytes, rx_packets, tx_packets, rx_errors, tx_errors } = counters;
             
-            // Calculate deltas since last measurement
-            let (rx_bytes_delta, tx_bytes_delta) = if let Some((prev_rx, prev_tx, _prev_rx_packets, _prev_tx_packets)) = 
-                self.previous_stats.get(interface_name.as_str()) {
-                (
-                    rx_bytes.saturating_sub(*prev_rx),
-                    tx_bytes.saturating_sub(*prev_tx),
-                )
-            } else {
-                (0, 0)
-            };
+            // Calculate deltas since last measurement (a new interface starts from zero)
+            let previous = self.previous_stats.get(interface_name.as_str()).copied().unwrap_or(counters);
             
             // Update previous stats
-            self.previous_stats.insert(
-                interface_name.clone(),
-                (rx_bytes, tx_bytes, rx_packets, tx_packets),
-            );
+            self.previous_stats.insert(interface_name.clone(), counters);
             
             // Skip loopback and inactive interfaces for cleaner display
             if interface_name == "lo" || interface_name.starts_with("lo") {
@@ -81,12 +82,17 @@ impl NetworkCollector {
                 interface_name: interface_name.clone(),
                 rx_bytes,
                 tx_bytes,
-                rx_bytes_delta,
-                tx_bytes_delta,
                 rx_packets,
                 tx_packets,
                 rx_errors,
                 tx_errors,
+                rx_bytes_delta: rx_bytes.saturating_sub(previous.rx_bytes),
+                tx_bytes_delta: tx_bytes.saturating_sub(previous.tx_bytes),
+                rx_packets_delta: rx_packets.saturating_sub(previous.rx_packets),
+                tx_packets_delta: tx_packets.saturating_sub(previous.tx_packets),
+                rx_errors_delta: rx_errors.saturating_sub(previous.rx_errors),
+                tx_errors_delta: tx_errors.saturating_sub(previous.tx_errors),
+                is_virtual: is_virtual_interface(interface_name),
             });
         }
         
@@ -107,6 +113,11 @@ impl NetworkCollector {
             tx_packets: 0,
             rx_errors: 0,
             tx_errors: 0,
+            rx_packets_delta: 0,
+            tx_packets_delta: 0,
+            rx_errors_delta: 0,
+            tx_errors_delta: 0,
+            is_virtual: false,
         };
         
         for net in networks {
@@ -118,8 +129,104 @@ impl NetworkCollector {
             aggregate.tx_packets += net.tx_packets;
             aggregate.rx_errors += net.rx_errors;
             aggregate.tx_errors += net.tx_errors;
+            aggregate.rx_packets_delta += net.rx_packets_delta;
+            aggregate.tx_packets_delta += net.tx_packets_delta;
+            aggregate.rx_errors_delta += net.rx_errors_delta;
+            aggregate.tx_errors_delta += net.tx_errors_delta;
         }
         
         Ok(aggregate)
     }
 }
+
+/// Name prefixes of the interfaces Docker, Podman, libvirt, LXC, VMware, VirtualBox and the
+/// common Kubernetes network plugins create
+const VIRTUAL_PREFIXES: &[&str] = &[
+    "docker", "veth", "br-", "virbr", "vnet", "podman", "cni", "flannel", "cali", "lxcbr", "lxdbr", "vmnet", "vboxnet",
+];
+
+/// Whether an interface carries traffic that a physical interface also counts
+pub fn is_virtual_interface(interface_name: &str) -> bool {
+    VIRTUAL_PREFIXES.iter().any(|prefix| interface_name.starts_with(prefix)) || is_bridge(interface_name)
+}
+
+/// Bridges under any name, e.g. a hand-made br0
+#[cfg(target_os = "linux")]
+fn is_bridge(interface_name: &str) -> bool {
+    std::path::Path::new("/sys/class/net").join(interface_name).join("bridge").is_dir()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_bridge(_interface_name: &str) -> bool {
+    false
+}
+
+/// Read addresses, driver and link properties of one interface
+pub fn interface_details(interface_name: &str) -> InterfaceDetails {
+    let mut details = InterfaceDetails::default();
+    read_interface_addresses(interface_name, &mut details);
+    read_link_properties(interface_name, &mut details);
+    details
+}
+
+#[cfg(unix)]
+fn read_interface_addresses(interface_name: &str, details: &mut InterfaceDetails) {
+    let Ok(addrs) = nix::ifaddrs::getifaddrs() else {
+        return;
+    };
+
+    for ifaddr in addrs.filter(|a| a.interface_name == interface_name) {
+        let Some(address) = ifaddr.address else {
+            continue;
+        };
+
+        if let Some(v4) = address.as_sockaddr_in() {
+            let prefix = ifaddr.netmask
+                .and_then(|mask| mask.as_sockaddr_in().map(|m| u32::from(m.ip()).count_ones()))
+                .unwrap_or(32);
+            details.addresses.push(format!("{}/{}", v4.ip(), prefix));
+        } else if let Some(v6) = address.as_sockaddr_in6() {
+            let prefix = ifaddr.netmask
+                .and_then(|mask| mask.as_sockaddr_in6().map(|m| u128::from(m.ip()).count_ones()))
+                .unwrap_or(128);
+            details.addresses.push(format!("{}/{}", v6.ip(), prefix));
+        } else if let Some(mac) = address.as_link_addr().and_then(|link| link.addr()) {
+            if mac != [0; 6] {
+                details.mac_address = Some(
+                    mac.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":")
+                );
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn read_interface_addresses(_interface_name: &str, _details: &mut InterfaceDetails) {
+    // Windows would use GetAdaptersAddresses
+}
+
+#[cfg(target_os = "linux")]
+fn read_link_properties(interface_name: &str, details: &mut InterfaceDetails) {
+    let base = std::path::Path::new("/sys/class/net").join(interface_name);
+    let read = |file: &str| {
+        std::fs::read_to_string(base.join(file))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    // Virtual interfaces have no device/driver link
+    details.driver = std::fs::read_link(base.join("device/driver"))
+        .ok()
+        .and_then(|target| target.file_name().map(|n| n.to_string_lossy().into_owned()));
+    // speed reads as -1 (or fails) while the link is down
+    details.speed_mbps = read("speed").and_then(|s| s.parse::<i64>().ok()).filter(|&s| s > 0).map(|s| s as u32);
+    details.duplex = read("duplex").filter(|d| d != "unknown");
+    details.mtu = read("mtu").and_then(|s| s.parse().ok());
+    details.operstate = read("operstate");
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_link_properties(_interface_name: &str, _details: &mut InterfaceDetails) {
+    // Link properties come from sysfs; other platforms would need SIOCGIFMEDIA / GetIfEntry2
+}
@@ -0,0 +1,96 @@
+use crate::model::{PodInfo, ProcessInfo};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The kubelet links every container's log here as "<pod>_<namespace>_<container>-<id>.log"
+const CONTAINER_LOGS: &str = "/var/log/containers";
+/// and keeps a directory per pod here, named "<namespace>_<pod>_<pod uid>"
+const POD_LOGS: &str = "/var/log/pods";
+/// How soon the directories are read again for a pod not seen before
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Names the Kubernetes pod and container of processes in `kubepods` cgroups. The cgroup
+/// path only carries the pod's UID and the container's ID; the names come from the
+/// kubelet's log directories, which spell out both, so it needs no API access or token.
+#[derive(Debug, Default)]
+pub(crate) struct PodResolver {
+    // short container ID -> its pod and container name
+    containers: HashMap<String, PodInfo>,
+    // pod UID -> pod, for processes outside any named container such as the sandbox
+    pods: HashMap<String, PodInfo>,
+    scanned_at: Option<Instant>,
+}
+
+impl PodResolver {
+    /// Set `pod` on every process running in one
+    pub(crate) fn annotate(&mut self, processes: &mut [ProcessInfo]) {
+        for process in processes.iter_mut() {
+            let Some(cgroup) = process.cgroup.as_deref().filter(|cgroup| cgroup.contains("kubepods")) else {
+                continue;
+            };
+            let mut pod = self.lookup(process.container_id.as_deref(), cgroup);
+            // A pod started since the last read
+            if pod.is_none() && !matches!(self.scanned_at, Some(at) if at.elapsed() < RESCAN_INTERVAL) {
+                self.scan();
+                pod = self.lookup(process.container_id.as_deref(), cgroup);
+            }
+            process.pod = pod;
+        }
+    }
+
+    fn lookup(&self, container_id: Option<&str>, cgroup: &str) -> Option<PodInfo> {
+        container_id
+            .and_then(|id| self.containers.get(id))
+            .or_else(|| self.pods.get(&pod_uid_from_cgroup(cgroup)?))
+            .cloned()
+    }
+
+    fn scan(&mut self) {
+        let names = |dir: &str| -> Vec<String> {
+            std::fs::read_dir(dir)
+                .map(|entries| entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect())
+                .unwrap_or_default()
+        };
+        self.containers = names(CONTAINER_LOGS).iter().filter_map(|name| parse_container_log_name(name)).collect();
+        self.pods = names(POD_LOGS).iter().filter_map(|name| parse_pod_log_dir(name)).collect();
+        self.scanned_at = Some(Instant::now());
+    }
+}
+
+/// The pod UID in a `kubepods` cgroup path: "pod<uid>" under the cgroupfs driver,
+/// "kubepods-burstable-pod<uid>.slice" with underscores for dashes under the systemd one
+pub fn pod_uid_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup.split('/').find_map(|segment| {
+        let segment = segment.strip_suffix(".slice").unwrap_or(segment);
+        let uid = segment.strip_prefix("pod").or_else(|| segment.rsplit_once("-pod").map(|(_, uid)| uid))?;
+        let uid = uid.replace('_', "-");
+        (uid.len() == 36 && uid.chars().all(|c| c.is_ascii_hexdigit() || c == '-')).then_some(uid)
+    })
+}
+
+/// Short container ID and pod out of a /var/log/containers file name. Pod, namespace and
+/// container names can't hold underscores, which is what makes the name unambiguous.
+pub fn parse_container_log_name(file_name: &str) -> Option<(String, PodInfo)> {
+    let (names, id) = file_name.strip_suffix(".log")?.rsplit_once('-')?;
+    if id.len() < 12 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut parts = names.splitn(3, '_');
+    let pod = PodInfo {
+        name: parts.next()?.to_string(),
+        namespace: parts.next()?.to_string(),
+        container: Some(parts.next()?.to_string()),
+    };
+    Some((id[..12].to_string(), pod))
+}
+
+/// Pod UID and pod out of a /var/log/pods directory name
+pub fn parse_pod_log_dir(name: &str) -> Option<(String, PodInfo)> {
+    let mut parts = name.splitn(3, '_');
+    let namespace = parts.next()?.to_string();
+    let name = parts.next()?.to_string();
+    let uid = parts.next()?.to_string();
+    Some((uid, PodInfo { namespace, name, container: None }))
+}
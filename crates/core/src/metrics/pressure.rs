@@ -0,0 +1,47 @@
+use crate::{error::Result, model::{PressureInfo, PressureStall}};
+
+pub struct PressureCollector;
+
+impl PressureCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn collect(&mut self) -> Result<PressureInfo> {
+        // Missing files (kernel without CONFIG_PSI) just leave the entry empty
+        Ok(PressureInfo {
+            cpu: read_pressure_file("/proc/pressure/cpu"),
+            memory: read_pressure_file("/proc/pressure/memory"),
+            io: read_pressure_file("/proc/pressure/io"),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn collect(&mut self) -> Result<PressureInfo> {
+        Ok(PressureInfo::default())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_pressure_file(path: &str) -> Option<PressureStall> {
+    parse_pressure(&std::fs::read_to_string(path).ok()?)
+}
+
+/// Parse "some avg10=1.00 avg60=... total=..." / "full avg10=..." lines
+pub fn parse_pressure(contents: &str) -> Option<PressureStall> {
+    let avg10 = |kind: &str| {
+        contents
+            .lines()
+            .find(|line| line.starts_with(kind))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse::<f32>()
+            .ok()
+    };
+
+    Some(PressureStall {
+        some_avg10: avg10("some")?,
+        full_avg10: avg10("full"),
+    })
+}
@@ -1,12 +1,36 @@
-use crate::{error::Result, model::{ProcessInfo, ProcessState, SortKey}};
-use std::{collections::HashMap, time::SystemTime};
-use sysinfo::{Pid, Process, System};
+use crate::{
+    error::Result,
+    metrics::{pods::PodResolver, restarts::RestartTracker},
+    model::{KillSignal, ProcessInfo, ProcessState, SortKey},
+    platform::container_id_from_cgroup,
+};
+use std::{collections::HashMap, time::{Duration, Instant, SystemTime}};
+use sysinfo::{Pid, Process, System, ThreadKind};
 
 pub struct ProcessCollector {
     sys: System,
     previous_cpu_times: HashMap<Pid, u64>,
+    // pid -> children's cutime + cstime in clock ticks, and when it was read
+    previous_children_ticks: HashMap<u32, u64>,
+    children_sampled_at: Option<Instant>,
+    // Processes read in full per pass, busiest by CPU first; the rest are left partial
+    limit: Option<usize>,
+    // pid -> (start time, when it went over the hog threshold)
+    busy_since: HashMap<u32, (SystemTime, SystemTime)>,
+    hog_threshold: f32,
+    restarts: RestartTracker,
+    pods: PodResolver,
+    // Read PSS and USS from smaps_rollup for the processes read in full
+    memory_breakdown: bool,
 }
 
+/// CPU percent above which a process counts as busy unless set otherwise
+pub const DEFAULT_HOG_THRESHOLD: f32 = 80.0;
+
+/// /proc reports times in USER_HZ, which the kernel fixes at 100 for userspace
+#[cfg(target_os = "linux")]
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
 impl ProcessCollector {
     pub fn new() -> Result<Self> {
         let mut sys = System::new_all();
@@ -15,9 +39,33 @@ impl ProcessCollector {
         Ok(Self {
             sys,
             previous_cpu_times: HashMap::new(),
+            previous_children_ticks: HashMap::new(),
+            children_sampled_at: None,
+            limit: None,
+            busy_since: HashMap::new(),
+            hog_threshold: DEFAULT_HOG_THRESHOLD,
+            restarts: RestartTracker::default(),
+            pods: PodResolver::default(),
+            memory_breakdown: false,
         })
     }
 
+    /// Read only the `limit` busiest processes in full; None reads them all
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// Read each process' PSS and USS; the kernel walks its page tables to answer, a few
+    /// milliseconds for a large process, so this is off unless asked for
+    pub fn set_memory_breakdown(&mut self, enabled: bool) {
+        self.memory_breakdown = enabled;
+    }
+
+    /// CPU percent a process must stay above for `busy_since` to keep running
+    pub fn set_hog_threshold(&mut self, percent: f32) {
+        self.hog_threshold = percent;
+    }
+
     pub fn init(&mut self) -> Result<()> {
         self.sys.refresh_processes();
         
@@ -36,13 +84,86 @@ impl ProcessCollector {
         let total_memory = self.sys.total_memory();
         
         for (pid, process) in self.sys.processes() {
+            // sysinfo lists each thread of a process on its own as well; they're counted in
+            // their process's `threads` instead
+            if process.thread_kind() == Some(ThreadKind::Userland) {
+                continue;
+            }
             let process_info = self.process_to_info(*pid, process, total_memory)?;
             processes.push(process_info);
         }
         
+        // Everything past the limit keeps only what sysinfo already had; the per-process
+        // file reads are what get expensive with tens of thousands of processes
+        let full = self.limit.map_or(processes.len(), |limit| limit.min(processes.len()));
+        if full < processes.len() {
+            processes.select_nth_unstable_by(full, |a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+        }
+        let (complete, partial) = processes.split_at_mut(full);
+        for process in complete.iter_mut() {
+            read_extra_fields(process);
+            if self.memory_breakdown {
+                read_memory_breakdown(process);
+            }
+        }
+        self.pods.annotate(complete);
+        for process in partial {
+            process.partial = true;
+        }
+        self.attach_stat_fields(&mut processes[..full]);
+        self.track_busy(&mut processes);
+        self.restarts.update(&mut processes, SystemTime::now());
+        
         Ok(processes)
     }
 
+    /// Carry forward when each process went over the hog threshold; dropping under it, or
+    /// the PID going to a new process, starts the clock again
+    fn track_busy(&mut self, processes: &mut [ProcessInfo]) {
+        let now = SystemTime::now();
+        let mut busy = HashMap::new();
+        for process in processes.iter_mut().filter(|p| p.cpu_percent > self.hog_threshold) {
+            let since = match self.busy_since.get(&process.pid) {
+                Some(&(start_time, since)) if start_time == process.start_time => since,
+                _ => now,
+            };
+            process.busy_since = Some(since);
+            busy.insert(process.pid, (process.start_time, since));
+        }
+        self.busy_since = busy;
+    }
+
+    /// Fill in what /proc/<pid>/stat has beyond sysinfo, including the time used by reaped
+    /// children, so the cost of short jobs shows against their parent
+    #[cfg(target_os = "linux")]
+    fn attach_stat_fields(&mut self, processes: &mut [ProcessInfo]) {
+        let now = Instant::now();
+        let elapsed = self.children_sampled_at.map(|at| now.duration_since(at).as_secs_f32());
+        let mut current = HashMap::with_capacity(processes.len());
+        
+        for process in processes.iter_mut() {
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", process.pid)) else {
+                continue;
+            };
+            read_stat_fields(process, &stat);
+            let Some(ticks) = parse_children_ticks(&stat) else {
+                continue;
+            };
+            process.children_cpu_time = Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SEC);
+            if let (Some(elapsed), Some(&previous)) = (elapsed, self.previous_children_ticks.get(&process.pid)) {
+                let delta = ticks.saturating_sub(previous) as f32 / CLOCK_TICKS_PER_SEC as f32;
+                process.children_cpu_percent = delta / elapsed.max(0.001) * 100.0;
+            }
+            current.insert(process.pid, ticks);
+        }
+        
+        self.previous_children_ticks = current;
+        self.children_sampled_at = Some(now);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn attach_stat_fields(&mut self, _processes: &mut [ProcessInfo]) {}
+
     pub fn collect_filtered(&mut self, filter: &str) -> Result<Vec<ProcessInfo>> {
         let processes = self.collect()?;
         
@@ -80,44 +201,13 @@ impl ProcessCollector {
     }
 
     fn sort_processes(&self, processes: &mut [ProcessInfo], sort_key: SortKey, reverse: bool) {
-        match sort_key {
-            SortKey::Cpu => {
-                processes.sort_by(|a, b| {
-                    if reverse {
-                        b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        a.cpu_percent.partial_cmp(&b.cpu_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            SortKey::Memory => {
-                processes.sort_by(|a, b| {
-                    if reverse {
-                        b.memory_percent.partial_cmp(&a.memory_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        a.memory_percent.partial_cmp(&b.memory_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
-            }
-            SortKey::Pid => {
-                processes.sort_by(|a, b| {
-                    if reverse {
-                        b.pid.cmp(&a.pid)
-                    } else {
-                        a.pid.cmp(&b.pid)
-                    }
-                });
+        processes.sort_by(|a, b| {
+            if reverse {
+                sort_key.compare(b, a)
+            } else {
+                sort_key.compare(a, b)
             }
-            SortKey::Name => {
-                processes.sort_by(|a, b| {
-                    if reverse {
-                        b.name.cmp(&a.name)
-                    } else {
-                        a.name.cmp(&b.name)
-                    }
-                });
-            }
-        }
+        });
     }
 
     fn process_to_info(&self, pid: Pid, process: &Process, total_memory: u64) -> Result<ProcessInfo> {
@@ -126,21 +216,24 @@ impl ProcessCollector {
         let cmd = process.cmd().to_vec();
         let cpu_percent = process.cpu_usage();
         let memory_percent = process.memory() as f32 / (total_memory as f32) * 100.0;
-        let memory_rss = process.memory() * 1024; // sysinfo returns KB, convert to bytes
-        let memory_vsz = process.virtual_memory() * 1024;
-        let threads = 1; // Threads not available in newer sysinfo API
+        // sysinfo already reports bytes
+        let memory_rss = process.memory();
+        let memory_vsz = process.virtual_memory();
+        // Tasks besides the main thread; only listed on Linux, so elsewhere this stays at 1
+        let threads = process.tasks().map_or(1, |tasks| tasks.len() as u64 + 1);
         let state = self.convert_process_status(process.status());
         
         // Convert start time from sysinfo's u64 (seconds since epoch) to SystemTime
         let start_time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(process.start_time());
         
         let parent_pid = process.parent().map(|p| p.as_u32());
+        // Flagged PF_KTHREAD, or failing that a child of kthreadd (PID 2) with no command line;
+        // inside a PID namespace PID 2 can be an ordinary program, whose children do have one
+        let is_kernel_thread = process.thread_kind() == Some(ThreadKind::Kernel)
+            || (cfg!(target_os = "linux") && parent_pid == Some(2) && cmd.is_empty());
         
         // Get user information
         let user = self.get_process_user(pid, process);
-        
-        // Get cgroup information (Linux only)
-        let cgroup = self.get_process_cgroup(pid);
 
         Ok(ProcessInfo {
             pid: pid_u32,
@@ -155,7 +248,26 @@ impl ProcessCollector {
             state,
             start_time,
             parent_pid,
-            cgroup,
+            pgid: None,
+            session_id: None,
+            tty: None,
+            cgroup: None,
+            container_id: None,
+            pod: None,
+            gpu_usage: Vec::new(),
+            category: None,
+            children_cpu_time: Duration::ZERO,
+            cpu_time_total: Duration::ZERO,
+            children_cpu_percent: 0.0,
+            wchan: None,
+            open_fds: None,
+            memory_pss: None,
+            memory_uss: None,
+            busy_since: None,
+            restarts: 0,
+            net_rate: None,
+            is_kernel_thread,
+            partial: false,
         })
     }
 
@@ -172,7 +284,7 @@ impl ProcessCollector {
             sysinfo::ProcessStatus::Waking => ProcessState::Waiting,
             sysinfo::ProcessStatus::Parked => ProcessState::Waiting,
             sysinfo::ProcessStatus::LockBlocked => ProcessState::Waiting,
-            sysinfo::ProcessStatus::UninterruptibleDiskSleep => ProcessState::Waiting,
+            sysinfo::ProcessStatus::UninterruptibleDiskSleep => ProcessState::DiskSleep,
             _ => ProcessState::Unknown,
         }
     }
@@ -205,39 +317,213 @@ impl ProcessCollector {
         "user".to_string()
     }
 
-    #[cfg(all(target_os = "linux", feature = "linux_procfs"))]
-    fn get_process_cgroup(&self, pid: Pid) -> Option<String> {
-        if let Ok(cgroup) = procfs::process::Process::new(pid.as_u32() as i32)
-            .and_then(|p| p.cgroups()) {
-            // Get the first cgroup or the one we're most interested in
-            cgroup.first().map(|cg| cg.pathname.clone())
-        } else {
-            None
-        }
-    }
-
-    #[cfg(not(all(target_os = "linux", feature = "linux_procfs")))]
-    fn get_process_cgroup(&self, _pid: Pid) -> Option<String> {
-        None
-    }
-
     /// Kill a process by PID (Unix only)
     #[cfg(unix)]
-    pub fn kill_process(&self, pid: u32) -> Result<()> {
+    pub fn kill_process(&self, pid: u32, signal: KillSignal) -> Result<()> {
         use nix::sys::signal::{kill, Signal};
         use nix::unistd::Pid as NixPid;
         
+        let nix_signal = match signal {
+            KillSignal::Term => Signal::SIGTERM,
+            KillSignal::Kill => Signal::SIGKILL,
+            KillSignal::Hup => Signal::SIGHUP,
+            KillSignal::Int => Signal::SIGINT,
+            KillSignal::Stop => Signal::SIGSTOP,
+            KillSignal::Cont => Signal::SIGCONT,
+            KillSignal::Usr1 => Signal::SIGUSR1,
+            KillSignal::Usr2 => Signal::SIGUSR2,
+        };
+        
         let nix_pid = NixPid::from_raw(pid as i32);
-        kill(nix_pid, Signal::SIGTERM)
-            .map_err(|e| crate::error::CoreError::platform(format!("Failed to kill process {}: {}", pid, e)))?;
+        kill(nix_pid, nix_signal)
+            .map_err(|e| crate::error::CoreError::platform(format!("Failed to send {} to process {}: {}", signal.name(), pid, e)))?;
         
         Ok(())
     }
 
     #[cfg(not(unix))]
-    pub fn kill_process(&self, _pid: u32) -> Result<()> {
+    pub fn kill_process(&self, _pid: u32, _signal: KillSignal) -> Result<()> {
         Err(crate::error::CoreError::unsupported_platform(
             "Process termination not supported on this platform".to_string()
         ))
     }
 }
+
+/// Fill in the fields a partial process skipped. Children's CPU percent needs two readings,
+/// so only their total CPU time is filled in.
+pub fn complete_process(process: &mut ProcessInfo) {
+    if !process.partial {
+        return;
+    }
+    read_extra_fields(process);
+    PodResolver::default().annotate(std::slice::from_mut(process));
+    #[cfg(target_os = "linux")]
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", process.pid)) {
+        read_stat_fields(process, &stat);
+        if let Some(ticks) = parse_children_ticks(&stat) {
+            process.children_cpu_time = Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SEC);
+        }
+    }
+    process.partial = false;
+}
+
+/// cgroup, wchan and open FDs, each a file read per process
+fn read_extra_fields(process: &mut ProcessInfo) {
+    process.cgroup = get_process_cgroup(process.pid);
+    process.container_id = process.cgroup.as_deref().and_then(container_id_from_cgroup);
+    process.open_fds = count_open_fds(process.pid);
+    // A running process isn't blocked anywhere, so skip the extra read
+    if process.state != ProcessState::Running {
+        process.wchan = get_process_wchan(process.pid);
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "linux_procfs"))]
+fn get_process_cgroup(pid: u32) -> Option<String> {
+    if let Ok(cgroup) = procfs::process::Process::new(pid as i32)
+        .and_then(|p| p.cgroups()) {
+        // Get the first cgroup or the one we're most interested in
+        cgroup.first().map(|cg| cg.pathname.clone())
+    } else {
+        None
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "linux_procfs")))]
+fn get_process_cgroup(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok().and_then(|contents| crate::platform::pick_cgroup(&contents))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_process_cgroup(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Kernel function the process is sleeping in, from /proc/<pid>/wchan
+#[cfg(target_os = "linux")]
+fn get_process_wchan(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/wchan", pid))
+        .ok()
+        .and_then(|contents| parse_wchan(&contents))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_process_wchan(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Entries in /proc/<pid>/fd
+#[cfg(target_os = "linux")]
+fn count_open_fds(pid: u32) -> Option<u64> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid)).ok().map(|entries| entries.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_open_fds(_pid: u32) -> Option<u64> {
+    // Windows would use GetProcessHandleCount, macOS proc_pidinfo(PROC_PIDLISTFDS)
+    None
+}
+
+/// PSS and USS from /proc/<pid>/smaps_rollup (Linux 4.14+), which sums smaps over every
+/// mapping in one read. Other users' processes need root.
+#[cfg(target_os = "linux")]
+pub fn read_memory_breakdown(process: &mut ProcessInfo) {
+    let breakdown = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", process.pid))
+        .ok()
+        .and_then(|contents| parse_smaps_rollup(&contents));
+    (process.memory_pss, process.memory_uss) = breakdown.unzip();
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_memory_breakdown(_process: &mut ProcessInfo) {}
+
+/// (PSS, USS) in bytes out of smaps_rollup's "Name:  1234 kB" lines. USS is the private
+/// pages, clean and dirty; kernel threads have no mappings and so no Pss line.
+pub fn parse_smaps_rollup(contents: &str) -> Option<(u64, u64)> {
+    let mut pss = None;
+    let mut private = 0;
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kb) = value.trim().strip_suffix("kB").and_then(|kb| kb.trim().parse::<u64>().ok()) else {
+            continue;
+        };
+        match key {
+            "Pss" => pss = Some(kb * 1024),
+            "Private_Clean" | "Private_Dirty" => private += kb * 1024,
+            _ => {},
+        }
+    }
+    pss.map(|pss| (pss, private))
+}
+
+/// The kernel writes "0" when the task is runnable or the symbol is hidden from us
+pub fn parse_wchan(contents: &str) -> Option<String> {
+    let symbol = contents.trim();
+    (!symbol.is_empty() && symbol != "0").then(|| symbol.to_string())
+}
+
+/// CPU time, process group, session and terminal out of /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+fn read_stat_fields(process: &mut ProcessInfo, stat: &str) {
+    if let Some(ticks) = parse_cpu_ticks(stat) {
+        process.cpu_time_total = Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SEC);
+    }
+    if let Some((pgid, session_id)) = parse_process_group(stat) {
+        process.pgid = Some(pgid);
+        process.session_id = Some(session_id);
+    }
+    process.tty = parse_tty_nr(stat).and_then(tty_name);
+}
+
+/// pgrp and session (fields 5 and 6) from /proc/<pid>/stat
+pub fn parse_process_group(stat: &str) -> Option<(u32, u32)> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(2);
+    let pgid = fields.next()?.parse().ok()?;
+    let session_id = fields.next()?.parse().ok()?;
+    Some((pgid, session_id))
+}
+
+/// tty_nr (field 7) from /proc/<pid>/stat; 0 when there's no controlling terminal
+pub fn parse_tty_nr(stat: &str) -> Option<u32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(4)?.parse::<i64>().ok().map(|tty_nr| tty_nr as u32)
+}
+
+/// Name a tty_nr the way ps does: "pts/3", "tty2", "ttyS0". None for no terminal; other
+/// devices come out as "major:minor".
+pub fn tty_name(tty_nr: u32) -> Option<String> {
+    if tty_nr == 0 {
+        return None;
+    }
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    Some(match major {
+        // Unix98 pseudo-terminals take majors 136-143, 256 minors each
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        4 if minor < 64 => format!("tty{}", minor),
+        4 => format!("ttyS{}", minor - 64),
+        _ => format!("{}:{}", major, minor),
+    })
+}
+
+/// utime + stime (fields 14 and 15) from /proc/<pid>/stat, in clock ticks
+pub fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// cutime + cstime (fields 16 and 17) from /proc/<pid>/stat, in clock ticks
+pub fn parse_children_ticks(stat: &str) -> Option<u64> {
+    // The command name can contain spaces and parentheses; fields resume after the last ')'
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(13);
+    let cutime: u64 = fields.next()?.parse().ok()?;
+    let cstime: u64 = fields.next()?.parse().ok()?;
+    Some(cutime + cstime)
+}
@@ -0,0 +1,197 @@
+use crate::{error::Result, model::NetRate};
+use std::{collections::HashMap, time::Instant};
+
+/// Per-process TCP throughput from the kernel's per-socket byte counters (sock_diag), summed
+/// over the sockets each process holds. Finding the owner of every socket walks every
+/// process's file descriptors, so like socket enumeration it is off until asked for.
+/// UDP sockets keep no byte counts and aren't included.
+pub struct ProcessNetCollector {
+    enabled: bool,
+    // socket inode -> (bytes received, bytes acked) at the last pass
+    previous: HashMap<u64, (u64, u64)>,
+    previous_at: Option<Instant>,
+}
+
+impl ProcessNetCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            enabled: false,
+            previous: HashMap::new(),
+            previous_at: None,
+        })
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            // Counters from before a pause would make the first pass after it look huge
+            self.previous.clear();
+            self.previous_at = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Rates per PID since the last call; empty while disabled, on the first pass and
+    /// where sock_diag isn't available
+    pub fn collect(&mut self) -> Result<HashMap<u32, NetRate>> {
+        if !self.enabled {
+            return Ok(HashMap::new());
+        }
+        let Some(sockets) = socket_bytes() else {
+            return Ok(HashMap::new());
+        };
+        let now = Instant::now();
+        let owners = socket_owners();
+
+        let mut rates = HashMap::new();
+        if let Some(elapsed) = self.previous_at.map(|at| now.duration_since(at).as_secs_f64().max(0.001)) {
+            let mut moved: HashMap<u32, (u64, u64)> = HashMap::new();
+            for (inode, &(rx, tx)) in &sockets {
+                let Some(&pid) = owners.get(inode) else {
+                    continue;
+                };
+                // A socket opened since the last pass moved all its bytes within the interval
+                let (previous_rx, previous_tx) = self.previous.get(inode).copied().unwrap_or_default();
+                let total = moved.entry(pid).or_default();
+                total.0 += rx.saturating_sub(previous_rx);
+                total.1 += tx.saturating_sub(previous_tx);
+            }
+            rates = moved
+                .into_iter()
+                .map(|(pid, (rx, tx))| {
+                    let rate = NetRate {
+                        rx_bytes_per_sec: (rx as f64 / elapsed).round() as u64,
+                        tx_bytes_per_sec: (tx as f64 / elapsed).round() as u64,
+                    };
+                    (pid, rate)
+                })
+                .collect();
+        }
+
+        self.previous = sockets;
+        self.previous_at = Some(now);
+        Ok(rates)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn socket_owners() -> HashMap<u64, u32> {
+    super::connections::linux::socket_inode_owners()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn socket_owners() -> HashMap<u64, u32> {
+    HashMap::new()
+}
+
+#[cfg(target_os = "linux")]
+fn socket_bytes() -> Option<HashMap<u64, (u64, u64)>> {
+    use nix::sys::socket::{socket, AddressFamily, SockFlag, SockProtocol, SockType};
+
+    let fd = socket(AddressFamily::Netlink, SockType::Datagram, SockFlag::SOCK_CLOEXEC, SockProtocol::NetlinkSockDiag).ok()?;
+    let mut sockets = HashMap::new();
+    for family in [sock_diag::AF_INET, sock_diag::AF_INET6] {
+        sock_diag::dump_tcp(&fd, family, &mut sockets).ok()?;
+    }
+    Some(sockets)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn socket_bytes() -> Option<HashMap<u64, (u64, u64)>> {
+    // macOS keeps per-socket counters in nstat/NetworkStatistics, Windows in GetPerTcpConnectionEStats
+    None
+}
+
+/// Just enough of the NETLINK_SOCK_DIAG protocol (linux/inet_diag.h) to dump TCP sockets
+/// with their `tcp_info`
+#[cfg(target_os = "linux")]
+pub mod sock_diag {
+    use nix::sys::socket::{recv, sendto, MsgFlags, NetlinkAddr};
+    use std::{
+        collections::HashMap,
+        os::fd::{AsRawFd, OwnedFd},
+    };
+
+    pub(super) const AF_INET: u8 = 2;
+    pub(super) const AF_INET6: u8 = 10;
+    const IPPROTO_TCP: u8 = 6;
+    const SOCK_DIAG_BY_FAMILY: u16 = 20;
+    const NLM_F_REQUEST: u16 = 0x1;
+    const NLM_F_DUMP: u16 = 0x300;
+    const NLMSG_ERROR: u16 = 2;
+    const NLMSG_DONE: u16 = 3;
+    const INET_DIAG_INFO: u16 = 2;
+    const NLMSG_HEADER_LEN: usize = 16;
+    /// struct inet_diag_msg; the socket's inode is its last field
+    const DIAG_MSG_LEN: usize = 72;
+    const DIAG_MSG_INODE: usize = 68;
+    /// tcpi_bytes_acked and tcpi_bytes_received in struct tcp_info, there since Linux 4.2
+    const TCPI_BYTES_ACKED: usize = 120;
+    const TCPI_BYTES_RECEIVED: usize = 128;
+
+    /// Ask for every TCP socket of `family` and add each one's (received, acked) bytes by inode
+    pub(super) fn dump_tcp(fd: &OwnedFd, family: u8, sockets: &mut HashMap<u64, (u64, u64)>) -> nix::Result<()> {
+        let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + 56);
+        request.extend_from_slice(&(NLMSG_HEADER_LEN as u32 + 56).to_ne_bytes());
+        request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+        request.extend_from_slice(&[0; 8]); // sequence number, port id
+        // struct inet_diag_req_v2: family, protocol, extensions wanted, pad, state mask, id
+        request.extend_from_slice(&[family, IPPROTO_TCP, 1 << (INET_DIAG_INFO - 1), 0]);
+        request.extend_from_slice(&u32::MAX.to_ne_bytes());
+        request.extend_from_slice(&[0; 48]);
+        sendto(fd.as_raw_fd(), &request, &NetlinkAddr::new(0, 0), MsgFlags::empty())?;
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let len = recv(fd.as_raw_fd(), &mut buffer, MsgFlags::empty())?;
+            if len == 0 {
+                return Ok(());
+            }
+            if parse_messages(&buffer[..len], sockets) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Add the sockets in one batch of netlink messages; true once the dump is over
+    pub fn parse_messages(mut data: &[u8], sockets: &mut HashMap<u64, (u64, u64)>) -> bool {
+        let u16_at = |bytes: &[u8], at: usize| u16::from_ne_bytes([bytes[at], bytes[at + 1]]);
+        let u32_at = |bytes: &[u8], at: usize| u32::from_ne_bytes(bytes[at..at + 4].try_into().unwrap_or_default());
+        let u64_at = |bytes: &[u8], at: usize| u64::from_ne_bytes(bytes[at..at + 8].try_into().unwrap_or_default());
+
+        while data.len() >= NLMSG_HEADER_LEN {
+            let len = u32_at(data, 0) as usize;
+            if len < NLMSG_HEADER_LEN || len > data.len() {
+                return true;
+            }
+            match u16_at(data, 4) {
+                NLMSG_DONE | NLMSG_ERROR => return true,
+                SOCK_DIAG_BY_FAMILY if len >= NLMSG_HEADER_LEN + DIAG_MSG_LEN => {
+                    let message = &data[NLMSG_HEADER_LEN..len];
+                    let inode = u32_at(message, DIAG_MSG_INODE) as u64;
+                    // Attributes follow the fixed part, each padded to 4 bytes
+                    let mut attributes = &message[DIAG_MSG_LEN..];
+                    while attributes.len() >= 4 {
+                        let attribute_len = u16_at(attributes, 0) as usize;
+                        if attribute_len < 4 || attribute_len > attributes.len() {
+                            break;
+                        }
+                        let payload = &attributes[4..attribute_len];
+                        // Sockets in TIME_WAIT have no inode and belong to nobody
+                        if u16_at(attributes, 2) == INET_DIAG_INFO && inode != 0 && payload.len() >= TCPI_BYTES_RECEIVED + 8 {
+                            sockets.insert(inode, (u64_at(payload, TCPI_BYTES_RECEIVED), u64_at(payload, TCPI_BYTES_ACKED)));
+                        }
+                        attributes = attributes.get((attribute_len + 3) & !3..).unwrap_or_default();
+                    }
+                },
+                _ => {},
+            }
+            data = data.get((len + 3) & !3..).unwrap_or_default();
+        }
+        false
+    }
+}
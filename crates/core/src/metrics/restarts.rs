@@ -0,0 +1,67 @@
+use crate::model::ProcessInfo;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, SystemTime},
+};
+
+/// Restarts are counted over this long
+const RESTART_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Exits and restarts of one name + command line
+#[derive(Debug, Default)]
+struct Identity {
+    live: HashSet<u32>,
+    /// Exits not yet followed by a new PID
+    exits: VecDeque<SystemTime>,
+    restarts: VecDeque<SystemTime>,
+}
+
+/// Counts restarts per process identity: a new PID with the same name and command line as
+/// one that exited. A daemon that keeps crashing and being brought back shows up even
+/// though no single PID lives long.
+#[derive(Debug, Default)]
+pub(crate) struct RestartTracker {
+    identities: HashMap<String, Identity>,
+}
+
+impl RestartTracker {
+    /// Compare the PIDs with the last pass and set each process's `restarts`
+    pub(crate) fn update(&mut self, processes: &mut [ProcessInfo], now: SystemTime) {
+        let mut current: HashMap<String, HashSet<u32>> = HashMap::new();
+        for process in processes.iter() {
+            current.entry(identity(process)).or_default().insert(process.pid);
+        }
+
+        // Identities gone entirely still have exits waiting for a restart
+        for (key, state) in self.identities.iter_mut() {
+            if !current.contains_key(key) {
+                state.exits.extend(state.live.drain().map(|_| now));
+            }
+        }
+        for (key, pids) in current {
+            let state = self.identities.entry(key).or_default();
+            state.exits.extend(state.live.difference(&pids).map(|_| now));
+            for _ in pids.difference(&state.live) {
+                if state.exits.pop_front().is_some() {
+                    state.restarts.push_back(now);
+                }
+            }
+            state.live = pids;
+        }
+
+        let cutoff = now.checked_sub(RESTART_WINDOW).unwrap_or(SystemTime::UNIX_EPOCH);
+        self.identities.retain(|_, state| {
+            state.exits.retain(|&at| at >= cutoff);
+            state.restarts.retain(|&at| at >= cutoff);
+            !state.live.is_empty() || !state.exits.is_empty() || !state.restarts.is_empty()
+        });
+
+        for process in processes.iter_mut() {
+            process.restarts = self.identities.get(&identity(process)).map_or(0, |state| state.restarts.len() as u32);
+        }
+    }
+}
+
+fn identity(process: &ProcessInfo) -> String {
+    format!("{}\0{}", process.name, process.cmd.join(" "))
+}
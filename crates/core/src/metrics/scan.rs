@@ -0,0 +1,198 @@
+use crate::{
+    metrics::disk::{nvme_controller, parent_device},
+    model::{DeepScan, DiskInfo, DiskUsage, OpenFiles, ProcessFileCount, SmartReport},
+};
+use std::{collections::HashMap, path::Path, time::Instant};
+
+/// Processes listed by descriptor count
+const BUSIEST_PROCESSES: usize = 15;
+
+/// Top-level entries listed by size
+const LARGEST_ENTRIES: usize = 15;
+
+/// Files between two progress reports while sizing a mount
+const PROGRESS_EVERY_FILES: u64 = 5000;
+
+/// Gather what is too slow for the refresh loop: every process's open descriptors, a fresh
+/// SMART read of each drive behind `disks`, and the space used under `mount_point`, staying
+/// on that filesystem. Blocks until done, so run it off the UI thread; `progress` hears
+/// what is being looked at as it goes.
+pub fn deep_scan(disks: &[DiskInfo], mount_point: Option<&str>, mut progress: impl FnMut(String)) -> DeepScan {
+    let started = Instant::now();
+
+    progress("counting open files".to_string());
+    let open_files = open_files(&mut progress);
+
+    let mut devices: Vec<String> = disks.iter().filter_map(|disk| smart_device(&disk.name)).collect();
+    devices.sort();
+    devices.dedup();
+    let smart = devices
+        .iter()
+        .map(|device| {
+            progress(format!("reading SMART data of {}", device));
+            smart_report(device)
+        })
+        .collect();
+
+    let disk_usage = mount_point.map(|mount_point| disk_usage(mount_point, &mut progress));
+
+    DeepScan {
+        open_files,
+        smart,
+        disk_usage,
+        duration: started.elapsed(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_files(progress: &mut impl FnMut(String)) -> Option<OpenFiles> {
+    let pids: Vec<u32> = std::fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+
+    let mut open = OpenFiles::default();
+    for (done, &pid) in pids.iter().enumerate() {
+        if done % 500 == 0 && done > 0 {
+            progress(format!("counting open files ({}/{} processes)", done, pids.len()));
+        }
+        // Other users' descriptors need root; the process may also have exited by now
+        let Ok(entries) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else {
+            open.unreadable += 1;
+            continue;
+        };
+        let count = entries.count() as u64;
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default().trim().to_string();
+        open.total += count;
+        open.busiest.push(ProcessFileCount { pid, name, count });
+    }
+
+    open.busiest.sort_by(|a, b| b.count.cmp(&a.count).then(a.pid.cmp(&b.pid)));
+    open.busiest.truncate(BUSIEST_PROCESSES);
+    Some(open)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files(_progress: &mut impl FnMut(String)) -> Option<OpenFiles> {
+    None
+}
+
+/// Whole device a disk name sits on, as smartctl wants it: "/dev/sda2" -> "/dev/sda",
+/// "/dev/nvme0n1p2" -> "/dev/nvme0". None for what has no drive behind it (overlay, tmpfs).
+pub fn smart_device(disk_name: &str) -> Option<String> {
+    if let Some(controller) = nvme_controller(disk_name) {
+        return Some(format!("/dev/{}", controller));
+    }
+    disk_name.starts_with("/dev/").then(|| format!("/dev/{}", parent_device(disk_name)))
+}
+
+fn smart_report(device: &str) -> SmartReport {
+    let failed = |error: String| SmartReport {
+        device: device.to_string(),
+        error: Some(error),
+        ..SmartReport::default()
+    };
+    let output = match std::process::Command::new("smartctl").args(["--json", "-i", "-H", "-A", device]).output() {
+        Ok(output) => output,
+        Err(_) => return failed("smartctl is not installed".to_string()),
+    };
+    // Like the NVMe health read, non-zero exit bits can just be drive warnings
+    match serde_json::from_slice(&output.stdout) {
+        Ok(json) => parse_smart_report(device, &json),
+        Err(_) => failed(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+}
+
+/// Model, verdict, temperature and power-on hours out of `smartctl --json -i -H -A`
+pub fn parse_smart_report(device: &str, json: &serde_json::Value) -> SmartReport {
+    let pointer = |path: &str| json.pointer(path);
+    let passed = pointer("/smart_status/passed").and_then(|v| v.as_bool());
+    // smartctl explains itself in messages when it can't open or identify the drive
+    let error = passed.is_none().then(|| {
+        pointer("/smartctl/messages/0/string")
+            .and_then(|v| v.as_str())
+            .unwrap_or("no health status reported")
+            .to_string()
+    });
+
+    SmartReport {
+        device: device.to_string(),
+        model: pointer("/model_name").and_then(|v| v.as_str()).map(str::to_string),
+        passed,
+        temperature: pointer("/temperature/current").and_then(|v| v.as_f64()).map(|t| t as f32),
+        power_on_hours: pointer("/power_on_time/hours").and_then(|v| v.as_u64()),
+        error,
+    }
+}
+
+/// Walk everything under `mount_point` on its own filesystem, summing what each top-level
+/// entry takes on disk
+fn disk_usage(mount_point: &str, progress: &mut impl FnMut(String)) -> DiskUsage {
+    let mut usage = DiskUsage {
+        mount_point: mount_point.to_string(),
+        ..DiskUsage::default()
+    };
+    let root = Path::new(mount_point);
+    let Ok(root_meta) = std::fs::symlink_metadata(root) else {
+        usage.unreadable += 1;
+        return usage;
+    };
+    let device = device_id(&root_meta);
+
+    // Top-level entry each pending directory belongs to
+    let mut sizes: HashMap<String, u64> = HashMap::new();
+    let mut pending: Vec<(std::path::PathBuf, Option<String>)> = vec![(root.to_path_buf(), None)];
+    while let Some((dir, top)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            usage.unreadable += 1;
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            // Other filesystems mounted below, /proc included, are not this mount's space
+            if device_id(&meta) != device {
+                continue;
+            }
+            let top = top.clone().unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+            let size = allocated(&meta);
+            usage.total_bytes += size;
+            usage.files += 1;
+            *sizes.entry(top.clone()).or_default() += size;
+            if meta.is_dir() {
+                pending.push((entry.path(), Some(top)));
+            }
+            if usage.files % PROGRESS_EVERY_FILES == 0 {
+                progress(format!("sizing {} ({} files so far)", mount_point, usage.files));
+            }
+        }
+    }
+
+    let mut largest: Vec<(String, u64)> = sizes.into_iter().collect();
+    largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    largest.truncate(LARGEST_ENTRIES);
+    usage.largest = largest;
+    usage
+}
+
+#[cfg(unix)]
+fn device_id(meta: &std::fs::Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::dev(meta)
+}
+
+#[cfg(not(unix))]
+fn device_id(_meta: &std::fs::Metadata) -> u64 {
+    0
+}
+
+/// Space actually taken, so sparse files count what they use, as du does
+#[cfg(unix)]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::blocks(meta) * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    meta.len()
+}
@@ -0,0 +1,114 @@
+use crate::{error::Result, model::RunQueueLatency};
+use std::collections::HashMap;
+
+/// Run-queue delay from per-thread /proc/<pid>/task/<tid>/schedstat deltas
+pub struct SchedLatencyCollector {
+    // tid -> (total ns spent waiting on a run queue, timeslices run)
+    previous: HashMap<u32, (u64, u64)>,
+}
+
+impl SchedLatencyCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            previous: HashMap::new(),
+        })
+    }
+
+    pub fn init(&mut self) -> Result<()> {
+        self.collect()?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn collect(&mut self) -> Result<Option<RunQueueLatency>> {
+        let current = read_thread_schedstats();
+        let mut samples = Vec::new();
+        for (tid, &(wait_ns, slices)) in &current {
+            // Threads new since the last pass have no baseline yet
+            if let Some(&(previous_wait, previous_slices)) = self.previous.get(tid) {
+                let slices = slices.saturating_sub(previous_slices);
+                if slices > 0 {
+                    samples.push((wait_ns.saturating_sub(previous_wait), slices));
+                }
+            }
+        }
+        self.previous = current;
+        Ok(run_queue_latency(&samples))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn collect(&mut self) -> Result<Option<RunQueueLatency>> {
+        Ok(None)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_thread_schedstats() -> HashMap<u32, (u64, u64)> {
+    let mut stats = HashMap::new();
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return stats;
+    };
+
+    for process in processes.flatten() {
+        if !process.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let Ok(threads) = std::fs::read_dir(process.path().join("task")) else {
+            continue;
+        };
+        for thread in threads.flatten() {
+            let Ok(tid) = thread.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            if let Some(stat) = std::fs::read_to_string(thread.path().join("schedstat"))
+                .ok()
+                .and_then(|contents| parse_schedstat(&contents))
+            {
+                stats.insert(tid, stat);
+            }
+        }
+    }
+
+    stats
+}
+
+/// Parse "<run ns> <wait ns> <timeslices>" into (wait ns, timeslices)
+pub fn parse_schedstat(contents: &str) -> Option<(u64, u64)> {
+    let mut fields = contents.split_whitespace().skip(1).map(|f| f.parse::<u64>().ok());
+    Some((fields.next()??, fields.next()??))
+}
+
+/// Mean and percentiles from per-thread (wait ns, timeslices) deltas. Each thread's average
+/// delay counts once per timeslice, so busy threads weigh more than ones that barely ran.
+pub fn run_queue_latency(samples: &[(u64, u64)]) -> Option<RunQueueLatency> {
+    let total_slices: u64 = samples.iter().map(|&(_, slices)| slices).sum();
+    if total_slices == 0 {
+        return None;
+    }
+    let total_wait: u64 = samples.iter().map(|&(wait, _)| wait).sum();
+
+    let mut delays: Vec<(f64, u64)> = samples
+        .iter()
+        .map(|&(wait, slices)| (wait as f64 / slices as f64, slices))
+        .collect();
+    delays.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let percentile = |p: f64| {
+        let target = (total_slices as f64 * p).ceil() as u64;
+        let mut seen = 0;
+        for &(delay, slices) in &delays {
+            seen += slices;
+            if seen >= target {
+                return delay;
+            }
+        }
+        delays.last().map(|&(delay, _)| delay).unwrap_or_default()
+    };
+
+    let to_us = |ns: f64| (ns / 1_000.0) as f32;
+    Some(RunQueueLatency {
+        mean_us: to_us(total_wait as f64 / total_slices as f64),
+        p50_us: to_us(percentile(0.50)),
+        p99_us: to_us(percentile(0.99)),
+    })
+}
@@ -0,0 +1,119 @@
+use crate::{
+    error::{CoreError, Result},
+    model::{ServiceAction, ServiceInfo},
+};
+
+/// Properties asked of every unit; `parse_show_output` reads them back
+const PROPERTIES: &str = "Id,Description,LoadState,ActiveState,SubState,MainPID,MemoryCurrent,NRestarts";
+
+/// systemd service units with their state, memory and restart counts, read with
+/// `systemctl show`. Asking systemd about every unit costs a few tens of milliseconds, so
+/// like socket enumeration the collector is off until something (the services view) needs
+/// it. Linux-only; elsewhere it reports no units.
+pub struct ServicesCollector {
+    enabled: bool,
+}
+
+impl ServicesCollector {
+    pub fn new() -> Result<Self> {
+        Ok(Self { enabled: false })
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Loaded service units sorted by name; empty while disabled
+    pub fn collect(&mut self) -> Result<Vec<ServiceInfo>> {
+        if !self.enabled {
+            return Ok(Vec::new());
+        }
+
+        self.collect_units()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn collect_units(&self) -> Result<Vec<ServiceInfo>> {
+        let output = std::process::Command::new("systemctl")
+            .args(["show", "--no-pager", "--property", PROPERTIES, "*.service"])
+            .output()
+            .map_err(|e| CoreError::unsupported_platform(format!("systemctl: {}", e)))?;
+        if !output.status.success() {
+            return Err(systemctl_error(&output.stderr));
+        }
+        let mut units = parse_show_output(&String::from_utf8_lossy(&output.stdout));
+        units.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(units)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn collect_units(&self) -> Result<Vec<ServiceInfo>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Queue a start, stop or restart of `unit` and return without waiting for it to finish;
+/// the unit's state shows the outcome on later passes. Never prompts for a password, so
+/// without the rights (root or a polkit rule) this fails straight away.
+#[cfg(target_os = "linux")]
+pub fn control_unit(unit: &str, action: ServiceAction) -> Result<()> {
+    let output = std::process::Command::new("systemctl")
+        .args(["--no-block", "--no-ask-password", action.verb(), "--", unit])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| CoreError::unsupported_platform(format!("systemctl: {}", e)))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(systemctl_error(&output.stderr))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn control_unit(_unit: &str, _action: ServiceAction) -> Result<()> {
+    Err(CoreError::unsupported_platform("systemd units are Linux-only"))
+}
+
+/// systemctl's own explanation, the first line of which is usually enough
+#[cfg(target_os = "linux")]
+fn systemctl_error(stderr: &[u8]) -> CoreError {
+    let stderr = String::from_utf8_lossy(stderr);
+    let message = stderr.lines().next().unwrap_or("systemctl failed").trim().to_string();
+    if message.contains("Access denied") || message.contains("authentication required") {
+        CoreError::permission_denied(message)
+    } else {
+        CoreError::system_info(message)
+    }
+}
+
+/// Units out of `systemctl show` output: KEY=value lines, a blank line between units.
+/// Units systemd couldn't find a file for are left out.
+pub fn parse_show_output(output: &str) -> Vec<ServiceInfo> {
+    let mut units = Vec::new();
+    for block in output.split("\n\n") {
+        let mut unit = ServiceInfo::default();
+        let mut loaded = true;
+        for (key, value) in block.lines().filter_map(|line| line.split_once('=')) {
+            match key {
+                "Id" => unit.name = value.to_string(),
+                "Description" => unit.description = value.to_string(),
+                "LoadState" => loaded = value != "not-found",
+                "ActiveState" => unit.active_state = value.to_string(),
+                "SubState" => unit.sub_state = value.to_string(),
+                "MainPID" => unit.main_pid = value.parse().ok().filter(|&pid| pid != 0),
+                // "[not set]" without accounting; u64::MAX on some versions for the same
+                "MemoryCurrent" => unit.memory = value.parse().ok().filter(|&bytes| bytes != u64::MAX),
+                "NRestarts" => unit.restarts = value.parse().unwrap_or(0),
+                _ => {},
+            }
+        }
+        if loaded && !unit.name.is_empty() {
+            units.push(unit);
+        }
+    }
+    units
+}
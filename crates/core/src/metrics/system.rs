@@ -2,15 +2,11 @@ use crate::{error::Result, model::SystemInfo};
 use std::time::{Duration, SystemTime};
 use sysinfo::System;
 
-pub struct SystemCollector {
-    sys: System,
-}
+pub struct SystemCollector;
 
 impl SystemCollector {
     pub fn new() -> Result<Self> {
-        let sys = System::new();
-        
-        Ok(Self { sys })
+        Ok(Self)
     }
 
     pub fn collect(&mut self) -> Result<SystemInfo> {
@@ -34,9 +30,21 @@ impl SystemCollector {
             load_avg_1,
             load_avg_5,
             load_avg_15,
+            processes_created: Self::get_processes_created(),
         })
     }
 
+    #[cfg(target_os = "linux")]
+    fn get_processes_created() -> Option<u64> {
+        let stat = std::fs::read_to_string("/proc/stat").ok()?;
+        stat.lines().find_map(|line| line.strip_prefix("processes "))?.trim().parse().ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn get_processes_created() -> Option<u64> {
+        None
+    }
+
     #[cfg(unix)]
     fn get_load_averages(&self) -> (f64, f64, f64) {
         let load_avg = System::load_average();
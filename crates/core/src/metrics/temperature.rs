@@ -114,7 +114,7 @@ impl TemperatureInfo {
     
     pub fn get_percentage(&self) -> f32 {
         let max_temp = self.max.or(self.critical).unwrap_or(100.0);
-        (self.temperature / max_temp * 100.0).min(100.0).max(0.0)
+        (self.temperature / max_temp * 100.0).clamp(0.0, 100.0)
     }
 }
 
@@ -0,0 +1,105 @@
+use crate::{
+    error::{CoreError, Result},
+    model::{ProcessState, ThreadInfo},
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// /proc reports times in USER_HZ, which the kernel fixes at 100 for userspace
+#[cfg(target_os = "linux")]
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// The threads of one process with their CPU use, from /proc/<pid>/task/<tid>/stat deltas
+pub struct ThreadCollector {
+    pid: u32,
+    // tid -> utime + stime in clock ticks at the last pass
+    previous: HashMap<u32, u64>,
+    sampled_at: Option<Instant>,
+}
+
+impl ThreadCollector {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            previous: HashMap::new(),
+            sampled_at: None,
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Every thread of the process; CPU percentages are zero until the second pass
+    #[cfg(target_os = "linux")]
+    pub fn collect(&mut self) -> Result<Vec<ThreadInfo>> {
+        let tasks = std::fs::read_dir(format!("/proc/{}/task", self.pid))
+            .map_err(|e| CoreError::process_info(format!("Failed to list threads of {}: {}", self.pid, e)))?;
+        let now = Instant::now();
+        let elapsed = self.sampled_at.map(|at| now.duration_since(at).as_secs_f32().max(0.001));
+
+        let mut threads = Vec::new();
+        let mut current = HashMap::new();
+        for task in tasks.flatten() {
+            let Ok(tid) = task.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            // Threads can exit between listing and reading
+            let Some((name, state, ticks)) = std::fs::read_to_string(task.path().join("stat"))
+                .ok()
+                .and_then(|stat| parse_thread_stat(&stat))
+            else {
+                continue;
+            };
+            let cpu_percent = match (elapsed, self.previous.get(&tid)) {
+                (Some(elapsed), Some(&previous)) => {
+                    ticks.saturating_sub(previous) as f32 / CLOCK_TICKS_PER_SEC as f32 / elapsed * 100.0
+                },
+                _ => 0.0,
+            };
+            current.insert(tid, ticks);
+            threads.push(ThreadInfo {
+                tid,
+                name,
+                state,
+                cpu_percent,
+                cpu_time: Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SEC),
+            });
+        }
+
+        self.previous = current;
+        self.sampled_at = Some(now);
+        threads.sort_by_key(|thread| thread.tid);
+        Ok(threads)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn collect(&mut self) -> Result<Vec<ThreadInfo>> {
+        Err(CoreError::unsupported_platform("Per-thread statistics are only available on Linux".to_string()))
+    }
+}
+
+/// Name, state and utime + stime (fields 2, 3, 14 and 15) from /proc/<pid>/task/<tid>/stat
+pub fn parse_thread_stat(stat: &str) -> Option<(String, ProcessState, u64)> {
+    // The name can contain spaces and parentheses; fields resume after the last ')'
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?.to_string();
+    let mut fields = stat[close + 1..].split_whitespace();
+    let state = match fields.next()? {
+        "R" => ProcessState::Running,
+        "S" | "I" => ProcessState::Sleeping,
+        "D" => ProcessState::DiskSleep,
+        "Z" => ProcessState::Zombie,
+        "T" | "t" => ProcessState::Stopped,
+        "W" => ProcessState::Paging,
+        "X" | "x" => ProcessState::Dead,
+        _ => ProcessState::Unknown,
+    };
+    let mut times = fields.skip(10);
+    let utime: u64 = times.next()?.parse().ok()?;
+    let stime: u64 = times.next()?.parse().ok()?;
+    Some((name, state, utime + stime))
+}
@@ -0,0 +1,381 @@
+//! Stand-ins for the platform and the collectors, for tests that shouldn't depend on the
+//! machine they run on. `FakeCollector` makes the same snapshots every run; `MockPlatformProvider`
+//! answers for whatever processes it was given.
+
+use crate::{
+    error::{CoreError, Result},
+    model::*,
+    platform::{PlatformProcessDetails, PlatformProvider, PlatformSystemMetrics},
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// `PlatformProvider` over a fixed set of processes and counters
+#[derive(Debug, Clone, Default)]
+pub struct MockPlatformProvider {
+    details: HashMap<u32, PlatformProcessDetails>,
+    metrics: PlatformSystemMetrics,
+}
+
+impl MockPlatformProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `get_process_details(pid)` with `details`; other PIDs don't exist
+    pub fn with_process(mut self, pid: u32, details: PlatformProcessDetails) -> Self {
+        self.details.insert(pid, details);
+        self
+    }
+
+    pub fn with_system_metrics(mut self, metrics: PlatformSystemMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+}
+
+impl PlatformProvider for MockPlatformProvider {
+    fn get_process_details(&self, pid: u32) -> Result<PlatformProcessDetails> {
+        self.details
+            .get(&pid)
+            .cloned()
+            .ok_or_else(|| CoreError::platform(format!("Failed to read process {}: no such process", pid)))
+    }
+
+    fn get_system_metrics(&self) -> Result<PlatformSystemMetrics> {
+        Ok(self.metrics.clone())
+    }
+
+    fn supports_process_kill(&self) -> bool {
+        false
+    }
+
+    fn platform_name(&self) -> &'static str {
+        "mock"
+    }
+}
+
+/// (pid, name, command line, user, parent) of the fake machine's processes
+const PROCESSES: &[(u32, &str, &str, &str, u32)] = &[
+    (1, "systemd", "/sbin/init", "root", 0),
+    (412, "sshd", "sshd: /usr/sbin/sshd -D", "root", 1),
+    (530, "postgres", "postgres -D /var/lib/postgresql/data", "postgres", 1),
+    (531, "postgres", "postgres: checkpointer", "postgres", 530),
+    (532, "postgres", "postgres: walwriter", "postgres", 530),
+    (610, "nginx", "nginx: master process /usr/sbin/nginx", "root", 1),
+    (611, "nginx", "nginx: worker process", "www-data", 610),
+    (612, "nginx", "nginx: worker process", "www-data", 610),
+    (1200, "bash", "-bash", "dev", 412),
+    (1304, "cargo", "cargo build --release", "dev", 1200),
+    (1305, "rustc", "rustc --crate-name kacemon --edition 2021", "dev", 1304),
+    (2048, "node", "node server.js", "dev", 1200),
+];
+
+/// Deterministic snapshots of a small made-up server: a database, a web server and a
+/// developer's build, with load that rises and falls over a few minutes and memory that
+/// creeps up in the node process. Snapshot N is the same on every run and every machine.
+pub struct FakeCollector {
+    tick: u64,
+    start: SystemTime,
+    interval: Duration,
+    // Time covered by the snapshots so far, which differs from `interval * tick` once the
+    // interval has been changed
+    elapsed: Duration,
+    // Time since the snapshot before the next one
+    pass: Duration,
+    totals: Traffic,
+}
+
+/// Disk and network bytes and packets, per pass or since the first snapshot
+#[derive(Debug, Clone, Copy, Default)]
+struct Traffic {
+    disk_read: u64,
+    disk_write: u64,
+    rx: u64,
+    tx: u64,
+    rx_packets: u64,
+    tx_packets: u64,
+}
+
+impl Traffic {
+    /// One pass of `seconds` at `load`
+    fn at(load: f64, seconds: f64) -> Self {
+        let per_pass = |rate: f64| (rate * seconds) as u64;
+        let rx = per_pass(5e5 + load * 12e6);
+        let tx = per_pass(2e5 + load * 3e6);
+        Self {
+            disk_read: per_pass(2e6 + load * 40e6),
+            disk_write: per_pass(1e6 + load * 25e6),
+            rx,
+            tx,
+            rx_packets: rx / 1200,
+            tx_packets: tx / 800,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            disk_read: self.disk_read + other.disk_read,
+            disk_write: self.disk_write + other.disk_write,
+            rx: self.rx + other.rx,
+            tx: self.tx + other.tx,
+            rx_packets: self.rx_packets + other.rx_packets,
+            tx_packets: self.tx_packets + other.tx_packets,
+        }
+    }
+}
+
+impl Default for FakeCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeCollector {
+    /// First snapshot at 2023-11-14 22:13:20 UTC, one every second
+    pub fn new() -> Self {
+        Self {
+            tick: 0,
+            start: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            interval: Duration::from_secs(1),
+            elapsed: Duration::ZERO,
+            pass: Duration::from_secs(1),
+            totals: Traffic::default(),
+        }
+    }
+
+    pub fn starting_at(mut self, start: SystemTime) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self.pass = interval;
+        self
+    }
+
+    /// Space later snapshots `interval` apart. Counters carry on from where they were.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Snapshots taken so far
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// The next snapshot
+    pub fn collect(&mut self) -> SystemSnapshot {
+        let traffic = Traffic::at(load_at(self.elapsed), self.pass.as_secs_f64());
+        self.totals = self.totals.add(traffic);
+        let snapshot = self.snapshot(self.tick, self.elapsed, traffic, self.totals);
+        self.tick += 1;
+        self.elapsed += self.interval;
+        self.pass = self.interval;
+        snapshot
+    }
+
+    /// Snapshot number `tick` had the interval never changed, without moving on
+    pub fn snapshot_at(&self, tick: u64) -> SystemSnapshot {
+        let seconds = self.interval.as_secs_f64();
+        let elapsed = self.interval * tick as u32;
+        let totals = (0..=tick).fold(Traffic::default(), |totals, pass| {
+            totals.add(Traffic::at(load_at(self.interval * pass as u32), seconds))
+        });
+        self.snapshot(tick, elapsed, Traffic::at(load_at(elapsed), seconds), totals)
+    }
+
+    /// The snapshot `elapsed` after the first, `traffic` of it in the last pass and `totals` in all
+    fn snapshot(&self, tick: u64, elapsed: Duration, traffic: Traffic, totals: Traffic) -> SystemSnapshot {
+        let timestamp = self.start + elapsed;
+        let load = load_at(elapsed);
+        // A megabyte a second, for the first hour
+        let leaked = elapsed.as_secs().min(3600) * 1024 * 1024;
+
+        let cpu_cores: Vec<CpuCore> = (0..4)
+            .map(|id| CpuCore {
+                id,
+                name: format!("cpu{}", id),
+                usage_percent: (8.0 + load * 84.0 + noise(tick, id as u64) * 8.0) as f32,
+                frequency: 2400 + (load * 1200.0) as u64,
+                base_frequency: Some(2400),
+                max_frequency: Some(4200),
+            })
+            .collect();
+
+        const GB: u64 = 1024 * 1024 * 1024;
+        let total = 16 * GB;
+        let used = 6 * GB + (load * 4.0 * GB as f64) as u64 + leaked;
+        let memory = MemoryInfo {
+            total,
+            used,
+            available: total - used,
+            free: (total - used) / 4,
+            buffers: GB / 4,
+            cached: (total - used) / 2,
+            swap_total: 4 * GB,
+            swap_used: 128 * 1024 * 1024,
+            swap_free: 4 * GB - 128 * 1024 * 1024,
+            swap_in_pages: 0,
+            swap_out_pages: 0,
+        };
+
+        let disks = vec![DiskInfo {
+            name: "/dev/nvme0n1p2".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            total_space: 500 * GB,
+            used_space: 210 * GB,
+            available_space: 290 * GB,
+            read_bytes: totals.disk_read,
+            write_bytes: totals.disk_write,
+            read_bytes_delta: traffic.disk_read,
+            write_bytes_delta: traffic.disk_write,
+            latency_ms: Some((0.2 + load * 2.0) as f32),
+            busy_percent: Some((load * 60.0) as f32),
+            nvme_health: None,
+        }];
+
+        let networks = vec![NetworkInfo {
+            interface_name: "eth0".to_string(),
+            rx_bytes: totals.rx,
+            tx_bytes: totals.tx,
+            rx_bytes_delta: traffic.rx,
+            tx_bytes_delta: traffic.tx,
+            rx_packets: totals.rx_packets,
+            tx_packets: totals.tx_packets,
+            rx_errors: 0,
+            tx_errors: 0,
+            rx_packets_delta: traffic.rx_packets,
+            tx_packets_delta: traffic.tx_packets,
+            rx_errors_delta: 0,
+            tx_errors_delta: 0,
+            is_virtual: false,
+        }];
+
+        let temperatures = vec![TemperatureInfo {
+            label: "Package id 0".to_string(),
+            temperature: (45.0 + load * 30.0) as f32,
+            critical: Some(100.0),
+            max: Some(90.0),
+        }];
+
+        let processes = PROCESSES
+            .iter()
+            .enumerate()
+            .map(|(index, &(pid, name, cmd, user, parent))| {
+                let cpu = match name {
+                    "rustc" => 40.0 + load * 300.0,
+                    "postgres" if pid == 530 => 5.0 + load * 30.0,
+                    "nginx" if pid != 610 => 2.0 + load * 15.0,
+                    "node" => 8.0,
+                    _ => 0.0,
+                } + noise(tick, 100 + index as u64);
+                let rss = match name {
+                    "postgres" => 256 * 1024 * 1024,
+                    "rustc" => 900 * 1024 * 1024 + (load * 600.0 * 1024.0 * 1024.0) as u64,
+                    // The leak
+                    "node" => 120 * 1024 * 1024 + leaked,
+                    _ => 8 * 1024 * 1024,
+                };
+                // Forked servers share most of their memory: postgres its buffers, nginx its code
+                let (private, sharers) = match name {
+                    "postgres" => (24 * 1024 * 1024, 3),
+                    "nginx" => (3 * 1024 * 1024, 3),
+                    _ => (rss, 1),
+                };
+                ProcessInfo {
+                    pid,
+                    name: name.to_string(),
+                    cmd: cmd.split(' ').map(str::to_string).collect(),
+                    user: user.to_string(),
+                    cpu_percent: cpu as f32,
+                    memory_percent: rss as f32 / total as f32 * 100.0,
+                    memory_rss: rss,
+                    memory_vsz: rss * 3,
+                    threads: if name == "rustc" { 16 } else { 1 },
+                    state: if cpu >= 1.0 { ProcessState::Running } else { ProcessState::Sleeping },
+                    // Started in PID order, the first an hour before the first snapshot
+                    start_time: self.start - Duration::from_secs(3600) + Duration::from_secs(index as u64 * 60),
+                    parent_pid: (parent != 0).then_some(parent),
+                    pgid: Some(if parent == 1 || parent == 0 { pid } else { parent }),
+                    session_id: Some(if user == "dev" { 1200 } else { pid }),
+                    tty: (user == "dev").then(|| "pts/0".to_string()),
+                    cgroup: None,
+                    container_id: None,
+                    pod: None,
+                    gpu_usage: Vec::new(),
+                    category: None,
+                    cpu_time_total: Duration::from_secs_f64(cpu / 100.0 * (3600.0 + elapsed.as_secs_f64())),
+                    children_cpu_time: Duration::ZERO,
+                    children_cpu_percent: 0.0,
+                    wchan: None,
+                    open_fds: Some(16 + index as u64),
+                    memory_pss: Some(private + (rss - private) / sharers),
+                    memory_uss: Some(private),
+                    busy_since: None,
+                    restarts: 0,
+                    net_rate: None,
+                    is_kernel_thread: false,
+                    partial: false,
+                }
+            })
+            .collect();
+
+        SystemSnapshot {
+            timestamp,
+            system: SystemInfo {
+                hostname: "demo".to_string(),
+                os_name: "Linux".to_string(),
+                os_version: "6.1".to_string(),
+                uptime: Duration::from_secs(86_400) + elapsed,
+                boot_time: self.start - Duration::from_secs(86_400),
+                load_avg_1: 0.3 + load * 3.5,
+                load_avg_5: 0.3 + load * 2.5,
+                load_avg_15: 0.3 + load * 1.5,
+                processes_created: None,
+            },
+            cpu_cores,
+            memory,
+            disks,
+            networks,
+            temperatures,
+            gpus: vec![],
+            connections: vec![],
+            pressure: PressureInfo {
+                cpu: Some(PressureStall { some_avg10: (load * 20.0) as f32, full_avg10: None }),
+                memory: Some(PressureStall { some_avg10: 0.0, full_avg10: Some(0.0) }),
+                io: Some(PressureStall { some_avg10: (load * 5.0) as f32, full_avg10: Some((load * 2.0) as f32) }),
+            },
+            run_queue: None,
+            cgroups: vec![],
+            services: vec![],
+            log_entries: vec![],
+            processes,
+            collector_failures: vec![],
+        }
+    }
+}
+
+/// Rises for two minutes, falls for two
+fn load_at(elapsed: Duration) -> f64 {
+    triangle(elapsed.as_secs_f64(), 240.0)
+}
+
+/// 0 → 1 → 0 over `period`
+fn triangle(t: f64, period: f64) -> f64 {
+    let phase = (t % period) / period;
+    1.0 - (2.0 * phase - 1.0).abs()
+}
+
+/// Jitter in 0..1 that's the same for the same tick and stream (splitmix64)
+fn noise(tick: u64, stream: u64) -> f64 {
+    let mut z = tick.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ stream.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
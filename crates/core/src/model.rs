@@ -1,48 +1,335 @@
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, SystemTime},
+};
 
 /// Sort key for process table
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SortKey {
+    #[default]
     Cpu,
     Memory,
     Pid,
     Name,
-}
-
-impl Default for SortKey {
-    fn default() -> Self {
-        Self::Cpu
-    }
+    Rss,
+    Vsz,
+    Threads,
+    State,
+    /// Time since the process started; the AGE column
+    StartTime,
+    User,
+    /// How long CPU has stayed over the hog threshold
+    Busy,
+    Restarts,
+    /// TCP bytes received plus sent per second
+    Net,
+    /// Open file descriptors
+    Fds,
+    /// CPU time used since the process started
+    CpuTime,
+    ProcessGroup,
+    Session,
+    /// Controlling terminal
+    Tty,
+    /// When the process started; the START column, oldest first
+    Started,
+    Container,
+    /// Kubernetes namespace/pod
+    Pod,
+    /// Proportional set size: resident memory with shared pages split among their users
+    Pss,
+    /// Unique set size: memory no other process maps, what exiting would free
+    Uss,
 }
 
 impl SortKey {
+    /// Cycle through the common keys (CPU% → MEM% → PID → NAME)
     pub fn next(self) -> Self {
         match self {
             Self::Cpu => Self::Memory,
             Self::Memory => Self::Pid,
             Self::Pid => Self::Name,
-            Self::Name => Self::Cpu,
+            _ => Self::Cpu,
+        }
+    }
+
+    /// Process table column header this key sorts by
+    pub fn column(self) -> &'static str {
+        match self {
+            Self::Cpu => "CPU%",
+            Self::Memory => "MEM%",
+            Self::Pid => "PID",
+            Self::Name => "NAME",
+            Self::Rss => "RSS",
+            Self::Vsz => "VSZ",
+            Self::Threads => "THR",
+            Self::State => "STATE",
+            Self::StartTime => "AGE",
+            Self::User => "USER",
+            Self::Busy => "BUSY",
+            Self::Restarts => "RST",
+            Self::Net => "NET",
+            Self::Fds => "FDS",
+            Self::CpuTime => "TIME",
+            Self::ProcessGroup => "PGID",
+            Self::Session => "SID",
+            Self::Tty => "TTY",
+            Self::Started => "START",
+            Self::Container => "CONTAINER",
+            Self::Pod => "POD",
+            Self::Pss => "PSS",
+            Self::Uss => "USS",
+        }
+    }
+
+    /// Sort key for a process table column header
+    pub fn from_column(column: &str) -> Option<Self> {
+        match column {
+            "CPU%" => Some(Self::Cpu),
+            "MEM%" => Some(Self::Memory),
+            "PID" => Some(Self::Pid),
+            "NAME" => Some(Self::Name),
+            "RSS" => Some(Self::Rss),
+            "VSZ" => Some(Self::Vsz),
+            "THR" => Some(Self::Threads),
+            "STATE" => Some(Self::State),
+            "AGE" => Some(Self::StartTime),
+            "TIME" => Some(Self::CpuTime),
+            "PGID" => Some(Self::ProcessGroup),
+            "SID" => Some(Self::Session),
+            "TTY" => Some(Self::Tty),
+            "START" => Some(Self::Started),
+            "CONTAINER" => Some(Self::Container),
+            "POD" => Some(Self::Pod),
+            "USER" => Some(Self::User),
+            "BUSY" => Some(Self::Busy),
+            "RST" => Some(Self::Restarts),
+            "NET" => Some(Self::Net),
+            "FDS" => Some(Self::Fds),
+            "PSS" => Some(Self::Pss),
+            "USS" => Some(Self::Uss),
+            _ => None,
+        }
+    }
+
+    /// Whether this key is usually wanted biggest-first
+    pub fn default_descending(self) -> bool {
+        matches!(self, Self::Cpu | Self::Memory | Self::Rss | Self::Vsz | Self::Threads | Self::Busy | Self::Restarts | Self::Net | Self::Fds | Self::CpuTime | Self::Pss | Self::Uss)
+    }
+
+    /// Compare two processes in ascending order of this key, names and users naturally
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> std::cmp::Ordering {
+        self.compare_with(a, b, NameCollation::default())
+    }
+
+    /// Compare two processes in ascending order of this key, names and users by `collation`
+    pub fn compare_with(self, a: &ProcessInfo, b: &ProcessInfo, collation: NameCollation) -> std::cmp::Ordering {
+        match self {
+            Self::Cpu => a.cpu_percent.partial_cmp(&b.cpu_percent).unwrap_or(std::cmp::Ordering::Equal),
+            Self::Memory => a.memory_percent.partial_cmp(&b.memory_percent).unwrap_or(std::cmp::Ordering::Equal),
+            Self::Pid => a.pid.cmp(&b.pid),
+            Self::Name => collation.compare(&a.name, &b.name),
+            Self::Rss => a.memory_rss.cmp(&b.memory_rss),
+            Self::Vsz => a.memory_vsz.cmp(&b.memory_vsz),
+            Self::Threads => a.threads.cmp(&b.threads),
+            Self::State => a.state.cmp(&b.state),
+            // The AGE column shows elapsed time, so ascending means most recently started first
+            Self::StartTime => b.start_time.cmp(&a.start_time),
+            Self::User => collation.compare(&a.user, &b.user),
+            // Busy since earlier means busy for longer; not busy at all sorts lowest
+            Self::Busy => a.busy_since.map(std::cmp::Reverse).cmp(&b.busy_since.map(std::cmp::Reverse)),
+            Self::Restarts => a.restarts.cmp(&b.restarts),
+            // Not measured sorts below no traffic
+            Self::Net => a.net_rate.map(|r| r.total()).cmp(&b.net_rate.map(|r| r.total())),
+            Self::Fds => a.open_fds.cmp(&b.open_fds),
+            Self::CpuTime => a.cpu_time_total.cmp(&b.cpu_time_total),
+            Self::ProcessGroup => a.pgid.cmp(&b.pgid),
+            Self::Session => a.session_id.cmp(&b.session_id),
+            // No terminal sorts first, as "?" does in ps; pts/9 before pts/10
+            Self::Tty => match (&a.tty, &b.tty) {
+                (Some(x), Some(y)) => collation.compare(x, y),
+                (x, y) => x.is_some().cmp(&y.is_some()),
+            },
+            Self::Started => a.start_time.cmp(&b.start_time),
+            Self::Container => a.container_id.cmp(&b.container_id),
+            Self::Pod => a.pod.cmp(&b.pod),
+            // Not measured sorts lowest, as with NET
+            Self::Pss => a.memory_pss.cmp(&b.memory_pss),
+            Self::Uss => a.memory_uss.cmp(&b.memory_uss),
+        }
+    }
+}
+
+/// How process and user names are ordered when sorting by them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameCollation {
+    /// Case-insensitive, with runs of digits compared as numbers: "Chrome", "chrome",
+    /// "chrome-2", "chrome-10"
+    #[default]
+    Natural,
+    /// Byte order, so every uppercase letter comes before any lowercase one
+    Bytes,
+}
+
+impl NameCollation {
+    pub fn compare(self, a: &str, b: &str) -> std::cmp::Ordering {
+        match self {
+            Self::Natural => natural_cmp(a, b),
+            Self::Bytes => a.cmp(b),
+        }
+    }
+}
+
+/// What the process table collapses into one row each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessGrouping {
+    /// Every process on its own row
+    #[default]
+    None,
+    /// Processes sharing a name
+    Name,
+    /// Processes in the same process group, which is what a shell calls a job
+    ProcessGroup,
+    /// Processes in the same session, usually everything started from one terminal
+    Session,
+    /// Processes in the same container, and everything outside one as the host
+    Container,
+}
+
+/// Case-insensitive comparison with digit runs taken as numbers, so "worker-9" comes before
+/// "worker-10". Names equal but for case or leading zeros fall back to byte order, uppercase
+/// first, to keep the order total.
+pub fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let (mut left, mut right) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let (x, y) = match (left.peek(), right.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(&x), Some(&y)) => (x, y),
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let digits = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                let mut run = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    run.push(c);
+                }
+                run.trim_start_matches('0').to_string()
+            };
+            let (x, y) = (digits(&mut left), digits(&mut right));
+            // Without leading zeros the longer run is the bigger number
+            let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            continue;
         }
+        let ordering = x.to_lowercase().cmp(y.to_lowercase());
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        left.next();
+        right.next();
     }
 }
 
 /// Process state
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub enum ProcessState {
     Running,
     Sleeping,
     Waiting,
+    /// Uninterruptible sleep (D), usually stuck on storage or NFS
+    DiskSleep,
     Zombie,
     Stopped,
     Paging,
     Dead,
+    #[default]
     Unknown,
 }
 
-impl Default for ProcessState {
-    fn default() -> Self {
-        Self::Unknown
+/// Signals that can be sent to a process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KillSignal {
+    #[default]
+    Term,
+    Kill,
+    Hup,
+    Int,
+    Stop,
+    Cont,
+    Usr1,
+    Usr2,
+}
+
+impl KillSignal {
+    pub const ALL: [KillSignal; 8] = [
+        KillSignal::Term,
+        KillSignal::Kill,
+        KillSignal::Hup,
+        KillSignal::Int,
+        KillSignal::Stop,
+        KillSignal::Cont,
+        KillSignal::Usr1,
+        KillSignal::Usr2,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KillSignal::Term => "SIGTERM",
+            KillSignal::Kill => "SIGKILL",
+            KillSignal::Hup => "SIGHUP",
+            KillSignal::Int => "SIGINT",
+            KillSignal::Stop => "SIGSTOP",
+            KillSignal::Cont => "SIGCONT",
+            KillSignal::Usr1 => "SIGUSR1",
+            KillSignal::Usr2 => "SIGUSR2",
+        }
+    }
+}
+
+impl std::str::FromStr for KillSignal {
+    type Err = String;
+
+    /// "TERM", "sigterm" and "SIGTERM" alike, plus the numbers that are the same everywhere
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let upper = s.to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        match name {
+            "1" => Ok(KillSignal::Hup),
+            "2" => Ok(KillSignal::Int),
+            "9" => Ok(KillSignal::Kill),
+            "15" => Ok(KillSignal::Term),
+            _ => KillSignal::ALL
+                .into_iter()
+                .find(|signal| &signal.name()[3..] == name)
+                .ok_or_else(|| format!("Unknown signal '{}': use TERM, KILL, HUP, INT, STOP, CONT, USR1 or USR2", s)),
+        }
+    }
+}
+
+/// Most processes one `kacemon kill` signals without --force
+pub const MAX_KILL_TARGETS: usize = 20;
+
+/// Why signalling all of `targets` at once needs --force, if it does: more than
+/// `MAX_KILL_TARGETS` of them, init among them, or a filter broad enough to take in the
+/// process asking (`matches_self`), which usually means it takes in everything
+pub fn kill_refusal(targets: &[&ProcessInfo], matches_self: bool) -> Option<String> {
+    if matches_self {
+        Some("the filter matches kacemon itself".to_string())
+    } else if targets.iter().any(|p| p.pid == 1) {
+        Some("PID 1 is among the matches".to_string())
+    } else if targets.len() > MAX_KILL_TARGETS {
+        Some(format!("{} processes match, more than {}", targets.len(), MAX_KILL_TARGETS))
+    } else {
+        None
     }
 }
 
@@ -61,7 +348,118 @@ pub struct ProcessInfo {
     pub state: ProcessState,
     pub start_time: SystemTime,
     pub parent_pid: Option<u32>,
+    /// Process group, the job a shell started it as; Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pgid: Option<u32>,
+    /// Session, usually one per login or terminal; Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+    /// Controlling terminal as ps names it, e.g. "pts/3"; None for daemons. Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tty: Option<String>,
     pub cgroup: Option<String>,  // Linux only
+    /// Short ID of the Docker, containerd, CRI-O or Podman container the cgroup belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+    /// Kubernetes pod and container the process runs in, see `metrics::pods`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pod: Option<PodInfo>,
+    #[serde(default)]
+    pub gpu_usage: Vec<ProcessGpuUsage>,
+    /// Name of the first matching `categories` rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// CPU time the process itself has used since it started (utime + stime); Linux only
+    #[serde(default)]
+    pub cpu_time_total: Duration,
+    /// CPU time of exited, waited-for children (cutime + cstime); Linux only
+    #[serde(default)]
+    pub children_cpu_time: Duration,
+    /// Children's CPU time over the last interval, as a percent of one core
+    #[serde(default)]
+    pub children_cpu_percent: f32,
+    /// Kernel function a sleeping process is blocked in; Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wchan: Option<String>,
+    /// Open file descriptors; None where /proc/<pid>/fd can't be read (other users'
+    /// processes without root). Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_fds: Option<u64>,
+    /// Proportional set size in bytes, each shared page divided among the processes mapping
+    /// it, so these add up where RSS counts shared memory once per process. None unless the
+    /// memory breakdown is on, see `CollectorBuilder::memory_breakdown`. Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_pss: Option<u64>,
+    /// Unique set size in bytes: private pages only, what would be freed if it exited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_uss: Option<u64>,
+    /// When CPU went over the hog threshold and has stayed there since; None while under it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_since: Option<SystemTime>,
+    /// New PIDs with this name and command line that replaced an exited one in the last hour
+    #[serde(default)]
+    pub restarts: u32,
+    /// TCP traffic over the last interval; None unless per-process network collection is
+    /// on, see `CollectorBuilder::process_network`. Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_rate: Option<NetRate>,
+    /// Kernel worker rather than a program; Linux only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_kernel_thread: bool,
+    /// Past the collection limit: wchan, cgroup, open FDs, CPU times, process group, session
+    /// and terminal weren't read, see
+    /// `metrics::process::complete_process`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+}
+
+/// Kubernetes pod a process runs in
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PodInfo {
+    pub namespace: String,
+    pub name: String,
+    /// Container within the pod; None for the pod's sandbox
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+impl std::fmt::Display for PodInfo {
+    /// "namespace/pod", as kubectl names it
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+/// Bytes per second a process moved over its TCP sockets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetRate {
+    pub rx_bytes_per_sec: u64,
+    pub tx_bytes_per_sec: u64,
+}
+
+impl NetRate {
+    pub fn total(&self) -> u64 {
+        self.rx_bytes_per_sec + self.tx_bytes_per_sec
+    }
+}
+
+/// One thread of a process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub state: ProcessState,
+    /// Over the last interval, as a percent of one core
+    pub cpu_percent: f32,
+    /// User plus system time since the thread started
+    pub cpu_time: Duration,
+}
+
+/// VRAM held by a process on a single GPU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessGpuUsage {
+    pub gpu_index: u32,
+    pub memory_used: u64,  // bytes
 }
 
 /// System information snapshot
@@ -75,6 +473,9 @@ pub struct SystemInfo {
     pub load_avg_1: f64,
     pub load_avg_5: f64,
     pub load_avg_15: f64,
+    /// Processes forked since boot, where the kernel keeps count (Linux)
+    #[serde(default)]
+    pub processes_created: Option<u64>,
 }
 
 /// CPU core information
@@ -84,6 +485,28 @@ pub struct CpuCore {
     pub name: String,
     pub usage_percent: f32,
     pub frequency: u64,  // MHz
+    /// Guaranteed (non-turbo) clock in MHz, where cpufreq reports one (Linux, Intel P-state)
+    #[serde(default)]
+    pub base_frequency: Option<u64>,
+    /// Highest clock the core may reach in MHz, turbo included
+    #[serde(default)]
+    pub max_frequency: Option<u64>,
+}
+
+impl CpuCore {
+    /// Running below its base clock while busy, the mark of thermal or power throttling
+    pub fn throttled(&self) -> bool {
+        self.usage_percent >= 50.0 && self.frequency > 0 && self.base_frequency.is_some_and(|base| self.frequency * 10 < base * 9)
+    }
+
+    /// Mean usage over `cores`; 0 without any
+    pub fn average(cores: &[CpuCore]) -> f32 {
+        if cores.is_empty() {
+            0.0
+        } else {
+            cores.iter().map(|c| c.usage_percent).sum::<f32>() / cores.len() as f32
+        }
+    }
 }
 
 /// Memory information
@@ -98,6 +521,70 @@ pub struct MemoryInfo {
     pub swap_total: u64,
     pub swap_used: u64,
     pub swap_free: u64,
+    #[serde(default)]
+    pub swap_in_pages: u64,   // since last snapshot
+    #[serde(default)]
+    pub swap_out_pages: u64,  // since last snapshot
+}
+
+impl MemoryInfo {
+    /// Share of RAM in use; 0 when the total is unknown
+    pub fn used_percent(&self) -> f64 {
+        percent(self.used, self.total)
+    }
+
+    /// Share of swap in use; 0 without swap
+    pub fn swap_percent(&self) -> f64 {
+        percent(self.swap_used, self.swap_total)
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// One line of a Linux pressure stall file: share of the last 10s spent stalled
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PressureStall {
+    pub some_avg10: f32,
+    pub full_avg10: Option<f32>,  // not reported for CPU on older kernels
+}
+
+/// Pressure stall information (PSI); None where the kernel doesn't provide it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureInfo {
+    pub cpu: Option<PressureStall>,
+    pub memory: Option<PressureStall>,
+    pub io: Option<PressureStall>,
+}
+
+/// How long runnable threads waited for a CPU over the last interval, in microseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunQueueLatency {
+    pub mean_us: f32,
+    pub p50_us: f32,  // Percentiles over timeslices, not threads
+    pub p99_us: f32,
+}
+
+/// Resource use of one cgroup v2 group, such as a systemd slice or service
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupUsage {
+    /// Path below the cgroup2 mount, e.g. "/system.slice/nginx.service"
+    pub path: String,
+    /// CPU used over the last interval, 100 per fully busy core
+    pub cpu_percent: f32,
+    /// cpu.stat usage_usec: CPU time since the group was created
+    pub cpu_usage_usec: u64,
+    /// memory.current, page cache included
+    pub memory_current: u64,
+    /// memory.max; None when unlimited
+    pub memory_max: Option<u64>,
+    pub io_read_bytes_per_sec: u64,
+    pub io_write_bytes_per_sec: u64,
 }
 
 /// Disk information
@@ -113,6 +600,23 @@ pub struct DiskInfo {
     pub write_bytes: u64,
     pub read_bytes_delta: u64,   // since last snapshot
     pub write_bytes_delta: u64,  // since last snapshot
+    #[serde(default)]
+    pub latency_ms: Option<f32>,     // average per completed I/O since last snapshot
+    #[serde(default)]
+    pub busy_percent: Option<f32>,   // share of time with I/O in flight since last snapshot
+    #[serde(default)]
+    pub nvme_health: Option<NvmeHealth>,  // NVMe drives only
+}
+
+/// NVMe controller health (SMART / health information log)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NvmeHealth {
+    pub controller: String,        // e.g. "nvme0"
+    pub temperature: Option<f32>,  // Celsius
+    pub percentage_used: u8,       // vendor estimate of life used, may exceed 100
+    pub available_spare: u8,       // percent
+    pub media_errors: u64,
+    pub critical_warning: u8,      // bitfield, non-zero means trouble
 }
 
 /// Network interface information
@@ -127,6 +631,239 @@ pub struct NetworkInfo {
     pub tx_packets: u64,
     pub rx_errors: u64,
     pub tx_errors: u64,
+    #[serde(default)]
+    pub rx_packets_delta: u64,   // since last snapshot
+    #[serde(default)]
+    pub tx_packets_delta: u64,   // since last snapshot
+    #[serde(default)]
+    pub rx_errors_delta: u64,    // since last snapshot
+    #[serde(default)]
+    pub tx_errors_delta: u64,    // since last snapshot
+    /// Container, VM or bridge interface whose traffic also crosses a physical one
+    #[serde(default)]
+    pub is_virtual: bool,
+}
+
+impl NetworkInfo {
+    /// RX and TX bytes since the last snapshot over all interfaces. Virtual ones are left
+    /// out unless asked for, or a container's traffic counts once on its veth, again on the
+    /// bridge and a third time on the uplink.
+    pub fn total_deltas(networks: &[Self], include_virtual: bool) -> (u64, u64) {
+        networks
+            .iter()
+            .filter(|net| include_virtual || !net.is_virtual)
+            .fold((0, 0), |(rx, tx), net| (rx + net.rx_bytes_delta, tx + net.tx_bytes_delta))
+    }
+}
+
+/// Static-ish properties of a network interface, read on demand
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterfaceDetails {
+    pub addresses: Vec<String>,       // CIDR notation, e.g. "192.168.1.10/24"
+    pub mac_address: Option<String>,
+    pub driver: Option<String>,
+    pub speed_mbps: Option<u32>,
+    pub duplex: Option<String>,
+    pub mtu: Option<u32>,
+    pub operstate: Option<String>,    // "up", "down", "dormant", ...
+}
+
+/// Boot time breakdown as reported by systemd, read on demand
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BootTiming {
+    pub firmware: Option<Duration>,   // Only known on EFI systems
+    pub loader: Option<Duration>,
+    pub kernel: Duration,
+    pub initrd: Option<Duration>,
+    pub userspace: Option<Duration>,  // None while boot is still in progress
+    pub slowest_units: Vec<UnitTiming>,  // Longest activation first
+}
+
+impl BootTiming {
+    /// Sum of the known phases
+    pub fn total(&self) -> Duration {
+        [self.firmware, self.loader, Some(self.kernel), self.initrd, self.userspace]
+            .into_iter()
+            .flatten()
+            .sum()
+    }
+}
+
+/// How long one unit took to start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitTiming {
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Results of a one-off deep scan: data too slow to gather on every refresh
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeepScan {
+    pub open_files: Option<OpenFiles>,  // None where /proc isn't available
+    pub smart: Vec<SmartReport>,
+    pub disk_usage: Option<DiskUsage>,
+    pub duration: Duration,
+}
+
+/// Open file descriptors across all processes that could be read
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenFiles {
+    pub total: u64,
+    pub busiest: Vec<ProcessFileCount>,  // Most descriptors first
+    pub unreadable: usize,               // Processes whose fd list was off limits
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessFileCount {
+    pub pid: u32,
+    pub name: String,
+    pub count: u64,
+}
+
+/// One drive's `smartctl` verdict
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmartReport {
+    pub device: String,                // e.g. "/dev/sda" or "/dev/nvme0"
+    pub model: Option<String>,
+    pub passed: Option<bool>,          // Overall health self-assessment
+    pub temperature: Option<f32>,      // Celsius
+    pub power_on_hours: Option<u64>,
+    pub error: Option<String>,         // Why there is no verdict
+}
+
+/// Space taken under one mount point, like `du -x`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub files: u64,
+    pub largest: Vec<(String, u64)>,   // Top-level entries, largest first
+    pub unreadable: u64,               // Directories that couldn't be listed
+}
+
+/// Socket protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SocketProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+}
+
+impl SocketProtocol {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Tcp6 => "tcp6",
+            Self::Udp => "udp",
+            Self::Udp6 => "udp6",
+        }
+    }
+}
+
+/// Socket state (TCP states, plus unconnected for UDP)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SocketState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    Unconnected,
+    #[default]
+    Unknown,
+}
+
+impl SocketState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Established => "ESTAB",
+            Self::SynSent => "SYN-SENT",
+            Self::SynRecv => "SYN-RECV",
+            Self::FinWait1 => "FIN-WAIT-1",
+            Self::FinWait2 => "FIN-WAIT-2",
+            Self::TimeWait => "TIME-WAIT",
+            Self::Close => "CLOSE",
+            Self::CloseWait => "CLOSE-WAIT",
+            Self::LastAck => "LAST-ACK",
+            Self::Listen => "LISTEN",
+            Self::Closing => "CLOSING",
+            Self::Unconnected => "UNCONN",
+            Self::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// Network socket (connection) information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub protocol: SocketProtocol,
+    pub local_address: SocketAddr,
+    pub remote_address: SocketAddr,
+    pub state: SocketState,
+    pub pid: Option<u32>,  // None if the owner isn't visible to us
+}
+
+/// One systemd service unit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    /// Unit name, e.g. "nginx.service"
+    pub name: String,
+    pub description: String,
+    /// active, inactive, failed, activating, deactivating or reloading
+    pub active_state: String,
+    /// The unit type's own state, e.g. running, exited or dead
+    pub sub_state: String,
+    pub main_pid: Option<u32>,
+    /// Memory charged to the unit's cgroup; None without memory accounting
+    pub memory: Option<u64>,
+    /// Automatic restarts since the unit was last started by hand
+    pub restarts: u32,
+}
+
+impl ServiceInfo {
+    pub fn is_failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+}
+
+/// One error-level entry from the system log: journald on Linux, the unified log on macOS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    /// Whoever logged it: the syslog identifier, or the process image's file name
+    pub source: String,
+    pub pid: Option<u32>,
+    /// "error" or "fault" on macOS, "emerg" through "err" from journald
+    pub level: String,
+    pub message: String,
+}
+
+/// What can be asked of a systemd unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    pub const ALL: [ServiceAction; 3] = [ServiceAction::Start, ServiceAction::Stop, ServiceAction::Restart];
+
+    /// The systemctl verb
+    pub fn verb(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
 }
 
 /// Temperature sensor information
@@ -138,6 +875,55 @@ pub struct TemperatureInfo {
     pub max: Option<f32>,        // Maximum temperature threshold
 }
 
+/// GPU device information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub uuid: String,
+    pub name: String,
+    pub utilization_percent: f32,
+    pub memory_used: u64,          // bytes
+    pub memory_total: u64,         // bytes
+    pub temperature: Option<f32>,  // Celsius
+}
+
+/// How GPU metrics are written by exporters: the CSV file, Telegraf output and the API's /gpus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuExportMode {
+    /// One combined set of values across all GPUs
+    #[default]
+    Aggregate,
+    /// Separate values for each GPU
+    PerDevice,
+}
+
+/// Combined view of all GPUs on the host
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GpuAggregate {
+    pub count: usize,
+    pub utilization_percent: f32,  // mean across devices
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub max_temperature: Option<f32>,
+}
+
+impl GpuAggregate {
+    pub fn from_gpus(gpus: &[GpuInfo]) -> Self {
+        if gpus.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            count: gpus.len(),
+            utilization_percent: gpus.iter().map(|g| g.utilization_percent).sum::<f32>() / gpus.len() as f32,
+            memory_used: gpus.iter().map(|g| g.memory_used).sum(),
+            memory_total: gpus.iter().map(|g| g.memory_total).sum(),
+            max_temperature: gpus.iter().filter_map(|g| g.temperature).reduce(f32::max),
+        }
+    }
+}
+
 /// Complete system metrics snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemSnapshot {
@@ -148,24 +934,216 @@ pub struct SystemSnapshot {
     pub disks: Vec<DiskInfo>,
     pub networks: Vec<NetworkInfo>,
     pub temperatures: Vec<TemperatureInfo>,
+    #[serde(default)]
+    pub gpus: Vec<GpuInfo>,
+    #[serde(default)]
+    pub connections: Vec<ConnectionInfo>,
+    #[serde(default)]
+    pub pressure: PressureInfo,
+    #[serde(default)]
+    pub run_queue: Option<RunQueueLatency>,
+    /// Slices and services from the cgroup v2 hierarchy; empty without one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cgroups: Vec<CgroupUsage>,
+    /// systemd services, while something asked for them (see `set_services_enabled`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<ServiceInfo>,
+    /// Recent system log errors, oldest first, while something asked for them (see
+    /// `set_log_feed_enabled`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_entries: Vec<LogEntry>,
     pub processes: Vec<ProcessInfo>,
+    /// Collectors that failed this pass; their sections hold what they last returned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collector_failures: Vec<CollectorFailure>,
+}
+
+impl SystemSnapshot {
+    /// Why `collector` ("disks", "networks", "temperatures", ...) failed this pass, if it did
+    pub fn collector_failure(&self, collector: &str) -> Option<&CollectorFailure> {
+        self.collector_failures.iter().find(|failure| failure.collector == collector)
+    }
+
+    /// Mean usage over all cores, the figure every summary reports as "CPU"
+    pub fn cpu_average(&self) -> f32 {
+        CpuCore::average(&self.cpu_cores)
+    }
+
+    /// Share of RAM in use
+    pub fn memory_percent(&self) -> f64 {
+        self.memory.used_percent()
+    }
+
+    /// `pid` and the processes it descends from, as far as this snapshot lists them: for a
+    /// command run from a shell, the shells whose command lines hold its arguments too
+    pub fn lineage(&self, pid: u32) -> Vec<u32> {
+        let parents: HashMap<u32, u32> = self.processes.iter().filter_map(|p| Some((p.pid, p.parent_pid?))).collect();
+        let mut lineage = vec![pid];
+        while let Some(&parent) = lineage.last().and_then(|pid| parents.get(pid)) {
+            if lineage.contains(&parent) {
+                break;
+            }
+            lineage.push(parent);
+        }
+        lineage
+    }
+}
+
+/// A collector whose latest pass failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectorFailure {
+    pub collector: String,
+    pub error: String,
+    /// When it last worked; None if it hasn't this session
+    pub last_success: Option<SystemTime>,
+}
+
+/// Relative weights of the inputs to the pressure score; only their ratios matter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PressureWeights {
+    pub cpu: f32,
+    pub memory: f32,
+    pub psi: f32,
+    pub swap: f32,
+    pub disk: f32,
+}
+
+impl Default for PressureWeights {
+    fn default() -> Self {
+        Self {
+            cpu: 0.30,
+            memory: 0.25,
+            psi: 0.20,
+            swap: 0.10,
+            disk: 0.15,
+        }
+    }
 }
 
-/// Theme configuration
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Theme configuration; anything but the built-in names refers to `Config::themes`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Theme {
+    #[default]
     Dark,
     Light,
+    Custom(String),
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Self::Dark
+impl From<String> for Theme {
+    fn from(name: String) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Theme::Dark,
+            "light" => Theme::Light,
+            _ => Theme::Custom(name),
+        }
     }
 }
 
+impl From<Theme> for String {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Dark => "Dark".to_string(),
+            Theme::Light => "Light".to_string(),
+            Theme::Custom(name) => name,
+        }
+    }
+}
+
+/// One color in a user theme: `#rrggbb`, `#rgb`, a 256-color index, a named
+/// ANSI color (`red`, `dark_grey`, ...) or `default` for the terminal's own color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PaletteColor {
+    Default,
+    Rgb(u8, u8, u8),
+    Indexed(u8),
+}
+
+impl PaletteColor {
+    /// ANSI names in palette order, so the position is the color index
+    const NAMES: [&'static str; 16] = [
+        "black", "dark_red", "dark_green", "dark_yellow", "dark_blue", "dark_magenta", "dark_cyan", "grey",
+        "dark_grey", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+}
+
+impl TryFrom<String> for PaletteColor {
+    type Error = String;
+
+    fn try_from(spec: String) -> std::result::Result<Self, Self::Error> {
+        let spec = spec.trim().to_ascii_lowercase();
+        if spec == "default" || spec == "reset" {
+            return Ok(PaletteColor::Default);
+        }
+
+        if let Some(hex) = spec.strip_prefix('#') {
+            let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+            let rgb = match hex.len() {
+                _ if !hex.is_ascii() => None,
+                6 => channel(&hex[0..2]).zip(channel(&hex[2..4])).zip(channel(&hex[4..6])),
+                // #abc is shorthand for #aabbcc
+                3 => channel(&hex[0..1]).zip(channel(&hex[1..2])).zip(channel(&hex[2..3]))
+                    .map(|((r, g), b)| ((r * 17, g * 17), b * 17)),
+                _ => None,
+            };
+            return rgb
+                .map(|((r, g), b)| PaletteColor::Rgb(r, g, b))
+                .ok_or_else(|| format!("invalid hex color '{}'", spec));
+        }
+
+        if let Ok(index) = spec.parse::<u8>() {
+            return Ok(PaletteColor::Indexed(index));
+        }
+
+        let name = spec.replace(['-', ' '], "_").replace("gray", "grey");
+        Self::NAMES
+            .iter()
+            .position(|n| *n == name || n.replace('_', "") == name)
+            .map(|i| PaletteColor::Indexed(i as u8))
+            .ok_or_else(|| format!("unknown color '{}'", spec))
+    }
+}
+
+impl From<PaletteColor> for String {
+    fn from(color: PaletteColor) -> Self {
+        match color {
+            PaletteColor::Default => "default".to_string(),
+            PaletteColor::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            PaletteColor::Indexed(i) => match PaletteColor::NAMES.get(i as usize) {
+                Some(name) => name.to_string(),
+                None => i.to_string(),
+            },
+        }
+    }
+}
+
+/// User-defined theme; unset colors fall back to the `base` built-in theme
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemePalette {
+    /// `Dark` or `Light`
+    pub base: Theme,
+    pub background: Option<PaletteColor>,
+    pub foreground: Option<PaletteColor>,
+    pub accent: Option<PaletteColor>,
+    pub border: Option<PaletteColor>,
+    pub highlight: Option<PaletteColor>,
+    pub warning: Option<PaletteColor>,
+    pub error: Option<PaletteColor>,
+    pub success: Option<PaletteColor>,
+    pub muted: Option<PaletteColor>,
+    pub gauge_bg: Option<PaletteColor>,
+    pub gauge_fill: Option<PaletteColor>,
+    pub table_header: Option<PaletteColor>,
+    pub table_row_alt: Option<PaletteColor>,
+    pub table_selected: Option<PaletteColor>,
+}
+
 /// Visible columns in process table
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ProcessColumns {
     pub pid: bool,
     pub name: bool,
@@ -176,7 +1154,51 @@ pub struct ProcessColumns {
     pub memory_vsz: bool,
     pub threads: bool,
     pub state: bool,
+    /// Time since start, the AGE column
     pub start_time: bool,
+    #[serde(default)]
+    pub children_cpu: bool,
+    #[serde(default)]
+    pub wchan: bool,
+    #[serde(default)]
+    pub busy: bool,
+    #[serde(default)]
+    pub restarts: bool,
+    #[serde(default)]
+    pub net: bool,
+    #[serde(default)]
+    pub fds: bool,
+    /// CPU time used since start, the TIME column
+    #[serde(default)]
+    pub cpu_time: bool,
+    #[serde(default)]
+    pub pgid: bool,
+    #[serde(default)]
+    pub session: bool,
+    /// Controlling terminal
+    #[serde(default)]
+    pub tty: bool,
+    /// When the process started, in UTC, the START column
+    #[serde(default)]
+    pub started: bool,
+    #[serde(default)]
+    pub container: bool,
+    /// Kubernetes namespace/pod
+    #[serde(default)]
+    pub pod: bool,
+    /// Proportional set size; reading it costs a page table walk per process, so it's only
+    /// collected while shown
+    #[serde(default)]
+    pub pss: bool,
+    /// Unique set size, collected along with PSS
+    #[serde(default)]
+    pub uss: bool,
+}
+
+impl ProcessColumns {
+    /// Every process table column header, in table order; CAT shows when categories are set up
+    pub const HEADERS: &'static [&'static str] =
+        &["PID", "NAME", "CAT", "CONTAINER", "POD", "USER", "CPU%", "CHLD%", "MEM%", "RSS", "PSS", "USS", "VSZ", "NET", "THR", "FDS", "PGID", "SID", "TTY", "STATE", "WCHAN", "BUSY", "RST", "TIME", "AGE", "START"];
 }
 
 impl Default for ProcessColumns {
@@ -192,6 +1214,21 @@ impl Default for ProcessColumns {
             threads: false,
             state: true,
             start_time: false,
+            children_cpu: false,
+            wchan: false,
+            busy: false,
+            restarts: false,
+            net: false,
+            fds: false,
+            cpu_time: false,
+            pgid: false,
+            session: false,
+            tty: false,
+            started: false,
+            container: false,
+            pod: false,
+            pss: false,
+            uss: false,
         }
     }
 }
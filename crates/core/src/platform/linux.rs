@@ -1,6 +1,9 @@
-use super::{PlatformProcessDetails, PlatformProvider, PlatformSystemMetrics};
+use super::{container_id_from_cgroup, PlatformProcessDetails, PlatformProvider, PlatformSystemMetrics};
+#[cfg(not(feature = "linux_procfs"))]
+use super::pick_cgroup;
 use crate::error::{CoreError, Result};
 
+#[derive(Default)]
 pub struct LinuxProvider;
 
 impl LinuxProvider {
@@ -18,7 +21,7 @@ impl PlatformProvider for LinuxProvider {
         
         #[cfg(not(feature = "linux_procfs"))]
         {
-            Ok(PlatformProcessDetails::default())
+            self.get_process_details_proc(pid)
         }
     }
     
@@ -30,7 +33,7 @@ impl PlatformProvider for LinuxProvider {
         
         #[cfg(not(feature = "linux_procfs"))]
         {
-            Ok(PlatformSystemMetrics::default())
+            self.get_system_metrics_proc()
         }
     }
     
@@ -80,29 +83,7 @@ impl LinuxProvider {
             .and_then(|cgroups| cgroups.first().map(|cg| cg.pathname.clone()));
         
         // Try to extract container ID from cgroup
-        let container_id = cgroup.as_ref().and_then(|cg| {
-            if cg.contains("docker") {
-                // Extract Docker container ID
-                cg.split('/').last().map(|id| {
-                    if id.len() >= 12 {
-                        id[..12].to_string()
-                    } else {
-                        id.to_string()
-                    }
-                })
-            } else if cg.contains("kubepods") {
-                // Extract Kubernetes pod container ID
-                cg.split('/').last().map(|id| {
-                    if id.len() >= 12 {
-                        id[..12].to_string()
-                    } else {
-                        id.to_string()
-                    }
-                })
-            } else {
-                None
-            }
-        });
+        let container_id = cgroup.as_deref().and_then(container_id_from_cgroup);
         
         Ok(PlatformProcessDetails {
             cmdline,
@@ -138,5 +119,85 @@ impl LinuxProvider {
 
 #[cfg(not(feature = "linux_procfs"))]
 impl LinuxProvider {
-    // Stub implementations when procfs feature is not enabled
+    /// Read process details straight from /proc when the procfs crate isn't enabled
+    fn get_process_details_proc(&self, pid: u32) -> Result<PlatformProcessDetails> {
+        use std::fs;
+        
+        let base = std::path::PathBuf::from(format!("/proc/{}", pid));
+        if !base.exists() {
+            return Err(CoreError::platform(format!("Failed to read process {}: no such process", pid)));
+        }
+        
+        // NUL-separated argv
+        let cmdline = fs::read(base.join("cmdline"))
+            .ok()
+            .map(|raw| {
+                raw.split(|&b| b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .filter(|cmd| !cmd.is_empty());
+        
+        let cwd = fs::read_link(base.join("cwd"))
+            .ok()
+            .map(|path| path.to_string_lossy().into_owned());
+        
+        // NUL-separated KEY=VALUE pairs
+        let environment = fs::read(base.join("environ"))
+            .ok()
+            .map(|raw| {
+                raw.split(|&b| b == 0)
+                    .filter_map(|pair| {
+                        let pair = String::from_utf8_lossy(pair);
+                        pair.split_once('=').map(|(k, v)| (k.to_string(), v.to_string()))
+                    })
+                    .collect()
+            });
+        
+        let open_files = fs::read_dir(base.join("fd"))
+            .ok()
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| fs::read_link(entry.path()).ok())
+                    .map(|target| target.to_string_lossy().into_owned())
+                    .collect()
+            });
+        
+        let cgroup = fs::read_to_string(base.join("cgroup")).ok().and_then(|contents| pick_cgroup(&contents));
+        
+        let container_id = cgroup.as_deref().and_then(container_id_from_cgroup);
+        
+        Ok(PlatformProcessDetails {
+            cmdline,
+            cwd,
+            environment,
+            open_files,
+            cgroup,
+            container_id,
+        })
+    }
+    
+    /// Read the system-wide counters straight from /proc/stat
+    fn get_system_metrics_proc(&self) -> Result<PlatformSystemMetrics> {
+        let stat = std::fs::read_to_string("/proc/stat")
+            .map_err(|e| CoreError::platform(format!("Failed to read /proc/stat: {}", e)))?;
+        
+        // Lines are "name value..."; intr leads with the total over all interrupts
+        let field = |name: &str| {
+            stat.lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+                .and_then(|values| values.split_whitespace().next()?.parse().ok())
+        };
+        
+        Ok(PlatformSystemMetrics {
+            context_switches: field("ctxt"),
+            interrupts: field("intr"),
+            processes_created: field("processes"),
+            processes_running: field("procs_running"),
+            processes_blocked: field("procs_blocked"),
+        })
+    }
 }
@@ -40,6 +40,38 @@ pub struct PlatformSystemMetrics {
     pub processes_blocked: Option<u64>,
 }
 
+/// Extract a short container ID from a cgroup path
+///
+/// Handles Docker, containerd/CRI-O and Kubernetes naming for both cgroup v1
+/// (`/docker/<id>`) and v2 systemd scopes (`/system.slice/docker-<id>.scope`).
+pub fn container_id_from_cgroup(cgroup: &str) -> Option<String> {
+    if !["docker", "kubepods", "containerd", "crio", "libpod"].iter().any(|runtime| cgroup.contains(runtime)) {
+        return None;
+    }
+    
+    let last = cgroup.rsplit('/').next()?;
+    let id = last.strip_suffix(".scope").unwrap_or(last);
+    let id = id.rsplit('-').next().unwrap_or(id);
+    
+    // Container IDs are 64 hex characters; anything else is a slice or pod directory
+    if id.len() < 12 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    
+    Some(id[..12].to_string())
+}
+
+/// The path out of a /proc/<pid>/cgroup file's "hierarchy-id:controllers:path" lines,
+/// preferring one that names a container since cgroup v1 lists several
+pub fn pick_cgroup(contents: &str) -> Option<String> {
+    let paths: Vec<&str> = contents.lines().filter_map(|line| line.splitn(3, ':').nth(2)).collect();
+    paths
+        .iter()
+        .find(|p| container_id_from_cgroup(p).is_some())
+        .or_else(|| paths.first())
+        .map(|p| p.to_string())
+}
+
 /// Get the appropriate platform provider for the current system
 pub fn get_platform_provider() -> Box<dyn PlatformProvider> {
     #[cfg(target_os = "linux")]
@@ -1,6 +1,7 @@
 use super::{PlatformProcessDetails, PlatformProvider, PlatformSystemMetrics};
 use crate::error::Result;
 
+#[derive(Default)]
 pub struct WindowsProvider;
 
 impl WindowsProvider {
@@ -0,0 +1,92 @@
+use crate::{
+    alerts::Alert,
+    error::{CoreError, Result},
+    model::SystemSnapshot,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// A remote host is given up on for this round after this long
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Another machine running `kacemon --serve`, shown in the fleet view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteHost {
+    /// Label shown in the fleet view and host switcher
+    pub name: String,
+    /// Base URL of its API, e.g. "http://10.0.0.5:9100"
+    pub url: String,
+}
+
+impl RemoteHost {
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(format!("Remote host '{}' needs a name", self.url));
+        }
+        split_url(&self.url).map(|_| ())
+    }
+
+    /// Latest snapshot and alerts from the host's API
+    pub fn fetch(&self) -> Result<(SystemSnapshot, Vec<Alert>)> {
+        let snapshot = self.get("/snapshot")?;
+        let alerts = self.get("/alerts")?;
+        Ok((snapshot, alerts))
+    }
+
+    fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let body = http_get(&self.url, endpoint).map_err(CoreError::remote)?;
+        serde_json::from_str(&body).map_err(|e| CoreError::remote(format!("unexpected reply from {}: {}", endpoint, e)))
+    }
+}
+
+/// "http://host:port/prefix" -> ("host:port", "/prefix")
+fn split_url(url: &str) -> std::result::Result<(&str, &str), String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Remote host URL '{}' must start with http://", url))?;
+    let (authority, prefix) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    if authority.is_empty() {
+        return Err(format!("Remote host URL '{}' has no host", url));
+    }
+    Ok((authority, prefix.trim_end_matches('/')))
+}
+
+/// Minimal HTTP/1.1 GET, enough for the fixed-length JSON replies `--serve` sends
+fn http_get(url: &str, endpoint: &str) -> std::result::Result<String, String> {
+    let (authority, prefix) = split_url(url)?;
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    let socket = address
+        .to_socket_addrs()
+        .map_err(|e| format!("cannot resolve {}: {}", authority, e))?
+        .next()
+        .ok_or_else(|| format!("cannot resolve {}", authority))?;
+
+    let mut stream = TcpStream::connect_timeout(&socket, REMOTE_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(REMOTE_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(REMOTE_TIMEOUT)).map_err(|e| e.to_string())?;
+    write!(
+        stream,
+        "GET {}{} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        prefix, endpoint, authority
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(|e| e.to_string())?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n").ok_or("truncated response")?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        let reason = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v.get("error")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| format!("HTTP {}", status));
+        return Err(reason);
+    }
+    Ok(body.to_string())
+}
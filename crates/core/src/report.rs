@@ -0,0 +1,206 @@
+use crate::{
+    alerts::Alert,
+    export::iso8601,
+    model::{ProcessInfo, SystemSnapshot},
+};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// How one-shot subcommands print what they found. Fields come out in the same order every
+/// time in all three, so scripts can rely on column positions as well as names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Aligned text for people
+    #[default]
+    Table,
+    /// One JSON document
+    Json,
+    /// A header line, then one line per record
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("Unknown format '{}': use table, json or csv", other)),
+        }
+    }
+}
+
+/// What `kacemon status` reports
+#[derive(Serialize)]
+struct Status<'a> {
+    timestamp: String,
+    hostname: &'a str,
+    /// No alert rule is firing
+    healthy: bool,
+    cpu_percent: f32,
+    memory_percent: f32,
+    swap_percent: f32,
+    load_avg: [f64; 3],
+    process_count: usize,
+    max_temp_celsius: Option<f32>,
+    alerts: Vec<StatusAlert<'a>>,
+}
+
+#[derive(Serialize)]
+struct StatusAlert<'a> {
+    kind: &'static str,
+    subject: &'a str,
+    pid: Option<u32>,
+    value: f32,
+    threshold: f32,
+    critical: bool,
+    message: String,
+}
+
+impl<'a> Status<'a> {
+    fn new(snapshot: &'a SystemSnapshot, alerts: &'a [Alert]) -> Self {
+        let system = &snapshot.system;
+        // Rounded so two runs over the same state print the same numbers
+        let tenths = |value: f32| (value * 10.0).round() / 10.0;
+
+        Self {
+            timestamp: iso8601(snapshot.timestamp),
+            hostname: &system.hostname,
+            healthy: alerts.is_empty(),
+            cpu_percent: tenths(snapshot.cpu_average()),
+            memory_percent: tenths(snapshot.memory_percent() as f32),
+            swap_percent: tenths(snapshot.memory.swap_percent() as f32),
+            load_avg: [system.load_avg_1, system.load_avg_5, system.load_avg_15],
+            process_count: snapshot.processes.len(),
+            max_temp_celsius: snapshot.temperatures.iter().map(|t| t.temperature).reduce(f32::max).map(tenths),
+            alerts: alerts
+                .iter()
+                .map(|alert| StatusAlert {
+                    kind: alert.kind_name(),
+                    subject: &alert.subject,
+                    pid: alert.pid,
+                    value: tenths(alert.value),
+                    threshold: alert.threshold,
+                    critical: alert.critical,
+                    message: alert.message(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Headline numbers of `snapshot` and the alert rules it breaks. CSV gives one row, with
+/// the alert messages joined by "; " in its last column.
+pub fn status_report(snapshot: &SystemSnapshot, alerts: &[Alert], format: OutputFormat) -> String {
+    let status = Status::new(snapshot, alerts);
+    let optional = |value: Option<f32>| value.map(|v| format!("{:.1}", v)).unwrap_or_default();
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&status).unwrap_or_default() + "\n",
+        OutputFormat::Csv => {
+            let messages: Vec<&str> = status.alerts.iter().map(|alert| alert.message.as_str()).collect();
+            format!(
+                "timestamp,hostname,healthy,cpu_percent,memory_percent,swap_percent,load_avg_1,load_avg_5,\
+                 load_avg_15,process_count,max_temp_celsius,alert_count,alerts\n\
+                 {},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{},{},{},{}\n",
+                status.timestamp,
+                csv_field(status.hostname),
+                status.healthy,
+                status.cpu_percent,
+                status.memory_percent,
+                status.swap_percent,
+                status.load_avg[0],
+                status.load_avg[1],
+                status.load_avg[2],
+                status.process_count,
+                optional(status.max_temp_celsius),
+                status.alerts.len(),
+                csv_field(&messages.join("; ")),
+            )
+        },
+        OutputFormat::Table => {
+            let mut lines = vec![
+                format!("{:<12}{}", "HOST", status.hostname),
+                format!("{:<12}{}", "TIME", status.timestamp),
+                format!("{:<12}{:.1}%", "CPU", status.cpu_percent),
+                format!("{:<12}{:.1}%", "MEMORY", status.memory_percent),
+                format!("{:<12}{:.1}%", "SWAP", status.swap_percent),
+                format!("{:<12}{:.2} {:.2} {:.2}", "LOAD", status.load_avg[0], status.load_avg[1], status.load_avg[2]),
+                format!("{:<12}{}", "PROCESSES", status.process_count),
+                format!("{:<12}{}", "TEMP", status.max_temp_celsius.map_or("-".to_string(), |t| format!("{:.1}C", t))),
+            ];
+            match status.alerts.len() {
+                0 => lines.push(format!("{:<12}OK", "STATUS")),
+                count => {
+                    lines.push(format!("{:<12}{} alert{} firing", "STATUS", count, if count == 1 { "" } else { "s" }));
+                    for alert in &status.alerts {
+                        let level = if alert.critical { "critical" } else { "warning" };
+                        lines.push(format!("  [{}] {}", level, alert.message));
+                    }
+                },
+            }
+            lines.join("\n") + "\n"
+        },
+    }
+}
+
+/// One process as listed by `process_list`
+#[derive(Serialize)]
+struct ListedProcess<'a> {
+    pid: u32,
+    user: &'a str,
+    name: &'a str,
+    cpu_percent: f32,
+    memory_rss: u64,
+    command: String,
+}
+
+/// `processes` in the order given, e.g. what `kacemon kill` is about to signal
+pub fn process_list(processes: &[&ProcessInfo], format: OutputFormat) -> String {
+    let listed: Vec<ListedProcess> = processes
+        .iter()
+        .map(|process| ListedProcess {
+            pid: process.pid,
+            user: &process.user,
+            name: &process.name,
+            cpu_percent: (process.cpu_percent * 10.0).round() / 10.0,
+            memory_rss: process.memory_rss,
+            command: if process.cmd.is_empty() { process.name.clone() } else { process.cmd.join(" ") },
+        })
+        .collect();
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&listed).unwrap_or_default() + "\n",
+        OutputFormat::Csv => {
+            let mut out = "pid,user,name,cpu_percent,memory_rss,command\n".to_string();
+            for process in &listed {
+                out += &format!(
+                    "{},{},{},{:.1},{},{}\n",
+                    process.pid,
+                    csv_field(process.user),
+                    csv_field(process.name),
+                    process.cpu_percent,
+                    process.memory_rss,
+                    csv_field(&process.command)
+                );
+            }
+            out
+        },
+        OutputFormat::Table => {
+            let mut out = format!("{:>8}  {:<12} {:>6}  COMMAND\n", "PID", "USER", "CPU%");
+            for process in &listed {
+                out += &format!("{:>8}  {:<12} {:>6.1}  {}\n", process.pid, process.user, process.cpu_percent, process.command);
+            }
+            out
+        },
+    }
+}
+
+/// Quote a CSV cell when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,65 @@
+use crate::model::{PressureWeights, SystemSnapshot};
+use serde::{Deserialize, Serialize};
+
+/// Swap traffic (in + out, pages per second) that counts as fully saturated
+const SWAP_PAGES_SATURATED: f32 = 1024.0;
+
+/// Composite 0-100 load indicator; higher means the machine is under more pressure
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PressureScore {
+    pub score: f32,
+    pub cpu: f32,
+    pub memory: f32,
+    pub psi: Option<f32>,   // None without kernel PSI support
+    pub swap: f32,
+    pub disk: Option<f32>,  // None when no disk reports busy time
+}
+
+impl PressureScore {
+    /// Score a snapshot; `interval_secs` is the time covered by its deltas
+    pub fn compute(snapshot: &SystemSnapshot, weights: &PressureWeights, interval_secs: f64) -> Self {
+        let cpu = snapshot.cpu_average();
+
+        let memory = &snapshot.memory;
+        let memory_pressure = if memory.total > 0 {
+            100.0 - memory.available as f32 / memory.total as f32 * 100.0
+        } else {
+            0.0
+        };
+
+        let pressure = &snapshot.pressure;
+        let psi = [pressure.cpu, pressure.memory, pressure.io]
+            .iter()
+            .flatten()
+            .map(|stall| stall.some_avg10)
+            .reduce(f32::max);
+
+        let swap_pages = (memory.swap_in_pages + memory.swap_out_pages) as f64 / interval_secs.max(0.001);
+        let swap = swap_pages as f32 / SWAP_PAGES_SATURATED * 100.0;
+
+        let disk = snapshot.disks.iter().filter_map(|d| d.busy_percent).reduce(f32::max);
+
+        // Weighted mean over the inputs this platform actually provides
+        let components = [
+            (Some(cpu), weights.cpu),
+            (Some(memory_pressure), weights.memory),
+            (psi, weights.psi),
+            (Some(swap), weights.swap),
+            (disk, weights.disk),
+        ];
+        let (sum, total_weight) = components
+            .iter()
+            .filter_map(|&(value, weight)| Some((value?.clamp(0.0, 100.0) * weight, weight)))
+            .fold((0.0, 0.0), |(sum, total), (value, weight)| (sum + value, total + weight));
+        let score = if total_weight > 0.0 { sum / total_weight } else { 0.0 };
+
+        Self {
+            score: score.clamp(0.0, 100.0),
+            cpu: cpu.clamp(0.0, 100.0),
+            memory: memory_pressure.clamp(0.0, 100.0),
+            psi: psi.map(|p| p.clamp(0.0, 100.0)),
+            swap: swap.clamp(0.0, 100.0),
+            disk: disk.map(|d| d.clamp(0.0, 100.0)),
+        }
+    }
+}
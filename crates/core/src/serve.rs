@@ -0,0 +1,424 @@
+use crate::{
+    alerts::Alert,
+    error::{CoreError, Result},
+    model::{GpuAggregate, GpuExportMode, SystemSnapshot},
+};
+use serde::Serialize;
+use std::{
+    io::{Read, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+
+/// Endpoints listed by `GET /`; `/ws` upgrades to a WebSocket that pushes every snapshot
+pub const ENDPOINTS: &[&str] = &[
+    "/ws",
+    "/snapshot",
+    "/system",
+    "/cpu",
+    "/memory",
+    "/disks",
+    "/networks",
+    "/temperatures",
+    "/gpus",
+    "/cgroups",
+    "/processes",
+    "/alerts",
+];
+
+/// Requests are a single GET line plus headers; anything bigger is refused
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// A slow or idle client gives up its thread after this long
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Fixed suffix of the WebSocket handshake (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Frames from clients are only ever close, ping or small chatter
+const MAX_CLIENT_FRAME_BYTES: u64 = 64 * 1024;
+
+/// The latest snapshot and the alerts it raised, as served to clients
+#[derive(Serialize)]
+pub struct Published {
+    pub snapshot: SystemSnapshot,
+    pub alerts: Vec<Alert>,
+    /// What /gpus answers with; /snapshot always lists every device
+    #[serde(skip)]
+    pub gpu_export: GpuExportMode,
+}
+
+type Latest = Arc<RwLock<Option<Arc<Published>>>>;
+type Subscribers = Arc<Mutex<Vec<Sender<Arc<Published>>>>>;
+
+/// State shared by the accept loop and every connection
+#[derive(Clone, Default)]
+struct Shared {
+    latest: Latest,
+    subscribers: Subscribers,
+    /// Web origins whose pages may read responses; none unless configured
+    allowed_origins: Arc<Vec<String>>,
+}
+
+impl Shared {
+    /// The request's `Origin`, if it is one of the allowed ones
+    fn allowed_origin<'a>(&self, request: &'a str) -> Option<&'a str> {
+        header(request, "origin").filter(|origin| self.allowed_origins.iter().any(|allowed| allowed == origin))
+    }
+}
+
+/// Read-only JSON API over plain HTTP/1.1. There is no authentication, so bind it to
+/// localhost or a trusted network. Browsers only let pages from `allowed_origins` read it.
+pub struct ApiServer {
+    shared: Shared,
+    local_addr: SocketAddr,
+    gpu_export: GpuExportMode,
+}
+
+impl ApiServer {
+    /// Listen on `addr` (e.g. "127.0.0.1:9100") and answer requests on background threads
+    pub fn bind(addr: &str) -> Result<Self> {
+        Self::bind_allowing(addr, Vec::new())
+    }
+
+    /// `bind`, letting web pages from `allowed_origins` (e.g. "http://localhost:3000") read
+    /// the API as well
+    pub fn bind_allowing(addr: &str, allowed_origins: Vec<String>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| CoreError::config(format!("Cannot listen on {}: {}", addr, e)))?;
+        let local_addr = listener.local_addr()?;
+        let shared = Shared { allowed_origins: Arc::new(allowed_origins), ..Shared::default() };
+
+        let accept_shared = shared.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = accept_shared.clone();
+                std::thread::spawn(move || {
+                    let _ = handle_client(stream, &shared);
+                });
+            }
+        });
+
+        Ok(Self { shared, local_addr, gpu_export: GpuExportMode::default() })
+    }
+
+    /// Answer /gpus with the combined figures or each device's
+    pub fn with_gpu_export(mut self, gpu_export: GpuExportMode) -> Self {
+        self.gpu_export = gpu_export;
+        self
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Make this snapshot the one clients see, and push it to every WebSocket
+    pub fn publish(&self, snapshot: &SystemSnapshot, alerts: &[Alert]) {
+        let published = Arc::new(Published {
+            snapshot: snapshot.clone(),
+            alerts: alerts.to_vec(),
+            gpu_export: self.gpu_export,
+        });
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            // A closed connection's receiver is gone, which drops it from the list
+            subscribers.retain(|subscriber| subscriber.send(Arc::clone(&published)).is_ok());
+        }
+        if let Ok(mut latest) = self.shared.latest.write() {
+            *latest = Some(published);
+        }
+    }
+}
+
+impl Drop for ApiServer {
+    /// Ends every WebSocket stream, closing those connections
+    fn drop(&mut self) {
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+}
+
+fn handle_client(mut stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+        if request.len() > MAX_REQUEST_BYTES {
+            return write_response(&mut stream, 431, &error_body("Request too large"), None);
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next().unwrap_or_default(), request_line.next().unwrap_or("/"));
+    let origin = shared.allowed_origin(&request);
+    if method != "GET" {
+        return write_response(&mut stream, 405, &error_body("Only GET is supported"), origin);
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path == "/ws" {
+        let Some(key) = header(&request, "sec-websocket-key") else {
+            return write_response(&mut stream, 400, &error_body("/ws expects a WebSocket upgrade"), origin);
+        };
+        // Browsers send an Origin with every upgrade and WebSockets skip CORS, so this is the
+        // only thing stopping any open page from subscribing; other clients send none
+        if header(&request, "origin").is_some() && origin.is_none() {
+            return write_response(&mut stream, 403, &error_body("Origin not allowed"), None);
+        }
+        return match parse_sections(query) {
+            Ok(sections) => stream_snapshots(stream, key, sections, shared),
+            Err(e) => write_response(&mut stream, 400, &error_body(&e), origin),
+        };
+    }
+
+    // Hold the lock only long enough to take a reference to the latest snapshot
+    let published = shared.latest.read().ok().and_then(|latest| latest.clone());
+    let (status, body) = respond(target, published.as_deref());
+    write_response(&mut stream, status, &body, origin)
+}
+
+/// Status code and JSON body for a GET of `target`; None until the first snapshot is in
+pub fn respond(target: &str, published: Option<&Published>) -> (u16, String) {
+    let path = target.split('?').next().unwrap_or_default().trim_end_matches('/');
+    if path.is_empty() {
+        return (200, json(&serde_json::json!({ "endpoints": ENDPOINTS })));
+    }
+    if !ENDPOINTS.contains(&path) || path == "/ws" {
+        return (404, error_body(&format!("No such endpoint: {}", path)));
+    }
+    let Some(published) = published else {
+        return (503, error_body("No snapshot collected yet"));
+    };
+    (200, json(&section(published, &path[1..])))
+}
+
+/// One part of a published snapshot by endpoint name, e.g. "memory"
+fn section(published: &Published, name: &str) -> serde_json::Value {
+    let Published { snapshot, alerts, gpu_export } = published;
+    let value = match name {
+        "snapshot" => serde_json::to_value(snapshot),
+        "system" => serde_json::to_value(&snapshot.system),
+        "cpu" => serde_json::to_value(&snapshot.cpu_cores),
+        "memory" => serde_json::to_value(&snapshot.memory),
+        "disks" => serde_json::to_value(&snapshot.disks),
+        "networks" => serde_json::to_value(&snapshot.networks),
+        "temperatures" => serde_json::to_value(&snapshot.temperatures),
+        "gpus" => match gpu_export {
+            GpuExportMode::Aggregate => serde_json::to_value(GpuAggregate::from_gpus(&snapshot.gpus)),
+            GpuExportMode::PerDevice => serde_json::to_value(&snapshot.gpus),
+        },
+        "cgroups" => serde_json::to_value(&snapshot.cgroups),
+        "processes" => serde_json::to_value(&snapshot.processes),
+        _ => serde_json::to_value(alerts),
+    };
+    value.unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }))
+}
+
+/// `/ws?sections=cpu,memory` streams just those parts; no query streams snapshot and alerts
+fn parse_sections(query: &str) -> std::result::Result<Vec<String>, String> {
+    let Some(list) = query.split('&').find_map(|pair| pair.strip_prefix("sections=")) else {
+        return Ok(Vec::new());
+    };
+    list.split(',')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let endpoint = format!("/{}", name);
+            if ENDPOINTS.contains(&endpoint.as_str()) && name != "ws" {
+                Ok(name.to_string())
+            } else {
+                Err(format!("Unknown section '{}'", name))
+            }
+        })
+        .collect()
+}
+
+/// The WebSocket message for one snapshot: the chosen sections keyed by name, plus its time
+pub fn stream_message(published: &Published, sections: &[String]) -> String {
+    if sections.is_empty() {
+        return json(published);
+    }
+    let mut message = serde_json::Map::new();
+    message.insert("timestamp".to_string(), serde_json::json!(published.snapshot.timestamp));
+    for name in sections {
+        message.insert(name.clone(), section(published, name));
+    }
+    serde_json::Value::Object(message).to_string()
+}
+
+/// Value of a request header, matched case-insensitively
+fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`
+pub fn websocket_accept(key: &str) -> String {
+    let digest = sha1_smol::Sha1::from(format!("{}{}", key, WEBSOCKET_GUID)).digest().bytes();
+    base64(&digest)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// An unmasked server frame: FIN set, then the payload length in the shortest form
+pub fn websocket_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        },
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        },
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Finish the handshake, then send the latest snapshot and every new one until the client leaves
+fn stream_snapshots(stream: TcpStream, key: &str, sections: Vec<String>, shared: &Shared) -> std::io::Result<()> {
+    // Subscribe before answering, so nothing published after the handshake is missed
+    let (tx, rx): (Sender<Arc<Published>>, Receiver<Arc<Published>>) = mpsc::channel();
+    if let Some(latest) = shared.latest.read().ok().and_then(|latest| latest.clone()) {
+        let _ = tx.send(latest);
+    }
+    if let Ok(mut subscribers) = shared.subscribers.lock() {
+        subscribers.push(tx);
+    }
+
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+    write!(
+        writer.lock().expect("writer lock"),
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        websocket_accept(key)
+    )?;
+
+    // Answer pings and notice the close on a thread of its own; sends wait on the channel
+    let control_writer = Arc::clone(&writer);
+    std::thread::spawn(move || {
+        let _ = read_client_frames(stream, &control_writer);
+    });
+
+    for published in rx {
+        let frame = websocket_frame(0x1, stream_message(&published, &sections).as_bytes());
+        let mut writer = writer.lock().expect("writer lock");
+        if writer.write_all(&frame).and_then(|_| writer.flush()).is_err() {
+            break;
+        }
+    }
+    // The reader waits on the client without a timeout; closing the socket ends it too
+    let writer = writer.lock().expect("writer lock");
+    writer.shutdown(Shutdown::Both)
+}
+
+fn read_client_frames(mut stream: TcpStream, writer: &Mutex<TcpStream>) -> std::io::Result<()> {
+    // Clients may stay quiet for as long as they like
+    stream.set_read_timeout(None)?;
+    loop {
+        let mut head = [0u8; 2];
+        stream.read_exact(&mut head)?;
+        let opcode = head[0] & 0x0f;
+        let masked = head[1] & 0x80 != 0;
+        let len = match head[1] & 0x7f {
+            126 => {
+                let mut len = [0u8; 2];
+                stream.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as u64
+            },
+            127 => {
+                let mut len = [0u8; 8];
+                stream.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            },
+            len => len as u64,
+        };
+        if len > MAX_CLIENT_FRAME_BYTES {
+            break;
+        }
+        let mut mask = [0u8; 4];
+        if masked {
+            stream.read_exact(&mut mask)?;
+        }
+        let mut payload = vec![0u8; len as usize];
+        stream.read_exact(&mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        match opcode {
+            0x8 => {
+                let _ = writer.lock().expect("writer lock").write_all(&websocket_frame(0x8, &[]));
+                break;
+            },
+            0x9 => writer.lock().expect("writer lock").write_all(&websocket_frame(0xA, &payload))?,
+            _ => {},
+        }
+    }
+    // Shutting the socket down makes the next send fail, which ends the sending side
+    stream.shutdown(Shutdown::Both)
+}
+
+fn json<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| error_body(&e.to_string()))
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Only an allowed origin is named in `Access-Control-Allow-Origin`; without the header a
+/// browser keeps the response from the page that asked
+fn write_response(stream: &mut TcpStream, status: u16, body: &str, origin: Option<&str>) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    let cors = origin.map(|origin| format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n", origin)).unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        cors,
+        body
+    )?;
+    stream.flush()
+}
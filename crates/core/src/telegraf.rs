@@ -0,0 +1,238 @@
+use crate::model::{GpuAggregate, GpuExportMode, SystemSnapshot};
+use serde_json::{Map, Value};
+use std::{str::FromStr, time::UNIX_EPOCH};
+
+/// What Telegraf's exec and execd inputs are told to parse (`data_format`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TelegrafFormat {
+    /// InfluxDB line protocol, one line per point
+    #[default]
+    Influx,
+    /// One JSON array of flat objects per pass, for `data_format = "json"` with
+    /// `json_name_key = "measurement"`, `json_time_key = "time"` and `json_time_format = "unix_ms"`
+    Json,
+}
+
+impl FromStr for TelegrafFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "influx" => Ok(Self::Influx),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unknown format '{}': use influx or json", other)),
+        }
+    }
+}
+
+enum Field {
+    Float(f64),
+    Int(u64),
+}
+
+/// One measurement: kacemon_system, kacemon_cpu, kacemon_mem, ...
+struct Point {
+    measurement: &'static str,
+    tags: Vec<(&'static str, String)>,
+    fields: Vec<(&'static str, Field)>,
+}
+
+impl Point {
+    fn new(measurement: &'static str, host: &str) -> Self {
+        Self { measurement, tags: vec![("host", host.to_string())], fields: Vec::new() }
+    }
+
+    fn tag(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.tags.push((key, value.into()));
+        self
+    }
+
+    fn float(mut self, key: &'static str, value: f64) -> Self {
+        // Line protocol has no NaN or infinity
+        if value.is_finite() {
+            self.fields.push((key, Field::Float((value * 100.0).round() / 100.0)));
+        }
+        self
+    }
+
+    fn int(mut self, key: &'static str, value: u64) -> Self {
+        self.fields.push((key, Field::Int(value)));
+        self
+    }
+}
+
+/// `snapshot` as Telegraf expects to read it. Byte and packet figures are running counters,
+/// as Telegraf's own plugins report them, so rates come from its derivative aggregators the
+/// same way for every source. GPUs come as one `gpu=all` point or one per device.
+pub fn telegraf_metrics(snapshot: &SystemSnapshot, format: TelegrafFormat, gpu_export: GpuExportMode) -> String {
+    let points = points(snapshot, gpu_export);
+    let time = snapshot.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    match format {
+        TelegrafFormat::Influx => points.iter().map(|point| influx_line(point, time.as_nanos())).collect(),
+        TelegrafFormat::Json => {
+            let objects: Vec<Value> = points.iter().map(|point| json_object(point, time.as_millis())).collect();
+            // A single line, since execd reads its output line by line
+            serde_json::to_string(&objects).unwrap_or_default() + "\n"
+        },
+    }
+}
+
+fn points(snapshot: &SystemSnapshot, gpu_export: GpuExportMode) -> Vec<Point> {
+    let host = snapshot.system.hostname.as_str();
+
+    let mut points = vec![Point::new("kacemon_system", host)
+        .float("cpu_percent", snapshot.cpu_average() as f64)
+        .float("load1", snapshot.system.load_avg_1)
+        .float("load5", snapshot.system.load_avg_5)
+        .float("load15", snapshot.system.load_avg_15)
+        .int("uptime", snapshot.system.uptime.as_secs())
+        .int("processes", snapshot.processes.len() as u64)];
+
+    for core in &snapshot.cpu_cores {
+        points.push(
+            Point::new("kacemon_cpu", host)
+                .tag("cpu", format!("cpu{}", core.id))
+                .float("usage_percent", core.usage_percent as f64)
+                .int("frequency_mhz", core.frequency),
+        );
+    }
+
+    let memory = &snapshot.memory;
+    points.push(
+        Point::new("kacemon_mem", host)
+            .int("total", memory.total)
+            .int("used", memory.used)
+            .int("available", memory.available)
+            .int("free", memory.free)
+            .int("buffers", memory.buffers)
+            .int("cached", memory.cached)
+            .float("used_percent", memory.used_percent())
+            .int("swap_total", memory.swap_total)
+            .int("swap_used", memory.swap_used)
+            .int("swap_free", memory.swap_free),
+    );
+
+    for disk in &snapshot.disks {
+        let mut point = Point::new("kacemon_disk", host)
+            .tag("device", &disk.name)
+            .tag("mount", &disk.mount_point)
+            .tag("fstype", &disk.file_system)
+            .int("total", disk.total_space)
+            .int("used", disk.used_space)
+            .int("free", disk.available_space)
+            .float("used_percent", if disk.total_space > 0 { disk.used_space as f64 / disk.total_space as f64 * 100.0 } else { 0.0 })
+            .int("read_bytes", disk.read_bytes)
+            .int("write_bytes", disk.write_bytes);
+        if let Some(latency) = disk.latency_ms {
+            point = point.float("latency_ms", latency as f64);
+        }
+        if let Some(busy) = disk.busy_percent {
+            point = point.float("busy_percent", busy as f64);
+        }
+        points.push(point);
+    }
+
+    for network in &snapshot.networks {
+        points.push(
+            Point::new("kacemon_net", host)
+                .tag("interface", &network.interface_name)
+                .int("bytes_recv", network.rx_bytes)
+                .int("bytes_sent", network.tx_bytes)
+                .int("packets_recv", network.rx_packets)
+                .int("packets_sent", network.tx_packets)
+                .int("err_in", network.rx_errors)
+                .int("err_out", network.tx_errors),
+        );
+    }
+
+    for sensor in &snapshot.temperatures {
+        points.push(Point::new("kacemon_temp", host).tag("sensor", &sensor.label).float("celsius", sensor.temperature as f64));
+    }
+
+    let pressure = &snapshot.pressure;
+    for (resource, stall) in [("cpu", &pressure.cpu), ("memory", &pressure.memory), ("io", &pressure.io)] {
+        let Some(stall) = stall else {
+            continue;
+        };
+        let mut point = Point::new("kacemon_pressure", host).tag("resource", resource).float("some_avg10", stall.some_avg10 as f64);
+        if let Some(full) = stall.full_avg10 {
+            point = point.float("full_avg10", full as f64);
+        }
+        points.push(point);
+    }
+
+    if gpu_export == GpuExportMode::Aggregate && !snapshot.gpus.is_empty() {
+        let gpus = GpuAggregate::from_gpus(&snapshot.gpus);
+        let mut point = Point::new("kacemon_gpu", host)
+            .tag("gpu", "all")
+            .int("count", gpus.count as u64)
+            .float("utilization_percent", gpus.utilization_percent as f64)
+            .int("memory_used", gpus.memory_used)
+            .int("memory_total", gpus.memory_total);
+        if let Some(temperature) = gpus.max_temperature {
+            point = point.float("temperature", temperature as f64);
+        }
+        points.push(point);
+    }
+    for gpu in snapshot.gpus.iter().filter(|_| gpu_export == GpuExportMode::PerDevice) {
+        let mut point = Point::new("kacemon_gpu", host)
+            .tag("gpu", gpu.index.to_string())
+            .tag("model", &gpu.name)
+            .float("utilization_percent", gpu.utilization_percent as f64)
+            .int("memory_used", gpu.memory_used)
+            .int("memory_total", gpu.memory_total);
+        if let Some(temperature) = gpu.temperature {
+            point = point.float("temperature", temperature as f64);
+        }
+        points.push(point);
+    }
+
+    points
+}
+
+/// "kacemon_disk,host=web1,mount=/ total=1000i,used_percent=12.5 1700000000000000000"
+fn influx_line(point: &Point, time_ns: u128) -> String {
+    let mut line = point.measurement.to_string();
+    // Empty tag values aren't allowed, so those tags are left off
+    for (key, value) in point.tags.iter().filter(|(_, value)| !value.is_empty()) {
+        line += &format!(",{}={}", key, escape_tag(value));
+    }
+    let fields: Vec<String> = point
+        .fields
+        .iter()
+        .map(|(key, value)| match value {
+            Field::Float(value) => format!("{}={}", key, value),
+            Field::Int(value) => format!("{}={}i", key, value),
+        })
+        .collect();
+    format!("{} {} {}\n", line, fields.join(","), time_ns)
+}
+
+fn json_object(point: &Point, time_ms: u128) -> Value {
+    let mut object = Map::new();
+    object.insert("measurement".to_string(), point.measurement.into());
+    for (key, value) in &point.tags {
+        object.insert(key.to_string(), value.as_str().into());
+    }
+    for (key, value) in &point.fields {
+        let value = match value {
+            Field::Float(value) => Value::from(*value),
+            Field::Int(value) => Value::from(*value),
+        };
+        object.insert(key.to_string(), value);
+    }
+    object.insert("time".to_string(), Value::from(time_ms as u64));
+    Value::Object(object)
+}
+
+/// Commas, equals signs and spaces in tag values are backslash-escaped
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
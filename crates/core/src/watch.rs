@@ -0,0 +1,304 @@
+use crate::{
+    filter::{parse_finite, parse_size, Comparison, ProcessFilter},
+    model::{ProcessInfo, ProcessState, SystemSnapshot},
+};
+use std::str::FromStr;
+
+/// What a comparison clause measures
+#[derive(Debug, Clone, PartialEq)]
+enum Metric {
+    /// Average across all cores, in percent
+    Cpu,
+    MemoryUsed,
+    MemoryAvailable,
+    MemoryFree,
+    SwapUsed,
+    SwapFree,
+    Load1,
+    Load5,
+    Load15,
+    /// Hottest sensor, in Celsius
+    Temperature,
+    Processes,
+    Zombies,
+    /// Share of the last 10s some task stalled on the resource
+    CpuPressure,
+    MemoryPressure,
+    IoPressure,
+    DiskUsed(String),
+    DiskFree(String),
+}
+
+/// How a metric's value is written
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    /// Always a percentage; the % is optional
+    Percent,
+    /// Bytes with an optional K/M/G/T suffix, or a percentage of the total with %
+    Bytes,
+    /// A plain number
+    Count,
+}
+
+impl Metric {
+    fn parse(words: &[&str]) -> Option<Self> {
+        let lower: Vec<String> = words.iter().map(|word| word.to_ascii_lowercase()).collect();
+        let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+        let metric = match lower.as_slice() {
+            ["cpu"] => Self::Cpu,
+            ["memory" | "mem"] | ["memory" | "mem", "used"] => Self::MemoryUsed,
+            ["memory" | "mem", "available" | "avail"] => Self::MemoryAvailable,
+            ["memory" | "mem", "free"] => Self::MemoryFree,
+            ["swap"] | ["swap", "used"] => Self::SwapUsed,
+            ["swap", "free"] => Self::SwapFree,
+            ["load" | "load1"] => Self::Load1,
+            ["load5"] => Self::Load5,
+            ["load15"] => Self::Load15,
+            ["temperature" | "temp"] => Self::Temperature,
+            ["processes" | "procs"] => Self::Processes,
+            ["zombies"] => Self::Zombies,
+            ["cpu", "pressure"] => Self::CpuPressure,
+            ["memory" | "mem", "pressure"] => Self::MemoryPressure,
+            ["io", "pressure"] => Self::IoPressure,
+            // Mount points keep their case
+            ["disk", _] | ["disk", _, "used"] => Self::DiskUsed(words[1].to_string()),
+            ["disk", _, "free" | "available"] => Self::DiskFree(words[1].to_string()),
+            _ => return None,
+        };
+        Some(metric)
+    }
+
+    fn unit(&self) -> Unit {
+        match self {
+            Self::Cpu | Self::CpuPressure | Self::MemoryPressure | Self::IoPressure => Unit::Percent,
+            Self::MemoryUsed | Self::MemoryAvailable | Self::MemoryFree | Self::SwapUsed | Self::SwapFree => Unit::Bytes,
+            Self::DiskUsed(_) | Self::DiskFree(_) => Unit::Bytes,
+            Self::Load1 | Self::Load5 | Self::Load15 | Self::Temperature | Self::Processes | Self::Zombies => Unit::Count,
+        }
+    }
+
+    /// Current value and, for sizes, the total it is a part of. None when the snapshot
+    /// doesn't have it: no such mount, no sensors, no pressure stall information.
+    fn measure(&self, snapshot: &SystemSnapshot) -> Option<(f64, f64)> {
+        let memory = &snapshot.memory;
+        let system = &snapshot.system;
+        let sized = |value: u64, total: u64| Some((value as f64, total as f64));
+        let disk = |mount: &str| snapshot.disks.iter().find(|disk| disk.mount_point == mount);
+        let counted = |value: f64| Some((value, 0.0));
+        match self {
+            Self::Cpu => (!snapshot.cpu_cores.is_empty()).then(|| snapshot.cpu_average() as f64).and_then(counted),
+            Self::MemoryUsed => sized(memory.used, memory.total),
+            Self::MemoryAvailable => sized(memory.available, memory.total),
+            Self::MemoryFree => sized(memory.free, memory.total),
+            Self::SwapUsed => sized(memory.swap_used, memory.swap_total),
+            Self::SwapFree => sized(memory.swap_free, memory.swap_total),
+            Self::Load1 => counted(system.load_avg_1),
+            Self::Load5 => counted(system.load_avg_5),
+            Self::Load15 => counted(system.load_avg_15),
+            Self::Temperature => snapshot
+                .temperatures
+                .iter()
+                .map(|t| t.temperature as f64)
+                .reduce(f64::max)
+                .and_then(counted),
+            Self::Processes => counted(snapshot.processes.len() as f64),
+            Self::Zombies => {
+                counted(snapshot.processes.iter().filter(|p| p.state == ProcessState::Zombie).count() as f64)
+            },
+            Self::CpuPressure => snapshot.pressure.cpu.and_then(|stall| counted(stall.some_avg10 as f64)),
+            Self::MemoryPressure => snapshot.pressure.memory.and_then(|stall| counted(stall.some_avg10 as f64)),
+            Self::IoPressure => snapshot.pressure.io.and_then(|stall| counted(stall.some_avg10 as f64)),
+            Self::DiskUsed(mount) => disk(mount).and_then(|d| sized(d.used_space, d.total_space)),
+            Self::DiskFree(mount) => disk(mount).and_then(|d| sized(d.available_space, d.total_space)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Clause {
+    Compare {
+        metric: Metric,
+        comparison: Comparison,
+        value: f64,
+        /// `value` is a percentage of the metric's total
+        of_total: bool,
+        text: String,
+    },
+    /// `process <filter>`, or `no process <filter>` when `present` is false
+    Process { filter: ProcessFilter, present: bool, text: String },
+}
+
+impl Clause {
+    fn parse(text: &str) -> Result<Self, String> {
+        let lower = text.to_ascii_lowercase();
+        for (prefix, present) in [("process ", true), ("no process ", false)] {
+            if lower.starts_with(prefix) {
+                let query = text[prefix.len()..].trim();
+                let filter: ProcessFilter = query.parse().map_err(|e| format!("Bad filter '{}': {}", query, e))?;
+                return Ok(Self::Process { filter, present, text: text.to_string() });
+            }
+        }
+
+        let Some(at) = text.find(['<', '>', '=']) else {
+            return Err(format!("'{}' needs a comparison such as < or >", text));
+        };
+        let operator = ["<=", ">=", "==", "<", ">", "="]
+            .into_iter()
+            .find(|op| text[at..].starts_with(op))
+            .unwrap_or_default();
+        let comparison = Comparison::from_operator(operator).unwrap_or(Comparison::Equal);
+        let (name, value) = (text[..at].trim(), text[at + operator.len()..].trim());
+
+        let words: Vec<&str> = name.split_whitespace().collect();
+        let metric = Metric::parse(&words).ok_or_else(|| {
+            format!(
+                "Unknown metric '{}': use cpu, memory [used|available|free], swap [used|free], load, load5, \
+                 load15, temperature, processes, zombies, cpu|memory|io pressure, disk <mount> [used|free] \
+                 or process <filter>",
+                name
+            )
+        })?;
+
+        // "1 GB" reads as naturally as "1GB"
+        let compact: String = value.split_whitespace().collect();
+        let percent = compact.strip_suffix('%');
+        let parsed = match (metric.unit(), percent) {
+            (Unit::Bytes, Some(digits)) | (Unit::Percent, Some(digits)) => parse_finite(digits),
+            (Unit::Bytes, None) => parse_size(&compact),
+            (Unit::Percent | Unit::Count, None) => parse_finite(&compact),
+            (Unit::Count, Some(_)) => return Err(format!("'{}' is a count, not a percentage", name)),
+        };
+        let value = parsed.ok_or_else(|| format!("'{}' is not a number", value))?;
+        Ok(Self::Compare {
+            of_total: metric.unit() == Unit::Bytes && percent.is_some(),
+            metric,
+            comparison,
+            value,
+            text: text.to_string(),
+        })
+    }
+
+    fn holds(&self, snapshot: &SystemSnapshot) -> bool {
+        match self {
+            Self::Compare { metric, comparison, value, of_total, .. } => match metric.measure(snapshot) {
+                Some((_, total)) if *of_total && total <= 0.0 => false,
+                Some((actual, total)) if *of_total => comparison.holds(actual / total * 100.0, *value),
+                Some((actual, _)) => comparison.holds(actual, *value),
+                None => false,
+            },
+            Self::Process { filter, present, .. } => (matching(filter, snapshot).next().is_some()) == *present,
+        }
+    }
+
+    /// The clause with what it currently reads, e.g. "memory available < 1GB (now 812.4MB)"
+    fn describe(&self, snapshot: &SystemSnapshot) -> String {
+        match self {
+            Self::Compare { metric, of_total, text, .. } => {
+                let now = match metric.measure(snapshot) {
+                    None => "not measured".to_string(),
+                    Some((actual, total)) if *of_total && total > 0.0 => format!("{:.1}%", actual / total * 100.0),
+                    Some((actual, _)) => match metric.unit() {
+                        Unit::Percent => format!("{:.1}%", actual),
+                        Unit::Bytes => format_size(actual),
+                        Unit::Count => format!("{}", (actual * 100.0).round() / 100.0),
+                    },
+                };
+                format!("{} (now {})", text, now)
+            },
+            Self::Process { filter, text, .. } => {
+                let count = matching(filter, snapshot).count();
+                format!("{} (now {} matching)", text, count)
+            },
+        }
+    }
+}
+
+/// A condition on a snapshot, as `kacemon watch` waits on. Clauses compare a system metric
+/// with a value, or test for a process matching a filter query; `and` binds tighter than
+/// `or`. Sizes take K, M, G or T suffixes (powers of 1024) or a % of the total.
+///
+/// ```
+/// let condition: kacemon_core::WatchCondition = "memory available < 1GB or swap used > 50%".parse().unwrap();
+/// assert_eq!(condition.to_string(), "memory available < 1GB or swap used > 50%");
+/// assert!("no process name:make and load < 2".parse::<kacemon_core::WatchCondition>().is_ok());
+/// assert!("load > 50%".parse::<kacemon_core::WatchCondition>().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WatchCondition {
+    /// Holds when every clause of any one group holds
+    any_of: Vec<Vec<Clause>>,
+}
+
+impl WatchCondition {
+    pub fn matches(&self, snapshot: &SystemSnapshot) -> bool {
+        self.any_of.iter().any(|group| group.iter().all(|clause| clause.holds(snapshot)))
+    }
+
+    /// Each clause with its current reading, one per line
+    pub fn describe(&self, snapshot: &SystemSnapshot) -> Vec<String> {
+        self.any_of.iter().flatten().map(|clause| clause.describe(snapshot)).collect()
+    }
+}
+
+impl FromStr for WatchCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err("Empty condition".to_string());
+        }
+        let mut any_of = vec![Vec::new()];
+        let mut words: Vec<&str> = Vec::new();
+        // The end of the text closes the last clause like a keyword would
+        for word in s.split_whitespace().map(Some).chain([None]) {
+            let keyword = word.map(str::to_ascii_lowercase);
+            if let (Some(word), false) = (word, matches!(keyword.as_deref(), Some("and" | "or"))) {
+                words.push(word);
+                continue;
+            }
+            if words.is_empty() {
+                return Err("'and' and 'or' need a condition on both sides".to_string());
+            }
+            let group = any_of.last_mut().expect("starts with one group");
+            group.push(Clause::parse(&words.join(" "))?);
+            words.clear();
+            if keyword.as_deref() == Some("or") {
+                any_of.push(Vec::new());
+            }
+        }
+        Ok(Self { any_of })
+    }
+}
+
+impl std::fmt::Display for WatchCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = |clause: &Clause| match clause {
+            Clause::Compare { text, .. } | Clause::Process { text, .. } => text.clone(),
+        };
+        let groups: Vec<String> =
+            self.any_of.iter().map(|group| group.iter().map(text).collect::<Vec<_>>().join(" and ")).collect();
+        write!(f, "{}", groups.join(" or "))
+    }
+}
+
+/// Processes the filter matches, leaving out this one and the shells it runs under: their
+/// command lines hold the filter text and would always match
+fn matching<'a>(filter: &'a ProcessFilter, snapshot: &'a SystemSnapshot) -> impl Iterator<Item = &'a ProcessInfo> {
+    let own = snapshot.lineage(std::process::id());
+    snapshot.processes.iter().filter(move |p| !own.contains(&p.pid) && filter.matches(p))
+}
+
+fn format_size(bytes: f64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", size as u64)
+    } else {
+        format!("{:.1}{}", size, UNITS[unit])
+    }
+}
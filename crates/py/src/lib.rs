@@ -0,0 +1,115 @@
+//! Python bindings: `kacemon.collect()` and `kacemon.Collector` return snapshots as plain
+//! dicts with the same keys and nesting as `SystemSnapshot` (and the HTTP API's /snapshot).
+
+// Fires on code generated by #[pymethods]/#[pyfunction], not on anything written here
+#![allow(clippy::useless_conversion)]
+
+use kacemon_core::{CoreError, MetricsCollector, SystemSnapshot};
+use pyo3::{
+    exceptions::PyRuntimeError,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use std::time::Duration;
+
+/// A collector kept between calls, so CPU and I/O rates cover the time since the last one
+#[pyclass(module = "kacemon")]
+struct Collector {
+    inner: MetricsCollector,
+}
+
+#[pymethods]
+impl Collector {
+    /// Every collector is on except sockets; pass False to skip the expensive ones
+    #[new]
+    #[pyo3(signature = (*, processes=true, disks=true, networks=true, temperatures=true, gpus=true, connections=false))]
+    fn new(
+        processes: bool,
+        disks: bool,
+        networks: bool,
+        temperatures: bool,
+        gpus: bool,
+        connections: bool,
+    ) -> PyResult<Self> {
+        let mut inner = MetricsCollector::builder()
+            .processes(processes)
+            .disks(disks)
+            .networks(networks)
+            .temperatures(temperatures)
+            .gpus(gpus)
+            .connections(connections)
+            .build()
+            .map_err(runtime_error)?;
+        inner.init().map_err(runtime_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Take a snapshot as a dict
+    fn collect(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        let snapshot = py.allow_threads(|| self.inner.collect()).map_err(runtime_error)?;
+        snapshot_to_py(py, &snapshot)
+    }
+}
+
+/// One-off snapshot: take a baseline, wait `interval` seconds so rates have something to
+/// cover, then collect
+#[pyfunction]
+#[pyo3(signature = (interval=0.5))]
+fn collect(py: Python<'_>, interval: f64) -> PyResult<PyObject> {
+    let wait = Duration::try_from_secs_f64(interval)
+        .map_err(|_| PyRuntimeError::new_err("interval must be a non-negative number of seconds"))?;
+    let snapshot = py
+        .allow_threads(|| {
+            let mut collector = MetricsCollector::new()?;
+            collector.init()?;
+            std::thread::sleep(wait);
+            collector.collect()
+        })
+        .map_err(runtime_error)?;
+    snapshot_to_py(py, &snapshot)
+}
+
+fn runtime_error(error: CoreError) -> PyErr {
+    PyRuntimeError::new_err(error.to_string())
+}
+
+fn snapshot_to_py(py: Python<'_>, snapshot: &SystemSnapshot) -> PyResult<PyObject> {
+    let value = serde_json::to_value(snapshot).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    to_py(py, &value)
+}
+
+/// JSON value -> None, bool, int, float, str, list or dict
+fn to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    use serde_json::Value;
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => u.into_py(py),
+            (None, Some(i)) => i.into_py(py),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_py(py),
+        },
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        },
+        Value::Object(fields) => {
+            let dict = PyDict::new_bound(py);
+            for (key, item) in fields {
+                dict.set_item(key, to_py(py, item)?)?;
+            }
+            dict.into_py(py)
+        },
+    })
+}
+
+#[pymodule]
+fn kacemon(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Collector>()?;
+    module.add_function(wrap_pyfunction!(collect, module)?)?;
+    Ok(())
+}
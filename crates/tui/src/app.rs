@@ -1,18 +1,241 @@
-use crate::input::{InputEvent, InputHandler};
-use crate::ui::ColorScheme;
-use kacemon_core::{Config, MetricsCollector, ProcessColumns, SortKey, SystemSnapshot};
+use crate::ascii::AsciiWriter;
+use crate::history::{History, SystemSample, DEFAULT_HISTORY_LEN};
+use crate::input::{InputEvent, InputHandler, KeyContext};
+use crate::screen::ScreenBuffer;
+use crate::ui::{sorted_interfaces, ColorScheme, CopyText, Dialog, DialogOutcome, DiskSample, FleetRow, GaugeMarks, NetworkSample};
+use crate::worker::{
+    spawn_collector_thread, spawn_deep_scan, spawn_demo_thread, spawn_input_thread, spawn_remote_threads, AppMessage,
+    CollectorCommand,
+};
+use kacemon_core::{
+    export::{Bundle, BundleView, CsvWriter},
+    metrics::{boot::boot_timing, network::interface_details, process::{self, complete_process}, ThreadCollector},
+    mock::{FakeCollector, MockPlatformProvider},
+    platform::{get_platform_provider, PlatformProcessDetails, PlatformSystemMetrics},
+    Alert, AlertDispatcher, AlertKind, AlertTracker, BootTiming, Classifier, Config, CpuCore, DeepScan, InterfaceDetails, KillSignal, MemoryInfo, MetricsCollector, NameCollation, NetworkInfo, PlatformProvider, PressureScore, ProcessColumns, ProcessGrouping, ProcessInfo, ProcessState, SortKey,
+    LeakDetector, LeakSuspect, ProcessFilter, ServiceAction, ServiceInfo, Spawner, SystemSnapshot, ThreadInfo,
+};
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
 use std::time::{Duration, Instant};
 
+/// Panel that receives navigation keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Panel {
+    Processes,
+    Disks,
+    Network,
+}
+
+/// Which sockets the connections panel shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionsView {
+    Hidden,
+    SelectedProcess,
+    All,
+}
+
+/// Which processes the table shows by controlling terminal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TerminalFilter {
+    All,
+    /// Only processes on this terminal, e.g. "pts/3"
+    Only(String),
+    /// Only processes without one: daemons, services and kernel threads, like `ps x` adds
+    Detached,
+}
+
+impl TerminalFilter {
+    /// Processes past the collection limit never had their terminal read, so neither side
+    /// can claim them; an idle shell would otherwise pass for a daemon
+    pub(crate) fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(tty) => !process.partial && process.tty.as_ref() == Some(tty),
+            Self::Detached => !process.partial && process.tty.is_none(),
+        }
+    }
+}
+
+/// State of the process detail view
+struct ProcessDetail {
+    process: ProcessInfo,
+    details: Option<PlatformProcessDetails>,
+    scroll: usize,
+}
+
+/// State of the thread list for one process
+struct ThreadView {
+    name: String,
+    collector: ThreadCollector,
+    threads: Vec<ThreadInfo>,
+    selected: usize,
+    exited: bool,
+}
+
+/// State of the systemd services view; the units themselves come with each snapshot
+struct ServicesView {
+    selected: usize,
+}
+
+/// State of the system log view; the entries come with each snapshot, newest shown first
+struct LogView {
+    scroll: usize,
+}
+
+/// State of the network interface detail view
+struct NetworkDetail {
+    interface_name: String,
+    details: InterfaceDetails,
+}
+
+/// Frozen plain-text table shown while selecting text with the mouse
+struct CopyMode {
+    text: CopyText,
+    scroll: usize,
+}
+
+/// What the open dialog's answer is used for
+enum DialogPurpose {
+    PickSignal { pid: u32, name: String },
+    ConfirmSignal { pid: u32, name: String, signal: KillSignal },
+    Filter,
+    /// Parents behind a `Spawning` alert, offered for a signal
+    PickSpawner(Vec<Spawner>),
+    PickPreset,
+    /// Users offered for the user filter; None stands for everyone
+    PickUser(Vec<Option<String>>),
+    PickServiceAction { unit: String },
+    ConfirmServiceAction { unit: String, action: ServiceAction },
+}
+
+/// Latest poll of one configured remote host
+struct RemoteHostState {
+    name: String,
+    snapshot: Option<SystemSnapshot>,
+    alerts: Vec<Alert>,
+    error: Option<String>,
+    updated_at: Option<Instant>,
+    history: History<SystemSample>,
+    interval: f64,
+}
+
+/// Everything the process table's order depends on
+#[derive(Debug, Clone, PartialEq)]
+struct OrderKey {
+    host: Option<usize>,
+    filter_text: String,
+    category_filter: Option<String>,
+    user_filter: Option<String>,
+    terminal_filter: TerminalFilter,
+    disk_sleep_filter: bool,
+    leak_filter: bool,
+    hide_kernel_threads: bool,
+    grouping: ProcessGrouping,
+    expanded_groups: BTreeSet<String>,
+    sort: SortKey,
+    reverse: bool,
+}
+
+/// Table order of the viewed snapshot's processes, kept until the snapshot, filters or sort
+/// change so frames and key presses don't refilter and resort. New snapshots clear it.
+struct ProcessOrder {
+    key: OrderKey,
+    rows: Vec<TableRow>,
+    /// Processes the filters let through that the display limit left out
+    hidden: usize,
+}
+
+/// One line of the process table
+#[derive(Debug, Clone)]
+pub(crate) enum TableRow {
+    /// Index into the snapshot's process list
+    Process(usize),
+    /// Processes sharing a name, summed into one line while grouping by name
+    Group {
+        name: String,
+        summary: Box<ProcessInfo>,
+        /// Indices into the snapshot's process list
+        members: Vec<usize>,
+    },
+}
+
+impl TableRow {
+    /// What the row shows: the process itself, or a group's totals
+    pub(crate) fn process<'a>(&'a self, processes: &'a [ProcessInfo]) -> &'a ProcessInfo {
+        match self {
+            Self::Process(index) => &processes[*index],
+            Self::Group { summary, .. } => summary,
+        }
+    }
+
+    /// Processes the row stands for; an expanded group's are rows of their own
+    pub(crate) fn members(&self) -> &[usize] {
+        match self {
+            Self::Process(index) => std::slice::from_ref(index),
+            Self::Group { members, .. } => members,
+        }
+    }
+}
+
+/// How long a status message stays in the footer
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+const ALERT_FLASH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Remote hosts are polled at the refresh rate, but at most once a second
+const REMOTE_POLL_MIN: Duration = Duration::from_secs(1);
+
+/// Longest a gauge takes to glide to a new sample with smooth_gauges on
+const GAUGE_ANIMATION_MAX: Duration = Duration::from_secs(1);
+
+/// Transient message shown in place of the footer keybinds
+struct StatusMessage {
+    text: String,
+    is_error: bool,
+    shown_at: Instant,
+}
+
+/// A file opened with `kacemon view`. Its frames stand in for collection, and nothing on
+/// this machine is read or signalled while they are shown.
+struct Replay {
+    /// File name, for the footer
+    name: String,
+    frames: Vec<Bundle>,
+    index: usize,
+}
+
+impl Replay {
+    fn frame(&self) -> &Bundle {
+        &self.frames[self.index]
+    }
+}
+
+/// A process picked out in the table, to follow or watch; the start time tells a reused PID
+/// apart
+struct Followed {
+    pid: u32,
+    name: String,
+    start_time: std::time::SystemTime,
+}
+
 /// Application state
 pub struct App {
     // Core components
     config: Config,
-    metrics_collector: MetricsCollector,
+    metrics_collector: Option<MetricsCollector>,  // moved to the collector thread by `run`
+    collector_commands: Option<Sender<CollectorCommand>>,
+    messages: Option<Sender<AppMessage>>,  // handed to background jobs by `run`
+    replay: Option<Replay>,
+    demo: bool,
+    demo_collector: Option<FakeCollector>,  // moved to the demo thread by `run`
+    platform: Box<dyn PlatformProvider>,
     input_handler: InputHandler,
     
     // UI state
     colors: ColorScheme,
     layout: crate::ui::Layout,
+    screen: ScreenBuffer,  // last frame, so only changed cells are redrawn
     
     // Data
     current_snapshot: Option<SystemSnapshot>,
@@ -25,11 +248,94 @@ pub struct App {
     sort_reverse: bool,
     filter_text: String,
     visible_columns: Vec<String>,
+    classifier: Classifier,
+    category_filter: Option<String>,
+    /// Only processes owned by this user
+    user_filter: Option<String>,
+    terminal_filter: TerminalFilter,
+    disk_sleep_filter: bool,
+    leak_filter: bool,
+    leak_detector: LeakDetector,
+    hide_kernel_threads: bool,
+    /// One row per process name, group or session, with counts and summed usage
+    grouping: ProcessGrouping,
+    /// Groups that show their processes below them
+    expanded_groups: BTreeSet<String>,
+    /// Process the selection is pinned to across refreshes and re-sorts
+    followed: Option<Followed>,
+    /// PID whose children `l` is stepping through, while the selection is on one of them
+    child_walk: Option<u32>,
+    /// Processes whose exit is announced
+    watched: Vec<Followed>,
+    /// Preset last switched to, where the picker opens
+    active_preset: Option<usize>,
+    process_order: RefCell<Option<ProcessOrder>>,
     
     // UI state
     show_help: bool,
+    /// Key to the table's colors and marks under it
+    show_legend: bool,
+    help_page: usize,
     quit_requested: bool,
     tree_view: bool,
+    connections_view: ConnectionsView,
+    /// Whether the collector is measuring per-process TCP throughput for the NET column
+    process_network_enabled: bool,
+    /// and reading PSS and USS for those columns
+    memory_breakdown_enabled: bool,
+    process_detail: Option<ProcessDetail>,
+    thread_view: Option<ThreadView>,
+    services_view: Option<ServicesView>,
+    log_view: Option<LogView>,
+    dialog: Option<(Dialog, DialogPurpose)>,
+    copy_mode: Option<CopyMode>,
+    status_message: Option<StatusMessage>,
+    focused_gpu: usize,
+    focused_panel: Panel,
+    selected_disk: usize,
+    disk_detail: Option<String>,  // disk name
+    selected_interface: Option<String>,
+    network_detail: Option<NetworkDetail>,
+    boot_detail: Option<BootTiming>,
+    scan_running: bool,
+    scan_detail: Option<DeepScan>,
+    
+    // Overall CPU / memory / network / GPU trend for the main view
+    system_history: History<SystemSample>,
+    
+    // Per-device history, keyed by disk / interface name
+    disk_history: HashMap<String, History<DiskSample>>,
+    network_history: HashMap<String, History<NetworkSample>>,
+    snapshot_interval: f64,  // seconds between the last two snapshots
+    kernel_rates: crate::ui::KernelRates,
+    counters_seen: Option<(PlatformSystemMetrics, Instant)>,  // kernel event counters at the last snapshot
+    
+    // Set whenever something visible changed since the last frame
+    dirty: bool,
+    
+    // Terminal title / OSC state, only used when enabled in the config
+    last_title: Option<String>,
+    pending_notifications: Vec<String>,
+    alert_progress_shown: bool,
+    
+    // Thresholds crossed in the latest snapshot; highlighted gauges blink while any are active
+    active_alerts: Vec<Alert>,
+    alert_dispatcher: AlertDispatcher,
+    alert_tracker: AlertTracker,
+    alert_flash_on: bool,
+    alert_flash_at: Instant,
+    
+    // Gauge values of the previous snapshot and when the current one arrived, for smooth_gauges
+    gauges_from: Option<(Vec<CpuCore>, MemoryInfo)>,
+    gauges_changed_at: Instant,
+    
+    // --csv export, dropped after the first write error
+    csv_writer: Option<CsvWriter>,
+    
+    // Configured remote hosts; the main screen shows this machine unless one is being viewed
+    remote_hosts: Vec<RemoteHostState>,
+    viewed_host: Option<usize>,
+    fleet_view: Option<usize>,  // selected row, 0 being this machine
     
     // Performance tracking
     update_count: u64,
@@ -38,21 +344,100 @@ pub struct App {
 
 impl App {
     pub fn new(config: Config) -> anyhow::Result<Self> {
-        let mut metrics_collector = MetricsCollector::new()?;
+        let mut metrics_collector = MetricsCollector::builder()
+            .process_limit(config.process_collect_limit)
+            .hog_threshold(config.cpu_hog_percent)
+            .build()?;
         metrics_collector.init()?;
+        Self::with_collector(config, Some(metrics_collector))
+    }
+
+    /// Explore frames read from a file, e.g. with `kacemon_core::export::read_recording`,
+    /// with collection off; `name` labels them in the footer. A bundle's saved filters, sort
+    /// and columns are put back.
+    pub fn view(mut config: Config, frames: Vec<Bundle>, name: String) -> anyhow::Result<Self> {
+        let Some(view) = frames.first().map(|frame| frame.view.clone()) else {
+            anyhow::bail!("{} holds no snapshots", name);
+        };
+        // Nothing live: no hosts to poll and no CSV rows to append
+        config.hosts.clear();
+        config.csv_path = None;
+        
+        let mut app = Self::with_collector(config, None)?;
+        app.replay = Some(Replay { name, frames, index: 0 });
+        app.show_frame(0);
+        // Plain snapshots and logs carry no view of their own
+        if view != BundleView::default() {
+            app.apply_bundle_view(&view);
+        }
+        Ok(app)
+    }
+
+    /// Animate the made-up machine of `FakeCollector` instead of this one: the same every
+    /// run, for screenshots, docs and working on the UI
+    pub fn demo(mut config: Config) -> anyhow::Result<Self> {
+        // Only the fake machine: no hosts to poll and no CSV rows of made-up data
+        config.hosts.clear();
+        config.csv_path = None;
         
-        let colors = ColorScheme::new(&config.theme, config.no_color);
+        let interval = config.refresh_interval();
+        let mut app = Self::with_collector(config, None)?;
+        app.demo = true;
+        app.demo_collector = Some(FakeCollector::new().starting_at(std::time::SystemTime::now()).with_interval(interval));
+        app.platform = Box::new(MockPlatformProvider::new());
+        Ok(app)
+    }
+
+    fn with_collector(config: Config, metrics_collector: Option<MetricsCollector>) -> anyhow::Result<Self> {
+        let colors = ColorScheme::from_config(&config);
         let layout = crate::ui::Layout::new()?;
         let input_handler = InputHandler::new();
         
-        let visible_columns = Self::default_visible_columns(&config.process_columns);
+        // Rules were checked when the config was loaded
+        let classifier = Classifier::new(&config.categories).unwrap_or_default();
+        let mut visible_columns = Self::default_visible_columns(&config.process_columns);
+        if !classifier.is_empty() {
+            let at = visible_columns.iter().position(|c| c == "NAME").map_or(0, |i| i + 1);
+            visible_columns.insert(at, "CAT".to_string());
+        }
+        let alert_dispatcher = AlertDispatcher::new(config.alerts.actions.clone());
+        let csv_writer = config
+            .csv_path
+            .as_deref()
+            .map(|path| {
+                CsvWriter::open(path).map(|writer| {
+                    writer.with_virtual_interfaces(config.count_virtual_interfaces).with_gpu_export(config.gpu_export)
+                })
+            })
+            .transpose()?;
+        let remote_hosts = config
+            .hosts
+            .iter()
+            .map(|host| RemoteHostState {
+                name: host.name.clone(),
+                snapshot: None,
+                alerts: Vec::new(),
+                error: None,
+                updated_at: None,
+                history: History::default(),
+                interval: 1.0,
+            })
+            .collect();
+        let hide_kernel_threads = config.hide_kernel_threads;
         
         Ok(Self {
             config,
             metrics_collector,
+            collector_commands: None,
+            messages: None,
+            replay: None,
+            demo: false,
+            demo_collector: None,
+            platform: get_platform_provider(),
             input_handler,
             colors,
             layout,
+            screen: ScreenBuffer::default(),
             current_snapshot: None,
             last_update: Instant::now(),
             selected_process_index: 0,
@@ -61,9 +446,66 @@ impl App {
             sort_reverse: true, // Default to descending for CPU usage
             filter_text: String::new(),
             visible_columns,
+            classifier,
+            category_filter: None,
+            user_filter: None,
+            terminal_filter: TerminalFilter::All,
+            grouping: ProcessGrouping::None,
+            expanded_groups: BTreeSet::new(),
+            followed: None,
+            child_walk: None,
+            watched: Vec::new(),
+            disk_sleep_filter: false,
+            leak_filter: false,
+            leak_detector: LeakDetector::default(),
+            hide_kernel_threads,
+            active_preset: None,
+            process_order: RefCell::new(None),
             show_help: false,
+            show_legend: false,
+            help_page: 0,
             quit_requested: false,
             tree_view: false,
+            connections_view: ConnectionsView::Hidden,
+            process_network_enabled: false,
+            memory_breakdown_enabled: false,
+            process_detail: None,
+            thread_view: None,
+            services_view: None,
+            log_view: None,
+            dialog: None,
+            copy_mode: None,
+            status_message: None,
+            focused_gpu: 0,
+            focused_panel: Panel::Processes,
+            selected_disk: 0,
+            disk_detail: None,
+            selected_interface: None,
+            network_detail: None,
+            boot_detail: None,
+            scan_running: false,
+            scan_detail: None,
+            system_history: History::default(),
+            disk_history: HashMap::new(),
+            network_history: HashMap::new(),
+            snapshot_interval: 1.0,
+            kernel_rates: crate::ui::KernelRates::default(),
+            counters_seen: None,
+            dirty: true,
+            last_title: None,
+            pending_notifications: Vec::new(),
+            alert_progress_shown: false,
+            active_alerts: Vec::new(),
+            alert_dispatcher,
+            alert_tracker: AlertTracker::default(),
+            alert_flash_on: true,
+            alert_flash_at: Instant::now(),
+            gauges_from: None,
+            gauges_changed_at: Instant::now(),
+            csv_writer,
+            remote_hosts,
+            viewed_host: None,
+            fleet_view: None,
             update_count: 0,
             render_count: 0,
         })
@@ -75,7 +517,10 @@ impl App {
         self.setup_terminal()?;
         
         // Ensure we restore terminal on exit
-        let _terminal_guard = TerminalGuard;
+        let _terminal_guard = TerminalGuard {
+            restore_title: self.config.terminal_title,
+            clear_progress: self.config.osc_notifications,
+        };
         
         // Clear screen once at startup
         crossterm::execute!(
@@ -83,58 +528,364 @@ impl App {
             crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
         )?;
         
-        // Initial data collection and render
-        self.update_data()?;
-        self.render(writer)?;
+        let (tx, rx) = mpsc::channel();
+        spawn_input_thread(tx.clone());
+        self.messages = Some(tx.clone());
+        spawn_remote_threads(&self.config.hosts, self.config.refresh_interval().max(REMOTE_POLL_MIN), &tx);
+        if let Some(collector) = self.metrics_collector.take() {
+            self.collector_commands = Some(spawn_collector_thread(collector, self.config.refresh_interval(), tx));
+        } else if let Some(collector) = self.demo_collector.take() {
+            self.collector_commands = Some(spawn_demo_thread(collector, self.config.refresh_interval(), tx));
+        }
+        self.sync_column_collectors();
         
-        // Main loop
-        let refresh_interval = self.config.refresh_interval();
-        let mut last_refresh = Instant::now();
+        // Main loop: apply messages as they arrive, draw only when something changed
+        // and at most once per frame interval
+        let mut last_render: Option<Instant> = None;
         
         while !self.quit_requested {
-            // Calculate timeout for next update
-            let elapsed_since_refresh = last_refresh.elapsed();
-            let timeout = if elapsed_since_refresh >= refresh_interval {
-                Duration::from_millis(10) // Very short timeout to update immediately
-            } else {
-                refresh_interval - elapsed_since_refresh
+            let frame_interval = self.config.frame_interval();
+            let timeout = match last_render {
+                Some(at) if self.dirty || self.gauges_animating() => frame_interval.saturating_sub(at.elapsed()),
+                // Nothing to draw; wake up now and then to expire status messages
+                _ => Duration::from_millis(250),
             };
             
-            // Poll for input
-            if let Some(event) = self.input_handler.poll_event(timeout)? {
-                let needs_redraw = self.needs_redraw_after_input(&event);
-                self.handle_event(event);
-                
-                // Only render after input if it affects display (reduce unnecessary redraws)
-                if needs_redraw {
-                    self.render(writer)?;
-                }
+            match rx.recv_timeout(timeout) {
+                Ok(message) => {
+                    self.handle_message(message);
+                    // Apply everything already queued before drawing
+                    while let Ok(message) = rx.try_recv() {
+                        self.handle_message(message);
+                    }
+                },
+                Err(RecvTimeoutError::Timeout) => {},
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            self.expire_status_message();
+            self.tick_alert_flash();
+            if self.gauges_animating() && self.copy_mode.is_none() {
+                self.dirty = true;
             }
             
-            // Update data if it's time
-            if last_refresh.elapsed() >= refresh_interval {
-                self.update_data()?;
-                self.render(writer)?;
-                last_refresh = Instant::now();
+            let frame_due = last_render.map_or(true, |at| at.elapsed() >= frame_interval);
+            if self.dirty && frame_due && !self.quit_requested {
+                self.draw_frame(writer)?;
+                last_render = Some(Instant::now());
+                self.dirty = false;
             }
         }
         
         Ok(())
     }
 
-    /// Update system metrics
-    fn update_data(&mut self) -> anyhow::Result<()> {
-        self.current_snapshot = Some(self.metrics_collector.collect()?);
+    /// Apply one message from the input or collector thread
+    fn handle_message(&mut self, message: AppMessage) {
+        match message {
+            AppMessage::Input(event) => {
+                let event = self.input_handler.map_event(event);
+                // Mouse moves, key releases and unbound keys leave the screen as it is, unless
+                // they close a dialog: a confirmation gives way to any key but yes
+                if !matches!(event, InputEvent::Unknown | InputEvent::Tick) {
+                    self.dirty = true;
+                }
+                let dialog_open = self.dialog.is_some();
+                self.handle_event(event);
+                if dialog_open && self.dialog.is_none() {
+                    self.dirty = true;
+                }
+            },
+            AppMessage::Snapshot(snapshot) => self.apply_snapshot(*snapshot),
+            AppMessage::CollectorError(error) => self.show_status(format!("Collection failed: {}", error), true),
+            AppMessage::KillResult { pid, name, signal, result } => match result {
+                Ok(()) => self.show_status(format!("Sent {} to {} ({})", signal.name(), name, pid), false),
+                Err(error) => self.show_status(error, true),
+            },
+            AppMessage::ServiceResult { unit, action, result } => match result {
+                Ok(()) => self.show_status(format!("Asked systemd to {} {}", action.verb(), unit), false),
+                Err(error) => self.show_status(error, true),
+            },
+            AppMessage::Remote { index, result } => self.apply_remote(index, result),
+            AppMessage::ScanProgress(step) => self.show_status(format!("Deep scan: {}...", step), false),
+            AppMessage::ScanDone(scan) => {
+                self.scan_running = false;
+                self.show_status(format!("Deep scan finished in {:.1}s", scan.duration.as_secs_f64()), false);
+                self.disk_detail = None;
+                self.network_detail = None;
+                self.boot_detail = None;
+                self.scan_detail = Some(*scan);
+            },
+        }
+    }
+
+    /// Ask the collector thread to do something
+    fn send_command(&self, command: CollectorCommand) {
+        if let Some(commands) = &self.collector_commands {
+            // A dead collector thread means no more updates; there is nothing useful to do here
+            let _ = commands.send(command);
+        }
+    }
+
+    /// Append overall usage figures to the main view history
+    fn record_system_history(&mut self, snapshot: &SystemSnapshot) {
+        let sample = system_sample(snapshot, self.snapshot_interval, &self.config);
+        self.system_history.push(sample);
+    }
+
+    /// Append the latest per-disk rates to each disk's history
+    fn record_disk_history(&mut self, snapshot: &SystemSnapshot) {
+        for disk in &snapshot.disks {
+            let sample = DiskSample {
+                read_rate: disk.read_bytes_delta as f64 / self.snapshot_interval,
+                write_rate: disk.write_bytes_delta as f64 / self.snapshot_interval,
+                latency_ms: disk.latency_ms.unwrap_or(0.0) as f64,
+            };
+            self.disk_history.entry(disk.name.clone()).or_default().push(sample);
+        }
+        
+        // Forget disks that were unmounted
+        self.disk_history.retain(|name, _| snapshot.disks.iter().any(|d| &d.name == name));
+    }
+
+    /// Take in a freshly collected snapshot
+    fn apply_snapshot(&mut self, mut snapshot: SystemSnapshot) {
+        self.classifier.tag(&mut snapshot.processes);
+        if self.viewed_host.is_none() {
+            self.layout.set_gpu_count(snapshot.gpus.len());
+            self.focused_gpu = self.focused_gpu.min(snapshot.gpus.len().saturating_sub(1));
+        }
+        if let Some(previous) = &self.current_snapshot {
+            if let Ok(elapsed) = snapshot.timestamp.duration_since(previous.timestamp) {
+                self.snapshot_interval = elapsed.as_secs_f64().max(0.001);
+            }
+        }
+        self.record_system_history(&snapshot);
+        self.record_disk_history(&snapshot);
+        self.record_network_history(&snapshot);
+        self.selected_disk = self.selected_disk.min(snapshot.disks.len().saturating_sub(1));
+        if let Some(name) = &self.disk_detail {
+            if !snapshot.disks.iter().any(|d| &d.name == name) {
+                self.disk_detail = None;
+            }
+        }
+        self.refresh_network_detail(&snapshot);
+        self.update_kernel_rates();
+        self.leak_detector.record(&snapshot);
+        self.update_alerts(&snapshot);
+        self.check_watched(&snapshot);
+        self.write_csv_row(&snapshot);
+        if self.config.smooth_gauges {
+            self.gauges_from = self.current_snapshot.as_ref().map(|s| self.displayed_gauges(s));
+            self.gauges_changed_at = Instant::now();
+        }
+        self.current_snapshot = Some(snapshot);
+        *self.process_order.get_mut() = None;
+        self.refresh_process_detail();
+        self.refresh_thread_view();
         self.update_count += 1;
         self.last_update = Instant::now();
-        Ok(())
+        // Copy mode stays frozen so redraws don't disturb a selection in progress
+        if self.copy_mode.is_none() {
+            self.dirty = true;
+        }
+    }
+
+    /// Put frame `index` of the viewed file on screen as it was recorded
+    fn show_frame(&mut self, index: usize) {
+        let Some(mut replay) = self.replay.take() else {
+            return;
+        };
+        replay.index = index.min(replay.frames.len() - 1);
+        
+        // Graphs lead up to the frame as they would have live
+        self.system_history = History::default();
+        self.disk_history.clear();
+        self.network_history.clear();
+        let first = (replay.index + 1).saturating_sub(DEFAULT_HISTORY_LEN);
+        for frame in &replay.frames[first..=replay.index] {
+            self.snapshot_interval = frame.interval_secs.unwrap_or_else(|| self.config.refresh_interval().as_secs_f64());
+            self.record_system_history(&frame.snapshot);
+            self.record_disk_history(&frame.snapshot);
+            self.record_network_history(&frame.snapshot);
+        }
+        
+        let frame = replay.frame();
+        let snapshot = frame.snapshot.clone();
+        self.active_alerts = frame.alerts.clone();
+        self.replay = Some(replay);
+        
+        self.layout.set_gpu_count(snapshot.gpus.len());
+        self.focused_gpu = self.focused_gpu.min(snapshot.gpus.len().saturating_sub(1));
+        self.selected_disk = self.selected_disk.min(snapshot.disks.len().saturating_sub(1));
+        if let Some(name) = &self.disk_detail {
+            if !snapshot.disks.iter().any(|d| &d.name == name) {
+                self.disk_detail = None;
+            }
+        }
+        if let Some(detail) = &self.network_detail {
+            if !snapshot.networks.iter().any(|n| n.interface_name == detail.interface_name) {
+                self.network_detail = None;
+            }
+        }
+        self.current_snapshot = Some(snapshot);
+        *self.process_order.get_mut() = None;
+        self.refresh_process_detail();
+        self.clamp_selection();
+        self.dirty = true;
+    }
+
+    /// Move through a viewed recording one snapshot at a time
+    fn step_frame(&mut self, forward: bool) {
+        let Some(replay) = &self.replay else {
+            self.show_status("Only for recordings opened with kacemon view".to_string(), false);
+            return;
+        };
+        
+        let (index, last) = (replay.index, replay.frames.len() - 1);
+        let next = if forward { (index + 1).min(last) } else { index.saturating_sub(1) };
+        if next == index {
+            let edge = if forward { "last" } else { "first" };
+            self.show_status(format!("Already at the {} snapshot", edge), false);
+            return;
+        }
+        self.show_frame(next);
+    }
+
+    /// Put back the filters, sort, columns and selection saved in a bundle
+    fn apply_bundle_view(&mut self, view: &BundleView) {
+        self.filter_text = view.filter.clone();
+        self.current_sort = view.sort;
+        self.sort_reverse = view.reverse;
+        self.category_filter = view.category.clone();
+        self.user_filter = view.user.clone();
+        self.terminal_filter = match &view.terminal {
+            Some(tty) => TerminalFilter::Only(tty.clone()),
+            None if view.detached_only => TerminalFilter::Detached,
+            None => TerminalFilter::All,
+        };
+        self.disk_sleep_filter = view.disk_sleep_only;
+        self.leak_filter = view.leaks_only;
+        self.hide_kernel_threads = view.hide_kernel_threads;
+        self.tree_view = view.tree_view;
+        self.grouping = match view.grouping {
+            ProcessGrouping::None if view.group_by_name => ProcessGrouping::Name,
+            grouping => grouping,
+        };
+        if !view.columns.is_empty() {
+            self.visible_columns = view.columns.clone();
+        }
+        
+        if let Some(pid) = view.selected_pid.filter(|_| self.current_snapshot.is_some()) {
+            self.selected_process_index = self.row_of_pid(pid).unwrap_or(0);
+            self.clamp_selection();
+        }
+    }
+
+    /// Take in the outcome of polling a remote host; a failure keeps its last snapshot around
+    fn apply_remote(&mut self, index: usize, result: Result<Box<(SystemSnapshot, Vec<Alert>)>, String>) {
+        let Some(host) = self.remote_hosts.get_mut(index) else {
+            return;
+        };
+        
+        match result {
+            Ok(polled) => {
+                let (snapshot, alerts) = *polled;
+                if let Some(previous) = &host.snapshot {
+                    if let Ok(elapsed) = snapshot.timestamp.duration_since(previous.timestamp) {
+                        host.interval = elapsed.as_secs_f64().max(0.001);
+                    }
+                }
+                host.history.push(system_sample(&snapshot, host.interval, &self.config));
+                host.snapshot = Some(snapshot);
+                *self.process_order.get_mut() = None;
+                host.alerts = alerts;
+                host.error = None;
+                host.updated_at = Some(Instant::now());
+            },
+            Err(error) => host.error = Some(error),
+        }
+        
+        if self.viewed_host == Some(index) {
+            self.sync_viewed_layout();
+        }
+        if (self.viewed_host == Some(index) || self.fleet_view.is_some()) && self.copy_mode.is_none() {
+            self.dirty = true;
+        }
+    }
+
+    /// Forks, context switches and interrupts per second since the last snapshot, from the
+    /// kernel's running counters
+    fn update_kernel_rates(&mut self) {
+        let Ok(counters) = self.platform.get_system_metrics() else {
+            self.counters_seen = None;
+            self.kernel_rates = crate::ui::KernelRates::default();
+            return;
+        };
+        let now = Instant::now();
+        let previous = self.counters_seen.as_ref();
+        let rate = |count: fn(&PlatformSystemMetrics) -> Option<u64>| {
+            let (earlier, at) = previous?;
+            Some(count(&counters)?.saturating_sub(count(earlier)?) as f64 / now.duration_since(*at).as_secs_f64().max(0.001))
+        };
+        self.kernel_rates = crate::ui::KernelRates {
+            forks: rate(|m| m.processes_created),
+            context_switches: rate(|m| m.context_switches),
+            interrupts: rate(|m| m.interrupts),
+        };
+        self.counters_seen = Some((counters, now));
+    }
+
+    fn write_csv_row(&mut self, snapshot: &SystemSnapshot) {
+        let Some(writer) = &mut self.csv_writer else {
+            return;
+        };
+        if let Err(e) = writer.write_row(snapshot, self.snapshot_interval) {
+            self.csv_writer = None;
+            self.show_status(format!("CSV export stopped: {}", e), true);
+        }
+    }
+
+    /// Append the latest per-interface rates to each interface's history
+    fn record_network_history(&mut self, snapshot: &SystemSnapshot) {
+        let per_sec = |delta: u64| delta as f64 / self.snapshot_interval;
+        for network in &snapshot.networks {
+            let sample = NetworkSample {
+                rx_rate: per_sec(network.rx_bytes_delta),
+                tx_rate: per_sec(network.tx_bytes_delta),
+                rx_packets: per_sec(network.rx_packets_delta),
+                tx_packets: per_sec(network.tx_packets_delta),
+                rx_errors: per_sec(network.rx_errors_delta),
+                tx_errors: per_sec(network.tx_errors_delta),
+            };
+            self.network_history.entry(network.interface_name.clone()).or_default().push(sample);
+        }
+        
+        self.network_history.retain(|name, _| snapshot.networks.iter().any(|n| &n.interface_name == name));
+    }
+
+    /// Re-read link properties for the interface detail view, closing it if the interface vanished
+    fn refresh_network_detail(&mut self, snapshot: &SystemSnapshot) {
+        let Some(detail) = &mut self.network_detail else {
+            return;
+        };
+        
+        if snapshot.networks.iter().any(|n| n.interface_name == detail.interface_name) {
+            detail.details = interface_details(&detail.interface_name);
+        } else {
+            self.network_detail = None;
+        }
     }
 
     /// Handle input events
     fn handle_event(&mut self, event: InputEvent) {
+        // Act on the followed process wherever the last snapshot moved it
+        self.sync_followed();
         match event {
             InputEvent::Quit => self.quit_requested = true,
-            InputEvent::ShowHelp => self.show_help = !self.show_help,
+            InputEvent::ShowHelp => {
+                self.show_help = !self.show_help;
+                self.help_page = 0;
+            },
             InputEvent::Resize => {
                 if let Ok(()) = self.layout.update_terminal_size() {
                     // Terminal size updated, render will handle the new layout
@@ -145,36 +896,140 @@ impl App {
             },
             
             // Navigation (only when not showing help)
-            _ if self.show_help => {
-                if event == InputEvent::ShowHelp {
-                    self.show_help = false;
-                }
+            InputEvent::FocusNext if self.show_help => {
+                self.help_page = (self.help_page + 1) % crate::ui::HelpOverlay::PAGES;
+            },
+            InputEvent::ClearFilter if self.show_help => self.show_help = false,
+            _ if self.show_help => {},
+            
+            // Dialogs are modal
+            _ if self.dialog.is_some() => self.handle_dialog_event(event),
+            _ if self.copy_mode.is_some() => self.handle_copy_mode_event(event),
+            
+            // Detail view consumes navigation while open
+            _ if self.process_detail.is_some() => self.handle_detail_event(event),
+            _ if self.thread_view.is_some() => self.handle_thread_event(event),
+            _ if self.services_view.is_some() => self.handle_services_event(event),
+            _ if self.log_view.is_some() => self.handle_log_event(event),
+            InputEvent::Select | InputEvent::ClearFilter | InputEvent::ShowBootTimes | InputEvent::DeepScan
+                if self.device_detail_open() =>
+            {
+                self.disk_detail = None;
+                self.network_detail = None;
+                self.boot_detail = None;
+                self.scan_detail = None;
+            },
+            _ if self.device_detail_open() => {},
+            _ if self.fleet_view.is_some() => self.handle_fleet_event(event),
+            
+            InputEvent::ToggleFleetView => self.open_fleet_view(),
+            InputEvent::ShowHost(index) => self.show_host(index),
+            // Details and signals reach this machine's processes and devices only
+            InputEvent::Select
+            | InputEvent::KillProcess
+            | InputEvent::ToggleWatch
+            | InputEvent::ShowBootTimes
+            | InputEvent::DeepScan
+            | InputEvent::ShowThreads
+            | InputEvent::ShowServices
+            | InputEvent::ShowLogFeed
+                if self.viewed_host.is_some() =>
+            {
+                self.show_status("Only available for this machine (press 0 to return to it)".to_string(), false);
+            },
+            // A saved file has no live processes, devices or collector behind it
+            InputEvent::KillProcess
+            | InputEvent::ToggleWatch
+            | InputEvent::ShowBootTimes
+            | InputEvent::DeepScan
+            | InputEvent::ShowThreads
+            | InputEvent::ShowServices
+            | InputEvent::ShowLogFeed
+            | InputEvent::ChangeRefreshRate
+            | InputEvent::RefreshFaster
+            | InputEvent::RefreshSlower
+                if self.replay.is_some() =>
+            {
+                self.show_status("Not available while viewing a saved file".to_string(), false);
+            },
+            // Made-up processes and devices have nothing real to act on
+            InputEvent::KillProcess
+            | InputEvent::ShowBootTimes
+            | InputEvent::DeepScan
+            | InputEvent::ShowThreads
+            | InputEvent::ShowServices
+            | InputEvent::ShowLogFeed
+                if self.demo =>
+            {
+                self.show_status("Not available in demo mode".to_string(), false);
+            },
+            InputEvent::PreviousFrame | InputEvent::NextFrame => self.step_frame(event == InputEvent::NextFrame),
+            InputEvent::FocusNext => {
+                self.focused_panel = match self.focused_panel {
+                    Panel::Processes => Panel::Disks,
+                    Panel::Disks => Panel::Network,
+                    Panel::Network => Panel::Processes,
+                };
             },
+            InputEvent::ToggleCopyMode => self.enter_copy_mode(),
+            InputEvent::ToggleLegend => {
+                self.show_legend = !self.show_legend;
+                self.clamp_selection();
+            },
+            InputEvent::ShowBootTimes => self.open_boot_detail(),
+            InputEvent::DeepScan => self.start_deep_scan(),
+            _ if self.focused_panel == Panel::Disks => self.handle_disk_panel_event(event),
+            _ if self.focused_panel == Panel::Network => self.handle_network_panel_event(event),
+            
+            InputEvent::Select if self.selected_group().is_some() => self.toggle_selected_group(),
+            InputEvent::KillProcess | InputEvent::ShowThreads if self.selected_group().is_some() => {
+                self.show_status("Expand the group with Enter to pick one of its processes".to_string(), false);
+            },
+            InputEvent::Select => self.open_process_detail(),
+            InputEvent::ShowThreads => self.open_thread_view(),
+            InputEvent::ShowServices => self.open_services_view(),
+            InputEvent::ShowLogFeed => self.open_log_view(),
             
             InputEvent::MoveUp => self.move_selection(-1),
             InputEvent::MoveDown => self.move_selection(1),
             InputEvent::PageUp => self.move_selection(-(self.get_visible_rows() as isize)),
             InputEvent::PageDown => self.move_selection(self.get_visible_rows() as isize),
-            InputEvent::Home => self.selected_process_index = 0,
-            InputEvent::End => {
-                if let Some(snapshot) = &self.current_snapshot {
-                    let process_count = self.get_filtered_processes(&snapshot.processes).len();
-                    self.selected_process_index = process_count.saturating_sub(1);
-                }
-            },
+            InputEvent::Home => self.move_selection(-(self.process_count() as isize)),
+            InputEvent::End => self.move_selection(self.process_count() as isize),
+            InputEvent::ToggleFollow => self.toggle_follow(),
+            InputEvent::SelectParent => self.select_parent(),
+            InputEvent::SelectNextChild => self.select_next_child(),
+            InputEvent::ToggleWatch => self.toggle_watch(),
             
             // Sorting
             InputEvent::CycleSort => {
                 self.current_sort = self.current_sort.next();
-                self.sort_reverse = matches!(self.current_sort, SortKey::Cpu | SortKey::Memory);
+                self.sort_reverse = self.current_sort.default_descending();
             },
+            InputEvent::SortLeft => self.move_sort_column(-1),
+            InputEvent::SortRight => self.move_sort_column(1),
             
             // Filtering
             InputEvent::StartFilter => {
-                // Filter mode handled by input handler
+                // Input handler is already in text mode
+                self.dialog = Some((Dialog::text_input("Filter processes", self.filter_text.clone()), DialogPurpose::Filter));
             },
+            InputEvent::CycleCategory => self.cycle_category_filter(),
+            InputEvent::ToggleDiskSleepFilter => self.toggle_disk_sleep_filter(),
+            InputEvent::ToggleKernelThreads => self.toggle_kernel_threads(),
+            InputEvent::ToggleLeakFilter => self.toggle_leak_filter(),
+            InputEvent::ToggleMyProcesses => self.toggle_my_processes(),
+            InputEvent::CycleTerminalFilter => self.cycle_terminal_filter(),
+            InputEvent::PickUser => self.open_user_dialog(),
+            InputEvent::PickPreset => self.open_presets_dialog(),
+            InputEvent::ApplyPreset(index) => self.apply_preset(index),
             InputEvent::ClearFilter => {
                 self.filter_text.clear();
+                self.category_filter = None;
+                self.user_filter = None;
+                self.terminal_filter = TerminalFilter::All;
+                self.disk_sleep_filter = false;
+                self.leak_filter = false;
                 self.input_handler.exit_filter_mode();
             },
             InputEvent::FilterChar(c) => {
@@ -188,16 +1043,43 @@ impl App {
             InputEvent::ToggleTreeView => {
                 self.tree_view = !self.tree_view;
             },
+            InputEvent::ToggleGrouping => self.toggle_grouping(),
+            InputEvent::ChangeRefreshRate => {
+                self.config.cycle_refresh_preset();
+                self.send_command(CollectorCommand::SetInterval(self.config.refresh_interval()));
+            },
+            InputEvent::RefreshFaster | InputEvent::RefreshSlower => {
+                self.config.step_refresh_preset(event == InputEvent::RefreshFaster);
+                self.send_command(CollectorCommand::SetInterval(self.config.refresh_interval()));
+            },
+            InputEvent::CycleGpu => {
+                if let Some(snapshot) = self.viewed_snapshot() {
+                    if !snapshot.gpus.is_empty() {
+                        self.focused_gpu = (self.focused_gpu + 1) % snapshot.gpus.len();
+                    }
+                }
+            },
+            InputEvent::ToggleConnections => {
+                self.connections_view = match self.connections_view {
+                    ConnectionsView::Hidden => ConnectionsView::SelectedProcess,
+                    ConnectionsView::SelectedProcess => ConnectionsView::All,
+                    ConnectionsView::All => ConnectionsView::Hidden,
+                };
+                let enabled = self.connections_view != ConnectionsView::Hidden;
+                self.send_command(CollectorCommand::SetConnectionsEnabled(enabled));
+                // Fetch sockets right away instead of waiting for the next refresh
+                if enabled {
+                    self.send_command(CollectorCommand::RefreshNow);
+                }
+            },
+            
+            InputEvent::ExportJson => self.export_processes_json(),
+            InputEvent::ExportBundle => self.export_bundle(),
             
             // Process control
             InputEvent::KillProcess => {
-                if let Some(snapshot) = &self.current_snapshot {
-                    let filtered_processes = self.get_filtered_processes(&snapshot.processes);
-                    if let Some(_process) = filtered_processes.get(self.selected_process_index) {
-                        // Attempt to kill the process
-                        // Note: kill_process is not available on MetricsCollector
-                        // We would need to add this functionality or use the platform provider
-                    }
+                if let Some(process) = self.selected_process() {
+                    self.open_kill_dialog(process.pid, &process.name);
                 }
             },
             
@@ -208,168 +1090,1749 @@ impl App {
         
         // Ensure selection is within bounds after any changes
         self.clamp_selection();
+        self.sync_column_collectors();
     }
 
-    /// Check if input event requires a redraw
-    fn needs_redraw_after_input(&self, event: &InputEvent) -> bool {
-        match event {
-            InputEvent::Quit => false, // No need to redraw before quitting
-            _ => true, // All other inputs affect display
+    /// Measure per-process throughput only while the NET column is shown or sorted on, and
+    /// PSS/USS only while one of theirs is
+    fn sync_column_collectors(&mut self) {
+        let shown = |keys: &[SortKey]| {
+            keys.contains(&self.current_sort) || self.visible_columns.iter().any(|column| keys.iter().any(|key| key.column() == column))
+        };
+        let wanted = shown(&[SortKey::Net]);
+        if wanted != self.process_network_enabled {
+            self.process_network_enabled = wanted;
+            self.send_command(CollectorCommand::SetProcessNetworkEnabled(wanted));
+        }
+        let wanted = shown(&[SortKey::Pss, SortKey::Uss]);
+        if wanted != self.memory_breakdown_enabled {
+            self.memory_breakdown_enabled = wanted;
+            self.send_command(CollectorCommand::SetMemoryBreakdownEnabled(wanted));
         }
     }
 
-    /// Render the UI
-    fn render<W: std::io::Write>(&mut self, writer: &mut W) -> anyhow::Result<()> {
-        // Clear screen only once during setup, then just move cursor
-        crossterm::queue!(
-            writer,
-            crossterm::cursor::MoveTo(0, 0),
-        )?;
-
-        let terminal_rect = self.layout.terminal_rect();
-        let main_layout = self.layout.main_layout();
-
-        if let Some(snapshot) = &self.current_snapshot {
-            // Render top bar
-            let top_bar = crate::ui::TopBar;
-            top_bar.render(writer, main_layout.top_bar, &snapshot.system, &self.colors)?;
-
-            // Render gauges
-            let gauges_layout = self.layout.gauges_layout(main_layout.gauges);
-            
-            let cpu_gauges = crate::ui::CpuGauges;
-            cpu_gauges.render(writer, gauges_layout.cpu, &snapshot.cpu_cores, &self.colors)?;
-            
-            let memory_gauges = crate::ui::MemoryGauges;
-            memory_gauges.render(writer, gauges_layout.memory, &snapshot.memory, &self.colors)?;
-
-            // Render process table
-            let filtered_processes = self.get_filtered_sorted_processes(&snapshot.processes);
-            let columns: Vec<&str> = self.visible_columns.iter().map(|s| s.as_str()).collect();
-            
-            let process_table = crate::ui::ProcessTable;
-            process_table.render(
-                writer,
-                main_layout.table,
-                &filtered_processes,
-                &columns,
-                self.selected_process_index,
-                self.table_start_index,
-                &self.colors,
-            )?;
-
-            // Render network section
-            let network_gauges = crate::ui::NetworkGauges;
-            network_gauges.render(writer, main_layout.network, &snapshot.networks, &self.colors)?;
-
-            // Render temperature section
-            let temperature_gauge = crate::ui::TemperatureGauge;
-            temperature_gauge.render(writer, main_layout.temperature, &snapshot.temperatures, &self.colors)?;
+    /// Handle input while the process detail view is open
+    fn handle_detail_event(&mut self, event: InputEvent) {
+        let Some(pid) = self.process_detail.as_ref().map(|detail| detail.process.pid) else {
+            return;
+        };
+        let leak = self.leak_suspect(pid).copied();
+        let Some(detail) = &mut self.process_detail else {
+            return;
+        };
+        
+        let max_scroll = crate::ui::ProcessDetailView::line_count(&detail.process, detail.details.as_ref(), leak.as_ref());
+        match event {
+            InputEvent::Select | InputEvent::ClearFilter => self.process_detail = None,
+            InputEvent::KillProcess => {
+                let (pid, name) = (detail.process.pid, detail.process.name.clone());
+                self.open_kill_dialog(pid, &name);
+            },
+            InputEvent::MoveUp => detail.scroll = detail.scroll.saturating_sub(1),
+            InputEvent::MoveDown => detail.scroll = (detail.scroll + 1).min(max_scroll),
+            InputEvent::PageUp => detail.scroll = detail.scroll.saturating_sub(10),
+            InputEvent::PageDown => detail.scroll = (detail.scroll + 10).min(max_scroll),
+            InputEvent::Home => detail.scroll = 0,
+            InputEvent::End => detail.scroll = max_scroll,
+            _ => {}
         }
+    }
 
-        // Render footer
-        let footer = crate::ui::Footer;
-        footer.render(writer, main_layout.footer, &self.colors)?;
-
-        // Render help overlay if shown
-        if self.show_help {
-            let help = crate::ui::HelpOverlay;
-            help.render(writer, terminal_rect, &self.colors)?;
+    /// Open the signal picker for a process
+    fn open_kill_dialog(&mut self, pid: u32, name: &str) {
+        if self.replay.is_some() {
+            self.show_status("Not available while viewing a saved file".to_string(), false);
+            return;
         }
-
-        // Show filter text if in filter mode
-        if self.input_handler.is_in_filter_mode() {
-            self.render_filter_prompt(writer, main_layout.footer)?;
+        if self.demo {
+            self.show_status("Not available in demo mode".to_string(), false);
+            return;
         }
-
-        writer.flush()?;
-        self.render_count += 1;
-        Ok(())
-    }
-
-    /// Render filter prompt
-    fn render_filter_prompt<W: std::io::Write>(
-        &self,
-        writer: &mut W,
-        area: crate::ui::Rect,
-    ) -> anyhow::Result<()> {
-        use crossterm::{cursor, style::Print, QueueableCommand};
-        
-        writer.queue(cursor::MoveTo(area.x, area.y))?;
-        writer.queue(Print(format!("Filter: {}_", self.filter_text)))?;
-        Ok(())
+        let options = KillSignal::ALL.iter().map(|s| s.name().to_string()).collect();
+        self.dialog = Some((
+            Dialog::select(format!("Send signal to {} ({})", name, pid), options, 0),
+            DialogPurpose::PickSignal { pid, name: name.to_string() },
+        ));
     }
 
-    /// Get filtered processes
-    fn get_filtered_processes(&self, processes: &[kacemon_core::ProcessInfo]) -> Vec<kacemon_core::ProcessInfo> {
-        if self.filter_text.is_empty() {
-            processes.to_vec()
-        } else {
-            let filter_lower = self.filter_text.to_lowercase();
-            processes
-                .iter()
-                .filter(|p| {
-                    p.name.to_lowercase().contains(&filter_lower)
-                        || p.cmd.join(" ").to_lowercase().contains(&filter_lower)
-                        || p.user.to_lowercase().contains(&filter_lower)
-                        || p.pid.to_string().contains(&filter_lower)
-                })
-                .cloned()
-                .collect()
+    /// Offer the parents spawning the most children for a signal
+    fn open_spawners_dialog(&mut self) {
+        let spawners = self.alert_tracker.spawners().to_vec();
+        if spawners.is_empty() {
+            return;
         }
+        let options = spawners
+            .iter()
+            .map(|s| format!("{} ({}): +{} children, {} total", s.name, s.pid, s.new_children, s.children))
+            .collect();
+        self.dialog = Some((
+            Dialog::select("Runaway spawning: signal a parent?", options, 0),
+            DialogPurpose::PickSpawner(spawners),
+        ));
     }
 
-    /// Get filtered and sorted processes
-    fn get_filtered_sorted_processes(&self, processes: &[kacemon_core::ProcessInfo]) -> Vec<kacemon_core::ProcessInfo> {
-        let mut filtered = self.get_filtered_processes(processes);
-        self.sort_processes(&mut filtered);
-        filtered
+    fn open_presets_dialog(&mut self) {
+        if self.config.presets.is_empty() {
+            self.show_status("No view presets configured (add \"presets\" to the config)".to_string(), false);
+            return;
+        }
+        let options = self
+            .config
+            .presets
+            .iter()
+            .enumerate()
+            .map(|(i, preset)| {
+                let key = if i < 12 { format!("F{:<3}", i + 1) } else { "    ".to_string() };
+                match preset.filter.as_str() {
+                    "" => format!("{} {}", key, preset.name),
+                    filter => format!("{} {}  {}", key, preset.name, filter),
+                }
+            })
+            .collect();
+        self.dialog = Some((
+            Dialog::select("View presets", options, self.active_preset.unwrap_or(0)),
+            DialogPurpose::PickPreset,
+        ));
     }
 
-    /// Sort processes according to current sort settings
-    fn sort_processes(&self, processes: &mut [kacemon_core::ProcessInfo]) {
-        match self.current_sort {
-            SortKey::Cpu => {
-                processes.sort_by(|a, b| {
-                    if self.sort_reverse {
-                        b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        a.cpu_percent.partial_cmp(&b.cpu_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
+    /// Switch the process table to a preset's filter, sort and columns
+    fn apply_preset(&mut self, index: usize) {
+        let Some(preset) = self.config.presets.get(index).cloned() else {
+            let message = match self.config.presets.len() {
+                0 => "No view presets configured (add \"presets\" to the config)".to_string(),
+                n => format!("No preset {}: {} configured", index + 1, n),
+            };
+            self.show_status(message, false);
+            return;
+        };
+        
+        (self.current_sort, self.sort_reverse) = preset.sort_order(self.current_sort, self.sort_reverse);
+        self.filter_text = preset.filter;
+        if let Some(columns) = preset.columns {
+            self.visible_columns = columns;
+        }
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        self.active_preset = Some(index);
+        self.show_status(format!("Preset: {}", preset.name), false);
+    }
+
+    /// Feed input to the open dialog and act on its answer
+    fn handle_dialog_event(&mut self, event: InputEvent) {
+        let Some((dialog, purpose)) = &mut self.dialog else {
+            return;
+        };
+        
+        let outcome = dialog.handle_event(&event);
+        match (&outcome, purpose) {
+            (DialogOutcome::Pending, _) => return,
+            (DialogOutcome::Edited(text), DialogPurpose::Filter) => {
+                // Filter live while typing, saying what's wrong with the query so far
+                dialog.message = text.parse::<ProcessFilter>().err();
+                self.filter_text = text.clone();
+                return;
+            },
+            (DialogOutcome::Edited(_), _) => return,
+            _ => {}
+        }
+        
+        let Some((_, purpose)) = self.dialog.take() else {
+            return;
+        };
+        match (purpose, outcome) {
+            (DialogPurpose::PickSignal { pid, name }, DialogOutcome::Selected(index)) => {
+                let signal = KillSignal::ALL[index];
+                self.dialog = Some((
+                    Dialog::confirm("Confirm", format!("Send {} to {} ({})?", signal.name(), name, pid)),
+                    DialogPurpose::ConfirmSignal { pid, name, signal },
+                ));
+            },
+            (DialogPurpose::ConfirmSignal { pid, name, signal }, DialogOutcome::Confirmed) => {
+                self.send_signal(pid, &name, signal);
+            },
+            (DialogPurpose::PickSpawner(spawners), DialogOutcome::Selected(index)) => {
+                let spawner = &spawners[index];
+                self.open_kill_dialog(spawner.pid, &spawner.name);
+            },
+            (DialogPurpose::PickServiceAction { unit }, DialogOutcome::Selected(index)) => {
+                let action = ServiceAction::ALL[index];
+                self.dialog = Some((
+                    Dialog::confirm("Confirm", format!("{} {}?", capitalize(action.verb()), unit)),
+                    DialogPurpose::ConfirmServiceAction { unit, action },
+                ));
+            },
+            (DialogPurpose::ConfirmServiceAction { unit, action }, DialogOutcome::Confirmed) => {
+                self.send_command(CollectorCommand::ControlService { unit, action });
+                self.send_command(CollectorCommand::RefreshNow);
+            },
+            (DialogPurpose::PickPreset, DialogOutcome::Selected(index)) => self.apply_preset(index),
+            (DialogPurpose::PickUser(mut users), DialogOutcome::Selected(index)) => {
+                self.set_user_filter(users.swap_remove(index));
+            },
+            (DialogPurpose::Filter, DialogOutcome::Submitted(text)) => {
+                if let Err(e) = text.parse::<ProcessFilter>() {
+                    self.show_status(format!("Filter: {} (matching as plain text)", e), true);
+                }
+                self.filter_text = text;
+                self.input_handler.exit_filter_mode();
+            },
+            (DialogPurpose::Filter, _) => {
+                self.filter_text.clear();
+                self.input_handler.exit_filter_mode();
+            },
+            _ => {}
+        }
+    }
+
+    /// Send a signal; the outcome comes back as a `KillResult` message
+    fn send_signal(&mut self, pid: u32, name: &str, signal: KillSignal) {
+        self.send_command(CollectorCommand::Kill {
+            pid,
+            name: name.to_string(),
+            signal,
+        });
+        self.send_command(CollectorCommand::RefreshNow);
+    }
+
+    /// Show a transient message in the footer
+    fn show_status(&mut self, text: String, is_error: bool) {
+        if is_error && self.config.osc_notifications {
+            self.pending_notifications.push(text.clone());
+        }
+        self.status_message = Some(StatusMessage {
+            text,
+            is_error,
+            shown_at: Instant::now(),
+        });
+        self.dirty = true;
+    }
+
+    /// Update the terminal title and send OSC 9 alerts, when enabled
+    fn queue_terminal_integration<W: std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        if self.config.terminal_title {
+            if let Some(title) = self.viewed_snapshot().map(title_summary) {
+                if self.last_title.as_ref() != Some(&title) {
+                    crossterm::queue!(writer, crossterm::terminal::SetTitle(&title))?;
+                    self.last_title = Some(title);
+                }
             }
-            SortKey::Memory => {
-                processes.sort_by(|a, b| {
-                    if self.sort_reverse {
-                        b.memory_percent.partial_cmp(&a.memory_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    } else {
-                        a.memory_percent.partial_cmp(&b.memory_percent).unwrap_or(std::cmp::Ordering::Equal)
-                    }
-                });
+        }
+        
+        if self.config.osc_notifications {
+            for text in self.pending_notifications.drain(..) {
+                crossterm::queue!(writer, crate::osc::Notify(&format!("kacemon: {}", text)))?;
             }
-            SortKey::Pid => {
-                processes.sort_by(|a, b| {
-                    if self.sort_reverse {
-                        b.pid.cmp(&a.pid)
-                    } else {
-                        a.pid.cmp(&b.pid)
-                    }
-                });
+            
+            // Flag the tab / taskbar red while an alert is on screen
+            let alert_shown = self.status_message.as_ref().is_some_and(|m| m.is_error);
+            if alert_shown != self.alert_progress_shown {
+                let progress = if alert_shown { crate::osc::Progress::Error(100) } else { crate::osc::Progress::Clear };
+                crossterm::queue!(writer, progress)?;
+                self.alert_progress_shown = alert_shown;
             }
-            SortKey::Name => {
-                processes.sort_by(|a, b| {
-                    if self.sort_reverse {
-                        b.name.cmp(&a.name)
-                    } else {
-                        a.name.cmp(&b.name)
-                    }
+        }
+        
+        Ok(())
+    }
+
+    /// Re-check the alert thresholds and announce conditions that just started
+    fn update_alerts(&mut self, snapshot: &SystemSnapshot) {
+        let alerts = self.alert_tracker.evaluate(&self.config.alerts, snapshot);
+        let new_alerts: Vec<String> = alerts
+            .iter()
+            .filter(|a| !self.active_alerts.iter().any(|b| a.same_condition(b)))
+            .map(Alert::message)
+            .collect();
+        
+        // Critical ones also reach the desktop in case the terminal is out of sight
+        if self.config.desktop_notifications {
+            let hostname = &snapshot.system.hostname;
+            for alert in alerts.iter().filter(|a| a.critical && !self.active_alerts.iter().any(|b| a.same_condition(b))) {
+                crate::notify::desktop_notify(format!("kacemon: critical alert on {}", hostname), alert.message());
+            }
+        }
+        
+        // A fork bomb leaves little time to go hunting for its parent
+        let spawning = |alerts: &[Alert]| alerts.iter().any(|a| a.kind == AlertKind::Spawning);
+        let spawning_started = spawning(&alerts) && !spawning(&self.active_alerts);
+        
+        // One footer line for the lot; the table highlight shows which processes
+        match new_alerts.as_slice() {
+            [] => {},
+            [message] => self.show_status(format!("ALERT: {}", message), true),
+            [first, rest @ ..] => self.show_status(format!("ALERT: {} (+{} more)", first, rest.len()), true),
+        }
+        
+        // Commands and webhooks run in the background; their failures show up a snapshot later
+        self.alert_dispatcher.dispatch(&snapshot.system.hostname, &alerts);
+        if let Some(failure) = self.alert_dispatcher.failures().pop() {
+            self.show_status(failure, true);
+        }
+        
+        if self.active_alerts.is_empty() && !alerts.is_empty() {
+            self.alert_flash_on = true;
+            self.alert_flash_at = Instant::now();
+        }
+        self.active_alerts = alerts;
+        if spawning_started && self.dialog.is_none() && self.viewed_host.is_none() {
+            self.open_spawners_dialog();
+        }
+    }
+
+    /// Blink alert highlights on and off while any threshold is exceeded
+    fn tick_alert_flash(&mut self) {
+        if !self.viewed_alerts().is_empty() && self.alert_flash_at.elapsed() >= ALERT_FLASH_INTERVAL {
+            self.alert_flash_on = !self.alert_flash_on;
+            self.alert_flash_at = Instant::now();
+            if self.copy_mode.is_none() {
+                self.dirty = true;
+            }
+        }
+    }
+
+    fn gauge_animation_time(&self) -> Duration {
+        self.config.refresh_interval().min(GAUGE_ANIMATION_MAX)
+    }
+
+    /// Still gliding from the previous sample to the latest one
+    fn gauges_animating(&self) -> bool {
+        self.gauges_from.is_some() && self.gauges_changed_at.elapsed() < self.gauge_animation_time()
+    }
+
+    /// CPU cores and memory as the gauges should show them right now: the snapshot's values,
+    /// or part way there from the previous sample while animating
+    fn displayed_gauges(&self, snapshot: &SystemSnapshot) -> (Vec<CpuCore>, MemoryInfo) {
+        let mut cores = snapshot.cpu_cores.clone();
+        let mut memory = snapshot.memory.clone();
+        let Some((from_cores, from_memory)) = self.gauges_from.as_ref().filter(|_| self.gauges_animating()) else {
+            return (cores, memory);
+        };
+        
+        let t = self.gauges_changed_at.elapsed().as_secs_f32() / self.gauge_animation_time().as_secs_f32().max(0.001);
+        let lerp = |from: f32, to: f32| from + (to - from) * t.min(1.0);
+        let lerp_bytes = |from: u64, to: u64| lerp(from as f32, to as f32).max(0.0) as u64;
+        // A hotplugged CPU changes the layout; just jump to the new values
+        if from_cores.len() == cores.len() {
+            for (core, from) in cores.iter_mut().zip(from_cores) {
+                core.usage_percent = lerp(from.usage_percent, core.usage_percent);
+            }
+        }
+        memory.used = lerp_bytes(from_memory.used, memory.used);
+        memory.available = lerp_bytes(from_memory.available, memory.available);
+        memory.swap_used = lerp_bytes(from_memory.swap_used, memory.swap_used);
+        (cores, memory)
+    }
+
+    /// Whether a gauge for `kind` should currently be drawn highlighted
+    fn alert_highlighted(&self, kind: AlertKind) -> bool {
+        self.alert_flash_on && self.viewed_alerts().iter().any(|a| a.kind == kind)
+    }
+
+    /// Drop the status message once it has been shown long enough
+    fn expire_status_message(&mut self) {
+        if self.status_message.as_ref().is_some_and(|m| m.shown_at.elapsed() >= STATUS_MESSAGE_DURATION) {
+            self.status_message = None;
+            self.dirty = true;
+        }
+    }
+
+    /// Write the filtered, sorted process list with every field to a JSON file
+    fn export_processes_json(&mut self) {
+        let Some(snapshot) = self.viewed_snapshot() else {
+            return;
+        };
+        
+        let mut rows: Vec<TableRow> = self.matching_rows(&snapshot.processes).into_iter().map(TableRow::Process).collect();
+        self.sort_rows(&snapshot.processes, &mut rows);
+        let processes: Vec<&ProcessInfo> = rows.iter().map(|row| row.process(&snapshot.processes)).collect();
+        match kacemon_core::export::write_processes_json(&self.config.export_dir(), &processes) {
+            Ok(path) => self.show_status(format!("Exported {} processes to {}", processes.len(), path.display()), false),
+            Err(e) => self.show_status(format!("Export failed: {}", e), true),
+        }
+    }
+
+    /// Save the viewed snapshot with the table's filters, sort and the alerts, so someone
+    /// else can open exactly this screen
+    fn export_bundle(&mut self) {
+        let Some(snapshot) = self.viewed_snapshot().cloned() else {
+            return;
+        };
+        
+        let view = BundleView {
+            filter: self.filter_text.clone(),
+            sort: self.current_sort,
+            reverse: self.sort_reverse,
+            category: self.category_filter.clone(),
+            user: self.user_filter.clone(),
+            terminal: match &self.terminal_filter {
+                TerminalFilter::Only(tty) => Some(tty.clone()),
+                TerminalFilter::All | TerminalFilter::Detached => None,
+            },
+            detached_only: self.terminal_filter == TerminalFilter::Detached,
+            disk_sleep_only: self.disk_sleep_filter,
+            leaks_only: self.leak_filter,
+            hide_kernel_threads: self.hide_kernel_threads,
+            tree_view: self.tree_view,
+            group_by_name: self.grouping == ProcessGrouping::Name,
+            grouping: self.grouping,
+            columns: self.visible_columns.clone(),
+            selected_pid: self.selected_process().map(|p| p.pid),
+        };
+        let bundle = Bundle::new(snapshot, view, self.viewed_alerts().to_vec(), self.leak_suspects())
+            .with_interval(self.viewed_interval());
+        match bundle.write(&self.config.export_dir()) {
+            Ok(path) => self.show_status(format!("Saved bundle to {}", path.display()), false),
+            Err(e) => self.show_status(format!("Bundle export failed: {}", e), true),
+        }
+    }
+
+    /// Snapshot the focused table as plain text
+    fn enter_copy_mode(&mut self) {
+        let Some(snapshot) = self.viewed_snapshot() else {
+            return;
+        };
+        
+        let (text, scroll) = match self.focused_panel {
+            Panel::Processes => {
+                let order = self.process_order();
+                let processes: Vec<&ProcessInfo> = order.rows.iter().map(|row| row.process(&snapshot.processes)).collect();
+                let columns: Vec<&str> = self.visible_columns.iter().map(|s| s.as_str()).collect();
+                (CopyText::processes(&processes, &columns), self.table_start_index)
+            },
+            Panel::Disks => (CopyText::disks(&snapshot.disks, self.viewed_interval()), 0),
+            Panel::Network => (CopyText::networks(&snapshot.networks, self.viewed_interval()), 0),
+        };
+        self.copy_mode = Some(CopyMode { text, scroll });
+    }
+
+    /// Scroll or leave copy mode
+    fn handle_copy_mode_event(&mut self, event: InputEvent) {
+        let page = self.layout.terminal_rect().height.saturating_sub(2).max(1) as usize;
+        let Some(copy) = &mut self.copy_mode else {
+            return;
+        };
+        
+        let last = copy.text.lines.len().saturating_sub(1);
+        match event {
+            InputEvent::ToggleCopyMode | InputEvent::ClearFilter | InputEvent::Select => self.copy_mode = None,
+            InputEvent::MoveUp => copy.scroll = copy.scroll.saturating_sub(1),
+            InputEvent::MoveDown => copy.scroll = (copy.scroll + 1).min(last),
+            InputEvent::PageUp => copy.scroll = copy.scroll.saturating_sub(page),
+            InputEvent::PageDown => copy.scroll = (copy.scroll + page).min(last),
+            InputEvent::Home => copy.scroll = 0,
+            InputEvent::End => copy.scroll = last.saturating_sub(page.saturating_sub(1)),
+            _ => {}
+        }
+    }
+
+    /// Handle navigation while the disk panel has focus
+    fn handle_disk_panel_event(&mut self, event: InputEvent) {
+        let disk_count = self.viewed_snapshot().map(|s| s.disks.len()).unwrap_or(0);
+        match event {
+            InputEvent::MoveUp => self.selected_disk = self.selected_disk.saturating_sub(1),
+            InputEvent::MoveDown => self.selected_disk = (self.selected_disk + 1).min(disk_count.saturating_sub(1)),
+            InputEvent::Home => self.selected_disk = 0,
+            InputEvent::End => self.selected_disk = disk_count.saturating_sub(1),
+            InputEvent::Select => {
+                self.disk_detail = self.current_snapshot
+                    .as_ref()
+                    .and_then(|s| s.disks.get(self.selected_disk))
+                    .map(|d| d.name.clone());
+            },
+            InputEvent::ClearFilter => self.focused_panel = Panel::Processes,
+            _ => {}
+        }
+    }
+
+    /// Handle navigation while the network panel has focus
+    fn handle_network_panel_event(&mut self, event: InputEvent) {
+        let Some(snapshot) = self.viewed_snapshot() else {
+            return;
+        };
+        
+        let names: Vec<String> = sorted_interfaces(&snapshot.networks)
+            .into_iter()
+            .map(|n| n.interface_name.clone())
+            .collect();
+        if names.is_empty() {
+            return;
+        }
+        let current = self.selected_interface
+            .as_ref()
+            .and_then(|name| names.iter().position(|n| n == name));
+        
+        match event {
+            InputEvent::MoveUp => {
+                let index = current.map(|i| i.saturating_sub(1)).unwrap_or(0);
+                self.selected_interface = Some(names[index].clone());
+            },
+            InputEvent::MoveDown => {
+                let index = current.map(|i| (i + 1).min(names.len() - 1)).unwrap_or(0);
+                self.selected_interface = Some(names[index].clone());
+            },
+            InputEvent::Home => self.selected_interface = names.first().cloned(),
+            InputEvent::End => self.selected_interface = names.last().cloned(),
+            InputEvent::Select => {
+                let name = names[current.unwrap_or(0)].clone();
+                self.network_detail = Some(NetworkDetail {
+                    details: if self.replay.is_some() || self.demo { InterfaceDetails::default() } else { interface_details(&name) },
+                    interface_name: name,
                 });
+            },
+            InputEvent::ClearFilter => self.focused_panel = Panel::Processes,
+            _ => {}
+        }
+    }
+
+    /// Open the detail view for the selected process
+    fn open_process_detail(&mut self) {
+        let Some(mut process) = self.selected_process() else {
+            return;
+        };
+        
+        // A saved file's processes only have what was recorded
+        let details = if self.replay.is_some() {
+            None
+        } else {
+            complete_process(&mut process);
+            self.platform.get_process_details(process.pid).ok()
+        };
+        self.process_detail = Some(ProcessDetail {
+            process,
+            details,
+            scroll: 0,
+        });
+    }
+
+    /// List the selected process's threads
+    fn open_thread_view(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        
+        let mut view = ThreadView {
+            name: process.name,
+            collector: ThreadCollector::new(process.pid),
+            threads: Vec::new(),
+            selected: 0,
+            exited: false,
+        };
+        match view.collector.collect() {
+            Ok(threads) => {
+                view.threads = threads;
+                self.thread_view = Some(view);
+            },
+            Err(e) => self.show_status(e.to_string(), true),
+        }
+    }
+
+    /// Take a fresh per-thread CPU sample, busiest first; an exited process keeps its last list
+    fn refresh_thread_view(&mut self) {
+        let Some(view) = &mut self.thread_view else {
+            return;
+        };
+        
+        match view.collector.collect() {
+            Ok(mut threads) => {
+                threads.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent).then(a.tid.cmp(&b.tid)));
+                view.selected = view.selected.min(threads.len().saturating_sub(1));
+                view.threads = threads;
+            },
+            Err(_) => view.exited = true,
+        }
+    }
+
+    fn handle_thread_event(&mut self, event: InputEvent) {
+        let Some(view) = &mut self.thread_view else {
+            return;
+        };
+        
+        let last = view.threads.len().saturating_sub(1);
+        match event {
+            InputEvent::ShowThreads | InputEvent::ClearFilter => self.thread_view = None,
+            InputEvent::MoveUp => view.selected = view.selected.saturating_sub(1),
+            InputEvent::MoveDown => view.selected = (view.selected + 1).min(last),
+            InputEvent::PageUp => view.selected = view.selected.saturating_sub(10),
+            InputEvent::PageDown => view.selected = (view.selected + 10).min(last),
+            InputEvent::Home => view.selected = 0,
+            InputEvent::End => view.selected = last,
+            _ => {}
+        }
+    }
+
+    /// List systemd's service units; the collector asks for them only while this is open
+    fn open_services_view(&mut self) {
+        if !cfg!(target_os = "linux") {
+            self.show_status("systemd services are only available on Linux".to_string(), false);
+            return;
+        }
+        self.services_view = Some(ServicesView { selected: 0 });
+        self.send_command(CollectorCommand::SetServicesEnabled(true));
+        self.send_command(CollectorCommand::RefreshNow);
+    }
+
+    fn close_services_view(&mut self) {
+        self.services_view = None;
+        self.send_command(CollectorCommand::SetServicesEnabled(false));
+    }
+
+    /// Units shown in the services view
+    fn services(&self) -> &[ServiceInfo] {
+        self.current_snapshot.as_ref().map_or(&[], |snapshot| snapshot.services.as_slice())
+    }
+
+    fn handle_services_event(&mut self, event: InputEvent) {
+        let units = self.services().len();
+        let Some(view) = &mut self.services_view else {
+            return;
+        };
+        
+        let last = units.saturating_sub(1);
+        view.selected = view.selected.min(last);
+        match event {
+            InputEvent::ShowServices | InputEvent::ClearFilter => self.close_services_view(),
+            InputEvent::MoveUp => view.selected = view.selected.saturating_sub(1),
+            InputEvent::MoveDown => view.selected = (view.selected + 1).min(last),
+            InputEvent::PageUp => view.selected = view.selected.saturating_sub(10),
+            InputEvent::PageDown => view.selected = (view.selected + 10).min(last),
+            InputEvent::Home => view.selected = 0,
+            InputEvent::End => view.selected = last,
+            InputEvent::Select => {
+                let selected = view.selected;
+                if let Some(unit) = self.services().get(selected).map(|unit| unit.name.clone()) {
+                    let options = ServiceAction::ALL.iter().map(|action| capitalize(action.verb())).collect();
+                    self.dialog = Some((Dialog::select(unit.clone(), options, 0), DialogPurpose::PickServiceAction { unit }));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Follow the system log's errors; the collector keeps the log tool running only while
+    /// this is open
+    fn open_log_view(&mut self) {
+        if !cfg!(any(target_os = "linux", target_os = "macos")) {
+            self.show_status("The system log feed is only available on Linux and macOS".to_string(), false);
+            return;
+        }
+        self.log_view = Some(LogView { scroll: 0 });
+        self.send_command(CollectorCommand::SetLogFeedEnabled(true));
+        self.send_command(CollectorCommand::RefreshNow);
+    }
+
+    fn close_log_view(&mut self) {
+        self.log_view = None;
+        self.send_command(CollectorCommand::SetLogFeedEnabled(false));
+    }
+
+    fn handle_log_event(&mut self, event: InputEvent) {
+        let entries = self.current_snapshot.as_ref().map_or(0, |snapshot| snapshot.log_entries.len());
+        let Some(view) = &mut self.log_view else {
+            return;
+        };
+        
+        let last = entries.saturating_sub(1);
+        view.scroll = view.scroll.min(last);
+        match event {
+            InputEvent::ShowLogFeed | InputEvent::ClearFilter => self.close_log_view(),
+            InputEvent::MoveUp => view.scroll = view.scroll.saturating_sub(1),
+            InputEvent::MoveDown => view.scroll = (view.scroll + 1).min(last),
+            InputEvent::PageUp => view.scroll = view.scroll.saturating_sub(10),
+            InputEvent::PageDown => view.scroll = (view.scroll + 10).min(last),
+            InputEvent::Home => view.scroll = 0,
+            InputEvent::End => view.scroll = last,
+            _ => {}
+        }
+    }
+
+    /// Re-read details for the process shown in the detail view
+    fn refresh_process_detail(&mut self) {
+        let Some(detail) = &mut self.process_detail else {
+            return;
+        };
+        
+        // Keep showing the last known state if the process has exited
+        if let Some(snapshot) = &self.current_snapshot {
+            if let Some(process) = snapshot.processes.iter().find(|p| p.pid == detail.process.pid) {
+                detail.process = process.clone();
+                if self.replay.is_none() {
+                    complete_process(&mut detail.process);
+                    detail.details = self.platform.get_process_details(process.pid).ok();
+                }
+            }
+        }
+    }
+
+    /// Which set of key bindings currently applies
+    fn key_context(&self) -> KeyContext {
+        if self.dialog.is_some() {
+            KeyContext::Dialog
+        } else if self.copy_mode.is_some() {
+            KeyContext::CopyMode
+        } else if self.process_detail.is_some() {
+            KeyContext::ProcessDetail
+        } else if self.thread_view.is_some() {
+            KeyContext::Threads
+        } else if self.services_view.is_some() {
+            KeyContext::Services
+        } else if self.log_view.is_some() {
+            KeyContext::LogFeed
+        } else if self.device_detail_open() {
+            KeyContext::DeviceDetail
+        } else if self.fleet_view.is_some() {
+            KeyContext::Fleet
+        } else {
+            match self.focused_panel {
+                Panel::Processes => KeyContext::ProcessTable,
+                Panel::Disks => KeyContext::DiskPanel,
+                Panel::Network => KeyContext::NetworkPanel,
+            }
+        }
+    }
+
+    /// Whether a disk, interface, boot or deep scan view is covering the main screen
+    fn device_detail_open(&self) -> bool {
+        self.disk_detail.is_some() || self.network_detail.is_some() || self.boot_detail.is_some() || self.scan_detail.is_some()
+    }
+
+    /// Start a deep scan in the background, sizing the disk selected in the disk panel;
+    /// its results open in a view of their own once it's done
+    fn start_deep_scan(&mut self) {
+        if self.scan_running {
+            self.show_status("Deep scan already running".to_string(), false);
+            return;
+        }
+        let (Some(snapshot), Some(messages)) = (&self.current_snapshot, &self.messages) else {
+            return;
+        };
+        let mount_point = snapshot.disks.get(self.selected_disk).map(|disk| disk.mount_point.clone());
+        spawn_deep_scan(snapshot.disks.clone(), mount_point, messages.clone());
+        self.scan_running = true;
+        self.show_status("Deep scan: starting...".to_string(), false);
+    }
+
+    /// Query systemd for the boot breakdown and show it
+    fn open_boot_detail(&mut self) {
+        self.boot_detail = boot_timing();
+        if self.boot_detail.is_none() {
+            self.show_status("Boot timing needs systemd (busctl could not reach it)".to_string(), false);
+        }
+    }
+
+    /// Show or hide the one-row-per-host overview
+    fn open_fleet_view(&mut self) {
+        if self.remote_hosts.is_empty() {
+            self.show_status("No remote hosts configured (add them under \"hosts\" in the config)".to_string(), false);
+            return;
+        }
+        self.fleet_view = Some(self.viewed_host.map_or(0, |index| index + 1));
+    }
+
+    /// Handle input while the fleet overview is open
+    fn handle_fleet_event(&mut self, event: InputEvent) {
+        let Some(selected) = self.fleet_view else {
+            return;
+        };
+        
+        let last = self.remote_hosts.len();
+        match event {
+            InputEvent::MoveUp => self.fleet_view = Some(selected.saturating_sub(1)),
+            InputEvent::MoveDown => self.fleet_view = Some((selected + 1).min(last)),
+            InputEvent::Home => self.fleet_view = Some(0),
+            InputEvent::End => self.fleet_view = Some(last),
+            InputEvent::Select => {
+                self.fleet_view = None;
+                self.show_host(selected);
+            },
+            InputEvent::ShowHost(index) => {
+                self.fleet_view = None;
+                self.show_host(index);
+            },
+            InputEvent::ToggleFleetView | InputEvent::ClearFilter => self.fleet_view = None,
+            _ => {}
+        }
+    }
+
+    /// Put this machine (0) or a remote host (1..) on the main screen
+    fn show_host(&mut self, index: usize) {
+        let viewed = match index {
+            0 => None,
+            n if n <= self.remote_hosts.len() => Some(n - 1),
+            n => {
+                self.show_status(format!("No host {}: {} remote hosts configured", n, self.remote_hosts.len()), false);
+                return;
+            },
+        };
+        if viewed == self.viewed_host {
+            return;
+        }
+        
+        self.viewed_host = viewed;
+        self.followed = None;
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        self.selected_disk = 0;
+        self.selected_interface = None;
+        self.focused_gpu = 0;
+        self.sync_viewed_layout();
+        let message = match self.viewed_remote() {
+            Some(host) if host.snapshot.is_none() => format!("Viewing {} (waiting for its first snapshot)", host.name),
+            Some(host) => format!("Viewing {} (0: back to this machine)", host.name),
+            None => "Viewing this machine".to_string(),
+        };
+        self.show_status(message, false);
+    }
+
+    /// Size the GPU row for the host on screen
+    fn sync_viewed_layout(&mut self) {
+        let gpu_count = self.viewed_snapshot().map_or(0, |s| s.gpus.len());
+        self.layout.set_gpu_count(gpu_count);
+        self.focused_gpu = self.focused_gpu.min(gpu_count.saturating_sub(1));
+    }
+
+    /// The remote host on the main screen, if it isn't showing this machine
+    fn viewed_remote(&self) -> Option<&RemoteHostState> {
+        self.remote_hosts.get(self.viewed_host?)
+    }
+
+    /// Latest snapshot of the host on the main screen
+    fn viewed_snapshot(&self) -> Option<&SystemSnapshot> {
+        match self.viewed_remote() {
+            Some(host) => host.snapshot.as_ref(),
+            None => self.current_snapshot.as_ref(),
+        }
+    }
+
+    fn viewed_history(&self) -> &History<SystemSample> {
+        self.viewed_remote().map_or(&self.system_history, |host| &host.history)
+    }
+
+    fn viewed_alerts(&self) -> &[Alert] {
+        self.viewed_remote().map_or(&self.active_alerts, |host| &host.alerts)
+    }
+
+    /// Seconds between the viewed host's last two snapshots
+    fn viewed_interval(&self) -> f64 {
+        self.viewed_remote().map_or(self.snapshot_interval, |host| host.interval)
+    }
+
+    /// This machine first, then every remote host in config order
+    fn fleet_rows(&self) -> Vec<FleetRow<'_>> {
+        let local = FleetRow {
+            name: self.current_snapshot.as_ref().map_or("localhost", |s| s.system.hostname.as_str()),
+            snapshot: self.current_snapshot.as_ref(),
+            alert_count: self.active_alerts.len(),
+            error: None,
+            local: true,
+            age: None,
+            viewed: self.viewed_host.is_none(),
+        };
+        let remotes = self.remote_hosts.iter().enumerate().map(|(index, host)| FleetRow {
+            name: &host.name,
+            snapshot: host.snapshot.as_ref(),
+            alert_count: host.alerts.len(),
+            error: host.error.as_deref(),
+            local: false,
+            age: host.updated_at.map(|at| at.elapsed()),
+            viewed: self.viewed_host == Some(index),
+        });
+        std::iter::once(local).chain(remotes).collect()
+    }
+
+    /// Get the currently selected process
+    fn selected_process(&self) -> Option<ProcessInfo> {
+        let snapshot = self.viewed_snapshot()?;
+        let order = self.process_order();
+        match order.rows.get(self.selected_process_index)? {
+            TableRow::Process(index) => Some(snapshot.processes[*index].clone()),
+            TableRow::Group { .. } => None,
+        }
+    }
+
+    /// Name of the group on the selected row
+    fn selected_group(&self) -> Option<String> {
+        let order = self.process_order();
+        match order.rows.get(self.selected_process_index)? {
+            TableRow::Group { name, .. } => Some(name.clone()),
+            TableRow::Process(_) => None,
+        }
+    }
+
+    /// Show or hide the processes of the selected group below it
+    fn toggle_selected_group(&mut self) {
+        if let Some(name) = self.selected_group() {
+            if !self.expanded_groups.remove(&name) {
+                self.expanded_groups.insert(name);
+            }
+        }
+    }
+
+    /// Step through collapsing processes by name, process group and session, then list them
+    /// all again
+    fn toggle_grouping(&mut self) {
+        self.grouping = match self.grouping {
+            ProcessGrouping::None => ProcessGrouping::Name,
+            ProcessGrouping::Name => ProcessGrouping::ProcessGroup,
+            ProcessGrouping::ProcessGroup => ProcessGrouping::Session,
+            ProcessGrouping::Session => ProcessGrouping::Container,
+            ProcessGrouping::Container => ProcessGrouping::None,
+        };
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        let message = match self.grouping {
+            ProcessGrouping::None => "Showing every process",
+            ProcessGrouping::Name => "Grouping processes by name (Enter expands a group)",
+            ProcessGrouping::ProcessGroup => "Grouping processes by process group (Enter expands a group)",
+            ProcessGrouping::Session => "Grouping processes by session (Enter expands a group)",
+            ProcessGrouping::Container => "Grouping processes by container (Enter expands a group)",
+        };
+        self.show_status(message.to_string(), false);
+    }
+
+    /// Draw the UI into the screen buffer, then send the terminal what changed
+    fn draw_frame<W: std::io::Write>(&mut self, writer: &mut W) -> anyhow::Result<()> {
+        self.sync_followed();
+        let terminal_rect = self.layout.terminal_rect();
+        let mut screen = std::mem::take(&mut self.screen);
+        screen.begin_frame(terminal_rect.width, terminal_rect.height);
+        let rendered = self.render(&mut screen);
+        let presented = if self.config.ascii {
+            screen.present(&mut AsciiWriter::new(&mut *writer))
+        } else {
+            screen.present(writer)
+        };
+        self.screen = screen;
+        rendered?;
+        Ok(presented?)
+    }
+
+    /// Render the UI
+    fn render<W: std::io::Write>(&mut self, writer: &mut W) -> anyhow::Result<()> {
+        // Clear screen only once during setup, then just move cursor
+        crossterm::queue!(
+            writer,
+            crossterm::cursor::MoveTo(0, 0),
+        )?;
+
+        let terminal_rect = self.layout.terminal_rect();
+        let main_layout = self.layout.main_layout();
+        let (table_area, connections_area) = self.table_areas();
+        let pressure_history = self.viewed_history().series(|s| s.pressure);
+        let pressure = self.viewed_history().latest().map(|s| (s.pressure as f32, pressure_history.as_slice()));
+        let alert_count = self.viewed_alerts().len();
+        // Only this machine's counters are read
+        let kernel_rates = if self.viewed_host.is_none() { self.kernel_rates } else { crate::ui::KernelRates::default() };
+        let process_counts = self.viewed_snapshot().map(|s| crate::ui::ProcessCounts::count(&s.processes, kernel_rates));
+
+        if let Some(copy) = &self.copy_mode {
+            // Everything above the footer, no top bar, so whole lines can be selected
+            let area = crate::ui::Rect::new(terminal_rect.x, terminal_rect.y, terminal_rect.width, main_layout.footer.y);
+            let copy_view = crate::ui::CopyView;
+            copy_view.render(writer, area, &copy.text, copy.scroll, &self.colors)?;
+        } else if let Some(selected) = self.fleet_view {
+            if let Some(snapshot) = self.viewed_snapshot() {
+                let top_bar = crate::ui::TopBar;
+                top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            }
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let fleet_view = crate::ui::FleetView;
+            fleet_view.render(writer, area, &self.fleet_rows(), selected, &self.colors)?;
+        } else if let (Some(snapshot), Some(detail)) = (&self.current_snapshot, &self.process_detail) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            // Detail view takes over everything between the top bar and footer
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let detail_view = crate::ui::ProcessDetailView;
+            let leak = self.leak_suspect(detail.process.pid);
+            detail_view.render(writer, area, &detail.process, detail.details.as_ref(), leak, detail.scroll, &self.colors)?;
+        } else if let (Some(snapshot), Some(view)) = (&self.current_snapshot, &self.thread_view) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let thread_list = crate::ui::ThreadListView;
+            thread_list.render(
+                writer,
+                area,
+                view.collector.pid(),
+                &view.name,
+                &view.threads,
+                view.selected,
+                view.exited,
+                &self.colors,
+            )?;
+        } else if let (Some(snapshot), Some(view)) = (&self.current_snapshot, &self.services_view) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let error = snapshot.collector_failure("services").map(|failure| failure.error.as_str());
+            let service_list = crate::ui::ServiceListView;
+            service_list.render(writer, area, &snapshot.services, view.selected, error, &self.colors)?;
+        } else if let (Some(snapshot), Some(view)) = (&self.current_snapshot, &self.log_view) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let error = snapshot.collector_failure("log_feed").map(|failure| failure.error.as_str());
+            let log_feed = crate::ui::LogFeedView;
+            log_feed.render(writer, area, &snapshot.log_entries, view.scroll, error, &self.colors)?;
+        } else if let Some((snapshot, disk)) = self.current_snapshot.as_ref().and_then(|s| {
+            let name = self.disk_detail.as_ref()?;
+            Some((s, s.disks.iter().find(|d| &d.name == name)?))
+        }) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let detail_view = crate::ui::DiskDetailView;
+            detail_view.render(writer, area, disk, &snapshot.disks, self.disk_history.get(&disk.name), &self.colors)?;
+        } else if let Some((snapshot, detail, network)) = self.current_snapshot.as_ref().and_then(|s| {
+            let detail = self.network_detail.as_ref()?;
+            Some((s, detail, s.networks.iter().find(|n| n.interface_name == detail.interface_name)?))
+        }) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let detail_view = crate::ui::NetworkDetailView;
+            detail_view.render(
+                writer,
+                area,
+                network,
+                Some(&detail.details),
+                self.network_history.get(&network.interface_name),
+                &self.colors,
+            )?;
+        } else if let (Some(snapshot), Some(scan)) = (&self.current_snapshot, &self.scan_detail) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let scan_view = crate::ui::DeepScanView;
+            scan_view.render(writer, area, scan, &self.colors)?;
+        } else if let (Some(snapshot), Some(timing)) = (&self.current_snapshot, &self.boot_detail) {
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+            
+            let area = crate::ui::Rect::new(
+                terminal_rect.x,
+                main_layout.top_bar.bottom(),
+                terminal_rect.width,
+                main_layout.footer.y.saturating_sub(main_layout.top_bar.bottom()),
+            );
+            let boot_view = crate::ui::BootTimeView;
+            boot_view.render(writer, area, timing, &self.colors)?;
+        } else if let Some(snapshot) = self.viewed_snapshot() {
+            // Render top bar
+            let top_bar = crate::ui::TopBar;
+            top_bar.render(writer, main_layout.top_bar, &snapshot.system, process_counts.as_ref(), pressure, alert_count, &self.colors)?;
+
+            // Render gauges
+            let gauges_layout = self.layout.gauges_layout(main_layout.gauges);
+            
+            // Remote hosts arrive too rarely to animate
+            let (cpu_cores, memory) = match self.viewed_host {
+                Some(_) => (snapshot.cpu_cores.clone(), snapshot.memory.clone()),
+                None => self.displayed_gauges(snapshot),
+            };
+            let cpu_gauges = crate::ui::CpuGauges;
+            let cpu_history = self.viewed_history().series(|s| s.cpu_percent);
+            cpu_gauges.render(
+                writer,
+                gauges_layout.cpu,
+                &cpu_cores,
+                &cpu_history,
+                snapshot.run_queue.as_ref(),
+                self.config.cpu_frequency,
+                self.gauge_marks(70.0, 90.0, self.config.alerts.cpu_percent),
+                self.alert_highlighted(AlertKind::Cpu),
+                &self.colors,
+            )?;
+            
+            let memory_gauges = crate::ui::MemoryGauges;
+            let memory_history = self.viewed_history().series(|s| s.memory_percent);
+            memory_gauges.render(
+                writer,
+                gauges_layout.memory,
+                &memory,
+                &memory_history,
+                self.viewed_interval(),
+                self.gauge_marks(80.0, 90.0, self.config.alerts.memory_percent),
+                self.alert_highlighted(AlertKind::Memory),
+                &self.colors,
+            )?;
+
+            // GPUs can come and go, so missing samples read as idle
+            let gpu_gauges = crate::ui::GpuGauges;
+            let focused_gpu = self.focused_gpu;
+            let gpu_history = self.viewed_history().series(|s| s.gpu_percent.get(focused_gpu).copied().unwrap_or(0.0));
+            gpu_gauges.render(writer, main_layout.gpu, &snapshot.gpus, self.focused_gpu, &gpu_history, &self.colors)?;
+
+            // Render process table
+            let order = self.process_order();
+            let filtered_processes: Vec<&ProcessInfo> = order.rows.iter().map(|row| row.process(&snapshot.processes)).collect();
+            let totals: crate::ui::TableTotals =
+                order.rows.iter().flat_map(|row| row.members()).map(|&i| &snapshot.processes[i]).collect();
+            let hidden = order.hidden;
+            let columns: Vec<&str> = self.visible_columns.iter().map(|s| s.as_str()).collect();
+            
+            if let Some(area) = self.category_strip_area() {
+                let usage = self.classifier.aggregate(&snapshot.processes);
+                let category_bar = crate::ui::CategoryBar;
+                category_bar.render(writer, area, &usage, self.category_filter.as_deref(), &self.colors)?;
+            }
+            if let Some(area) = self.legend_area() {
+                let cpu = self.gauge_marks(70.0, 90.0, self.config.alerts.cpu_percent);
+                let memory = self.gauge_marks(80.0, 90.0, self.config.alerts.memory_percent);
+                crate::ui::Legend.render(writer, area, cpu, memory, &self.colors)?;
+            }
+            
+            // Offending rows stay marked without blinking so they remain readable
+            let alert_pids: Vec<u32> = self.viewed_alerts().iter().filter_map(|a| a.pid).collect();
+            let mut pid_marks: Vec<(u32, char)> = self.watched.iter().map(|w| (w.pid, '◎')).collect();
+            pid_marks.extend(self.followed.as_ref().map(|followed| (followed.pid, '◉')));
+            // Ranked by match quality rather than a column while fuzzy filtering
+            let filter = self.process_filter();
+            let fuzzy = filter.is_fuzzy().then_some(&filter);
+            let process_table = crate::ui::ProcessTable;
+            process_table.render(
+                writer,
+                table_area,
+                &filtered_processes,
+                &columns,
+                self.selected_process_index,
+                self.table_start_index,
+                fuzzy.is_none().then(|| (self.current_sort.column(), self.sort_reverse)),
+                hidden,
+                &alert_pids,
+                fuzzy,
+                &pid_marks,
+                &totals,
+                &self.colors,
+            )?;
+
+            // Render connections panel
+            if let Some(area) = connections_area {
+                let selected_pid = filtered_processes.get(self.selected_process_index).map(|p| p.pid);
+                let (title, connections): (String, Vec<&kacemon_core::ConnectionInfo>) = match self.connections_view {
+                    ConnectionsView::SelectedProcess => match selected_pid {
+                        Some(pid) => (
+                            format!("SOCKETS for PID {}", pid),
+                            snapshot.connections.iter().filter(|c| c.pid == Some(pid)).collect(),
+                        ),
+                        None => ("SOCKETS (no process selected)".to_string(), Vec::new()),
+                    },
+                    _ => ("SOCKETS (all)".to_string(), snapshot.connections.iter().collect()),
+                };
+
+                let connections_panel = crate::ui::ConnectionsPanel;
+                connections_panel.render(
+                    writer,
+                    area,
+                    &title,
+                    &connections,
+                    &snapshot.processes,
+                    &self.colors,
+                )?;
+            }
+
+            // Render network section
+            let network_gauges = crate::ui::NetworkGauges;
+            let selected_interface = match self.focused_panel {
+                Panel::Network => Some(
+                    self.selected_interface
+                        .as_deref()
+                        .or_else(|| sorted_interfaces(&snapshot.networks).first().map(|n| n.interface_name.as_str()))
+                        .unwrap_or(""),
+                ),
+                _ => None,
+            };
+            let network_history = self.viewed_history().series(|s| s.network_rate);
+            network_gauges.render(
+                writer,
+                main_layout.network,
+                &snapshot.networks,
+                selected_interface,
+                &network_history,
+                self.config.count_virtual_interfaces,
+                &self.colors,
+            )?;
+
+            // Render disk section
+            let disk_panel = crate::ui::DiskPanel;
+            let selected_disk = (self.focused_panel == Panel::Disks).then_some(self.selected_disk);
+            let disk_alert = self.alert_highlighted(AlertKind::Disk) || self.alert_highlighted(AlertKind::NvmeWear);
+            disk_panel.render(
+                writer,
+                main_layout.disks,
+                &snapshot.disks,
+                selected_disk,
+                self.viewed_interval(),
+                self.gauge_marks(80.0, 90.0, self.config.alerts.disk_percent),
+                disk_alert,
+                &self.colors,
+            )?;
+
+            // Render temperature section
+            let temperature_gauge = crate::ui::TemperatureGauge;
+            temperature_gauge.render(
+                writer,
+                main_layout.temperature,
+                &snapshot.temperatures,
+                &self.config.temperature_priority,
+                self.alert_highlighted(AlertKind::Temperature),
+                &self.colors,
+            )?;
+
+            // A failing collector's panel keeps its last good data, with the error over its
+            // separator line, or its bottom row where it has none
+            let failure_note = crate::ui::FailureNote;
+            let placements = [
+                ("disks", Some(main_layout.disks), false),
+                ("networks", Some(main_layout.network), false),
+                ("temperatures", Some(main_layout.temperature), false),
+                ("connections", connections_area, false),
+                ("gpus", Some(main_layout.gpu), true),
+                ("processes", Some(table_area), true),
+                ("process_network", Some(table_area), true),
+                ("pressure", Some(gauges_layout.cpu), true),
+                ("run_queue", Some(gauges_layout.cpu), true),
+            ];
+            for (collector, area, bottom) in placements {
+                let (Some(failure), Some(area)) = (snapshot.collector_failure(collector), area) else {
+                    continue;
+                };
+                if area.height < if bottom { 1 } else { 2 } {
+                    continue;
+                }
+                let y = if bottom { area.y + area.height - 1 } else { area.y + 1 };
+                let row = crate::ui::Rect::new(area.x, y, area.width, 1);
+                failure_note.render(writer, row, failure, snapshot.timestamp, &self.colors)?;
+            }
+        }
+
+        // Render footer
+        let footer = crate::ui::Footer;
+        let status = self.status_message.as_ref().map(|m| (m.text.as_str(), m.is_error));
+        let badge = match &self.replay {
+            Some(replay) => format!("{} {}/{}", replay.name, replay.index + 1, replay.frames.len()),
+            None if self.demo => format!("demo ⟳ {}", crate::ui::widgets::format_interval(self.config.refresh_interval())),
+            None => format!("⟳ {}", crate::ui::widgets::format_interval(self.config.refresh_interval())),
+        };
+        footer.render(writer, main_layout.footer, status, Some(&badge), &self.colors)?;
+
+        // Render dialog on top of everything but help
+        if let Some((dialog, _)) = &self.dialog {
+            let dialog_widget = crate::ui::DialogWidget;
+            dialog_widget.render(writer, terminal_rect, dialog, &self.colors)?;
+        }
+
+        // Render help overlay if shown
+        if self.show_help {
+            let help = crate::ui::HelpOverlay;
+            help.render(writer, terminal_rect, self.key_context(), self.help_page, &self.colors)?;
+        }
+
+        self.queue_terminal_integration(writer)?;
+
+        writer.flush()?;
+        self.render_count += 1;
+        Ok(())
+    }
+
+    /// Indices of the processes that pass the category, state and text filters
+    fn matching_rows(&self, processes: &[ProcessInfo]) -> Vec<usize> {
+        let filter = self.process_filter();
+        processes
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| self.category_filter.is_none() || p.category == self.category_filter)
+            .filter(|(_, p)| self.user_filter.as_ref().map_or(true, |user| &p.user == user))
+            .filter(|(_, p)| self.terminal_filter.matches(p))
+            .filter(|(_, p)| !self.disk_sleep_filter || p.state == ProcessState::DiskSleep)
+            .filter(|(_, p)| !self.hide_kernel_threads || !p.is_kernel_thread)
+            .filter(|(_, p)| !self.leak_filter || self.leak_suspect(p.pid).is_some())
+            .filter(|(_, p)| filter.matches(p))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Step the category filter through every configured category, then back to all
+    fn cycle_category_filter(&mut self) {
+        let names: Vec<&str> = self.classifier.rules().map(|r| r.name.as_str()).collect();
+        if names.is_empty() {
+            self.show_status("No categories configured".to_string(), false);
+            return;
+        }
+        
+        let next = match &self.category_filter {
+            None => names.first(),
+            Some(current) => names.iter().skip_while(|n| *n != current).nth(1),
+        };
+        self.category_filter = next.map(|n| n.to_string());
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        let label = self.category_filter.clone().unwrap_or_else(|| "all".to_string());
+        self.show_status(format!("Category: {}", label), false);
+    }
+
+    /// Narrow the table to processes owned by whoever runs kacemon, or show everyone's again
+    fn toggle_my_processes(&mut self) {
+        if self.user_filter.is_some() {
+            self.set_user_filter(None);
+            return;
+        }
+        match self.current_user() {
+            Some(user) => self.set_user_filter(Some(user)),
+            None => self.show_status("Can't tell which user kacemon runs as".to_string(), true),
+        }
+    }
+
+    /// Offer every user with a process in the viewed snapshot, busiest first
+    fn open_user_dialog(&mut self) {
+        let processes = self.viewed_snapshot().map_or(&[][..], |s| s.processes.as_slice());
+        let counts = users_by_count(processes, self.config.name_collation);
+        let total: usize = counts.iter().map(|(_, count)| count).sum();
+        let mut options = vec![format!("All users  ({})", total)];
+        let mut users = vec![None];
+        for (user, count) in counts {
+            options.push(format!("{}  ({})", user, count));
+            users.push(Some(user.to_string()));
+        }
+        let selected = users.iter().position(|user| *user == self.user_filter).unwrap_or(0);
+        self.dialog = Some((Dialog::select("Show processes of", options, selected), DialogPurpose::PickUser(users)));
+    }
+
+    fn set_user_filter(&mut self, user: Option<String>) {
+        self.user_filter = user;
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        let message = match &self.user_filter {
+            Some(user) => format!("Processes of {} only (u or Esc to show all)", user),
+            None => "Showing processes of all users".to_string(),
+        };
+        self.show_status(message, false);
+    }
+
+    /// Step the table through what runs on kacemon's own terminal, like `ps T`, what runs on
+    /// no terminal at all, and everything. Where kacemon's terminal isn't known the first
+    /// step is skipped.
+    fn cycle_terminal_filter(&mut self) {
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        self.terminal_filter = match (&self.terminal_filter, self.current_tty()) {
+            (TerminalFilter::All, Some(tty)) => TerminalFilter::Only(tty),
+            (TerminalFilter::All | TerminalFilter::Only(_), _) => TerminalFilter::Detached,
+            (TerminalFilter::Detached, _) => TerminalFilter::All,
+        };
+        let message = match &self.terminal_filter {
+            TerminalFilter::All => "Showing processes on every terminal and none".to_string(),
+            TerminalFilter::Only(tty) => format!("Processes on {} only (a for daemons, Esc to show all)", tty),
+            TerminalFilter::Detached => "Processes without a terminal only (a or Esc to show all)".to_string(),
+        };
+        self.show_status(message, false);
+    }
+
+    /// Controlling terminal of this kacemon process, when the viewed snapshot is this
+    /// machine's live one. Read from /proc rather than our own row, which may be past the
+    /// collection limit and so have no terminal.
+    fn current_tty(&self) -> Option<String> {
+        if self.replay.is_some() || self.demo {
+            return None;
+        }
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        process::tty_name(process::parse_tty_nr(&stat)?)
+    }
+
+    /// Owner of this kacemon process, as the table names users; the environment covers
+    /// snapshots this machine didn't take
+    fn current_user(&self) -> Option<String> {
+        let pid = std::process::id();
+        self.current_snapshot
+            .as_ref()
+            .filter(|_| self.replay.is_none())
+            .and_then(|snapshot| snapshot.processes.iter().find(|p| p.pid == pid))
+            .map(|process| process.user.clone())
+            .or_else(|| std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok())
+    }
+
+    /// Narrow the table to D-state processes, the ones a storage stall leaves behind
+    fn toggle_disk_sleep_filter(&mut self) {
+        self.disk_sleep_filter = !self.disk_sleep_filter;
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        if self.disk_sleep_filter {
+            let blocked = self
+                .viewed_snapshot()
+                .map_or(0, |s| s.processes.iter().filter(|p| p.state == ProcessState::DiskSleep).count());
+            self.show_status(format!("Uninterruptible sleep only: {} processes", blocked), false);
+        } else {
+            self.show_status("Showing all process states".to_string(), false);
+        }
+    }
+
+    /// Narrow the table to processes whose memory has grown steadily for a while
+    fn toggle_leak_filter(&mut self) {
+        self.leak_filter = !self.leak_filter;
+        self.selected_process_index = 0;
+        self.table_start_index = 0;
+        if !self.leak_filter {
+            self.show_status("Showing all processes".to_string(), false);
+            return;
+        }
+        let processes = self.viewed_snapshot().map_or(&[][..], |s| s.processes.as_slice());
+        let fastest = processes
+            .iter()
+            .filter_map(|p| Some((p, self.leak_suspect(p.pid)?)))
+            .max_by(|a, b| a.1.growth_per_min.total_cmp(&b.1.growth_per_min));
+        let message = match fastest {
+            Some((process, leak)) => format!(
+                "Suspected leaks: {} processes, fastest {} ({}) {}",
+                self.leak_suspects().len(),
+                process.name,
+                process.pid,
+                crate::ui::leak_summary(leak)
+            ),
+            // Memory is sampled once a minute and a trend needs ten samples
+            None if self.viewed_host.is_none() => "No suspected leaks (needs 10+ minutes of history)".to_string(),
+            None => "Leak detection covers this machine only".to_string(),
+        };
+        self.show_status(message, false);
+    }
+
+    /// Growth trend of a process on this machine, or as saved in a viewed bundle; remote
+    /// hosts aren't tracked
+    fn leak_suspect(&self, pid: u32) -> Option<&LeakSuspect> {
+        match &self.replay {
+            Some(replay) => replay.frame().leak_suspects.iter().find(|suspect| suspect.pid == pid),
+            None => self.leak_detector.suspect(pid).filter(|_| self.viewed_host.is_none()),
+        }
+    }
+
+    fn leak_suspects(&self) -> Vec<LeakSuspect> {
+        match &self.replay {
+            Some(replay) => replay.frame().leak_suspects.clone(),
+            None if self.viewed_host.is_some() => Vec::new(),
+            None => self.leak_detector.suspects().copied().collect(),
+        }
+    }
+
+    /// Show or hide kernel threads, which crowd the table on Linux without being programs
+    fn toggle_kernel_threads(&mut self) {
+        self.hide_kernel_threads = !self.hide_kernel_threads;
+        let kernel_threads = self.viewed_snapshot().map_or(0, |s| s.processes.iter().filter(|p| p.is_kernel_thread).count());
+        let verb = if self.hide_kernel_threads { "Hiding" } else { "Showing" };
+        self.show_status(format!("{} {} kernel threads", verb, kernel_threads), false);
+    }
+
+    fn order_key(&self) -> OrderKey {
+        OrderKey {
+            host: self.viewed_host,
+            filter_text: self.filter_text.clone(),
+            category_filter: self.category_filter.clone(),
+            user_filter: self.user_filter.clone(),
+            terminal_filter: self.terminal_filter.clone(),
+            disk_sleep_filter: self.disk_sleep_filter,
+            leak_filter: self.leak_filter,
+            hide_kernel_threads: self.hide_kernel_threads,
+            grouping: self.grouping,
+            expanded_groups: if self.grouping == ProcessGrouping::None { BTreeSet::new() } else { self.expanded_groups.clone() },
+            sort: self.current_sort,
+            reverse: self.sort_reverse,
+        }
+    }
+
+    /// The process table's rows: the filtered processes, cut down to the busiest when the
+    /// display limit is exceeded, then sorted. Rebuilt only when something it depends on changed.
+    fn process_order(&self) -> Ref<'_, ProcessOrder> {
+        let key = self.order_key();
+        if self.process_order.borrow().as_ref().map_or(true, |order| order.key != key) {
+            let processes = self.viewed_snapshot().map_or(&[][..], |s| s.processes.as_slice());
+            let mut matching = self.matching_rows(processes);
+            let mut hidden = 0;
+            if let Some(limit) = self.config.process_display_limit {
+                let count = matching.len();
+                matching = sample_busiest(processes, matching, limit);
+                hidden = count - matching.len();
+            }
+            let mut rows = match self.grouping {
+                ProcessGrouping::None => matching.into_iter().map(TableRow::Process).collect(),
+                grouping => group_rows(processes, matching, grouping, &self.expanded_groups),
+            };
+            self.sort_rows(processes, &mut rows);
+            if self.grouping != ProcessGrouping::None {
+                rows = self.expand_groups(processes, rows);
+            }
+            *self.process_order.borrow_mut() = Some(ProcessOrder { key, rows, hidden });
+        }
+        Ref::map(self.process_order.borrow(), |order| order.as_ref().expect("process order just built"))
+    }
+
+    /// Rows in the process table
+    fn process_count(&self) -> usize {
+        self.process_order().rows.len()
+    }
+
+    /// The filter being typed or applied; half-typed queries like "cpu>" still narrow the
+    /// table as plain text
+    fn process_filter(&self) -> ProcessFilter {
+        self.filter_text.parse().unwrap_or_else(|_| ProcessFilter::substring(&self.filter_text))
+    }
+
+    /// Sort process indices according to current sort settings
+    fn sort_rows(&self, processes: &[ProcessInfo], rows: &mut [TableRow]) {
+        let filter = self.process_filter();
+        sort_rows(processes, rows, self.current_sort, self.sort_reverse, self.config.name_collation, &filter);
+    }
+
+    /// Put the processes of each expanded group, sorted the same way, right below it
+    fn expand_groups(&self, processes: &[ProcessInfo], rows: Vec<TableRow>) -> Vec<TableRow> {
+        let mut table = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            let members = match &mut row {
+                TableRow::Group { name, members, .. } if self.expanded_groups.contains(name) => std::mem::take(members),
+                _ => Vec::new(),
+            };
+            table.push(row);
+            let mut members: Vec<TableRow> = members.into_iter().map(TableRow::Process).collect();
+            self.sort_rows(processes, &mut members);
+            table.extend(members);
+        }
+        table
+    }
+
+    /// Sort by the visible column left/right of the current sort column
+    fn move_sort_column(&mut self, delta: isize) {
+        let sortable: Vec<SortKey> = self.visible_columns
+            .iter()
+            .filter_map(|c| SortKey::from_column(c))
+            .collect();
+        if sortable.is_empty() {
+            return;
+        }
+        
+        let next = match sortable.iter().position(|&k| k == self.current_sort) {
+            Some(i) => (i as isize + delta).clamp(0, sortable.len() as isize - 1) as usize,
+            None => 0,
+        };
+        if sortable[next] != self.current_sort {
+            self.current_sort = sortable[next];
+            self.sort_reverse = self.current_sort.default_descending();
+        }
+    }
+
+    /// Pin the selection to the selected process, or let go of it
+    fn toggle_follow(&mut self) {
+        if let Some(followed) = self.followed.take() {
+            self.show_status(format!("Stopped following {} ({})", followed.name, followed.pid), false);
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        self.show_status(format!("Following {} ({}); f or moving the selection lets go", process.name, process.pid), false);
+        self.followed = Some(Followed {
+            pid: process.pid,
+            name: process.name,
+            start_time: process.start_time,
+        });
+    }
+
+    /// Announce when the selected process exits, or stop watching it
+    fn toggle_watch(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        if let Some(index) = self.watched.iter().position(|w| w.pid == process.pid && w.start_time == process.start_time) {
+            self.watched.remove(index);
+            self.show_status(format!("Stopped watching {} ({})", process.name, process.pid), false);
+            return;
+        }
+        self.show_status(format!("Watching {} ({}) for its exit", process.name, process.pid), false);
+        self.watched.push(Followed {
+            pid: process.pid,
+            name: process.name,
+            start_time: process.start_time,
+        });
+    }
+
+    /// Announce watched processes missing from this machine's new snapshot, and run the alert
+    /// actions for them when configured to
+    fn check_watched(&mut self, snapshot: &SystemSnapshot) {
+        let (exited, alive): (Vec<Followed>, Vec<Followed>) = std::mem::take(&mut self.watched)
+            .into_iter()
+            .partition(|w| !snapshot.processes.iter().any(|p| p.pid == w.pid && p.start_time == w.start_time));
+        self.watched = alive;
+        if exited.is_empty() {
+            return;
+        }
+        
+        let seen = kacemon_core::export::iso8601(snapshot.timestamp);
+        let names: Vec<String> = exited.iter().map(|w| format!("{} ({})", w.name, w.pid)).collect();
+        self.show_status(format!("EXITED: {} (noticed {})", names.join(", "), seen), true);
+        if self.config.desktop_notifications {
+            crate::notify::desktop_notify(format!("kacemon: watched process exited on {}", snapshot.system.hostname), names.join(", "));
+        }
+        if self.config.alerts.actions.watched_exits {
+            for watched in exited {
+                let alert = Alert {
+                    kind: AlertKind::ProcessExit,
+                    subject: watched.name,
+                    pid: Some(watched.pid),
+                    value: 0.0,
+                    threshold: 0.0,
+                    critical: true,
+                };
+                self.alert_dispatcher.fire(&snapshot.system.hostname, &alert);
             }
         }
     }
 
-    /// Move selection by delta
+    /// Move the selection to wherever the followed process now sits; stop following once it
+    /// has exited. One filtered out keeps being followed until it shows again.
+    fn sync_followed(&mut self) {
+        let Some(followed) = &self.followed else {
+            return;
+        };
+        let Some(snapshot) = self.viewed_snapshot() else {
+            return;
+        };
+        
+        let alive = snapshot.processes.iter().any(|p| p.pid == followed.pid && p.start_time == followed.start_time);
+        if !alive {
+            let message = format!("{} ({}) exited; stopped following", followed.name, followed.pid);
+            self.followed = None;
+            self.show_status(message, false);
+            return;
+        }
+        if let Some(row) = self.row_of_pid(followed.pid) {
+            self.selected_process_index = row;
+            self.clamp_selection();
+        }
+    }
+
+    /// Table row of the process with this PID, when it has one of its own
+    fn row_of_pid(&self, pid: u32) -> Option<usize> {
+        let processes = self.viewed_snapshot()?.processes.as_slice();
+        self.process_order()
+            .rows
+            .iter()
+            .position(|row| matches!(row, TableRow::Process(i) if processes[*i].pid == pid))
+    }
+
+    /// Move the selection up to the selected process's parent
+    fn select_parent(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let Some(parent) = process.parent_pid.filter(|&pid| pid != 0) else {
+            self.show_status(format!("{} ({}) has no parent", process.name, process.pid), false);
+            return;
+        };
+        match self.row_of_pid(parent) {
+            Some(row) => self.jump_to_row(row),
+            None => self.show_status(format!("Parent {} of {} isn't in the table", parent, process.name), false),
+        }
+    }
+
+    /// Move the selection down to the selected process's first child in table order; pressed
+    /// again on that child, on to the next of its siblings, wrapping around
+    fn select_next_child(&mut self) {
+        let Some(process) = self.selected_process() else {
+            return;
+        };
+        let parent = match self.child_walk {
+            Some(parent) if process.parent_pid == Some(parent) => parent,
+            _ => process.pid,
+        };
+        let Some(snapshot) = self.viewed_snapshot() else {
+            return;
+        };
+        let children: Vec<usize> = self
+            .process_order()
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, TableRow::Process(i) if snapshot.processes[*i].parent_pid == Some(parent)))
+            .map(|(row, _)| row)
+            .collect();
+        
+        let walking = parent != process.pid;
+        let next = children
+            .iter()
+            .position(|&row| walking && row > self.selected_process_index)
+            .unwrap_or(0);
+        let Some(&row) = children.get(next) else {
+            self.show_status(format!("{} ({}) has no children in the table", process.name, process.pid), false);
+            return;
+        };
+        self.jump_to_row(row);
+        self.child_walk = Some(parent);
+        self.show_status(format!("Child {} of {} of PID {}", next + 1, children.len(), parent), false);
+    }
+
+    /// Select a row picked by PID rather than moved to, letting go of a followed process
+    fn jump_to_row(&mut self, row: usize) {
+        self.followed = None;
+        self.child_walk = None;
+        self.selected_process_index = row;
+        self.clamp_selection();
+    }
+
+    /// Limits marked on a gauge: where its colors turn, with the critical line moved to the
+    /// alert threshold when one is set
+    fn gauge_marks(&self, warning: f32, critical: f32, alert: Option<f32>) -> GaugeMarks {
+        let alert = alert.filter(|_| self.config.alerts.enabled);
+        GaugeMarks::new(warning, alert.unwrap_or(critical))
+    }
+
+    /// Move selection by delta; the selection lets go of a followed process
     fn move_selection(&mut self, delta: isize) {
+        self.followed = None;
+        self.child_walk = None;
         let new_index = if delta < 0 {
             self.selected_process_index.saturating_sub((-delta) as usize)
         } else {
@@ -382,8 +2845,8 @@ impl App {
 
     /// Ensure selection is within valid bounds
     fn clamp_selection(&mut self) {
-        if let Some(snapshot) = &self.current_snapshot {
-            let process_count = self.get_filtered_processes(&snapshot.processes).len();
+        if self.viewed_snapshot().is_some() {
+            let process_count = self.process_count();
             if process_count == 0 {
                 self.selected_process_index = 0;
                 self.table_start_index = 0;
@@ -401,10 +2864,39 @@ impl App {
         }
     }
 
+    /// Get the process table area and, if shown, the connections panel area
+    fn table_areas(&self) -> (crate::ui::Rect, Option<crate::ui::Rect>) {
+        let mut table = self.layout.main_layout().table;
+        if let Some(strip) = self.category_strip_area() {
+            table = crate::ui::Rect::new(table.x, strip.bottom(), table.width, table.height - strip.height);
+        }
+        if self.legend_area().is_some() {
+            table.height -= 1;
+        }
+        if self.connections_view == ConnectionsView::Hidden {
+            (table, None)
+        } else {
+            let (process_table, connections) = self.layout.connections_layout(table);
+            (process_table, Some(connections))
+        }
+    }
+
+    /// First row of the table area, given to per-category totals when categories exist
+    fn category_strip_area(&self) -> Option<crate::ui::Rect> {
+        let table = self.layout.main_layout().table;
+        (!self.classifier.is_empty() && table.height > 4).then(|| crate::ui::Rect::new(table.x, table.y, table.width, 1))
+    }
+
+    /// Last row of the table area, given to the legend while it's shown
+    fn legend_area(&self) -> Option<crate::ui::Rect> {
+        let table = self.layout.main_layout().table;
+        (self.show_legend && table.height > 6).then(|| crate::ui::Rect::new(table.x, table.bottom() - 1, table.width, 1))
+    }
+
     /// Get number of visible rows in process table
     fn get_visible_rows(&self) -> usize {
-        let main_layout = self.layout.main_layout();
-        (main_layout.table.height.saturating_sub(1)) as usize // Subtract header row
+        let (table, _) = self.table_areas();
+        crate::ui::widgets::table_body_rows(table.height)
     }
 
     /// Get default visible columns from configuration
@@ -413,14 +2905,29 @@ impl App {
         
         if columns.pid { visible.push("PID".to_string()); }
         if columns.name { visible.push("NAME".to_string()); }
+        if columns.container { visible.push("CONTAINER".to_string()); }
+        if columns.pod { visible.push("POD".to_string()); }
         if columns.user { visible.push("USER".to_string()); }
         if columns.cpu_percent { visible.push("CPU%".to_string()); }
+        if columns.children_cpu { visible.push("CHLD%".to_string()); }
         if columns.memory_percent { visible.push("MEM%".to_string()); }
         if columns.memory_rss { visible.push("RSS".to_string()); }
+        if columns.pss { visible.push("PSS".to_string()); }
+        if columns.uss { visible.push("USS".to_string()); }
         if columns.memory_vsz { visible.push("VSZ".to_string()); }
+        if columns.net { visible.push("NET".to_string()); }
         if columns.threads { visible.push("THR".to_string()); }
+        if columns.fds { visible.push("FDS".to_string()); }
+        if columns.pgid { visible.push("PGID".to_string()); }
+        if columns.session { visible.push("SID".to_string()); }
+        if columns.tty { visible.push("TTY".to_string()); }
         if columns.state { visible.push("STATE".to_string()); }
-        if columns.start_time { visible.push("TIME".to_string()); }
+        if columns.wchan { visible.push("WCHAN".to_string()); }
+        if columns.busy { visible.push("BUSY".to_string()); }
+        if columns.restarts { visible.push("RST".to_string()); }
+        if columns.cpu_time { visible.push("TIME".to_string()); }
+        if columns.start_time { visible.push("AGE".to_string()); }
+        if columns.started { visible.push("START".to_string()); }
         
         visible
     }
@@ -432,15 +2939,206 @@ impl App {
             crossterm::terminal::EnterAlternateScreen,
             crossterm::cursor::Hide
         )?;
+        if self.config.terminal_title {
+            crossterm::execute!(std::io::stdout(), crate::osc::PushTitle)?;
+        }
         Ok(())
     }
 }
 
+/// Gather `rows` by name, process group, session or container: keys several processes share
+/// become one group row, the rest stay as they are, as do processes whose group or session
+/// isn't known. Processes outside any container share the host's group. Groups come in the
+/// order their keys first appear.
+pub(crate) fn group_rows(
+    processes: &[ProcessInfo],
+    rows: Vec<usize>,
+    grouping: ProcessGrouping,
+    expanded: &BTreeSet<String>,
+) -> Vec<TableRow> {
+    let mut groups: Vec<(Option<String>, Vec<usize>)> = Vec::new();
+    let mut slots: HashMap<String, usize> = HashMap::new();
+    for index in rows {
+        let process = &processes[index];
+        let key = match grouping {
+            ProcessGrouping::None => None,
+            ProcessGrouping::Name => Some(process.name.clone()),
+            ProcessGrouping::ProcessGroup => process.pgid.map(|pgid| format!("pgid {}", pgid)),
+            ProcessGrouping::Session => process.session_id.map(|sid| format!("sid {}", sid)),
+            ProcessGrouping::Container => {
+                Some(process.container_id.as_ref().map_or_else(|| "host".to_string(), |id| format!("container {}", id)))
+            },
+        };
+        let Some(key) = key else {
+            groups.push((None, vec![index]));
+            continue;
+        };
+        let slot = *slots.entry(key.clone()).or_insert_with(|| {
+            groups.push((Some(key), Vec::new()));
+            groups.len() - 1
+        });
+        groups[slot].1.push(index);
+    }
+    
+    groups
+        .into_iter()
+        .map(|(key, members)| match (key, members.as_slice()) {
+            (Some(key), [_, _, ..]) => TableRow::Group {
+                summary: Box::new(group_summary(processes, &members, grouping, expanded.contains(&key))),
+                name: key,
+                members,
+            },
+            _ => TableRow::Process(members[0]),
+        })
+        .collect()
+}
+
+/// A group's row: "▶ chrome ×42" with CPU, memory and threads summed over its members,
+/// "▶ make (pgid 812) ×9" when grouping by process group or session, or "▶ container
+/// 4f1c2b3a5d6e ×7" by container. The oldest member, usually the one that started the rest,
+/// lends its PID, user and state.
+fn group_summary(processes: &[ProcessInfo], members: &[usize], grouping: ProcessGrouping, expanded: bool) -> ProcessInfo {
+    let oldest = members
+        .iter()
+        .map(|&i| &processes[i])
+        .min_by_key(|p| (p.start_time, p.pid))
+        .expect("groups have members");
+    let mut summary = oldest.clone();
+    let label = match grouping {
+        ProcessGrouping::ProcessGroup => format!("{} (pgid {})", oldest.name, oldest.pgid.unwrap_or_default()),
+        ProcessGrouping::Session => format!("{} (sid {})", oldest.name, oldest.session_id.unwrap_or_default()),
+        ProcessGrouping::Container => match &oldest.container_id {
+            Some(id) => format!("container {}", id),
+            None => "host".to_string(),
+        },
+        ProcessGrouping::None | ProcessGrouping::Name => oldest.name.clone(),
+    };
+    summary.name = format!("{} {} ×{}", if expanded { "▼" } else { "▶" }, label, members.len());
+    summary.cpu_percent = 0.0;
+    summary.memory_percent = 0.0;
+    summary.memory_rss = 0;
+    summary.memory_vsz = 0;
+    summary.memory_pss = None;
+    summary.memory_uss = None;
+    summary.threads = 0;
+    summary.children_cpu_percent = 0.0;
+    summary.gpu_usage.clear();
+    for process in members.iter().map(|&i| &processes[i]) {
+        summary.cpu_percent += process.cpu_percent;
+        summary.memory_percent += process.memory_percent;
+        summary.memory_rss += process.memory_rss;
+        summary.memory_vsz += process.memory_vsz;
+        // Unlike RSS these add up to what the group really holds
+        summary.memory_pss = add_known(summary.memory_pss, process.memory_pss);
+        summary.memory_uss = add_known(summary.memory_uss, process.memory_uss);
+        summary.threads += process.threads;
+        summary.children_cpu_percent += process.children_cpu_percent;
+        summary.gpu_usage.extend(process.gpu_usage.iter().cloned());
+        // Members share a restart count when they share a command line
+        summary.restarts = summary.restarts.max(process.restarts);
+    }
+    summary
+}
+
+/// Sum of the members measured so far, None while none of them are
+fn add_known(total: Option<u64>, value: Option<u64>) -> Option<u64> {
+    match (total, value) {
+        (Some(total), Some(value)) => Some(total + value),
+        (total, value) => total.or(value),
+    }
+}
+
+/// The `limit` busiest of `rows`, half of them the top CPU users and the rest the largest by
+/// memory, in no particular order. Linear time, so it stays cheap however many there are.
+pub(crate) fn sample_busiest(processes: &[ProcessInfo], mut rows: Vec<usize>, limit: usize) -> Vec<usize> {
+    if rows.len() <= limit {
+        return rows;
+    }
+    let by_cpu = limit.div_ceil(2);
+    rows.select_nth_unstable_by(by_cpu, |&a, &b| processes[b].cpu_percent.total_cmp(&processes[a].cpu_percent));
+    let by_memory = limit - by_cpu;
+    rows[by_cpu..].select_nth_unstable_by(by_memory, |&a, &b| processes[b].memory_rss.cmp(&processes[a].memory_rss));
+    rows.truncate(limit);
+    rows
+}
+
+/// Sort `rows` by `sort`, backwards if `reverse`; a fuzzy `filter` then puts its best
+/// matches first, ties kept in sort order
+pub(crate) fn sort_rows(
+    processes: &[ProcessInfo],
+    rows: &mut [TableRow],
+    sort: SortKey,
+    reverse: bool,
+    collation: NameCollation,
+    filter: &ProcessFilter,
+) {
+    rows.sort_by(|a, b| {
+        let (a, b) = (a.process(processes), b.process(processes));
+        if reverse {
+            sort.compare_with(b, a, collation)
+        } else {
+            sort.compare_with(a, b, collation)
+        }
+    });
+    if filter.is_fuzzy() {
+        rows.sort_by_cached_key(|row| std::cmp::Reverse(filter.fuzzy_score(row.process(processes))));
+    }
+}
+
+/// Each user with their number of processes, most first and ties by name
+pub(crate) fn users_by_count(processes: &[ProcessInfo], collation: NameCollation) -> Vec<(&str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for process in processes {
+        *counts.entry(process.user.as_str()).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| collation.compare(a.0, b.0)));
+    counts
+}
+
+/// Short "host cpu 42% mem 71%" line for the terminal title
+fn title_summary(snapshot: &SystemSnapshot) -> String {
+    format!(
+        "{} cpu {:.0}% mem {:.0}%",
+        snapshot.system.hostname,
+        snapshot.cpu_average(),
+        snapshot.memory_percent()
+    )
+}
+
+/// "restart" -> "Restart", for dialog options
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Overall usage figures of one snapshot for a host's history
+fn system_sample(snapshot: &SystemSnapshot, interval_secs: f64, config: &Config) -> SystemSample {
+    let (rx, tx) = NetworkInfo::total_deltas(&snapshot.networks, config.count_virtual_interfaces);
+    SystemSample {
+        cpu_percent: snapshot.cpu_average() as f64,
+        memory_percent: snapshot.memory_percent(),
+        network_rate: (rx + tx) as f64 / interval_secs,
+        gpu_percent: snapshot.gpus.iter().map(|g| g.utilization_percent as f64).collect(),
+        pressure: PressureScore::compute(snapshot, &config.pressure_weights, interval_secs).score as f64,
+    }
+}
+
 /// RAII guard to restore terminal state on drop
-struct TerminalGuard;
+struct TerminalGuard {
+    restore_title: bool,
+    /// Only when OSC notifications are on could a progress state have been set
+    clear_progress: bool,
+}
 
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
+        if self.restore_title {
+            let _ = crossterm::execute!(std::io::stdout(), crate::osc::PopTitle);
+        }
+        if self.clear_progress {
+            let _ = crossterm::execute!(std::io::stdout(), crate::osc::Progress::Clear);
+        }
         let _ = crossterm::execute!(
             std::io::stdout(),
             crossterm::cursor::Show,
@@ -0,0 +1,95 @@
+use std::io::{self, Write};
+
+/// Plain ASCII stand-in for a glyph, one column for one column so layouts still line up
+pub fn ascii_glyph(c: char) -> char {
+    match c {
+        c if c.is_ascii() => c,
+        '─' | '━' => '-',
+        '│' | '┃' => '|',
+        '┊' => ':',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => '+',
+        '█' | '▇' => '#',
+        '▓' => '%',
+        '▆' | '▅' => '=',
+        '▄' | '▃' => '-',
+        '▂' | '▁' => '_',
+        '▒' => ':',
+        '░' => '.',
+        '▼' | '⬇' | '↓' => 'v',
+        '▲' | '⬆' | '↑' => '^',
+        '▶' | '→' => '>',
+        '←' => '<',
+        '●' | '◉' | '◐' => '*',
+        '○' | '◎' | '◯' => 'o',
+        '⟳' => '@',
+        '⚠' => '!',
+        '°' => '\'',
+        'µ' => 'u',
+        '…' => '.',
+        '×' => 'x',
+        // Emoji status icons
+        '🔥' | '🌡' | '♨' | '❄' => '*',
+        // Emoji presentation selector takes a cell in the width math; keep it as a blank
+        '\u{fe0f}' => ' ',
+        _ => '?',
+    }
+}
+
+/// Writer that transliterates everything passing through it with `ascii_glyph`
+pub struct AsciiWriter<W: Write> {
+    inner: W,
+    // Tail of a UTF-8 sequence split across two writes
+    pending: Vec<u8>,
+}
+
+impl<W: Write> AsciiWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for AsciiWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+
+        let mut out = Vec::with_capacity(self.pending.len());
+        let mut rest = &self.pending[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    out.extend(text.chars().map(|c| ascii_glyph(c) as u8));
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, tail) = rest.split_at(e.valid_up_to());
+                    // Only the checked prefix is valid UTF-8
+                    let valid = std::str::from_utf8(valid).unwrap_or_default();
+                    out.extend(valid.chars().map(|c| ascii_glyph(c) as u8));
+                    match e.error_len() {
+                        Some(len) => {
+                            out.push(b'?');
+                            rest = &tail[len..];
+                        }
+                        // Incomplete sequence at the end; wait for the next write
+                        None => {
+                            rest = tail;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.pending = rest.to_vec();
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
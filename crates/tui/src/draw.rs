@@ -1,4 +1,4 @@
-use crate::ui::{ColorScheme, Layout, Rect};
+use crate::ui::{ColorScheme, GaugeMarks, Layout, Rect};
 use crossterm::{
     cursor,
     style::{Print, SetBackgroundColor, SetForegroundColor},
@@ -68,7 +68,7 @@ impl Drawer {
         system_info: &kacemon_core::SystemInfo,
     ) -> io::Result<()> {
         let widget = crate::ui::TopBar;
-        widget.render(writer, area, system_info, &self.colors)
+        widget.render(writer, area, system_info, None, None, 0, &self.colors)
     }
 
     fn draw_gauges<W: Write>(
@@ -81,11 +81,11 @@ impl Drawer {
 
         // CPU gauges
         let cpu_widget = crate::ui::CpuGauges;
-        cpu_widget.render(writer, gauges_layout.cpu, &snapshot.cpu_cores, &self.colors)?;
+        cpu_widget.render(writer, gauges_layout.cpu, &snapshot.cpu_cores, &[], snapshot.run_queue.as_ref(), false, GaugeMarks::default(), false, &self.colors)?;
 
         // Memory gauges
         let memory_widget = crate::ui::MemoryGauges;
-        memory_widget.render(writer, gauges_layout.memory, &snapshot.memory, &self.colors)?;
+        memory_widget.render(writer, gauges_layout.memory, &snapshot.memory, &[], 1.0, GaugeMarks::default(), false, &self.colors)?;
 
         Ok(())
     }
@@ -99,14 +99,21 @@ impl Drawer {
     ) -> io::Result<()> {
         let widget = crate::ui::ProcessTable;
         let columns: Vec<&str> = app_state.visible_columns.iter().map(|s| s.as_str()).collect();
+        let processes: Vec<&kacemon_core::ProcessInfo> = app_state.filtered_processes.iter().collect();
 
         widget.render(
             writer,
             area,
-            &app_state.filtered_processes,
+            &processes,
             &columns,
             app_state.selected_index,
             app_state.table_start_index,
+            None,
+            0,
+            &[],
+            None,
+            &[],
+            &processes.iter().copied().collect(),
             &self.colors,
         )
     }
@@ -118,7 +125,7 @@ impl Drawer {
         snapshot: &SystemSnapshot,
     ) -> io::Result<()> {
         let widget = crate::ui::NetworkGauges;
-        widget.render(writer, area, &snapshot.networks, &self.colors)
+        widget.render(writer, area, &snapshot.networks, None, &[], false, &self.colors)
     }
 
     fn draw_temperature_section<W: Write>(
@@ -128,18 +135,18 @@ impl Drawer {
         snapshot: &SystemSnapshot,
     ) -> io::Result<()> {
         let widget = crate::ui::TemperatureGauge;
-        widget.render(writer, area, &snapshot.temperatures, &self.colors)
+        widget.render(writer, area, &snapshot.temperatures, &[], false, &self.colors)
     }
 
     fn draw_footer<W: Write>(&self, writer: &mut W, area: Rect) -> io::Result<()> {
         let widget = crate::ui::Footer;
-        widget.render(writer, area, &self.colors)
+        widget.render(writer, area, None, None, &self.colors)
     }
 
     fn draw_help_overlay<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         let terminal_rect = self.layout.terminal_rect();
         let widget = crate::ui::HelpOverlay;
-        widget.render(writer, terminal_rect, &self.colors)
+        widget.render(writer, terminal_rect, crate::input::KeyContext::ProcessTable, 0, &self.colors)
     }
 
     fn draw_filter_input<W: Write>(
@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+/// Default number of samples kept per series
+pub const DEFAULT_HISTORY_LEN: usize = 120;
+
+/// System-wide figures from one snapshot, for the overview sparklines
+#[derive(Debug, Clone, Default)]
+pub struct SystemSample {
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+    /// Combined rx + tx bytes per second over all interfaces
+    pub network_rate: f64,
+    /// Utilization per GPU, in snapshot order
+    pub gpu_percent: Vec<f64>,
+    /// Composite pressure score, 0-100
+    pub pressure: f64,
+}
+
+/// Fixed-capacity ring buffer of recent samples, oldest first
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    samples: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append a sample, dropping the oldest once full
+    pub fn push(&mut self, sample: T) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.samples.back()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.samples.iter()
+    }
+
+    /// Project one field of each sample into a series for charting
+    pub fn series<F: Fn(&T) -> f64>(&self, f: F) -> Vec<f64> {
+        self.samples.iter().map(f).collect()
+    }
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LEN)
+    }
+}
@@ -1,4 +1,4 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use std::time::Duration;
 
 /// Input events that the application can handle
@@ -7,14 +7,33 @@ pub enum InputEvent {
     // Navigation
     MoveUp,
     MoveDown,
+    /// Jump to the selected process's parent
+    SelectParent,
+    /// Jump to the selected process's next child
+    SelectNextChild,
     PageUp,
     PageDown,
     Home,
     End,
+    Select,
+    Confirm,
+    FocusNext,
     
     // Sorting and filtering
     CycleSort,
+    SortLeft,
+    SortRight,
     StartFilter,
+    CycleCategory,
+    ToggleDiskSleepFilter,
+    ToggleKernelThreads,
+    ToggleLeakFilter,
+    ToggleMyProcesses,
+    CycleTerminalFilter,
+    PickUser,
+    PickPreset,
+    /// 0-based index into the configured presets
+    ApplyPreset(usize),
     ClearFilter,
     FilterChar(char),
     FilterBackspace,
@@ -22,7 +41,32 @@ pub enum InputEvent {
     // Display controls
     ToggleColumns,
     ChangeRefreshRate,
+    RefreshFaster,
+    RefreshSlower,
     ToggleTreeView,
+    ToggleGrouping,
+    ToggleFollow,
+    ToggleWatch,
+    ToggleConnections,
+    CycleGpu,
+    ToggleCopyMode,
+    ToggleLegend,
+    ExportJson,
+    ExportBundle,
+    ShowBootTimes,
+    DeepScan,
+    ShowThreads,
+    ShowServices,
+    ShowLogFeed,
+    
+    // Viewing a recording
+    PreviousFrame,
+    NextFrame,
+    
+    // Fleet
+    ToggleFleetView,
+    /// 0 is this machine, 1.. the configured remote hosts
+    ShowHost(usize),
     
     // Process control
     KillProcess,
@@ -39,7 +83,128 @@ pub enum InputEvent {
     Unknown,
 }
 
+/// Screen or mode a key binding applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Global,
+    ProcessTable,
+    DiskPanel,
+    NetworkPanel,
+    ProcessDetail,
+    Threads,
+    Services,
+    LogFeed,
+    DeviceDetail,
+    Dialog,
+    CopyMode,
+    Fleet,
+}
+
+impl KeyContext {
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Global => "Global keys",
+            KeyContext::ProcessTable => "Process table",
+            KeyContext::DiskPanel => "Disk panel",
+            KeyContext::NetworkPanel => "Network panel",
+            KeyContext::ProcessDetail => "Process details",
+            KeyContext::Threads => "Threads",
+            KeyContext::Services => "systemd services",
+            KeyContext::LogFeed => "System log errors",
+            KeyContext::DeviceDetail => "Device details",
+            KeyContext::Dialog => "Dialog",
+            KeyContext::CopyMode => "Copy mode",
+            KeyContext::Fleet => "Fleet overview",
+        }
+    }
+}
+
+/// A documented key binding; the help overlay is generated from these
+pub struct KeyBinding {
+    pub keys: &'static str,
+    pub description: &'static str,
+    /// What `InputHandler` turns `keys` into, in the same order; one event for all of them
+    /// when they are alternatives. A range like "F1-F12" lists its first and last.
+    pub events: &'static [InputEvent],
+    pub contexts: &'static [KeyContext],
+}
+
+use KeyContext::*;
+
+const PANELS: &[KeyContext] = &[ProcessTable, DiskPanel, NetworkPanel];
+
+/// Every binding, in the order shown in help
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding { keys: "↑/k, ↓/j", description: "Move selection up/down", events: &[InputEvent::MoveUp, InputEvent::MoveDown], contexts: &[ProcessTable, DiskPanel, NetworkPanel, Threads, Services, Dialog, Fleet] },
+    KeyBinding { keys: "↑/k, ↓/j", description: "Scroll", events: &[InputEvent::MoveUp, InputEvent::MoveDown], contexts: &[ProcessDetail, LogFeed] },
+    KeyBinding { keys: "Page Up/Down", description: "Page up/down", events: &[InputEvent::PageUp, InputEvent::PageDown], contexts: &[ProcessTable, ProcessDetail, LogFeed] },
+    KeyBinding { keys: "h, l", description: "Go to the parent / next child of the selected process", events: &[InputEvent::SelectParent, InputEvent::SelectNextChild], contexts: &[ProcessTable] },
+    KeyBinding { keys: "Home/End", description: "Go to top/bottom", events: &[InputEvent::Home, InputEvent::End], contexts: &[ProcessTable, DiskPanel, NetworkPanel, ProcessDetail, Threads, Services, LogFeed, Dialog, Fleet] },
+    KeyBinding { keys: "Enter", description: "Open process details", events: &[InputEvent::Select], contexts: &[ProcessTable] },
+    KeyBinding { keys: "Enter", description: "Open disk details", events: &[InputEvent::Select], contexts: &[DiskPanel] },
+    KeyBinding { keys: "Enter", description: "Open interface details", events: &[InputEvent::Select], contexts: &[NetworkPanel] },
+    KeyBinding { keys: "Enter", description: "View the selected host", events: &[InputEvent::Select], contexts: &[Fleet] },
+    KeyBinding { keys: "Enter", description: "Start, stop or restart the selected unit (asks first)", events: &[InputEvent::Select], contexts: &[Services] },
+    KeyBinding { keys: "Enter, Esc", description: "Close details", events: &[InputEvent::Select, InputEvent::ClearFilter], contexts: &[ProcessDetail, DeviceDetail] },
+    KeyBinding { keys: "Tab", description: "Focus next panel (processes/disks/network)", events: &[InputEvent::FocusNext], contexts: PANELS },
+    KeyBinding { keys: "Esc", description: "Return focus to process table", events: &[InputEvent::ClearFilter], contexts: &[DiskPanel, NetworkPanel] },
+    KeyBinding { keys: "s", description: "Cycle sort (CPU% → MEM% → PID → NAME)", events: &[InputEvent::CycleSort], contexts: &[ProcessTable] },
+    KeyBinding { keys: "<, >", description: "Sort by column to the left/right", events: &[InputEvent::SortLeft, InputEvent::SortRight], contexts: &[ProcessTable] },
+    KeyBinding { keys: "/", description: "Filter, e.g. nginx, user:root cpu>50 state:run, re:^ngin.x or fz:chrmhlpr", events: &[InputEvent::StartFilter], contexts: &[ProcessTable] },
+    KeyBinding { keys: "C", description: "Show one process category at a time", events: &[InputEvent::CycleCategory], contexts: &[ProcessTable] },
+    KeyBinding { keys: "D", description: "Show only processes in uninterruptible sleep", events: &[InputEvent::ToggleDiskSleepFilter], contexts: &[ProcessTable] },
+    KeyBinding { keys: "H", description: "Hide/show kernel threads", events: &[InputEvent::ToggleKernelThreads], contexts: &[ProcessTable] },
+    KeyBinding { keys: "L", description: "Show only suspected memory leaks", events: &[InputEvent::ToggleLeakFilter], contexts: &[ProcessTable] },
+    KeyBinding { keys: "u", description: "Show only my processes (again for all users)", events: &[InputEvent::ToggleMyProcesses], contexts: &[ProcessTable] },
+    KeyBinding { keys: "U", description: "Pick a user to show the processes of", events: &[InputEvent::PickUser], contexts: &[ProcessTable] },
+    KeyBinding { keys: "a", description: "Processes on this terminal → without a terminal (daemons) → all", events: &[InputEvent::CycleTerminalFilter], contexts: &[ProcessTable] },
+    KeyBinding { keys: "p", description: "Pick a view preset (filter, sort and columns from config)", events: &[InputEvent::PickPreset], contexts: &[ProcessTable] },
+    KeyBinding { keys: "F1-F12", description: "Switch to view preset 1-12", events: &[InputEvent::ApplyPreset(0), InputEvent::ApplyPreset(11)], contexts: &[ProcessTable] },
+    KeyBinding { keys: "Esc", description: "Clear current filter", events: &[InputEvent::ClearFilter], contexts: &[ProcessTable] },
+    KeyBinding { keys: "c", description: "Toggle column visibility", events: &[InputEvent::ToggleColumns], contexts: &[ProcessTable] },
+    KeyBinding { keys: "r", description: "Cycle refresh rate (250ms → 500ms → 1s → 2s → 5s)", events: &[InputEvent::ChangeRefreshRate], contexts: &[ProcessTable] },
+    KeyBinding { keys: "+, -", description: "Refresh faster/slower", events: &[InputEvent::RefreshFaster, InputEvent::RefreshSlower], contexts: &[ProcessTable] },
+    KeyBinding { keys: "t", description: "Toggle tree view", events: &[InputEvent::ToggleTreeView], contexts: &[ProcessTable] },
+    KeyBinding { keys: "f", description: "Follow the selected process through re-sorts until it exits", events: &[InputEvent::ToggleFollow], contexts: &[ProcessTable] },
+    KeyBinding { keys: "w", description: "Watch the selected process and announce when it exits", events: &[InputEvent::ToggleWatch], contexts: &[ProcessTable] },
+    KeyBinding { keys: "G", description: "Group processes by name → process group → session → container (Enter expands a group)", events: &[InputEvent::ToggleGrouping], contexts: &[ProcessTable] },
+    KeyBinding { keys: "n", description: "Sockets: selected process → all → off", events: &[InputEvent::ToggleConnections], contexts: &[ProcessTable] },
+    KeyBinding { keys: "g", description: "Cycle focused GPU", events: &[InputEvent::CycleGpu], contexts: &[ProcessTable] },
+    KeyBinding { keys: "T", description: "Show the process's threads with per-thread CPU", events: &[InputEvent::ShowThreads], contexts: &[ProcessTable] },
+    KeyBinding { keys: "T, Esc", description: "Back to the process table", events: &[InputEvent::ShowThreads, InputEvent::ClearFilter], contexts: &[Threads] },
+    KeyBinding { keys: "d", description: "systemd services with state, memory and restarts (Linux)", events: &[InputEvent::ShowServices], contexts: &[ProcessTable] },
+    KeyBinding { keys: "d, Esc", description: "Back to the process table", events: &[InputEvent::ShowServices, InputEvent::ClearFilter], contexts: &[Services] },
+    KeyBinding { keys: "o", description: "Recent errors from the system log (journald, macOS unified log)", events: &[InputEvent::ShowLogFeed], contexts: &[ProcessTable] },
+    KeyBinding { keys: "o, Esc", description: "Back to the process table", events: &[InputEvent::ShowLogFeed, InputEvent::ClearFilter], contexts: &[LogFeed] },
+    KeyBinding { keys: "K", description: "Send a signal to the process", events: &[InputEvent::KillProcess], contexts: &[ProcessTable, ProcessDetail] },
+    KeyBinding { keys: "e", description: "Export the filtered process list to JSON", events: &[InputEvent::ExportJson], contexts: &[ProcessTable] },
+    KeyBinding { keys: "E", description: "Save the screen, filters and alerts as a .kmon bundle (open with kacemon view FILE)", events: &[InputEvent::ExportBundle], contexts: &[ProcessTable] },
+    KeyBinding { keys: "[, ]", description: "Previous/next snapshot of a recording opened with kacemon view", events: &[InputEvent::PreviousFrame, InputEvent::NextFrame], contexts: PANELS },
+    KeyBinding { keys: "b", description: "Boot time breakdown (systemd)", events: &[InputEvent::ShowBootTimes], contexts: &[ProcessTable] },
+    KeyBinding { keys: "S", description: "Deep scan: open files, SMART and space used on the selected disk", events: &[InputEvent::DeepScan], contexts: PANELS },
+    KeyBinding { keys: "F", description: "Fleet overview of every configured host", events: &[InputEvent::ToggleFleetView], contexts: PANELS },
+    KeyBinding { keys: "0-9", description: "View this machine (0) or remote host 1-9", events: &[InputEvent::ShowHost(0), InputEvent::ShowHost(9)], contexts: &[ProcessTable, DiskPanel, NetworkPanel, Fleet] },
+    KeyBinding { keys: "F, Esc", description: "Close the fleet overview", events: &[InputEvent::ToggleFleetView, InputEvent::ClearFilter], contexts: &[Fleet] },
+    KeyBinding { keys: "v", description: "Copy mode: plain, frozen table for mouse selection", events: &[InputEvent::ToggleCopyMode], contexts: PANELS },
+    KeyBinding { keys: "i", description: "Show/hide a legend of the table's colors and marks", events: &[InputEvent::ToggleLegend], contexts: &[ProcessTable] },
+    KeyBinding { keys: "↑/↓, Page Up/Down", description: "Scroll", events: &[InputEvent::MoveUp, InputEvent::MoveDown, InputEvent::PageUp, InputEvent::PageDown], contexts: &[CopyMode] },
+    KeyBinding { keys: "Home/End", description: "Go to top/bottom", events: &[InputEvent::Home, InputEvent::End], contexts: &[CopyMode] },
+    KeyBinding { keys: "v, Esc", description: "Leave copy mode", events: &[InputEvent::ToggleCopyMode, InputEvent::ClearFilter], contexts: &[CopyMode] },
+    KeyBinding { keys: "Enter", description: "Choose / confirm / apply", events: &[InputEvent::Select], contexts: &[Dialog] },
+    KeyBinding { keys: "y", description: "Answer yes to a question", events: &[InputEvent::Confirm], contexts: &[Dialog] },
+    KeyBinding { keys: "Esc", description: "Cancel (clears the filter when editing it)", events: &[InputEvent::ClearFilter], contexts: &[Dialog] },
+    KeyBinding { keys: "?", description: "Show/hide this help", events: &[InputEvent::ShowHelp], contexts: &[Global] },
+    KeyBinding { keys: "Tab", description: "Next help page (while help is open)", events: &[InputEvent::FocusNext], contexts: &[Global] },
+    KeyBinding { keys: "q, Ctrl+C", description: "Quit application", events: &[InputEvent::Quit], contexts: &[Global] },
+];
+
+/// Bindings that apply in the given context
+pub fn bindings_for(context: KeyContext) -> impl Iterator<Item = &'static KeyBinding> {
+    KEY_BINDINGS.iter().filter(move |b| b.contexts.contains(&context))
+}
+
 /// Input handler that converts crossterm events to application events
+#[derive(Default)]
 pub struct InputHandler {
     in_filter_mode: bool,
 }
@@ -54,23 +219,27 @@ impl InputHandler {
     /// Poll for input events with a timeout
     pub fn poll_event(&mut self, timeout: Duration) -> anyhow::Result<Option<InputEvent>> {
         if event::poll(timeout)? {
-            match event::read()? {
-                Event::Key(key_event) => Ok(Some(self.handle_key_event(key_event))),
-                Event::Resize(_, _) => Ok(Some(InputEvent::Resize)),
-                _ => Ok(Some(InputEvent::Unknown)),
-            }
+            Ok(Some(self.map_event(event::read()?)))
         } else {
             Ok(Some(InputEvent::Tick))
         }
     }
 
+    /// Convert a terminal event read elsewhere (e.g. on the input thread)
+    pub fn map_event(&mut self, event: Event) -> InputEvent {
+        match event {
+            Event::Key(key_event) if key_event.kind != KeyEventKind::Release => self.handle_key_event(key_event),
+            Event::Resize(_, _) => InputEvent::Resize,
+            _ => InputEvent::Unknown,
+        }
+    }
+
     /// Handle keyboard input
     fn handle_key_event(&mut self, key_event: KeyEvent) -> InputEvent {
         // Handle Ctrl+C for quit
         if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-            match key_event.code {
-                KeyCode::Char('c') => return InputEvent::Quit,
-                _ => {}
+            if let KeyCode::Char('c') = key_event.code {
+                return InputEvent::Quit;
             }
         }
 
@@ -83,24 +252,62 @@ impl InputHandler {
         match key_event.code {
             // Navigation
             KeyCode::Up | KeyCode::Char('k') => InputEvent::MoveUp,
+            KeyCode::Char('h') => InputEvent::SelectParent,
+            KeyCode::Char('l') => InputEvent::SelectNextChild,
             KeyCode::Down | KeyCode::Char('j') => InputEvent::MoveDown,
             KeyCode::PageUp => InputEvent::PageUp,
             KeyCode::PageDown => InputEvent::PageDown,
             KeyCode::Home => InputEvent::Home,
             KeyCode::End => InputEvent::End,
+            KeyCode::Enter => InputEvent::Select,
+            KeyCode::Tab => InputEvent::FocusNext,
+            KeyCode::Char('y') => InputEvent::Confirm,
             
             // Sorting and filtering
             KeyCode::Char('s') => InputEvent::CycleSort,
+            KeyCode::Char('<') => InputEvent::SortLeft,
+            KeyCode::Char('>') => InputEvent::SortRight,
             KeyCode::Char('/') => {
                 self.in_filter_mode = true;
                 InputEvent::StartFilter
             },
             KeyCode::Esc => InputEvent::ClearFilter,
+            KeyCode::Char('C') => InputEvent::CycleCategory,
+            KeyCode::Char('D') => InputEvent::ToggleDiskSleepFilter,
+            KeyCode::Char('H') => InputEvent::ToggleKernelThreads,
+            KeyCode::Char('L') => InputEvent::ToggleLeakFilter,
+            KeyCode::Char('u') => InputEvent::ToggleMyProcesses,
+            KeyCode::Char('U') => InputEvent::PickUser,
+            KeyCode::Char('a') => InputEvent::CycleTerminalFilter,
+            KeyCode::Char('p') => InputEvent::PickPreset,
+            KeyCode::F(n @ 1..=12) => InputEvent::ApplyPreset(n as usize - 1),
             
             // Display controls
             KeyCode::Char('c') => InputEvent::ToggleColumns,
             KeyCode::Char('r') => InputEvent::ChangeRefreshRate,
+            KeyCode::Char('+') | KeyCode::Char('=') => InputEvent::RefreshFaster,
+            KeyCode::Char('-') => InputEvent::RefreshSlower,
             KeyCode::Char('t') => InputEvent::ToggleTreeView,
+            KeyCode::Char('G') => InputEvent::ToggleGrouping,
+            KeyCode::Char('f') => InputEvent::ToggleFollow,
+            KeyCode::Char('w') => InputEvent::ToggleWatch,
+            KeyCode::Char('n') => InputEvent::ToggleConnections,
+            KeyCode::Char('g') => InputEvent::CycleGpu,
+            KeyCode::Char('v') => InputEvent::ToggleCopyMode,
+            KeyCode::Char('i') => InputEvent::ToggleLegend,
+            KeyCode::Char('e') => InputEvent::ExportJson,
+            KeyCode::Char('E') => InputEvent::ExportBundle,
+            KeyCode::Char('b') => InputEvent::ShowBootTimes,
+            KeyCode::Char('S') => InputEvent::DeepScan,
+            KeyCode::Char('T') => InputEvent::ShowThreads,
+            KeyCode::Char('d') => InputEvent::ShowServices,
+            KeyCode::Char('o') => InputEvent::ShowLogFeed,
+            KeyCode::Char('[') => InputEvent::PreviousFrame,
+            KeyCode::Char(']') => InputEvent::NextFrame,
+            
+            // Fleet
+            KeyCode::Char('F') => InputEvent::ToggleFleetView,
+            KeyCode::Char(c @ '0'..='9') => InputEvent::ShowHost(c as usize - '0' as usize),
             
             // Process control  
             KeyCode::Char('K') => InputEvent::KillProcess, // Use uppercase K to avoid conflict with navigation
@@ -122,7 +329,7 @@ impl InputHandler {
             },
             KeyCode::Enter => {
                 self.in_filter_mode = false;
-                InputEvent::Unknown // Just exit filter mode
+                InputEvent::Select
             },
             KeyCode::Backspace => InputEvent::FilterBackspace,
             KeyCode::Char(c) => InputEvent::FilterChar(c),
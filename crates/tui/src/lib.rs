@@ -1,17 +1,22 @@
 pub mod app;
+pub mod ascii;
 pub mod draw;
+pub mod history;
 pub mod input;
+pub mod notify;
+pub mod osc;
+pub mod screen;
 pub mod ui;
+pub mod worker;
 
 pub use app::App;
 pub use draw::{DrawState, Drawer};
-pub use input::{InputEvent, InputHandler};
+pub use input::{InputEvent, InputHandler, KeyContext};
 pub use ui::*;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kacemon_core::Config;
     
     #[test]
     fn test_input_handler_creation() {
@@ -22,7 +27,7 @@ mod tests {
     #[test]
     fn test_layout_creation() {
         // This test requires a terminal, so we'll just test the basic structure
-        let result = std::panic::catch_unwind(|| {
+        let _result = std::panic::catch_unwind(|| {
             ui::Layout::new()
         });
         // Don't assert success since we might not have a terminal in CI
@@ -31,11 +36,12 @@ mod tests {
     
     #[test]
     fn test_color_scheme_creation() {
-        let scheme = ui::ColorScheme::new(&kacemon_core::Theme::Dark, false);
+        let _scheme = ui::ColorScheme::new(&kacemon_core::Theme::Dark, false);
         // Just verify it creates without panicking
         
         let no_color_scheme = ui::ColorScheme::new(&kacemon_core::Theme::Dark, true);
         // Verify no-color mode
+        assert_eq!(no_color_scheme.foreground, crossterm::style::Color::Reset);
     }
     
     #[test]
@@ -67,4 +73,433 @@ mod tests {
         assert!(state.filter_text.is_empty());
         assert!(!state.visible_columns.is_empty());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_history_ring_buffer() {
+        let mut history = history::History::new(3);
+        assert!(history.is_empty());
+        
+        for value in 1..=5 {
+            history.push(value);
+        }
+        
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.latest(), Some(&5));
+        assert_eq!(history.series(|v| *v as f64), vec![3.0, 4.0, 5.0]);
+    }
+    
+    #[test]
+    fn test_every_key_context_has_bindings() {
+        use input::{bindings_for, KeyContext};
+        
+        for context in [
+            KeyContext::Global,
+            KeyContext::ProcessTable,
+            KeyContext::DiskPanel,
+            KeyContext::NetworkPanel,
+            KeyContext::ProcessDetail,
+            KeyContext::Threads,
+            KeyContext::Services,
+            KeyContext::LogFeed,
+            KeyContext::DeviceDetail,
+            KeyContext::Dialog,
+            KeyContext::CopyMode,
+            KeyContext::Fleet,
+        ] {
+            assert!(bindings_for(context).next().is_some(), "no bindings for {:?}", context);
+        }
+    }
+    
+    #[test]
+    fn test_key_bindings_send_their_events() {
+        use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+        use input::{InputHandler, KEY_BINDINGS};
+        
+        let key = |name: &str| {
+            let code = match name {
+                "↑" => KeyCode::Up,
+                "↓" => KeyCode::Down,
+                "Page Up" => KeyCode::PageUp,
+                "Page Down" => KeyCode::PageDown,
+                "Home" => KeyCode::Home,
+                "End" => KeyCode::End,
+                "Enter" => KeyCode::Enter,
+                "Esc" => KeyCode::Esc,
+                "Tab" => KeyCode::Tab,
+                "Ctrl+C" => return KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+                _ if name.len() > 1 && name.starts_with('F') => KeyCode::F(name[1..].parse().unwrap()),
+                _ if name.chars().count() == 1 => KeyCode::Char(name.chars().next().unwrap()),
+                _ => panic!("unknown key {:?}", name),
+            };
+            KeyEvent::new(code, KeyModifiers::NONE)
+        };
+        
+        for binding in KEY_BINDINGS {
+            // "↑/k, ↓/j" is two groups of alternatives, "Page Up/Down" two keys sharing "Page",
+            // "F1-F12" the two ends of a range
+            let groups: Vec<Vec<String>> = binding
+                .keys
+                .split(", ")
+                .map(|group| {
+                    let mut keys: Vec<String> = Vec::new();
+                    let parts: Vec<&str> = if group == "/" { vec![group] } else { group.split('/').collect() };
+                    for part in parts {
+                        match (part.split_once('-').filter(|_| part.len() > 1), keys.last()) {
+                            (Some((first, last)), _) => keys.extend([first.to_string(), last.to_string()]),
+                            (None, Some(previous)) if !part.contains(' ') && previous.contains(' ') => {
+                                keys.push(format!("{} {}", previous.split(' ').next().unwrap(), part));
+                            },
+                            _ => keys.push(part.to_string()),
+                        }
+                    }
+                    keys
+                })
+                .collect();
+            let keys: Vec<&String> = groups.iter().flatten().collect();
+            let expected: Vec<(&String, &InputEvent)> = if keys.len() == binding.events.len() {
+                keys.into_iter().zip(binding.events).collect()
+            } else if groups.len() == binding.events.len() {
+                groups.iter().zip(binding.events).flat_map(|(group, event)| group.iter().map(move |key| (key, event))).collect()
+            } else {
+                assert_eq!(binding.events.len(), 1, "{:?} lists {} events", binding.keys, binding.events.len());
+                keys.into_iter().map(|key| (key, &binding.events[0])).collect()
+            };
+            
+            for (name, event) in expected {
+                let sent = InputHandler::new().map_event(Event::Key(key(name)));
+                assert_eq!(&sent, event, "{} in {:?} ({})", name, binding.keys, binding.description);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_dialog_outcomes() {
+        use ui::{Dialog, DialogOutcome};
+        
+        let mut select = Dialog::select("Pick", vec!["a".into(), "b".into()], 0);
+        assert_eq!(select.handle_event(&InputEvent::MoveDown), DialogOutcome::Pending);
+        assert_eq!(select.handle_event(&InputEvent::MoveDown), DialogOutcome::Pending);
+        assert_eq!(select.handle_event(&InputEvent::Select), DialogOutcome::Selected(1));
+        
+        let mut text = Dialog::text_input("Filter", "ab");
+        assert!(text.wants_text());
+        assert_eq!(text.handle_event(&InputEvent::FilterBackspace), DialogOutcome::Edited("a".into()));
+        assert_eq!(text.handle_event(&InputEvent::FilterChar('x')), DialogOutcome::Edited("ax".into()));
+        assert_eq!(text.handle_event(&InputEvent::Select), DialogOutcome::Submitted("ax".into()));
+        
+        let mut confirm = Dialog::confirm("Sure?", "Really?");
+        assert_eq!(confirm.clone().handle_event(&InputEvent::Confirm), DialogOutcome::Confirmed);
+        assert_eq!(confirm.handle_event(&InputEvent::ToggleConnections), DialogOutcome::Cancelled);
+    }
+    
+    #[test]
+    fn test_osc_sequences() {
+        use crossterm::Command;
+        
+        let mut out = String::new();
+        osc::Notify("disk\nfull").write_ansi(&mut out).unwrap();
+        assert_eq!(out, "\x1b]9;diskfull\x07");
+        
+        out.clear();
+        osc::Progress::Error(150).write_ansi(&mut out).unwrap();
+        assert_eq!(out, "\x1b]9;4;2;100\x07");
+    }
+    
+    #[test]
+    fn test_gradient_colors() {
+        use crossterm::style::Color;
+        
+        let mut scheme = ui::ColorScheme::new(&kacemon_core::Theme::Dark, false);
+        scheme.truecolor = false;
+        assert_eq!(scheme.gradient(95.0), scheme.error);
+        
+        scheme.truecolor = true;
+        assert_eq!(scheme.gradient(0.0), Color::Rgb { r: 46, g: 204, b: 64 });
+        assert_eq!(scheme.gradient(50.0), Color::Rgb { r: 255, g: 220, b: 0 });
+        assert_eq!(scheme.gradient(150.0), Color::Rgb { r: 230, g: 40, b: 40 });
+    }
+    
+    #[test]
+    fn test_ascii_writer() {
+        use std::io::Write;
+        
+        let mut out = Vec::new();
+        let mut writer = ascii::AsciiWriter::new(&mut out);
+        let text = "┌─█░▼ 🌡️ é\x1b[0m";
+        // Split inside a multi-byte character
+        let (head, tail) = text.as_bytes().split_at(4);
+        writer.write_all(head).unwrap();
+        writer.write_all(tail).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "+-#.v *  ?\x1b[0m");
+    }
+    
+    #[test]
+    fn test_screen_buffer_redraws_only_changes() {
+        use crossterm::{cursor::MoveTo, style::{Color, Print, SetForegroundColor}, terminal::SetTitle, QueueableCommand};
+        use std::io::Write;
+        
+        let mut screen = screen::ScreenBuffer::default();
+        let mut frame = |text: &str, out: &mut Vec<u8>| {
+            screen.begin_frame(10, 2);
+            screen.queue(MoveTo(0, 1)).unwrap();
+            screen.queue(SetForegroundColor(Color::Red)).unwrap();
+            screen.queue(Print(text)).unwrap();
+            screen.queue(SetTitle("t")).unwrap();
+            screen.flush().unwrap();
+            screen.present(out).unwrap();
+        };
+        
+        let mut first = Vec::new();
+        frame("cpu 10%", &mut first);
+        assert!(String::from_utf8_lossy(&first).contains("cpu 10%"));
+        
+        // Only the changed digit is sent, colored and positioned; the title passes through
+        let mut second = Vec::new();
+        frame("cpu 15%", &mut second);
+        assert_eq!(String::from_utf8(second).unwrap(), "\x1b[2;6H\x1b[38;5;9m\x1b[49m5\x1b]0;t\x07");
+        
+        let mut third = Vec::new();
+        frame("cpu 15%", &mut third);
+        assert_eq!(String::from_utf8(third).unwrap(), "\x1b]0;t\x07");
+    }
+    
+    fn test_process(pid: u32, name: &str, cpu: f32, rss: u64) -> kacemon_core::ProcessInfo {
+        kacemon_core::ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cmd: Vec::new(),
+            user: "test".to_string(),
+            cpu_percent: cpu,
+            memory_percent: 0.0,
+            memory_rss: rss,
+            memory_vsz: rss,
+            threads: 1,
+            state: kacemon_core::ProcessState::Sleeping,
+            start_time: std::time::SystemTime::now(),
+            parent_pid: None,
+            pgid: None,
+            session_id: None,
+            tty: None,
+            cgroup: None,
+            container_id: None,
+            pod: None,
+            gpu_usage: Vec::new(),
+            category: None,
+            children_cpu_time: std::time::Duration::ZERO,
+            cpu_time_total: std::time::Duration::ZERO,
+            children_cpu_percent: 0.0,
+            wchan: None,
+            open_fds: None,
+            memory_pss: None,
+            memory_uss: None,
+            busy_since: None,
+            restarts: 0,
+            net_rate: None,
+            is_kernel_thread: false,
+            partial: false,
+        }
+    }
+    
+    #[test]
+    fn test_sample_busiest_keeps_top_cpu_and_memory() {
+        use kacemon_core::ProcessInfo;
+        let process = |pid: u32, cpu: f32, rss: u64| test_process(pid, &format!("p{}", pid), cpu, rss);
+        
+        // PIDs 1-2 burn CPU, 3 holds the most memory, the rest are idle
+        let mut processes: Vec<ProcessInfo> = (10..1000).map(|pid| process(pid, 0.0, 1024)).collect();
+        processes.push(process(1, 90.0, 1024));
+        processes.push(process(2, 40.0, 1024));
+        processes.push(process(3, 0.0, 1 << 30));
+        
+        let all: Vec<usize> = (0..processes.len()).collect();
+        
+        let mut kept: Vec<u32> = app::sample_busiest(&processes, all.clone(), 3).iter().map(|&i| processes[i].pid).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, vec![1, 2, 3]);
+        assert_eq!(app::sample_busiest(&processes, all.clone(), 5000), all);
+        assert_eq!(processes[app::sample_busiest(&processes, all, 1)[0]].pid, 1);
+    }
+    
+    #[test]
+    fn test_ranked_sensors() {
+        use kacemon_core::TemperatureInfo;
+        let sensor = |label: &str, temperature: f32| TemperatureInfo { label: label.to_string(), temperature, critical: None, max: None };
+        let sensors = vec![sensor("acpitz", 30.0), sensor("nvme Composite", 45.0), sensor("coretemp Package id 0", 60.0), sensor("SYSTIN", 35.0)];
+        let labels = |priority: &[String]| -> Vec<String> {
+            ui::widgets::ranked_sensors(&sensors, priority).iter().map(|s| s.label.clone()).collect()
+        };
+        
+        assert_eq!(labels(&[]), ["coretemp Package id 0", "nvme Composite", "SYSTIN", "acpitz"]);
+        assert_eq!(labels(&["systin".to_string(), "package".to_string()]), ["SYSTIN", "coretemp Package id 0", "nvme Composite", "acpitz"]);
+    }
+    
+    #[test]
+    fn test_memory_segments_fill_total() {
+        let memory = |used: u64| kacemon_core::MemoryInfo {
+            total: 1000,
+            used,
+            available: 1000 - used,
+            free: 0,
+            buffers: 50,
+            cached: 300,
+            swap_total: 0,
+            swap_used: 0,
+            swap_free: 0,
+            swap_in_pages: 0,
+            swap_out_pages: 0,
+        };
+        assert_eq!(ui::widgets::memory_segments(&memory(400)), [400, 50, 300, 250]);
+        
+        // Estimates that overlap used are cut to what's left
+        assert_eq!(ui::widgets::memory_segments(&memory(900)), [900, 50, 50, 0]);
+    }
+    
+    #[test]
+    fn test_cpu_time_age_and_temperature_formats() {
+        use std::time::Duration;
+        use ui::widgets::{format_cpu_time, format_process_age, format_temperature};
+        
+        assert_eq!(format_cpu_time(Duration::from_millis(245_320)), "4:05.32");
+        assert_eq!(format_cpu_time(Duration::from_secs(43_445)), "12:04:05");
+        assert_eq!(format_process_age(Duration::from_secs(42)), "42s");
+        assert_eq!(format_process_age(Duration::from_secs(18_720)), "5h12m");
+        assert_eq!(format_process_age(Duration::from_secs(3 * 86_400 + 4 * 3600 + 59)), "3d4h");
+        // Sensors, GPUs and NVMe drives all show the same unit
+        assert_eq!(format_temperature(80.0), "176°F");
+        assert_eq!(format_temperature(36.6), "98°F");
+    }
+    
+    #[test]
+    fn test_event_rate_format() {
+        use ui::widgets::format_count;
+        
+        assert_eq!(format_count(950.4), "950");
+        assert_eq!(format_count(1234.0), "1.2k");
+        assert_eq!(format_count(48_700.0), "49k");
+        assert_eq!(format_count(2_150_000.0), "2.1M");
+        // Either side of each step, and values that only reach it by rounding
+        assert_eq!(format_count(999.4), "999");
+        assert_eq!(format_count(999.6), "1.0k");
+        assert_eq!(format_count(9_940.0), "9.9k");
+        assert_eq!(format_count(9_999.6), "10k");
+        assert_eq!(format_count(10_000.0), "10k");
+        assert_eq!(format_count(999_400.0), "999k");
+        assert_eq!(format_count(999_600.0), "1.0M");
+    }
+    
+    #[test]
+    fn test_sort_rows() {
+        use app::TableRow;
+        use kacemon_core::{NameCollation, ProcessFilter, SortKey};
+        let mut shell = test_process(2, "bash", 50.0, 10);
+        shell.cmd = vec!["bash".to_string(), "-c".to_string(), "top".to_string()];
+        let processes = vec![
+            test_process(1, "top", 1.0, 10),
+            shell,
+            test_process(3, "nginx", 30.0, 10),
+            test_process(4, "top", 5.0, 10),
+        ];
+        let sorted = |reverse: bool, filter: &str| -> Vec<u32> {
+            let mut rows: Vec<TableRow> = (0..processes.len()).map(TableRow::Process).collect();
+            let filter: ProcessFilter = filter.parse().unwrap();
+            app::sort_rows(&processes, &mut rows, SortKey::Cpu, reverse, NameCollation::Natural, &filter);
+            rows.iter().map(|row| row.process(&processes).pid).collect()
+        };
+        
+        assert_eq!(sorted(true, ""), vec![2, 3, 4, 1]);
+        assert_eq!(sorted(false, ""), vec![1, 4, 3, 2]);
+        // Plain text filters leave the order alone
+        assert_eq!(sorted(true, "top"), vec![2, 3, 4, 1]);
+        // Fuzzy matches go best first: the name before the command line, ties by CPU
+        assert_eq!(sorted(true, "fz:top"), vec![4, 1, 2, 3]);
+    }
+    
+    #[test]
+    fn test_users_by_count() {
+        use kacemon_core::NameCollation;
+        let owned = |pid: u32, user: &str| kacemon_core::ProcessInfo { user: user.to_string(), ..test_process(pid, "p", 0.0, 10) };
+        let processes = vec![owned(1, "root"), owned(2, "www"), owned(3, "alice"), owned(4, "root"), owned(5, "Bob")];
+        
+        assert_eq!(app::users_by_count(&processes, NameCollation::Natural), vec![("root", 2), ("alice", 1), ("Bob", 1), ("www", 1)]);
+        assert_eq!(app::users_by_count(&processes, NameCollation::Bytes), vec![("root", 2), ("Bob", 1), ("alice", 1), ("www", 1)]);
+        assert!(app::users_by_count(&[], NameCollation::Natural).is_empty());
+    }
+    
+    #[test]
+    fn test_terminal_filter() {
+        use app::TerminalFilter;
+        let mut shell = test_process(10, "bash", 0.0, 10);
+        shell.tty = Some("pts/3".to_string());
+        let mut other = test_process(11, "vim", 0.0, 10);
+        other.tty = Some("pts/4".to_string());
+        let daemon = test_process(12, "sshd", 0.0, 10);
+        let processes = [shell, other, daemon];
+        let shown = |filter: &TerminalFilter| -> Vec<u32> {
+            processes.iter().filter(|p| filter.matches(p)).map(|p| p.pid).collect()
+        };
+        
+        assert_eq!(shown(&TerminalFilter::All), vec![10, 11, 12]);
+        assert_eq!(shown(&TerminalFilter::Only("pts/3".to_string())), vec![10]);
+        assert_eq!(shown(&TerminalFilter::Detached), vec![12]);
+        
+        // Past the collection limit the terminal was never read, so neither filter keeps it
+        let mut unread = test_process(13, "bash", 0.0, 10);
+        unread.partial = true;
+        assert!(TerminalFilter::All.matches(&unread));
+        assert!(!TerminalFilter::Detached.matches(&unread));
+        unread.tty = Some("pts/3".to_string());
+        assert!(!TerminalFilter::Only("pts/3".to_string()).matches(&unread));
+    }
+    
+    #[test]
+    fn test_group_rows_sums_by_name() {
+        use app::TableRow;
+        use kacemon_core::ProcessGrouping;
+        let processes = vec![
+            test_process(10, "chrome", 5.0, 100),
+            test_process(11, "bash", 1.0, 10),
+            test_process(12, "chrome", 20.0, 300),
+            test_process(13, "chrome", 0.5, 50),
+        ];
+        let expanded = std::collections::BTreeSet::from(["chrome".to_string()]);
+        
+        let rows = app::group_rows(&processes, (0..processes.len()).collect(), ProcessGrouping::Name, &Default::default());
+        assert_eq!(rows.len(), 2);
+        let TableRow::Group { name, summary, members } = &rows[0] else {
+            panic!("chrome should be grouped: {:?}", rows[0]);
+        };
+        assert_eq!(name, "chrome");
+        assert_eq!(members, &vec![0, 2, 3]);
+        assert_eq!(summary.name, "▶ chrome ×3");
+        assert_eq!(summary.pid, 10);
+        assert_eq!(summary.cpu_percent, 25.5);
+        assert_eq!(summary.memory_rss, 450);
+        assert_eq!(summary.threads, 3);
+        assert!(matches!(rows[1], TableRow::Process(1)));
+        
+        // The totals line counts each process once, grouped or not
+        let totals: ui::TableTotals = rows.iter().flat_map(|row| row.members()).map(|&i| &processes[i]).collect();
+        assert_eq!((totals.count, totals.cpu_percent, totals.memory_rss), (4, 26.5, 460));
+        assert_eq!(ui::widgets::table_body_rows(2), 1);
+        assert_eq!(ui::widgets::table_body_rows(20), 18);
+        
+        let rows = app::group_rows(&processes, vec![0, 1, 2], ProcessGrouping::Name, &expanded);
+        assert_eq!(rows[0].process(&processes).name, "▼ chrome ×2");
+        assert!(matches!(app::group_rows(&processes, vec![1, 3], ProcessGrouping::Name, &expanded)[..], [TableRow::Process(1), TableRow::Process(3)]));
+        
+        // By process group, processes without one stay on their own rows
+        let mut jobs = processes.clone();
+        jobs[1].pgid = Some(11);
+        jobs[2].pgid = Some(11);
+        jobs[3].pgid = Some(13);
+        let rows = app::group_rows(&jobs, (0..jobs.len()).collect(), ProcessGrouping::ProcessGroup, &Default::default());
+        assert!(matches!(&rows[..], [TableRow::Process(0), TableRow::Group { name, members, .. }, TableRow::Process(3)]
+            if name == "pgid 11" && members == &vec![1, 2]));
+        
+        // By container, everything outside one is bucketed as the host
+        jobs[0].container_id = Some("4f1c2b3a5d6e".to_string());
+        let rows = app::group_rows(&jobs, (0..jobs.len()).collect(), ProcessGrouping::Container, &Default::default());
+        assert!(matches!(&rows[..], [TableRow::Process(0), TableRow::Group { name, summary, .. }]
+            if name == "host" && summary.name == "▶ host ×3" && summary.memory_rss == 360));
+    }
+}
@@ -0,0 +1,16 @@
+/// Pop a desktop notification for a critical alert. Runs on its own thread because the
+/// notification daemon can be slow to answer; without one the alert is simply dropped.
+#[cfg(feature = "desktop-notifications")]
+pub fn desktop_notify(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification.appname("kacemon").summary(&summary).body(&body);
+        #[cfg(all(unix, not(target_os = "macos")))]
+        notification.urgency(notify_rust::Urgency::Critical);
+        let _ = notification.show();
+    });
+}
+
+/// Built without the desktop-notifications feature: nothing to notify through
+#[cfg(not(feature = "desktop-notifications"))]
+pub fn desktop_notify(_summary: String, _body: String) {}
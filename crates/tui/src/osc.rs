@@ -0,0 +1,69 @@
+use crossterm::Command;
+use std::fmt;
+
+/// Desktop notification via OSC 9 (iTerm2, WezTerm, Windows Terminal, kitty, ...)
+pub struct Notify<'a>(pub &'a str);
+
+impl Command for Notify<'_> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        // Control characters would end the sequence early
+        let text: String = self.0.chars().filter(|c| !c.is_control()).collect();
+        write!(f, "\x1b]9;{}\x07", text)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Taskbar / tab progress state via OSC 9;4 (ConEmu, Windows Terminal)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    Clear,
+    Normal(u8),
+    Error(u8),
+}
+
+impl Command for Progress {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match *self {
+            Progress::Clear => write!(f, "\x1b]9;4;0;0\x07"),
+            Progress::Normal(percent) => write!(f, "\x1b]9;4;1;{}\x07", percent.min(100)),
+            Progress::Error(percent) => write!(f, "\x1b]9;4;2;{}\x07", percent.min(100)),
+        }
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Save the current window title on the terminal's title stack (XTWINOPS 22)
+pub struct PushTitle;
+
+impl Command for PushTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str("\x1b[22;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Restore the title saved by `PushTitle` (XTWINOPS 23)
+pub struct PopTitle;
+
+impl Command for PopTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str("\x1b[23;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
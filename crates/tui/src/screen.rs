@@ -0,0 +1,232 @@
+use crossterm::{
+    cursor,
+    style::{Color, Colored, Print, SetBackgroundColor, SetForegroundColor},
+    terminal, QueueableCommand,
+};
+use std::io::{self, Write};
+
+/// One character cell and its colors
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    ch: char,
+    fg: Color,
+    bg: Color,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: Color::Reset,
+            bg: Color::Reset,
+        }
+    }
+}
+
+/// Where the escape-sequence parser is between writes
+enum Parse {
+    Ground,
+    Escape,
+    Csi(String),
+    Osc(Vec<u8>),
+}
+
+/// Off-screen cell grid the widgets draw into. Widgets keep writing cursor moves, colors and
+/// text as before; `present` then compares the grid with the previous frame and sends the
+/// terminal only the cells that changed. Escape sequences that aren't drawing (titles, OSC
+/// notifications) are passed through untouched.
+pub struct ScreenBuffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    // What the terminal shows; None forces a full redraw
+    previous: Option<Vec<Cell>>,
+    cursor: (u16, u16),
+    fg: Color,
+    bg: Color,
+    parse: Parse,
+    utf8: Vec<u8>,
+    passthrough: Vec<u8>,
+}
+
+impl Default for ScreenBuffer {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            previous: None,
+            cursor: (0, 0),
+            fg: Color::Reset,
+            bg: Color::Reset,
+            parse: Parse::Ground,
+            utf8: Vec::new(),
+            passthrough: Vec::new(),
+        }
+    }
+}
+
+impl ScreenBuffer {
+    /// Start drawing a frame over the last one; cells not drawn again keep their contents,
+    /// just as they would on the terminal. A new size starts from a blank screen.
+    pub fn begin_frame(&mut self, width: u16, height: u16) {
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.cells = vec![Cell::default(); width as usize * height as usize];
+            self.previous = None;
+        }
+        self.cursor = (0, 0);
+    }
+
+    /// Forget what the terminal shows, so the next `present` redraws every cell
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    /// Write the cells that differ from the previous frame, then any passed-through sequences
+    pub fn present<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        let width = self.width as usize;
+        let full_redraw = self.previous.is_none();
+        if full_redraw {
+            out.queue(SetBackgroundColor(Color::Reset))?;
+            out.queue(terminal::Clear(terminal::ClearType::All))?;
+        }
+
+        let (mut fg, mut bg) = (None, None);
+        for (y, row) in self.cells.chunks(width.max(1)).enumerate() {
+            let previous_row = self.previous.as_ref().map(|previous| &previous[y * width..(y + 1) * width]);
+            let changed = |x: &usize| previous_row.map_or(true, |previous| previous[*x] != row[*x]);
+            let Some(first) = (0..width).find(changed) else {
+                continue;
+            };
+            let last = (0..width).rev().find(changed).unwrap_or(first);
+
+            // Wide glyphs make the terminal's columns drift from the grid's, so rows with
+            // any are redrawn from the left edge where the drift starts
+            let start = if row.iter().any(|cell| is_wide(cell.ch)) { 0 } else { first };
+            out.queue(cursor::MoveTo(start as u16, y as u16))?;
+            for cell in &row[start..=last] {
+                if fg != Some(cell.fg) {
+                    out.queue(SetForegroundColor(cell.fg))?;
+                    fg = Some(cell.fg);
+                }
+                if bg != Some(cell.bg) {
+                    out.queue(SetBackgroundColor(cell.bg))?;
+                    bg = Some(cell.bg);
+                }
+                out.queue(Print(cell.ch))?;
+            }
+        }
+
+        out.write_all(&self.passthrough)?;
+        self.passthrough.clear();
+        self.previous = Some(self.cells.clone());
+        out.flush()
+    }
+
+    fn feed(&mut self, byte: u8) {
+        match &mut self.parse {
+            Parse::Ground if byte == 0x1b => self.parse = Parse::Escape,
+            Parse::Ground => self.feed_text(byte),
+            Parse::Escape => {
+                self.parse = match byte {
+                    b'[' => Parse::Csi(String::new()),
+                    b']' => Parse::Osc(vec![0x1b, b']']),
+                    _ => {
+                        self.passthrough.extend_from_slice(&[0x1b, byte]);
+                        Parse::Ground
+                    },
+                };
+            },
+            Parse::Csi(params) if (0x40..=0x7e).contains(&byte) => {
+                let params = std::mem::take(params);
+                self.parse = Parse::Ground;
+                self.apply_csi(&params, byte);
+            },
+            Parse::Csi(params) => params.push(byte as char),
+            Parse::Osc(sequence) => {
+                sequence.push(byte);
+                // Ends with BEL or ESC \
+                if byte == 0x07 || sequence.ends_with(b"\x1b\\") {
+                    let sequence = std::mem::take(sequence);
+                    self.passthrough.extend_from_slice(&sequence);
+                    self.parse = Parse::Ground;
+                }
+            },
+        }
+    }
+
+    fn feed_text(&mut self, byte: u8) {
+        self.utf8.push(byte);
+        let ch = match std::str::from_utf8(&self.utf8) {
+            Ok(text) => text.chars().next().unwrap_or(' '),
+            // Incomplete sequence; wait for the rest
+            Err(e) if e.error_len().is_none() => return,
+            Err(_) => char::REPLACEMENT_CHARACTER,
+        };
+        self.utf8.clear();
+        if ch.is_control() {
+            return;
+        }
+
+        let (x, y) = self.cursor;
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = Cell {
+                ch,
+                fg: self.fg,
+                bg: self.bg,
+            };
+        }
+        self.cursor.0 = x.saturating_add(1);
+    }
+
+    fn apply_csi(&mut self, params: &str, command: u8) {
+        match command {
+            b'H' => {
+                let mut position = params.split(';').map(|n| n.parse::<u16>().unwrap_or(1).max(1) - 1);
+                let row = position.next().unwrap_or(0);
+                let column = position.next().unwrap_or(0);
+                self.cursor = (column, row);
+            },
+            b'm' if params.is_empty() || params == "0" => {
+                self.fg = Color::Reset;
+                self.bg = Color::Reset;
+            },
+            b'm' => match Colored::parse_ansi(params) {
+                Some(Colored::ForegroundColor(color)) => self.fg = color,
+                Some(Colored::BackgroundColor(color)) => self.bg = color,
+                // Underline colors and text attributes aren't drawn by any widget
+                _ => {},
+            },
+            _ => {
+                // A clear behind our back leaves the terminal out of step with the last frame
+                if command == b'J' {
+                    self.invalidate();
+                }
+                self.passthrough.extend_from_slice(b"\x1b[");
+                self.passthrough.extend_from_slice(params.as_bytes());
+                self.passthrough.push(command);
+            },
+        }
+    }
+}
+
+impl Write for ScreenBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &byte in buf {
+            self.feed(byte);
+        }
+        Ok(buf.len())
+    }
+
+    /// Nothing reaches the terminal until `present`
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Emoji and the variation selector that asks for emoji presentation
+fn is_wide(ch: char) -> bool {
+    ch >= '\u{1F000}' || ch == '\u{FE0F}' || ('\u{2600}'..='\u{27BF}').contains(&ch)
+}
@@ -1,5 +1,5 @@
 use crossterm::style::Color;
-use kacemon_core::Theme;
+use kacemon_core::{Config, PaletteColor, Theme, ThemePalette};
 
 /// Color scheme for the TUI
 #[derive(Debug, Clone)]
@@ -18,6 +18,10 @@ pub struct ColorScheme {
     pub table_header: Color,
     pub table_row_alt: Color,
     pub table_selected: Color,
+    /// Terminal takes 24-bit colors, so bars can use smooth gradients
+    pub truecolor: bool,
+    /// Badge colors of the configured process categories
+    pub category_colors: Vec<(String, Color)>,
 }
 
 impl ColorScheme {
@@ -25,11 +29,74 @@ impl ColorScheme {
         if no_color {
             Self::no_color()
         } else {
-            match theme {
-                Theme::Dark => Self::dark(),
+            let mut scheme = match theme {
+                Theme::Dark | Theme::Custom(_) => Self::dark(),
                 Theme::Light => Self::light(),
+            };
+            scheme.truecolor = Self::detect_truecolor();
+            scheme
+        }
+    }
+
+    /// Terminals advertise 24-bit support through COLORTERM
+    pub fn detect_truecolor() -> bool {
+        std::env::var("COLORTERM")
+            .map(|v| matches!(v.to_ascii_lowercase().as_str(), "truecolor" | "24bit"))
+            .unwrap_or(false)
+    }
+
+    /// Scheme for the configured theme, including user-defined palettes
+    pub fn from_config(config: &Config) -> Self {
+        let mut scheme = match (&config.theme, config.no_color) {
+            (Theme::Custom(name), false) => match config.themes.get(name) {
+                Some(palette) => Self::from_palette(palette),
+                None => Self::dark(),
+            },
+            (theme, no_color) => Self::new(theme, no_color),
+        };
+        if !config.no_color {
+            scheme.category_colors = config
+                .categories
+                .iter()
+                .filter_map(|rule| Some((rule.name.clone(), to_color(rule.color?))))
+                .collect();
+        }
+        scheme
+    }
+
+    /// Badge color for a process category
+    pub fn category_color(&self, category: &str) -> Color {
+        self.category_colors
+            .iter()
+            .find(|(name, _)| name == category)
+            .map_or(self.accent, |&(_, color)| color)
+    }
+
+    /// Built-in base theme with the palette's colors laid over it
+    pub fn from_palette(palette: &ThemePalette) -> Self {
+        let mut scheme = Self::new(&palette.base, false);
+        let fields = [
+            (&mut scheme.background, palette.background),
+            (&mut scheme.foreground, palette.foreground),
+            (&mut scheme.accent, palette.accent),
+            (&mut scheme.border, palette.border),
+            (&mut scheme.highlight, palette.highlight),
+            (&mut scheme.warning, palette.warning),
+            (&mut scheme.error, palette.error),
+            (&mut scheme.success, palette.success),
+            (&mut scheme.muted, palette.muted),
+            (&mut scheme.gauge_bg, palette.gauge_bg),
+            (&mut scheme.gauge_fill, palette.gauge_fill),
+            (&mut scheme.table_header, palette.table_header),
+            (&mut scheme.table_row_alt, palette.table_row_alt),
+            (&mut scheme.table_selected, palette.table_selected),
+        ];
+        for (field, color) in fields {
+            if let Some(color) = color {
+                *field = to_color(color);
             }
         }
+        scheme
     }
 
     fn dark() -> Self {
@@ -48,6 +115,8 @@ impl ColorScheme {
             table_header: Color::Cyan,
             table_row_alt: Color::DarkGrey,
             table_selected: Color::Yellow,
+            truecolor: false,
+            category_colors: Vec::new(),
         }
     }
 
@@ -67,6 +136,8 @@ impl ColorScheme {
             table_header: Color::DarkBlue,
             table_row_alt: Color::Grey,
             table_selected: Color::DarkBlue,
+            truecolor: false,
+            category_colors: Vec::new(),
         }
     }
 
@@ -86,6 +157,8 @@ impl ColorScheme {
             table_header: Color::Reset,
             table_row_alt: Color::Reset,
             table_selected: Color::Reset,
+            truecolor: false,
+            category_colors: Vec::new(),
         }
     }
 
@@ -100,6 +173,29 @@ impl ColorScheme {
         }
     }
 
+    /// Green → yellow → red for 0-100%; falls back to the three status colors without truecolor
+    pub fn gradient(&self, percent: f32) -> Color {
+        let percent = percent.clamp(0.0, 100.0);
+        if !self.truecolor {
+            return self.cpu_usage_color(percent);
+        }
+
+        const GREEN: (f32, f32, f32) = (46.0, 204.0, 64.0);
+        const YELLOW: (f32, f32, f32) = (255.0, 220.0, 0.0);
+        const RED: (f32, f32, f32) = (230.0, 40.0, 40.0);
+        let (from, to, t) = if percent < 50.0 {
+            (GREEN, YELLOW, percent / 50.0)
+        } else {
+            (YELLOW, RED, (percent - 50.0) / 50.0)
+        };
+        let lerp = |a: f32, b: f32| (a + (b - a) * t).round() as u8;
+        Color::Rgb {
+            r: lerp(from.0, to.0),
+            g: lerp(from.1, to.1),
+            b: lerp(from.2, to.2),
+        }
+    }
+
     /// Get color for memory usage percentage
     pub fn memory_usage_color(&self, usage: f32) -> Color {
         if usage > 90.0 {
@@ -117,6 +213,7 @@ impl ColorScheme {
             kacemon_core::ProcessState::Running => self.success,
             kacemon_core::ProcessState::Sleeping => self.muted,
             kacemon_core::ProcessState::Waiting => self.warning,
+            kacemon_core::ProcessState::DiskSleep => self.error,
             kacemon_core::ProcessState::Zombie => self.error,
             kacemon_core::ProcessState::Stopped => self.error,
             kacemon_core::ProcessState::Paging => self.warning,
@@ -125,3 +222,11 @@ impl ColorScheme {
         }
     }
 }
+
+fn to_color(color: PaletteColor) -> Color {
+    match color {
+        PaletteColor::Default => Color::Reset,
+        PaletteColor::Rgb(r, g, b) => Color::Rgb { r, g, b },
+        PaletteColor::Indexed(i) => Color::AnsiValue(i),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::ui::widgets::{format_bytes, format_rate, process_cell, sorted_interfaces};
+use crate::ui::{pad_or_truncate, ColorScheme, Rect};
+use crossterm::{
+    cursor,
+    style::{Print, SetBackgroundColor, SetForegroundColor},
+    QueueableCommand,
+};
+use kacemon_core::{DiskInfo, NetworkInfo, ProcessInfo};
+use std::io::{self, Write};
+
+/// Frozen plain-text copy of a table, shown while in copy mode
+#[derive(Debug, Clone)]
+pub struct CopyText {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl CopyText {
+    pub fn processes(processes: &[&ProcessInfo], columns: &[&str]) -> Self {
+        let rows = processes
+            .iter()
+            .map(|p| columns.iter().map(|c| process_cell(p, c).trim().to_string()).collect())
+            .collect();
+        Self {
+            title: format!("PROCESSES ({})", processes.len()),
+            lines: plain_table(columns, rows),
+        }
+    }
+
+    pub fn disks(disks: &[DiskInfo], interval_secs: f64) -> Self {
+        let per_sec = |bytes: u64| format!("{}/s", format_rate((bytes as f64 / interval_secs.max(0.001)) as u64));
+        let rows = disks
+            .iter()
+            .map(|d| {
+                let usage = if d.total_space > 0 { d.used_space as f64 / d.total_space as f64 * 100.0 } else { 0.0 };
+                vec![
+                    d.name.clone(),
+                    d.mount_point.clone(),
+                    d.file_system.clone(),
+                    format!("{:.0}%", usage),
+                    format_bytes(d.used_space),
+                    format_bytes(d.total_space),
+                    per_sec(d.read_bytes_delta),
+                    per_sec(d.write_bytes_delta),
+                ]
+            })
+            .collect();
+        Self {
+            title: format!("DISKS ({})", disks.len()),
+            lines: plain_table(&["DEVICE", "MOUNT", "FS", "USE%", "USED", "SIZE", "READ", "WRITE"], rows),
+        }
+    }
+
+    pub fn networks(networks: &[NetworkInfo], interval_secs: f64) -> Self {
+        let per_sec = |bytes: u64| format!("{}/s", format_rate((bytes as f64 / interval_secs.max(0.001)) as u64));
+        let rows = sorted_interfaces(networks)
+            .into_iter()
+            .map(|n| {
+                vec![
+                    n.interface_name.clone(),
+                    per_sec(n.rx_bytes_delta),
+                    per_sec(n.tx_bytes_delta),
+                    format_bytes(n.rx_bytes),
+                    format_bytes(n.tx_bytes),
+                    (n.rx_errors + n.tx_errors).to_string(),
+                ]
+            })
+            .collect();
+        Self {
+            title: format!("NETWORK ({})", networks.len()),
+            lines: plain_table(&["INTERFACE", "RX", "TX", "RX TOTAL", "TX TOTAL", "ERRORS"], rows),
+        }
+    }
+}
+
+/// Lay out rows as space-aligned columns sized to their content, without trailing blanks
+pub fn plain_table(headers: &[&str], rows: Vec<Vec<String>>) -> Vec<String> {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<String>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    std::iter::once(format_row(headers.iter().map(|h| h.to_string()).collect()))
+        .chain(rows.into_iter().map(format_row))
+        .collect()
+}
+
+/// Full-screen, single-color rendering of `CopyText` for terminal-native selection
+pub struct CopyView;
+
+impl CopyView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        text: &CopyText,
+        scroll: usize,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.muted))?;
+        let title = format!("COPY MODE: {} - select with the mouse, v/Esc to exit", text.title);
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        // Plain foreground on plain background so selections copy cleanly
+        writer.queue(SetForegroundColor(colors.foreground))?;
+        let rows = area.height as usize - 1;
+        for row in 0..rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 1 + row as u16))?;
+            let line = text.lines.get(scroll + row).map(String::as_str).unwrap_or("");
+            writer.queue(Print(pad_or_truncate(line, width)))?;
+        }
+
+        Ok(())
+    }
+}
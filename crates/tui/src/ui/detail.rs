@@ -0,0 +1,912 @@
+use crate::history::History;
+use crate::ui::{
+    busy_summary, format_bytes, format_cpu_time, format_process_age, format_rate, format_temperature, leak_summary, pad_or_truncate, ColorScheme,
+    HistoryChart, Rect,
+};
+use crossterm::{
+    cursor,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+    QueueableCommand,
+};
+use kacemon_core::{
+    metrics::disk::{device_partitions, parent_device, NvmeWearStatus},
+    platform::PlatformProcessDetails,
+    BootTiming, DeepScan, DiskInfo, InterfaceDetails, LeakSuspect, LogEntry, NetworkInfo, ProcessInfo, ServiceInfo, ThreadInfo,
+};
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime};
+
+/// Full-screen process detail view
+pub struct ProcessDetailView;
+
+impl ProcessDetailView {
+    #[allow(clippy::too_many_arguments)]
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        process: &ProcessInfo,
+        details: Option<&PlatformProcessDetails>,
+        leak: Option<&LeakSuspect>,
+        scroll: usize,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        let lines = Self::build_lines(process, details, leak, colors);
+
+        // Title line stays fixed, the rest scrolls
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ PROCESS {} ({}) ── Esc/Enter: back  ↑↓: scroll ", process.pid, process.name);
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        let body_rows = (area.height as usize).saturating_sub(1);
+        let scroll = scroll.min(lines.len().saturating_sub(body_rows));
+
+        for row in 0..body_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 1 + row as u16))?;
+            match lines.get(scroll + row) {
+                Some((color, text)) => {
+                    writer.queue(SetForegroundColor(*color))?;
+                    writer.queue(Print(pad_or_truncate(text, width)))?;
+                }
+                None => {
+                    writer.queue(Print(" ".repeat(width)))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number of content lines, used by the app to clamp scrolling
+    pub fn line_count(process: &ProcessInfo, details: Option<&PlatformProcessDetails>, leak: Option<&LeakSuspect>) -> usize {
+        Self::build_lines(process, details, leak, &ColorScheme::new(&kacemon_core::Theme::Dark, true)).len()
+    }
+
+    fn build_lines(
+        process: &ProcessInfo,
+        details: Option<&PlatformProcessDetails>,
+        leak: Option<&LeakSuspect>,
+        colors: &ColorScheme,
+    ) -> Vec<(Color, String)> {
+        let mut lines = Vec::new();
+        let header = |lines: &mut Vec<(Color, String)>, text: String| {
+            lines.push((colors.foreground, String::new()));
+            lines.push((colors.table_header, text));
+        };
+
+        let parent = process.parent_pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string());
+        let fds = process.open_fds.map(|fds| format!("   FDs: {}", fds)).unwrap_or_default();
+        lines.push((colors.foreground, format!(
+            "  State: {:?}   User: {}   PPID: {}   Threads: {}{}",
+            process.state, process.user, parent, process.threads, fds
+        )));
+        if let (Some(pgid), Some(session_id)) = (process.pgid, process.session_id) {
+            lines.push((colors.foreground, format!(
+                "  Terminal: {}   Session: {}   Process group: {}",
+                process.tty.as_deref().unwrap_or("none"),
+                session_id,
+                pgid
+            )));
+        }
+        if let Some(wchan) = &process.wchan {
+            lines.push((colors.foreground, format!("  Blocked in: {}", wchan)));
+        }
+        lines.push((colors.foreground, format!(
+            "  CPU: {:.1}%   MEM: {:.1}%   RSS: {}   VSZ: {}",
+            process.cpu_percent,
+            process.memory_percent,
+            format_bytes(process.memory_rss),
+            format_bytes(process.memory_vsz)
+        )));
+        if let (Some(pss), Some(uss)) = (process.memory_pss, process.memory_uss) {
+            lines.push((colors.foreground, format!("  PSS: {}   USS: {}", format_bytes(pss), format_bytes(uss))));
+        }
+        let age = std::time::SystemTime::now().duration_since(process.start_time).unwrap_or_default();
+        lines.push((colors.foreground, format!(
+            "  Started: {} ({} ago)   CPU time: {}",
+            kacemon_core::export::iso8601(process.start_time),
+            format_process_age(age),
+            format_cpu_time(process.cpu_time_total)
+        )));
+        if let Some(busy) = busy_summary(process) {
+            lines.push((colors.warning, format!("  CPU-bound: {}", busy)));
+        }
+        if let Some(leak) = leak {
+            lines.push((colors.warning, format!("  Suspected leak: RSS {}", leak_summary(leak))));
+        }
+        // Reaped children's time, e.g. everything a shell or supervisor has spawned so far
+        if !process.children_cpu_time.is_zero() {
+            let total = process.children_cpu_time.as_secs();
+            lines.push((colors.foreground, format!(
+                "  Children CPU: {:.1}% now   {}:{:02}:{:02} total",
+                process.children_cpu_percent,
+                total / 3600,
+                total % 3600 / 60,
+                total % 60
+            )));
+        }
+
+        let details = details.cloned().unwrap_or_default();
+        let missing = |value: Option<String>| value.unwrap_or_else(|| "(unavailable)".to_string());
+
+        header(&mut lines, "Command line:".to_string());
+        let cmdline = details.cmdline.clone().or_else(|| {
+            (!process.cmd.is_empty()).then(|| process.cmd.join(" "))
+        });
+        lines.push((colors.foreground, format!("  {}", missing(cmdline))));
+
+        header(&mut lines, "Working directory:".to_string());
+        lines.push((colors.foreground, format!("  {}", missing(details.cwd.clone()))));
+
+        header(&mut lines, "Cgroup / container:".to_string());
+        lines.push((colors.foreground, format!("  cgroup:    {}", missing(details.cgroup.clone().or_else(|| process.cgroup.clone())))));
+        lines.push((colors.foreground, format!("  container: {}", details.container_id.clone().unwrap_or_else(|| "-".to_string()))));
+        if let Some(pod) = &process.pod {
+            let container = pod.container.as_deref().map(|name| format!("   container {}", name)).unwrap_or_default();
+            lines.push((colors.foreground, format!("  pod:       {} (namespace {}){}", pod.name, pod.namespace, container)));
+        }
+
+        if !process.gpu_usage.is_empty() {
+            let total: u64 = process.gpu_usage.iter().map(|g| g.memory_used).sum();
+            header(&mut lines, format!("GPU ({} VRAM):", format_bytes(total)));
+            for usage in &process.gpu_usage {
+                lines.push((colors.foreground, format!("  GPU{}  {}", usage.gpu_index, format_bytes(usage.memory_used))));
+            }
+        }
+
+        match &details.open_files {
+            Some(files) => {
+                header(&mut lines, format!("Open files ({}):", files.len()));
+                for file in files {
+                    lines.push((colors.foreground, format!("  {}", file)));
+                }
+            }
+            None => {
+                header(&mut lines, "Open files:".to_string());
+                lines.push((colors.muted, "  (unavailable - insufficient permissions?)".to_string()));
+            }
+        }
+
+        match &details.environment {
+            Some(environment) => {
+                header(&mut lines, format!("Environment ({}):", environment.len()));
+                for (key, value) in environment {
+                    lines.push((colors.foreground, format!("  {}={}", key, value)));
+                }
+            }
+            None => {
+                header(&mut lines, "Environment:".to_string());
+                lines.push((colors.muted, "  (unavailable - insufficient permissions?)".to_string()));
+            }
+        }
+
+        lines
+    }
+}
+
+/// Full-screen list of one process's threads, busiest first
+pub struct ThreadListView;
+
+impl ThreadListView {
+    #[allow(clippy::too_many_arguments)]
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        pid: u32,
+        name: &str,
+        threads: &[ThreadInfo],
+        selected: usize,
+        exited: bool,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 3 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(if exited { colors.warning } else { colors.accent }))?;
+        let status = if exited { ", exited" } else { "" };
+        let title = format!(
+            "─ THREADS of {} (PID {}): {}{} ── Esc/T: back  ↑↓: select ",
+            name,
+            pid,
+            threads.len(),
+            status
+        );
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let header = format!("{:<8} {:<20} {:<10} {:>6} {:>10}", "TID", "NAME", "STATE", "CPU%", "TIME");
+        writer.queue(Print(pad_or_truncate(&header, width)))?;
+
+        let body_rows = (area.height as usize).saturating_sub(2);
+        let start = selected.saturating_sub(body_rows.saturating_sub(1));
+        for row in 0..body_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + row as u16))?;
+            let Some(thread) = threads.get(start + row) else {
+                writer.queue(SetBackgroundColor(colors.background))?;
+                writer.queue(Print(" ".repeat(width)))?;
+                continue;
+            };
+
+            let total = thread.cpu_time.as_secs();
+            let text = format!(
+                "{:<8} {:<20} {:<10} {:>6.1} {:>4}:{:02}:{:02}",
+                thread.tid,
+                thread.name,
+                format!("{:?}", thread.state),
+                thread.cpu_percent,
+                total / 3600,
+                total % 3600 / 60,
+                total % 60
+            );
+            if start + row == selected {
+                writer.queue(SetBackgroundColor(colors.table_selected))?;
+                writer.queue(SetForegroundColor(colors.background))?;
+            } else {
+                writer.queue(SetBackgroundColor(colors.background))?;
+                writer.queue(SetForegroundColor(if thread.cpu_percent > 50.0 { colors.warning } else { colors.foreground }))?;
+            }
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+        }
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        Ok(())
+    }
+}
+
+/// Full-screen list of systemd service units, failed ones highlighted
+pub struct ServiceListView;
+
+impl ServiceListView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        services: &[ServiceInfo],
+        selected: usize,
+        error: Option<&str>,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 3 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let failed = services.iter().filter(|service| service.is_failed()).count();
+        let title = format!(
+            "─ SYSTEMD SERVICES: {}, {} failed ── Esc/d: back  ↑↓: select  Enter: start/stop/restart ",
+            services.len(),
+            failed
+        );
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let header = format!(
+            "{:<32} {:<10} {:<10} {:>8} {:>9} {:>8}  {}",
+            "UNIT", "ACTIVE", "SUB", "PID", "MEMORY", "RESTARTS", "DESCRIPTION"
+        );
+        writer.queue(Print(pad_or_truncate(&header, width)))?;
+
+        let body_rows = (area.height as usize).saturating_sub(2);
+        let start = selected.saturating_sub(body_rows.saturating_sub(1));
+        for row in 0..body_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + row as u16))?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            let Some(service) = services.get(start + row) else {
+                // systemctl's complaint, or a placeholder until the first pass comes back
+                let text = match (row, error) {
+                    (0, Some(error)) if services.is_empty() => error.to_string(),
+                    (0, None) if services.is_empty() => "Asking systemd...".to_string(),
+                    _ => String::new(),
+                };
+                writer.queue(SetForegroundColor(if error.is_some() { colors.error } else { colors.muted }))?;
+                writer.queue(Print(pad_or_truncate(&text, width)))?;
+                continue;
+            };
+
+            let text = format!(
+                "{:<32} {:<10} {:<10} {:>8} {:>9} {:>8}  {}",
+                pad_or_truncate(&service.name, 32),
+                service.active_state,
+                service.sub_state,
+                service.main_pid.map_or("-".to_string(), |pid| pid.to_string()),
+                service.memory.map_or("-".to_string(), format_bytes),
+                service.restarts,
+                service.description
+            );
+            if start + row == selected {
+                writer.queue(SetBackgroundColor(colors.table_selected))?;
+                writer.queue(SetForegroundColor(colors.background))?;
+            } else if service.is_failed() {
+                writer.queue(SetForegroundColor(colors.error))?;
+            } else if service.active_state != "active" {
+                writer.queue(SetForegroundColor(colors.muted))?;
+            } else {
+                writer.queue(SetForegroundColor(colors.foreground))?;
+            }
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+        }
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        Ok(())
+    }
+}
+
+/// Full-screen list of recent system log errors, newest first
+pub struct LogFeedView;
+
+impl LogFeedView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        entries: &[LogEntry],
+        scroll: usize,
+        error: Option<&str>,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 3 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ SYSTEM LOG ERRORS: {} ── Esc/o: back  ↑↓: scroll ", entries.len());
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let header = format!("{:<10} {:<7} {:<24} {}", "TIME", "LEVEL", "SOURCE", "MESSAGE");
+        writer.queue(Print(pad_or_truncate(&header, width)))?;
+
+        let now = SystemTime::now();
+        for row in 0..(area.height as usize).saturating_sub(2) {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + row as u16))?;
+            let Some(entry) = entries.iter().rev().nth(scroll + row) else {
+                // Why the log can't be read, or a placeholder until something goes wrong
+                let text = match (row, error) {
+                    (0, Some(error)) if entries.is_empty() => error.to_string(),
+                    (0, None) if entries.is_empty() => "No errors in the system log yet".to_string(),
+                    _ => String::new(),
+                };
+                writer.queue(SetForegroundColor(if error.is_some() { colors.error } else { colors.muted }))?;
+                writer.queue(Print(pad_or_truncate(&text, width)))?;
+                continue;
+            };
+
+            let source = match entry.pid {
+                Some(pid) => format!("{}[{}]", entry.source, pid),
+                None => entry.source.clone(),
+            };
+            let text = format!(
+                "{:<10} {:<7} {:<24} {}",
+                kacemon_core::export::short_timestamp(entry.time, now),
+                entry.level,
+                pad_or_truncate(&source, 24),
+                entry.message.lines().collect::<Vec<_>>().join(" ")
+            );
+            let severe = matches!(entry.level.as_str(), "fault" | "emerg" | "alert" | "crit");
+            writer.queue(SetForegroundColor(if severe { colors.error } else { colors.foreground }))?;
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// One point of a disk's throughput/latency history
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiskSample {
+    pub read_rate: f64,   // bytes/s
+    pub write_rate: f64,  // bytes/s
+    pub latency_ms: f64,
+}
+
+/// Full-screen view of one block device
+pub struct DiskDetailView;
+
+impl DiskDetailView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        disk: &DiskInfo,
+        all_disks: &[DiskInfo],
+        history: Option<&History<DiskSample>>,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ DISK {} on {} ── Esc/Enter: back ", disk.name, disk.mount_point);
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        let lines = Self::build_lines(disk, all_disks, colors);
+        let body_rows = (area.height as usize).saturating_sub(1);
+
+        // Charts get whatever is left after the text, split three ways with a label row each
+        let chart_rows = body_rows.saturating_sub(lines.len() + 1) / 3;
+        let mut y = area.y + 1;
+
+        if chart_rows >= 2 {
+            let latest = history.and_then(|h| h.latest()).copied().unwrap_or_default();
+            let empty = History::new(1);
+            let history = history.unwrap_or(&empty);
+            let read = history.series(|s| s.read_rate);
+            let write = history.series(|s| s.write_rate);
+            // Read and write share a scale so they can be compared at a glance
+            let throughput_max = read.iter().chain(write.iter()).cloned().fold(0.0, f64::max);
+
+            let charts = [
+                (format!("Read  {}/s", format_rate(latest.read_rate as u64)), read, Some(throughput_max), colors.success),
+                (format!("Write {}/s", format_rate(latest.write_rate as u64)), write, Some(throughput_max), colors.accent),
+                (format!("Latency {:.2} ms/op", latest.latency_ms), history.series(|s| s.latency_ms), None, colors.warning),
+            ];
+
+            for (label, values, max, color) in charts {
+                writer.queue(cursor::MoveTo(area.x, y))?;
+                writer.queue(SetForegroundColor(colors.table_header))?;
+                writer.queue(Print(pad_or_truncate(&label, width)))?;
+                let chart_area = Rect::new(area.x, y + 1, area.width, (chart_rows - 1) as u16);
+                HistoryChart.render(writer, chart_area, &values, max, color, colors)?;
+                y += chart_rows as u16;
+            }
+        }
+
+        let bottom = area.y + area.height;
+        for (color, text) in &lines {
+            if y >= bottom {
+                break;
+            }
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(SetForegroundColor(*color))?;
+            writer.queue(Print(pad_or_truncate(text, width)))?;
+            y += 1;
+        }
+
+        while y < bottom {
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(Print(" ".repeat(width)))?;
+            y += 1;
+        }
+
+        Ok(())
+    }
+
+    fn build_lines(disk: &DiskInfo, all_disks: &[DiskInfo], colors: &ColorScheme) -> Vec<(Color, String)> {
+        let mut lines = Vec::new();
+
+        let usage = if disk.total_space > 0 {
+            disk.used_space as f32 / disk.total_space as f32 * 100.0
+        } else {
+            0.0
+        };
+        lines.push((colors.foreground, String::new()));
+        lines.push((colors.foreground, format!(
+            "  {}   {} used of {} ({:.1}%)   Busy: {}",
+            disk.file_system,
+            format_bytes(disk.used_space),
+            format_bytes(disk.total_space),
+            usage,
+            disk.busy_percent.map(|b| format!("{:.0}%", b)).unwrap_or_else(|| "-".to_string())
+        )));
+
+        lines.push((colors.table_header, "Health:".to_string()));
+        match &disk.nvme_health {
+            Some(health) => {
+                let color = match health.get_status() {
+                    NvmeWearStatus::Healthy => colors.success,
+                    NvmeWearStatus::Warning => colors.warning,
+                    NvmeWearStatus::Critical => colors.error,
+                };
+                lines.push((color, format!(
+                    "  {:?}   Used: {}%   Spare: {}%   Media errors: {}   Temp: {}",
+                    health.get_status(),
+                    health.percentage_used,
+                    health.available_spare,
+                    health.media_errors,
+                    health.temperature.map(format_temperature).unwrap_or_else(|| "-".to_string())
+                )));
+            }
+            None => lines.push((colors.muted, "  (no SMART data - non-NVMe drive or smartctl unavailable)".to_string())),
+        }
+
+        let parent = parent_device(&disk.name);
+        let partitions = device_partitions(&parent);
+        lines.push((colors.table_header, format!("Device {}:", parent)));
+        lines.push((colors.foreground, format!(
+            "  Partitions: {}",
+            if partitions.is_empty() { "-".to_string() } else { partitions.join(", ") }
+        )));
+
+        for other in all_disks.iter().filter(|d| parent_device(&d.name) == parent) {
+            lines.push((colors.foreground, format!(
+                "  {:<16} {:<10} {:>10} free",
+                other.mount_point,
+                other.file_system,
+                format_bytes(other.available_space)
+            )));
+        }
+
+        lines
+    }
+}
+
+/// One point of an interface's traffic history (all rates per second)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkSample {
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_packets: f64,
+    pub tx_packets: f64,
+    pub rx_errors: f64,
+    pub tx_errors: f64,
+}
+
+/// Full-screen view of one network interface
+pub struct NetworkDetailView;
+
+impl NetworkDetailView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        network: &NetworkInfo,
+        details: Option<&InterfaceDetails>,
+        history: Option<&History<NetworkSample>>,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ INTERFACE {} ── Esc/Enter: back ", network.interface_name);
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        let latest = history.and_then(|h| h.latest()).copied().unwrap_or_default();
+        let lines = Self::build_lines(network, details, &latest, colors);
+        let body_rows = (area.height as usize).saturating_sub(1);
+
+        // Two charts (RX, TX) share the space left by the text
+        let chart_rows = body_rows.saturating_sub(lines.len()) / 2;
+        let mut y = area.y + 1;
+
+        if chart_rows >= 2 {
+            let empty = History::new(1);
+            let history = history.unwrap_or(&empty);
+            let rx = history.series(|s| s.rx_rate);
+            let tx = history.series(|s| s.tx_rate);
+            let max = rx.iter().chain(tx.iter()).cloned().fold(0.0, f64::max);
+
+            let charts = [
+                (format!("⬇ RX {}/s", format_rate(latest.rx_rate as u64)), rx, colors.success),
+                (format!("⬆ TX {}/s", format_rate(latest.tx_rate as u64)), tx, colors.warning),
+            ];
+
+            for (label, values, color) in charts {
+                writer.queue(cursor::MoveTo(area.x, y))?;
+                writer.queue(SetForegroundColor(colors.table_header))?;
+                writer.queue(Print(pad_or_truncate(&label, width)))?;
+                let chart_area = Rect::new(area.x, y + 1, area.width, (chart_rows - 1) as u16);
+                HistoryChart.render(writer, chart_area, &values, Some(max), color, colors)?;
+                y += chart_rows as u16;
+            }
+        }
+
+        let bottom = area.y + area.height;
+        for (color, text) in &lines {
+            if y >= bottom {
+                break;
+            }
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(SetForegroundColor(*color))?;
+            writer.queue(Print(pad_or_truncate(text, width)))?;
+            y += 1;
+        }
+
+        while y < bottom {
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(Print(" ".repeat(width)))?;
+            y += 1;
+        }
+
+        Ok(())
+    }
+
+    fn build_lines(
+        network: &NetworkInfo,
+        details: Option<&InterfaceDetails>,
+        latest: &NetworkSample,
+        colors: &ColorScheme,
+    ) -> Vec<(Color, String)> {
+        let mut lines = Vec::new();
+        let details = details.cloned().unwrap_or_default();
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+        lines.push((colors.table_header, "Traffic:".to_string()));
+        lines.push((colors.foreground, format!(
+            "  Packets/s  RX {:>8.0}   TX {:>8.0}",
+            latest.rx_packets, latest.tx_packets
+        )));
+        let error_color = if latest.rx_errors > 0.0 || latest.tx_errors > 0.0 { colors.error } else { colors.foreground };
+        lines.push((error_color, format!(
+            "  Errors/s   RX {:>8.1}   TX {:>8.1}   (total {} / {})",
+            latest.rx_errors, latest.tx_errors, network.rx_errors, network.tx_errors
+        )));
+        lines.push((colors.foreground, format!(
+            "  Total      RX {:>8}   TX {:>8}   ({} / {} packets)",
+            format_bytes(network.rx_bytes),
+            format_bytes(network.tx_bytes),
+            network.rx_packets,
+            network.tx_packets
+        )));
+
+        lines.push((colors.table_header, "Link:".to_string()));
+        lines.push((colors.foreground, format!(
+            "  State: {}   Speed: {}   Duplex: {}   MTU: {}",
+            or_dash(details.operstate.clone()),
+            or_dash(details.speed_mbps.map(|s| format!("{} Mb/s", s))),
+            or_dash(details.duplex.clone()),
+            or_dash(details.mtu.map(|m| m.to_string()))
+        )));
+        lines.push((colors.foreground, format!(
+            "  Driver: {}   MAC: {}",
+            or_dash(details.driver.clone()),
+            or_dash(details.mac_address.clone())
+        )));
+
+        lines.push((colors.table_header, format!("Addresses ({}):", details.addresses.len())));
+        for address in &details.addresses {
+            lines.push((colors.foreground, format!("  {}", address)));
+        }
+
+        lines
+    }
+}
+
+/// Full-screen boot time breakdown, like `systemd-analyze time` + `blame`
+pub struct BootTimeView;
+
+impl BootTimeView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        timing: &BootTiming,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let total = match timing.userspace {
+            Some(_) => format_boot_duration(timing.total()),
+            None => "still booting".to_string(),
+        };
+        let title = format!("─ BOOT TIME {} ── Esc/Enter: back ", total);
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        let mut y = area.y + 1;
+        let bottom = area.y + area.height;
+        for (color, text) in Self::build_lines(timing, width, colors) {
+            if y >= bottom {
+                break;
+            }
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(SetForegroundColor(color))?;
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+            y += 1;
+        }
+
+        while y < bottom {
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(Print(" ".repeat(width)))?;
+            y += 1;
+        }
+
+        Ok(())
+    }
+
+    fn build_lines(timing: &BootTiming, width: usize, colors: &ColorScheme) -> Vec<(Color, String)> {
+        let mut lines = Vec::new();
+        let bar_width = (width / 3).clamp(10, 40);
+        let bar = |duration: Duration, max: Duration| {
+            let filled = if max.is_zero() {
+                0
+            } else {
+                ((duration.as_secs_f64() / max.as_secs_f64()) * bar_width as f64).round() as usize
+            };
+            format!("{:<width$}", "█".repeat(filled.min(bar_width)), width = bar_width)
+        };
+
+        let phases = [
+            ("Firmware", timing.firmware),
+            ("Loader", timing.loader),
+            ("Kernel", Some(timing.kernel)),
+            ("Initrd", timing.initrd),
+            ("Userspace", timing.userspace),
+        ];
+        let longest_phase = phases.iter().filter_map(|(_, d)| *d).max().unwrap_or_default();
+
+        lines.push((colors.table_header, "Phases:".to_string()));
+        for (name, duration) in phases {
+            let Some(duration) = duration else {
+                continue;
+            };
+            lines.push((colors.foreground, format!(
+                "  {:<10} {:>10}  {}",
+                name,
+                format_boot_duration(duration),
+                bar(duration, longest_phase)
+            )));
+        }
+
+        lines.push((colors.table_header, format!("Slowest units ({}):", timing.slowest_units.len())));
+        let slowest = timing.slowest_units.first().map(|u| u.duration).unwrap_or_default();
+        for unit in &timing.slowest_units {
+            // Anything over a second stands out on an embedded boot
+            let color = if unit.duration >= Duration::from_secs(1) { colors.warning } else { colors.foreground };
+            lines.push((color, format!(
+                "  {:>10}  {}  {}",
+                format_boot_duration(unit.duration),
+                bar(unit.duration, slowest),
+                unit.name
+            )));
+        }
+        if timing.slowest_units.is_empty() {
+            lines.push((colors.muted, "  (systemd-analyze blame returned nothing)".to_string()));
+        }
+
+        lines
+    }
+}
+
+/// Full-screen results of a deep scan
+pub struct DeepScanView;
+
+impl DeepScanView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        scan: &DeepScan,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ DEEP SCAN took {:.1}s ── Esc/Enter: back ", scan.duration.as_secs_f64());
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        let mut y = area.y + 1;
+        let bottom = area.y + area.height;
+        for (color, text) in Self::build_lines(scan, colors) {
+            if y >= bottom {
+                break;
+            }
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(SetForegroundColor(color))?;
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+            y += 1;
+        }
+
+        while y < bottom {
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(Print(" ".repeat(width)))?;
+            y += 1;
+        }
+
+        Ok(())
+    }
+
+    fn build_lines(scan: &DeepScan, colors: &ColorScheme) -> Vec<(Color, String)> {
+        let mut lines = Vec::new();
+
+        match &scan.disk_usage {
+            Some(usage) => {
+                lines.push((colors.table_header, format!(
+                    "Space used under {}: {} in {} files",
+                    usage.mount_point,
+                    format_bytes(usage.total_bytes),
+                    usage.files
+                )));
+                for (name, bytes) in &usage.largest {
+                    lines.push((colors.foreground, format!("  {:>10}  {}", format_bytes(*bytes), name)));
+                }
+                if usage.unreadable > 0 {
+                    lines.push((colors.muted, format!("  ({} directories could not be read)", usage.unreadable)));
+                }
+            },
+            None => lines.push((colors.muted, "No disk selected to size (pick one in the disk panel)".to_string())),
+        }
+
+        lines.push((colors.table_header, "SMART:".to_string()));
+        for report in &scan.smart {
+            let (color, verdict) = match report.passed {
+                Some(true) => (colors.success, "PASSED".to_string()),
+                Some(false) => (colors.error, "FAILING".to_string()),
+                None => (colors.muted, report.error.clone().unwrap_or_default()),
+            };
+            let temperature = report.temperature.map(|t| format!("  {}", format_temperature(t))).unwrap_or_default();
+            let hours = report.power_on_hours.map(|h| format!("  {}h on", h)).unwrap_or_default();
+            lines.push((color, format!(
+                "  {:<14} {}{}{}  {}",
+                report.device,
+                verdict,
+                temperature,
+                hours,
+                report.model.as_deref().unwrap_or_default()
+            )));
+        }
+        if scan.smart.is_empty() {
+            lines.push((colors.muted, "  (no drives found behind the mounted disks)".to_string()));
+        }
+
+        match &scan.open_files {
+            Some(open) => {
+                lines.push((colors.table_header, format!("Open files: {} in total, most held by:", open.total)));
+                for process in &open.busiest {
+                    lines.push((colors.foreground, format!("  {:>8}  {:<8} {}", process.count, process.pid, process.name)));
+                }
+                if open.unreadable > 0 {
+                    lines.push((colors.muted, format!("  ({} processes not readable; run as root to count them)", open.unreadable)));
+                }
+            },
+            None => lines.push((colors.muted, "Open files: not available on this platform".to_string())),
+        }
+
+        lines
+    }
+}
+
+/// "512ms", "2.345s" or "1min 2.3s"
+fn format_boot_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 1.0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs < 60.0 {
+        format!("{:.3}s", secs)
+    } else {
+        format!("{}min {:.1}s", duration.as_secs() / 60, secs % 60.0)
+    }
+}
@@ -0,0 +1,215 @@
+use crate::input::InputEvent;
+use crate::ui::{pad_or_truncate, ColorScheme, Rect};
+use crossterm::{
+    cursor,
+    style::{Print, SetBackgroundColor, SetForegroundColor},
+    QueueableCommand,
+};
+use std::io::{self, Write};
+
+/// What a dialog asks the user for
+#[derive(Debug, Clone)]
+pub enum DialogKind {
+    /// Yes/no question
+    Confirm,
+    /// Single line of free text
+    TextInput { value: String },
+    /// Pick one entry from a list
+    Select { options: Vec<String>, selected: usize },
+}
+
+/// Result of feeding an input event to a dialog
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogOutcome {
+    /// Still open
+    Pending,
+    /// Text changed (text input only), for live previews
+    Edited(String),
+    Cancelled,
+    Confirmed,
+    Submitted(String),
+    Selected(usize),
+}
+
+/// Modal dialog state; the app owns it and renders it with `DialogWidget`
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    pub title: String,
+    pub message: Option<String>,
+    pub kind: DialogKind,
+}
+
+impl Dialog {
+    pub fn confirm(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: Some(message.into()),
+            kind: DialogKind::Confirm,
+        }
+    }
+
+    pub fn text_input(title: impl Into<String>, initial: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            message: None,
+            kind: DialogKind::TextInput { value: initial.into() },
+        }
+    }
+
+    pub fn select(title: impl Into<String>, options: Vec<String>, selected: usize) -> Self {
+        let selected = selected.min(options.len().saturating_sub(1));
+        Self {
+            title: title.into(),
+            message: None,
+            kind: DialogKind::Select { options, selected },
+        }
+    }
+
+    /// Whether the dialog needs raw characters (the input handler must be in text mode)
+    pub fn wants_text(&self) -> bool {
+        matches!(self.kind, DialogKind::TextInput { .. })
+    }
+
+    pub fn handle_event(&mut self, event: &InputEvent) -> DialogOutcome {
+        match &mut self.kind {
+            // Anything but an explicit yes cancels
+            DialogKind::Confirm => match event {
+                InputEvent::Select | InputEvent::Confirm => DialogOutcome::Confirmed,
+                _ => DialogOutcome::Cancelled,
+            },
+            DialogKind::TextInput { value } => match event {
+                InputEvent::FilterChar(c) => {
+                    value.push(*c);
+                    DialogOutcome::Edited(value.clone())
+                }
+                InputEvent::FilterBackspace => {
+                    value.pop();
+                    DialogOutcome::Edited(value.clone())
+                }
+                InputEvent::Select => DialogOutcome::Submitted(value.clone()),
+                InputEvent::ClearFilter => DialogOutcome::Cancelled,
+                _ => DialogOutcome::Pending,
+            },
+            DialogKind::Select { options, selected } => {
+                let last = options.len().saturating_sub(1);
+                match event {
+                    InputEvent::MoveUp => *selected = selected.saturating_sub(1),
+                    InputEvent::MoveDown => *selected = (*selected + 1).min(last),
+                    InputEvent::Home => *selected = 0,
+                    InputEvent::End => *selected = last,
+                    InputEvent::Select if !options.is_empty() => return DialogOutcome::Selected(*selected),
+                    InputEvent::ClearFilter => return DialogOutcome::Cancelled,
+                    _ => {}
+                }
+                DialogOutcome::Pending
+            }
+        }
+    }
+
+    fn hint(&self) -> &'static str {
+        match self.kind {
+            DialogKind::Confirm => "y/Enter:yes  other:no",
+            DialogKind::TextInput { .. } => "Enter:apply  Esc:clear",
+            DialogKind::Select { .. } => "↑↓:select  Enter:choose  Esc:cancel",
+        }
+    }
+}
+
+/// Centered popup renderer for `Dialog`
+pub struct DialogWidget;
+
+impl DialogWidget {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        dialog: &Dialog,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        let body_rows = match &dialog.kind {
+            DialogKind::Select { options, .. } => options.len(),
+            _ => 1,
+        } + dialog.message.is_some() as usize;
+
+        // Border + title + body + hint
+        let popup_width = 48.min(area.width.saturating_sub(4));
+        let popup_height = (body_rows as u16 + 4).min(area.height.saturating_sub(2));
+        if popup_width < 12 || popup_height < 4 {
+            return Ok(());
+        }
+
+        let popup = Rect::new(
+            area.x + (area.width - popup_width) / 2,
+            area.y + (area.height - popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+        let inner_width = popup.width as usize - 2;
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.border))?;
+        writer.queue(cursor::MoveTo(popup.x, popup.y))?;
+        writer.queue(Print(format!("┌{}┐", "─".repeat(inner_width))))?;
+        for y in popup.y + 1..popup.bottom() - 1 {
+            writer.queue(cursor::MoveTo(popup.x, y))?;
+            writer.queue(Print("│"))?;
+            writer.queue(cursor::MoveTo(popup.right() - 1, y))?;
+            writer.queue(Print("│"))?;
+        }
+        writer.queue(cursor::MoveTo(popup.x, popup.bottom() - 1))?;
+        writer.queue(Print(format!("└{}┘", "─".repeat(inner_width))))?;
+
+        let mut y = popup.y + 1;
+        let last_body_row = popup.bottom() - 2;
+
+        writer.queue(cursor::MoveTo(popup.x + 1, y))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        writer.queue(Print(pad_or_truncate(&format!(" {}", dialog.title), inner_width)))?;
+        y += 1;
+
+        if let Some(message) = &dialog.message {
+            if y < last_body_row {
+                writer.queue(cursor::MoveTo(popup.x + 1, y))?;
+                writer.queue(SetForegroundColor(colors.foreground))?;
+                writer.queue(Print(pad_or_truncate(&format!(" {}", message), inner_width)))?;
+                y += 1;
+            }
+        }
+
+        match &dialog.kind {
+            DialogKind::Confirm => {}
+            DialogKind::TextInput { value } => {
+                // Keep the end of long input visible
+                let field_width = inner_width.saturating_sub(3);
+                let chars: Vec<char> = value.chars().collect();
+                let visible: String = chars[chars.len().saturating_sub(field_width)..].iter().collect();
+                writer.queue(cursor::MoveTo(popup.x + 1, y))?;
+                writer.queue(SetForegroundColor(colors.foreground))?;
+                writer.queue(Print(pad_or_truncate(&format!(" {}_", visible), inner_width)))?;
+            }
+            DialogKind::Select { options, selected } => {
+                let rows = last_body_row.saturating_sub(y) as usize;
+                let start = selected.saturating_sub(rows.saturating_sub(1));
+                for (i, option) in options.iter().enumerate().skip(start).take(rows) {
+                    writer.queue(cursor::MoveTo(popup.x + 1, y))?;
+                    if i == *selected {
+                        writer.queue(SetBackgroundColor(colors.table_selected))?;
+                        writer.queue(SetForegroundColor(colors.background))?;
+                    } else {
+                        writer.queue(SetBackgroundColor(colors.background))?;
+                        writer.queue(SetForegroundColor(colors.foreground))?;
+                    }
+                    writer.queue(Print(pad_or_truncate(&format!("   {}", option), inner_width)))?;
+                    y += 1;
+                }
+                writer.queue(SetBackgroundColor(colors.background))?;
+            }
+        }
+
+        writer.queue(cursor::MoveTo(popup.x + 1, last_body_row))?;
+        writer.queue(SetForegroundColor(colors.muted))?;
+        writer.queue(Print(pad_or_truncate(&format!(" {}", dialog.hint()), inner_width)))?;
+
+        Ok(())
+    }
+}
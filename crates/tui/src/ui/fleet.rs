@@ -0,0 +1,117 @@
+use crate::ui::{pad_or_truncate, ColorScheme, Rect};
+use crossterm::{
+    cursor,
+    style::{Print, SetBackgroundColor, SetForegroundColor},
+    QueueableCommand,
+};
+use kacemon_core::SystemSnapshot;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// One host in the fleet overview
+pub struct FleetRow<'a> {
+    pub name: &'a str,
+    /// Latest snapshot, which may be stale when `error` is set
+    pub snapshot: Option<&'a SystemSnapshot>,
+    pub alert_count: usize,
+    /// Why the last poll failed
+    pub error: Option<&'a str>,
+    /// This machine rather than a remote host
+    pub local: bool,
+    /// Time since the last successful poll of a remote host
+    pub age: Option<Duration>,
+    /// Host currently shown on the main screen
+    pub viewed: bool,
+}
+
+/// Full-screen overview with one row per host
+pub struct FleetView;
+
+impl FleetView {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        rows: &[FleetRow],
+        selected: usize,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 3 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.accent))?;
+        let title = format!("─ FLEET ({} hosts) ── Enter: view host  0-9: switch  Esc: back ", rows.len());
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let header = format!(
+            "{:<4} {:<20} {:<8} {:>6} {:>6} {:>6} {:>6} {:>8}  {}",
+            "KEY", "HOST", "STATUS", "CPU%", "MEM%", "LOAD", "ALERTS", "UPDATED", "NOTE"
+        );
+        writer.queue(Print(pad_or_truncate(&header, width)))?;
+
+        let body_rows = (area.height as usize).saturating_sub(2);
+        let start = selected.saturating_sub(body_rows.saturating_sub(1));
+        for row in 0..body_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + row as u16))?;
+            let Some(host) = rows.get(start + row) else {
+                writer.queue(SetBackgroundColor(colors.background))?;
+                writer.queue(Print(" ".repeat(width)))?;
+                continue;
+            };
+            let index = start + row;
+
+            let (status, status_color) = match (host.snapshot, host.error) {
+                _ if host.local => ("local", colors.success),
+                (Some(_), None) => ("up", colors.success),
+                (Some(_), Some(_)) => ("stale", colors.warning),
+                (None, Some(_)) => ("down", colors.error),
+                (None, None) => ("waiting", colors.muted),
+            };
+            let figures = host.snapshot.map(|snapshot| {
+                format!(
+                    "{:>6.1} {:>6.1} {:>6.2} {:>6}",
+                    snapshot.cpu_average(),
+                    snapshot.memory_percent(),
+                    snapshot.system.load_avg_1,
+                    host.alert_count
+                )
+            });
+            let text = format!(
+                "{:<4} {:<20} {:<8} {:>27} {:>8}  {}",
+                format!("{}{}", index, if host.viewed { "*" } else { "" }),
+                host.name,
+                status,
+                figures.unwrap_or_else(|| "-".to_string()),
+                host.age.map_or_else(|| "-".to_string(), format_age),
+                host.error.unwrap_or_default()
+            );
+
+            if index == selected {
+                writer.queue(SetBackgroundColor(colors.table_selected))?;
+                writer.queue(SetForegroundColor(colors.background))?;
+            } else {
+                writer.queue(SetBackgroundColor(colors.background))?;
+                writer.queue(SetForegroundColor(if host.alert_count > 0 { colors.warning } else { status_color }))?;
+            }
+            writer.queue(Print(pad_or_truncate(&text, width)))?;
+        }
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        Ok(())
+    }
+}
+
+/// "3s ago", "2m ago"
+pub(crate) fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        secs @ 0..=59 => format!("{}s ago", secs),
+        secs @ 60..=3599 => format!("{}m ago", secs / 60),
+        secs => format!("{}h ago", secs / 3600),
+    }
+}
@@ -1,4 +1,5 @@
-use crate::ui::{ColorScheme, Rect};
+use crate::input::{bindings_for, KeyContext};
+use crate::ui::{pad_or_truncate, ColorScheme, Rect};
 use crossterm::{
     cursor,
     style::{Print, SetBackgroundColor, SetForegroundColor},
@@ -7,51 +8,41 @@ use crossterm::{
 use std::io::{self, Write};
 
 /// Help overlay widget
+///
+/// Page 0 lists the bindings for the current context, page 1 the global keys.
 pub struct HelpOverlay;
 
 impl HelpOverlay {
+    pub const PAGES: usize = 2;
+
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
+        context: KeyContext,
+        page: usize,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         // Calculate centered popup area
-        let popup_width = 60.min(area.width - 4);
-        let popup_height = 20.min(area.height - 4);
+        let popup_width = 64.min(area.width.saturating_sub(4));
+        let popup_height = 24.min(area.height.saturating_sub(4));
+        if popup_width < 10 || popup_height < 4 {
+            return Ok(());
+        }
         let popup_x = area.x + (area.width - popup_width) / 2;
         let popup_y = area.y + (area.height - popup_height) / 2;
         
         let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
         // Help content
-        let help_lines = vec![
-            "                    SRMON HELP",
-            "",
-            "Navigation:",
-            "  ↑/k, ↓/j         Move up/down in process list",
-            "  Page Up/Down     Page up/down in process list", 
-            "  Home/End         Go to top/bottom of list",
-            "",
-            "Sorting:",
-            "  s                Cycle sort (CPU% → MEM% → PID → NAME)",
-            "",
-            "Filtering:",
-            "  /                Filter processes by name/command",
-            "  Esc              Clear current filter",
-            "",
-            "Display:",
-            "  c                Toggle column visibility",
-            "  r                Change refresh rate",
-            "  t                Toggle tree view",
-            "",
-            "Process Control:",
-            "  k                Send SIGTERM to selected process",
-            "",
-            "Other:",
-            "  ?                Show this help",
-            "  q, Ctrl+C        Quit application",
+        let page_context = if page == 0 { context } else { KeyContext::Global };
+        let mut help_lines = vec![
+            format!("HELP - {} ({}/{})", page_context.title(), page + 1, Self::PAGES),
+            String::new(),
         ];
+        help_lines.extend(
+            bindings_for(page_context).map(|b| format!("  {:<16} {}", b.keys, b.description))
+        );
 
         // Render popup background
         for y in popup_area.y..popup_area.bottom() {
@@ -76,31 +67,21 @@ impl HelpOverlay {
             // Color the title differently
             if i == 0 {
                 writer.queue(SetForegroundColor(colors.accent))?;
-            } else if line.trim().is_empty() {
-                writer.queue(SetForegroundColor(colors.foreground))?;
-            } else if line.ends_with(':') {
-                writer.queue(SetForegroundColor(colors.table_header))?;
             } else {
                 writer.queue(SetForegroundColor(colors.foreground))?;
             }
 
-            let truncated = if line.len() > content_area.width as usize {
-                &line[..content_area.width as usize]
-            } else {
-                line
-            };
-
-            writer.queue(Print(truncated))?;
+            writer.queue(Print(pad_or_truncate(line, content_area.width as usize)))?;
         }
 
-        // Show how to close help
-        if popup_area.height > 2 {
+        // Show how to page and close help
+        if popup_area.height > 2 && popup_area.width > 26 {
             writer.queue(cursor::MoveTo(
-                popup_area.x + popup_area.width - 20,
+                popup_area.x + popup_area.width - 26,
                 popup_area.y + popup_area.height - 1,
             ))?;
             writer.queue(SetForegroundColor(colors.muted))?;
-            writer.queue(Print("Press ? to close"))?;
+            writer.queue(Print("Tab: next page  ?: close"))?;
         }
 
         Ok(())
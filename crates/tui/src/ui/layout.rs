@@ -51,12 +51,18 @@ impl Rect {
 /// Layout manager for the TUI
 pub struct Layout {
     terminal_rect: Rect,
+    gpu_rows: u16,
 }
 
 impl Layout {
     pub fn new() -> anyhow::Result<Self> {
         let terminal_rect = Rect::from_terminal_size()?;
-        Ok(Self { terminal_rect })
+        Ok(Self { terminal_rect, gpu_rows: 0 })
+    }
+
+    /// Reserve one gauge row per GPU (capped) below the CPU/memory gauges
+    pub fn set_gpu_count(&mut self, count: usize) {
+        self.gpu_rows = count.min(4) as u16;
     }
 
     pub fn update_terminal_size(&mut self) -> anyhow::Result<()> {
@@ -84,6 +90,14 @@ impl Layout {
             gauges_height.min(rect.height.saturating_sub(1).saturating_sub(1)),
         );
         
+        // GPU gauges: one line per device, only when GPUs are present
+        let gpu = Rect::new(
+            rect.x,
+            gauges.bottom(),
+            rect.width,
+            self.gpu_rows.min(rect.height.saturating_sub(gauges.bottom()).saturating_sub(1)),
+        );
+        
         // Footer: keybind hints (1 line)
         let footer = Rect::new(
             rect.x,
@@ -93,44 +107,62 @@ impl Layout {
         );
         
         // Bottom section: split into network (left) and temperature (right)
-        let available_height = footer.y.saturating_sub(gauges.bottom());
+        let available_height = footer.y.saturating_sub(gpu.bottom());
         let bottom_height = (available_height / 3).max(4);
         let bottom_y = footer.y.saturating_sub(bottom_height);
         
-        // Split bottom section vertically (50/50)
-        let bottom_width = rect.width / 2;
+        // Split bottom section vertically (network 40%, disks 30%, temperature 30%)
+        let network_width = rect.width * 2 / 5;
+        let disks_width = (rect.width - network_width) / 2;
         let network = Rect::new(
             rect.x,
             bottom_y,
-            bottom_width,
+            network_width,
+            bottom_height,
+        );
+        
+        let disks = Rect::new(
+            network.right(),
+            bottom_y,
+            disks_width,
             bottom_height,
         );
         
         let temperature = Rect::new(
-            rect.x + bottom_width,
+            disks.right(),
             bottom_y,
-            rect.width - bottom_width,
+            rect.width - network_width - disks_width,
             bottom_height,
         );
         
         // Process table: remaining space between gauges and bottom section
         let table = Rect::new(
             rect.x,
-            gauges.bottom(),
+            gpu.bottom(),
             rect.width,
-            bottom_y.saturating_sub(gauges.bottom()),
+            bottom_y.saturating_sub(gpu.bottom()),
         );
 
         MainLayout {
             top_bar,
             gauges,
+            gpu,
             table,
             network,
+            disks,
             temperature,
             footer,
         }
     }
 
+    /// Split the process table area to make room for the connections panel
+    pub fn connections_layout(&self, table: Rect) -> (Rect, Rect) {
+        let panel_height = (table.height * 2 / 5).max(4).min(table.height);
+        let process_table = Rect::new(table.x, table.y, table.width, table.height - panel_height);
+        let connections = Rect::new(table.x, process_table.bottom(), table.width, panel_height);
+        (process_table, connections)
+    }
+
     /// Calculate layout for gauges section
     pub fn gauges_layout(&self, area: Rect) -> GaugesLayout {
         let width = area.width;
@@ -163,12 +195,25 @@ impl Layout {
                 "PID" => 8,
                 "USER" => 12,
                 "CPU%" => 6,
+                "CHLD%" => 6,
                 "MEM%" => 6,
                 "RSS" => 8,
                 "VSZ" => 8,
+                "PSS" | "USS" => 8,
+                "NET" => 15,
                 "THR" => 4,
+                "FDS" => 6,
+                "PGID" | "SID" | "TTY" => 8,
                 "STATE" => 6,
-                "TIME" => 8,
+                "WCHAN" => 16,
+                "BUSY" => 12,
+                "RST" => 4,
+                "TIME" => 9,
+                "AGE" => 7,
+                "START" => 10,
+                "CAT" => 10,
+                "CONTAINER" => 12,
+                "POD" => 24,
                 "NAME" => 20, // This will expand to fill remaining space
                 _ => 10,
             }
@@ -213,8 +258,10 @@ impl Layout {
 pub struct MainLayout {
     pub top_bar: Rect,
     pub gauges: Rect,
+    pub gpu: Rect,
     pub table: Rect,
     pub network: Rect,
+    pub disks: Rect,
     pub temperature: Rect,
     pub footer: Rect,
 }
@@ -1,9 +1,17 @@
 pub mod colors;
+pub mod copy;
+pub mod detail;
+pub mod dialog;
+pub mod fleet;
 pub mod help;
 pub mod layout;
 pub mod widgets;
 
 pub use colors::ColorScheme;
+pub use copy::{CopyText, CopyView};
+pub use dialog::{Dialog, DialogKind, DialogOutcome, DialogWidget};
+pub use detail::{BootTimeView, DeepScanView, DiskDetailView, DiskSample, LogFeedView, NetworkDetailView, NetworkSample, ProcessDetailView, ServiceListView, ThreadListView};
+pub use fleet::{FleetRow, FleetView};
 pub use help::HelpOverlay;
 pub use layout::{Layout, Rect};
 pub use widgets::*;
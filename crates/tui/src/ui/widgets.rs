@@ -1,21 +1,62 @@
 use crate::ui::{ColorScheme, Rect};
 use crossterm::{
     cursor,
-    style::{Print, SetBackgroundColor, SetForegroundColor},
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
     QueueableCommand,
 };
-use kacemon_core::{CpuCore, MemoryInfo, NetworkInfo, ProcessInfo, SystemInfo, TemperatureInfo};
-use std::io::{self, Write};
+use kacemon_core::{
+    CategoryUsage, CollectorFailure, ConnectionInfo, CpuCore, DiskInfo, GpuInfo, LeakSuspect, MemoryInfo, NetworkInfo, ProcessFilter, ProcessInfo, ProcessState, RunQueueLatency, SocketState,
+    SystemInfo, TemperatureInfo,
+};
+use std::{collections::HashMap, io::{self, Write}, time::{Duration, SystemTime}};
+
+/// Per-second rates of the kernel's event counters, where they're readable (Linux)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KernelRates {
+    /// Processes created
+    pub forks: Option<f64>,
+    pub context_switches: Option<f64>,
+    pub interrupts: Option<f64>,
+}
+
+/// Process totals by state for the top bar, with the kernel's event rates
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessCounts {
+    pub total: usize,
+    pub running: usize,
+    pub sleeping: usize,
+    pub zombies: usize,
+    pub rates: KernelRates,
+}
+
+impl ProcessCounts {
+    pub fn count(processes: &[ProcessInfo], rates: KernelRates) -> Self {
+        let mut counts = Self { total: processes.len(), rates, ..Self::default() };
+        for process in processes {
+            match process.state {
+                ProcessState::Running => counts.running += 1,
+                ProcessState::Sleeping | ProcessState::Waiting | ProcessState::DiskSleep => counts.sleeping += 1,
+                ProcessState::Zombie => counts.zombies += 1,
+                _ => {},
+            }
+        }
+        counts
+    }
+}
 
 /// Top bar widget showing system information
 pub struct TopBar;
 
 impl TopBar {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
         system_info: &SystemInfo,
+        processes: Option<&ProcessCounts>,
+        pressure: Option<(f32, &[f64])>,  // (current score, history)
+        alert_count: usize,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height == 0 {
@@ -48,18 +89,67 @@ impl TopBar {
             now.as_secs() % 60
         );
 
+        // A fork bomb shows up here as a climbing fork rate long before the table catches up
+        let tasks_str = processes.map_or_else(String::new, |counts| {
+            let forks = counts.rates.forks.map_or_else(String::new, |rate| format!(" | Forks: {:.0}/s", rate));
+            format!(
+                " | Tasks: {} ({} run, {} slp, {} zmb){}",
+                counts.total, counts.running, counts.sleeping, counts.zombies, forks
+            )
+        });
+        // Scheduler churn and interrupt storms, which load and CPU% only hint at
+        let events_str = processes.map_or_else(String::new, |counts| {
+            let rates = counts.rates;
+            let mut events = String::new();
+            if let Some(rate) = rates.context_switches {
+                events += &format!(" | Ctxsw: {}/s", format_count(rate));
+            }
+            if let Some(rate) = rates.interrupts {
+                events += &format!(" | Intr: {}/s", format_count(rate));
+            }
+            events
+        });
+
         let content = format!(
-            "{} | {} {} | Up: {} | {} | {}",
+            "{} | {} {} | Up: {} | {}{}{} | {}",
             system_info.hostname,
             system_info.os_name,
             system_info.os_version,
             uptime_str,
             load_str,
+            tasks_str,
+            events_str,
             time_str
         );
 
+        // Alert badge leads the line so it is never truncated away
+        let badge = if alert_count > 0 {
+            format!(" ⚠ {} ALERT{} ", alert_count, if alert_count == 1 { "" } else { "S" })
+        } else {
+            String::new()
+        };
+        let badge_width = badge.chars().count();
+        if alert_count > 0 {
+            writer.queue(SetForegroundColor(colors.background))?;
+            writer.queue(SetBackgroundColor(colors.error))?;
+            writer.queue(Print(&badge))?;
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            writer.queue(Print(" "))?;
+        }
+        let badge_width = if alert_count > 0 { badge_width + 1 } else { 0 };
+
+        // Pressure score and its trend sit on the right when there is room
+        let pressure_label = pressure.map(|(score, _)| format!(" PRESSURE {:3.0} ", score));
+        let spark_width = 20;
+        let right_width = pressure_label
+            .as_ref()
+            .map(|label| label.len() + spark_width)
+            .filter(|&width| width + 40 <= area.width as usize)
+            .unwrap_or(0);
+
         // Truncate if too long
-        let max_width = area.width as usize;
+        let max_width = (area.width as usize).saturating_sub(right_width + badge_width);
         let truncated = if content.len() > max_width {
             format!("{}...", &content[..max_width.saturating_sub(3)])
         } else {
@@ -67,6 +157,14 @@ impl TopBar {
         };
 
         writer.queue(Print(truncated))?;
+
+        if let (Some(label), Some((score, history))) = (pressure_label.filter(|_| right_width > 0), pressure) {
+            writer.queue(SetForegroundColor(colors.background))?;
+            writer.queue(SetBackgroundColor(colors.gradient(score)))?;
+            writer.queue(Print(&label))?;
+            let spark_area = Rect::new(area.right() - spark_width as u16, area.y, spark_width as u16, 1);
+            HistoryChart.render(writer, spark_area, history, Some(100.0), colors.gradient(score), colors)?;
+        }
         Ok(())
     }
 }
@@ -74,7 +172,24 @@ impl TopBar {
 /// Gauge widget for displaying usage percentages
 pub struct Gauge;
 
+/// Warning and critical limits marked on a gauge bar, as percentages
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GaugeMarks {
+    pub warning: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+impl GaugeMarks {
+    pub fn new(warning: f32, critical: f32) -> Self {
+        Self {
+            warning: Some(warning.min(critical)),
+            critical: Some(critical),
+        }
+    }
+}
+
 impl Gauge {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
@@ -82,6 +197,8 @@ impl Gauge {
         title: &str,
         percentage: f32,
         label: &str,
+        marks: GaugeMarks,
+        alert: bool,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height < 2 || area.width < 3 {
@@ -90,34 +207,15 @@ impl Gauge {
 
         let percentage = percentage.clamp(0.0, 100.0);
         let gauge_width = (area.width as usize).saturating_sub(2); // Account for borders
-        let fill_width = ((percentage / 100.0) * gauge_width as f32) as usize;
 
-        // Title line
-        writer.queue(cursor::MoveTo(area.x, area.y))?;
-        writer.queue(SetForegroundColor(colors.table_header))?;
-        writer.queue(SetBackgroundColor(colors.background))?;
-        writer.queue(Print(format!("{:width$}", title, width = area.width as usize)))?;
+        gauge_title(writer, area, title, alert, colors)?;
 
         // Gauge line
         if area.height > 1 {
             writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
             writer.queue(SetForegroundColor(colors.foreground))?;
             writer.queue(Print("["))?;
-
-            // Fill portion
-            writer.queue(SetBackgroundColor(colors.gauge_fill))?;
-            writer.queue(SetForegroundColor(colors.background))?;
-            for _ in 0..fill_width {
-                writer.queue(Print("█"))?;
-            }
-
-            // Empty portion
-            writer.queue(SetBackgroundColor(colors.gauge_bg))?;
-            writer.queue(SetForegroundColor(colors.muted))?;
-            for _ in fill_width..gauge_width {
-                writer.queue(Print("░"))?;
-            }
-
+            gauge_bar(writer, gauge_width, percentage, marks, colors)?;
             writer.queue(SetBackgroundColor(colors.background))?;
             writer.queue(SetForegroundColor(colors.foreground))?;
             writer.queue(Print("]"))?;
@@ -140,15 +238,84 @@ impl Gauge {
     }
 }
 
+/// Title line of a gauge, inverted while an alert is flashing
+fn gauge_title<W: Write>(writer: &mut W, area: Rect, title: &str, alert: bool, colors: &ColorScheme) -> io::Result<()> {
+    writer.queue(cursor::MoveTo(area.x, area.y))?;
+    if alert {
+        writer.queue(SetForegroundColor(colors.background))?;
+        writer.queue(SetBackgroundColor(colors.error))?;
+        writer.queue(Print(format!("{} ", title)))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(Print(" ".repeat((area.width as usize).saturating_sub(title.len() + 1))))?;
+    } else {
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(Print(format!("{:width$}", title, width = area.width as usize)))?;
+    }
+    Ok(())
+}
+
+/// One gauge bar `width` cells wide. The fill is shaded along its length with truecolor and
+/// otherwise takes the color of the limit the value has passed; quarter ticks dot the empty
+/// part and the warning and critical limits are drawn as lines across both.
+pub(crate) fn gauge_bar<W: Write>(
+    writer: &mut W,
+    width: usize,
+    percentage: f32,
+    marks: GaugeMarks,
+    colors: &ColorScheme,
+) -> io::Result<()> {
+    let percentage = percentage.clamp(0.0, 100.0);
+    let cell = |percent: f32| (((percent.clamp(0.0, 100.0) / 100.0) * width as f32) as usize).min(width.saturating_sub(1));
+    let fill = ((percentage / 100.0) * width as f32) as usize;
+    let warning = marks.warning.map(cell);
+    let critical = marks.critical.map(cell);
+    // Ticks only help once there's room between them
+    let ticks: Vec<usize> = if width >= 20 { [25.0, 50.0, 75.0].into_iter().map(cell).collect() } else { Vec::new() };
+    let level = if marks.critical.is_some_and(|limit| percentage >= limit) {
+        colors.error
+    } else if marks.warning.is_some_and(|limit| percentage >= limit) {
+        colors.warning
+    } else {
+        colors.gauge_fill
+    };
+    
+    for i in 0..width {
+        let (glyph, color, background) = if Some(i) == critical {
+            ("│", colors.error, colors.background)
+        } else if Some(i) == warning {
+            ("│", colors.warning, colors.background)
+        } else if i < fill && colors.truecolor {
+            ("█", colors.gradient(i as f32 * 100.0 / width as f32), colors.background)
+        } else if i < fill {
+            ("█", level, colors.background)
+        } else if ticks.contains(&i) {
+            ("┊", colors.muted, colors.gauge_bg)
+        } else {
+            ("░", colors.muted, colors.gauge_bg)
+        };
+        writer.queue(SetBackgroundColor(background))?;
+        writer.queue(SetForegroundColor(color))?;
+        writer.queue(Print(glyph))?;
+    }
+    Ok(())
+}
+
 /// CPU gauges widget
 pub struct CpuGauges;
 
 impl CpuGauges {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
         cpu_cores: &[CpuCore],
+        history: &[f64],
+        run_queue: Option<&RunQueueLatency>,
+        show_frequency: bool,
+        marks: GaugeMarks,
+        alert: bool,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if cpu_cores.is_empty() || area.height < 2 {
@@ -156,19 +323,34 @@ impl CpuGauges {
         }
 
         // Calculate overall CPU usage
-        let overall_usage = cpu_cores.iter().map(|c| c.usage_percent).sum::<f32>() / cpu_cores.len() as f32;
+        let overall_usage = CpuCore::average(cpu_cores);
 
         // Render overall CPU gauge
         let gauge = Gauge;
-        let cores_info = format!("{} cores", cpu_cores.len());
-        gauge.render(writer, area, "CPU", overall_usage, &cores_info, colors)?;
+        // Run-queue delay shows contention that utilization alone hides
+        let mut cores_info = match run_queue {
+            Some(rq) => format!(
+                "{} cores  rq p50 {} p99 {}",
+                cpu_cores.len(),
+                format_micros(rq.p50_us),
+                format_micros(rq.p99_us)
+            ),
+            None => format!("{} cores", cpu_cores.len()),
+        };
+        cores_info += &frequency_summary(cpu_cores, show_frequency);
+        gauge.render(writer, area, "CPU", overall_usage, &cores_info, marks, alert, colors)?;
+        title_sparkline(writer, area, "CPU".len(), history, Some(100.0), colors.cpu_usage_color(overall_usage), colors)?;
 
         // If we have space, show individual core usage in a compact format
         if area.height > 3 && cpu_cores.len() <= 16 {
             writer.queue(cursor::MoveTo(area.x, area.y + 3))?;
             writer.queue(SetForegroundColor(colors.muted))?;
             
-            let cores_per_line = (area.width as usize) / 8; // "C0:100% "
+            // Clocks too where every core still fits: "C0:100% 2.4G "
+            let lines = area.bottom().saturating_sub(area.y + 3) as usize;
+            let with_frequency = cpu_cores.iter().any(|core| core.frequency > 0)
+                && cpu_cores.len() <= (area.width as usize / 13) * lines;
+            let cores_per_line = (area.width as usize) / if with_frequency { 13 } else { 8 }; // "C0:100% "
             for (i, core) in cpu_cores.iter().enumerate() {
                 if i > 0 && i % cores_per_line == 0 && (area.y + 3 + (i / cores_per_line) as u16) < area.bottom() {
                     writer.queue(cursor::MoveTo(area.x, area.y + 3 + (i / cores_per_line) as u16))?;
@@ -177,7 +359,11 @@ impl CpuGauges {
                 let usage_color = colors.cpu_usage_color(core.usage_percent);
                 writer.queue(SetForegroundColor(usage_color))?;
                 writer.queue(Print(format!("C{}:{:3.0}% ", core.id, core.usage_percent)))?;
-                writer.queue(SetForegroundColor(colors.muted))?;
+                writer.queue(SetForegroundColor(if core.throttled() { colors.warning } else { colors.muted }))?;
+                if with_frequency {
+                    writer.queue(Print(format!("{:.1}G ", core.frequency as f64 / 1000.0)))?;
+                    writer.queue(SetForegroundColor(colors.muted))?;
+                }
             }
         }
 
@@ -185,45 +371,276 @@ impl CpuGauges {
     }
 }
 
+/// "  base 2.4 max 4.2GHz", led by the average clock with `average` ("  2.9GHz (base 2.4 max
+/// 4.2)"); the highest of any core where hybrid CPUs' cores differ
+fn frequency_summary(cores: &[CpuCore], average: bool) -> String {
+    let ghz = |mhz: u64| format!("{:.1}", mhz as f64 / 1000.0);
+    let base = cores.iter().filter_map(|core| core.base_frequency).max();
+    let max = cores.iter().filter_map(|core| core.max_frequency).max();
+    let limits = match (base, max) {
+        (Some(base), Some(max)) => format!("base {} max {}", ghz(base), ghz(max)),
+        (Some(base), None) => format!("base {}", ghz(base)),
+        (None, Some(max)) => format!("max {}", ghz(max)),
+        (None, None) => String::new(),
+    };
+    let clocked: Vec<u64> = cores.iter().map(|core| core.frequency).filter(|&mhz| mhz > 0).collect();
+    if average && !clocked.is_empty() {
+        let mean = clocked.iter().sum::<u64>() / clocked.len() as u64;
+        if limits.is_empty() {
+            format!("  {}GHz", ghz(mean))
+        } else {
+            format!("  {}GHz ({})", ghz(mean), limits)
+        }
+    } else if limits.is_empty() {
+        String::new()
+    } else {
+        format!("  {}GHz", limits)
+    }
+}
+
+/// Swap-in plus swap-out, in pages per second, past which swapping shows as heavy
+const SWAP_BUSY_PAGES: u64 = 256;
+
 /// Memory gauges widget
 pub struct MemoryGauges;
 
 impl MemoryGauges {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
         memory: &MemoryInfo,
+        history: &[f64],
+        interval_secs: f64,
+        marks: GaugeMarks,
+        alert: bool,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height < 2 {
             return Ok(());
         }
 
-        let memory_usage = if memory.total > 0 {
-            (memory.used as f32 / memory.total as f32) * 100.0
+        let memory_usage = memory.used_percent() as f32;
+
+        // Used alone overstates it on Linux, where the page cache takes whatever is free,
+        // so the bar shows what buffers and cache hold too
+        let [used, buffers, cached, free] = memory_segments(memory);
+        let level = if marks.critical.is_some_and(|limit| memory_usage >= limit) {
+            colors.error
+        } else if marks.warning.is_some_and(|limit| memory_usage >= limit) {
+            colors.warning
         } else {
-            0.0
+            colors.gauge_fill
         };
+        let segments = [
+            ("used", used, '█', level),
+            ("buf", buffers, '▓', colors.accent),
+            ("cache", cached, '▒', colors.success),
+            ("free", free, '░', colors.muted),
+        ];
 
-        let memory_label = format!("{} / {}", 
-            format_bytes(memory.used), 
-            format_bytes(memory.total)
-        );
+        gauge_title(writer, area, "Memory", alert, colors)?;
+        title_sparkline(writer, area, "Memory".len(), history, Some(100.0), colors.gauge_fill, colors)?;
 
-        let gauge = Gauge;
-        gauge.render(writer, area, "Memory", memory_usage, &memory_label, colors)?;
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.foreground))?;
+        writer.queue(Print("["))?;
+        stacked_bar(writer, (area.width as usize).saturating_sub(2), &segments, marks, colors)?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.foreground))?;
+        writer.queue(Print("]"))?;
+
+        // Legend: each segment's glyph, name and size, as many as fit
+        if area.height > 2 {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2))?;
+            let mut room = area.width as usize;
+            let head = format!("{:.1}%", memory_usage);
+            if head.chars().count() <= room {
+                writer.queue(SetForegroundColor(colors.muted))?;
+                writer.queue(Print(&head))?;
+                room -= head.chars().count();
+            }
+            for (name, bytes, glyph, color) in segments {
+                let text = format!(" {} {}", name, format_bytes(bytes));
+                if text.chars().count() + 1 > room {
+                    break;
+                }
+                writer.queue(SetForegroundColor(colors.muted))?;
+                writer.queue(Print(" "))?;
+                writer.queue(SetForegroundColor(color))?;
+                writer.queue(Print(glyph))?;
+                writer.queue(SetForegroundColor(colors.muted))?;
+                writer.queue(Print(&text[1..]))?;
+                room -= text.chars().count() + 1;
+            }
+            writer.queue(Print(" ".repeat(room)))?;
+        }
 
-        // Show swap if available
+        // Swap on one line: how full it is, then whether pages are moving right now. A full
+        // swap nobody touches is harmless; steady swap-in is what makes a box crawl.
         if area.height > 3 && memory.swap_total > 0 {
-            let swap_usage = (memory.swap_used as f32 / memory.swap_total as f32) * 100.0;
-            let swap_label = format!("{} / {}", 
-                format_bytes(memory.swap_used), 
-                format_bytes(memory.swap_total)
+            let swap_usage = memory.swap_percent() as f32;
+            let per_sec = |pages: u64| (pages as f64 / interval_secs.max(0.001)).round() as u64;
+            let (swap_in, swap_out) = (per_sec(memory.swap_in_pages), per_sec(memory.swap_out_pages));
+            let (indicator, activity, activity_color) = match swap_in + swap_out {
+                0 => ("○", "idle".to_string(), colors.muted),
+                rate => (
+                    "●",
+                    format!("in {}/s out {}/s", swap_in, swap_out),
+                    if rate >= SWAP_BUSY_PAGES { colors.error } else { colors.warning },
+                ),
+            };
+            let usage = format!(" {:.0}% of {} ", swap_usage, format_bytes(memory.swap_total));
+            let tail_width = usage.len() + 2 + activity.len();
+            let bar_width = (area.width as usize).saturating_sub("Swap [".len() + "]".len() + tail_width);
+
+            writer.queue(cursor::MoveTo(area.x, area.y + 3))?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            writer.queue(SetForegroundColor(colors.table_header))?;
+            writer.queue(Print("Swap "))?;
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(Print("["))?;
+            gauge_bar(writer, bar_width, swap_usage, marks, colors)?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(Print("]"))?;
+            writer.queue(SetForegroundColor(colors.muted))?;
+            writer.queue(Print(&usage))?;
+            writer.queue(SetForegroundColor(activity_color))?;
+            writer.queue(Print(format!("{} {}", indicator, activity)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Memory split into used, buffers, cached and free, summing to the total. Buffers and
+/// cache are taken out of what isn't used, since the estimates on some platforms overlap.
+pub(crate) fn memory_segments(memory: &MemoryInfo) -> [u64; 4] {
+    let used = memory.used.min(memory.total);
+    let buffers = memory.buffers.min(memory.total - used);
+    let cached = memory.cached.min(memory.total - used - buffers);
+    [used, buffers, cached, memory.total - used - buffers - cached]
+}
+
+/// Gauge bar split into consecutive segments, each its size's share of the bar in its own
+/// glyph and color. The last segment is the empty part: it gets the quarter ticks, and the
+/// warning and critical limits are drawn across the whole bar as in `gauge_bar`.
+fn stacked_bar<W: Write>(
+    writer: &mut W,
+    width: usize,
+    segments: &[(&str, u64, char, crossterm::style::Color)],
+    marks: GaugeMarks,
+    colors: &ColorScheme,
+) -> io::Result<()> {
+    let total: u64 = segments.iter().map(|s| s.1).sum();
+    let cell = |percent: f32| (((percent.clamp(0.0, 100.0) / 100.0) * width as f32) as usize).min(width.saturating_sub(1));
+    let warning = marks.warning.map(cell);
+    let critical = marks.critical.map(cell);
+    let ticks: Vec<usize> = if width >= 20 { [25.0, 50.0, 75.0].into_iter().map(cell).collect() } else { Vec::new() };
+
+    // Where each segment ends, rounded from the running total so the cells add up to the width
+    let mut running = 0;
+    let ends: Vec<usize> = segments
+        .iter()
+        .map(|segment| {
+            running += segment.1;
+            if total > 0 { (running as f64 / total as f64 * width as f64).round() as usize } else { 0 }
+        })
+        .collect();
+
+    for i in 0..width {
+        let index = ends.iter().position(|&end| i < end).unwrap_or(segments.len().saturating_sub(1));
+        let (_, _, glyph, color) = segments[index];
+        let empty = index + 1 == segments.len();
+        let (glyph, color, background) = if Some(i) == critical {
+            ('│', colors.error, colors.background)
+        } else if Some(i) == warning {
+            ('│', colors.warning, colors.background)
+        } else if empty && ticks.contains(&i) {
+            ('┊', colors.muted, colors.gauge_bg)
+        } else if empty {
+            (glyph, color, colors.gauge_bg)
+        } else {
+            (glyph, color, colors.background)
+        };
+        writer.queue(SetBackgroundColor(background))?;
+        writer.queue(SetForegroundColor(color))?;
+        writer.queue(Print(glyph))?;
+    }
+    Ok(())
+}
+
+/// Per-GPU gauge rows, one line per device
+pub struct GpuGauges;
+
+impl GpuGauges {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        gpus: &[GpuInfo],
+        focused: usize,
+        focused_history: &[f64],
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height == 0 || gpus.is_empty() {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        // Keep the focused GPU visible when there are more devices than rows
+        let rows = area.height as usize;
+        let start = focused.saturating_sub(rows.saturating_sub(1)).min(gpus.len().saturating_sub(rows));
+
+        for (row, gpu) in gpus.iter().enumerate().skip(start).take(rows) {
+            let y = area.y + (row - start) as u16;
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+
+            let is_focused = row == focused;
+            let marker = if is_focused { "▶" } else { " " };
+            let name: String = gpu.name.chars().take(18).collect();
+            let label = format!("{}GPU{} {:<18} ", marker, gpu.index, name);
+            writer.queue(SetForegroundColor(if is_focused { colors.highlight } else { colors.table_header }))?;
+            writer.queue(Print(&label))?;
+
+            let temp = gpu.temperature
+                .map(|t| format!(" {}", format_temperature(t)))
+                .unwrap_or_default();
+            let stats = format!(
+                " {:5.1}% {}/{}{}",
+                gpu.utilization_percent,
+                format_bytes(gpu.memory_used),
+                format_bytes(gpu.memory_total),
+                temp
             );
 
-            let swap_area = Rect::new(area.x, area.y + 3, area.width, 1);
-            gauge.render(writer, swap_area, "Swap", swap_usage, &swap_label, colors)?;
+            // Bar takes whatever space is left between label and stats, minus a
+            // utilization sparkline on the focused row
+            let free = width.saturating_sub(label.chars().count() + stats.chars().count() + 2);
+            let spark_width = if is_focused && !focused_history.is_empty() && free >= 6 { (free / 3).min(30) } else { 0 };
+            let bar_width = free - spark_width;
+
+            if spark_width > 0 {
+                let spark_area = Rect::new(area.x + label.chars().count() as u16, y, spark_width as u16 - 1, 1);
+                HistoryChart.render(writer, spark_area, focused_history, Some(100.0), colors.accent, colors)?;
+                writer.queue(Print(" "))?;
+            }
+
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(Print("["))?;
+            // Same limits as the CPU colors
+            gauge_bar(writer, bar_width, gpu.utilization_percent, GaugeMarks::new(70.0, 90.0), colors)?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(Print("]"))?;
+            writer.queue(SetForegroundColor(colors.muted))?;
+
+            let used = label.chars().count() + spark_width + bar_width + 2;
+            writer.queue(Print(pad_or_truncate(&stats, width.saturating_sub(used))))?;
         }
 
         Ok(())
@@ -234,14 +651,21 @@ impl MemoryGauges {
 pub struct ProcessTable;
 
 impl ProcessTable {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
-        processes: &[ProcessInfo],
+        processes: &[&ProcessInfo],
         columns: &[&str],
         selected_index: usize,
         start_index: usize,
+        sort: Option<(&str, bool)>,  // (sorted column, descending)
+        hidden: usize,  // matching processes left out by the display limit
+        alert_pids: &[u32],
+        fuzzy: Option<&ProcessFilter>,  // fuzzy query whose matched letters are highlighted in NAME
+        pid_marks: &[(u32, char)],  // followed and watched PIDs, with the glyph after each
+        totals: &TableTotals,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height < 2 {
@@ -249,7 +673,7 @@ impl ProcessTable {
         }
 
         // Calculate column layout
-        let layout = crate::ui::Layout::new().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let layout = crate::ui::Layout::new().map_err(std::io::Error::other)?;
         let column_rects = layout.table_layout(area, columns);
 
         // Render header
@@ -261,19 +685,37 @@ impl ProcessTable {
             if i < column_rects.len() {
                 let rect = column_rects[i];
                 writer.queue(cursor::MoveTo(rect.x, rect.y))?;
-                let header = format!("{:width$}", column, width = rect.width as usize);
-                writer.queue(Print(header))?;
+                let header = match sort {
+                    Some((sorted, descending)) if sorted == *column => {
+                        writer.queue(SetForegroundColor(colors.highlight))?;
+                        format!("{}{}", column, if descending { "▼" } else { "▲" })
+                    }
+                    _ => {
+                        writer.queue(SetForegroundColor(colors.table_header))?;
+                        column.to_string()
+                    }
+                };
+                writer.queue(Print(pad_or_truncate(&header, rect.width as usize)))?;
+            }
+        }
+        if hidden > 0 {
+            let note = format!(" +{} more ", hidden);
+            if note.len() < area.width as usize {
+                writer.queue(cursor::MoveTo(area.x + area.width - note.len() as u16, area.y))?;
+                writer.queue(SetForegroundColor(colors.muted))?;
+                writer.queue(Print(note))?;
             }
         }
 
         // Render process rows
-        let visible_rows = (area.height as usize).saturating_sub(1); // Subtract header
+        let visible_rows = table_body_rows(area.height);
         let end_index = (start_index + visible_rows).min(processes.len());
 
         for (row_idx, process) in processes[start_index..end_index].iter().enumerate() {
             let y = area.y + 1 + row_idx as u16;
             let is_selected = start_index + row_idx == selected_index;
             let is_alternate = row_idx % 2 == 1;
+            let is_alert = alert_pids.contains(&process.pid);
 
             // Set row background
             if is_selected {
@@ -293,45 +735,78 @@ impl ProcessTable {
                     writer.queue(cursor::MoveTo(rect.x, y))?;
 
                     let content = match column {
-                        "PID" => process.pid.to_string(),
-                        "NAME" => process.name.clone(),
-                        "USER" => process.user.clone(),
-                        "CPU%" => format!("{:5.1}", process.cpu_percent),
-                        "MEM%" => format!("{:5.1}", process.memory_percent),
-                        "RSS" => format_bytes(process.memory_rss),
-                        "VSZ" => format_bytes(process.memory_vsz),
-                        "THR" => process.threads.to_string(),
-                        "STATE" => format!("{:?}", process.state),
-                        "TIME" => {
-                            let elapsed = std::time::SystemTime::now()
-                                .duration_since(process.start_time)
-                                .unwrap_or_default();
-                            format!("{:02}:{:02}", elapsed.as_secs() / 60, elapsed.as_secs() % 60)
+                        "PID" => {
+                            let marks: String = pid_marks.iter().filter(|(pid, _)| *pid == process.pid).map(|(_, mark)| mark).collect();
+                            format!("{}{}", process.pid, marks)
                         },
-                        _ => String::new(),
+                        _ => process_cell(process, column),
                     };
 
                     // Apply column-specific colors
+                    let mut color = if is_selected { colors.background } else { colors.foreground };
                     if !is_selected {
-                        match column {
-                            "STATE" => { writer.queue(SetForegroundColor(colors.process_state_color(&process.state)))?; },
-                            "CPU%" if process.cpu_percent > 50.0 => { writer.queue(SetForegroundColor(colors.warning))?; },
-                            "MEM%" if process.memory_percent > 50.0 => { writer.queue(SetForegroundColor(colors.warning))?; },
-                            _ => {}
-                        }
+                        color = match column {
+                            "STATE" => colors.process_state_color(&process.state),
+                            "CAT" => process.category.as_deref().map_or(colors.muted, |c| colors.category_color(c)),
+                            _ if is_alert => colors.error,
+                            "CPU%" if process.cpu_percent > 50.0 => colors.warning,
+                            "MEM%" if process.memory_percent > 50.0 => colors.warning,
+                            _ => colors.foreground,
+                        };
+                        writer.queue(SetForegroundColor(color))?;
                     }
 
-                    let truncated = if content.len() > rect.width as usize {
-                        format!("{}...", &content[..rect.width as usize - 3])
-                    } else {
-                        format!("{:width$}", content, width = rect.width as usize)
-                    };
-
-                    writer.queue(Print(truncated))?;
+                    let truncated = pad_or_truncate(&content, rect.width as usize);
+
+                    match fuzzy.filter(|_| column == "NAME") {
+                        Some(filter) => {
+                            let highlights = filter.highlights(&process.name);
+                            // Letters cut off by the "..." stay plain
+                            let length = content.chars().count();
+                            let shown = if length > rect.width as usize { rect.width as usize - 3 } else { length };
+                            for (i, ch) in truncated.chars().enumerate() {
+                                let matched = i < shown && highlights.contains(&i);
+                                writer.queue(SetForegroundColor(if matched { colors.highlight } else { color }))?;
+                                writer.queue(Print(ch))?;
+                            }
+                        },
+                        None => {
+                            writer.queue(Print(truncated))?;
+                        },
+                    }
                 }
             }
         }
 
+        // Blank the rows a shorter list no longer reaches
+        writer.queue(SetBackgroundColor(colors.background))?;
+        for row_idx in end_index.saturating_sub(start_index)..visible_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 1 + row_idx as u16))?;
+            writer.queue(Print(" ".repeat(area.width as usize)))?;
+        }
+
+        // Totals stay on the last line however far the list scrolls
+        if visible_rows + 1 < area.height as usize {
+            let y = area.y + area.height - 1;
+            writer.queue(SetBackgroundColor(colors.table_row_alt))?;
+            writer.queue(SetForegroundColor(colors.table_header))?;
+            writer.queue(cursor::MoveTo(area.x, y))?;
+            writer.queue(Print(" ".repeat(area.width as usize)))?;
+            for (column, rect) in columns.iter().zip(&column_rects) {
+                let content = match *column {
+                    "PID" => "Σ".to_string(),
+                    "NAME" => format!("{} process{}", totals.count, if totals.count == 1 { "" } else { "es" }),
+                    "CPU%" => format!("{:5.1}", totals.cpu_percent),
+                    "MEM%" => format!("{:5.1}", totals.memory_percent),
+                    "RSS" => format_bytes(totals.memory_rss),
+                    "PSS" => format_bytes(totals.memory_pss),
+                    _ => continue,
+                };
+                writer.queue(cursor::MoveTo(rect.x, y))?;
+                writer.queue(Print(pad_or_truncate(&content, rect.width as usize)))?;
+            }
+        }
+
         // Reset colors
         writer.queue(SetBackgroundColor(colors.background))?;
         writer.queue(SetForegroundColor(colors.foreground))?;
@@ -340,6 +815,181 @@ impl ProcessTable {
     }
 }
 
+/// Sums over every process the table's filters let through, for its totals line
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TableTotals {
+    pub count: usize,
+    pub cpu_percent: f32,
+    pub memory_percent: f32,
+    pub memory_rss: u64,
+    /// Unlike RSS, counts memory shared between processes once
+    pub memory_pss: u64,
+}
+
+impl<'a> FromIterator<&'a ProcessInfo> for TableTotals {
+    fn from_iter<I: IntoIterator<Item = &'a ProcessInfo>>(processes: I) -> Self {
+        processes.into_iter().fold(Self::default(), |totals, process| Self {
+            count: totals.count + 1,
+            cpu_percent: totals.cpu_percent + process.cpu_percent,
+            memory_percent: totals.memory_percent + process.memory_percent,
+            memory_rss: totals.memory_rss + process.memory_rss,
+            memory_pss: totals.memory_pss + process.memory_pss.unwrap_or(0),
+        })
+    }
+}
+
+/// Process rows that fit under the header, leaving the last line to the totals when
+/// there's room for both
+pub(crate) fn table_body_rows(height: u16) -> usize {
+    match height {
+        0..=2 => (height as usize).saturating_sub(1),
+        _ => height as usize - 2,
+    }
+}
+
+/// Text shown for one process in the given table column
+pub(crate) fn process_cell(process: &ProcessInfo, column: &str) -> String {
+    match column {
+        "PID" => process.pid.to_string(),
+        "NAME" => process.name.clone(),
+        "CAT" => process.category.clone().unwrap_or_default(),
+        "CONTAINER" => process.container_id.clone().unwrap_or_else(|| "-".to_string()),
+        "POD" => process.pod.as_ref().map_or_else(|| "-".to_string(), |pod| pod.to_string()),
+        "USER" => process.user.clone(),
+        "CPU%" => format!("{:5.1}", process.cpu_percent),
+        "CHLD%" => format!("{:5.1}", process.children_cpu_percent),
+        "MEM%" => format!("{:5.1}", process.memory_percent),
+        "RSS" => format_bytes(process.memory_rss),
+        "VSZ" => format_bytes(process.memory_vsz),
+        "PSS" => process.memory_pss.map_or_else(|| "-".to_string(), format_bytes),
+        "USS" => process.memory_uss.map_or_else(|| "-".to_string(), format_bytes),
+        "NET" => process
+            .net_rate
+            .map(|rate| format!("{}/{}", format_rate(rate.rx_bytes_per_sec), format_rate(rate.tx_bytes_per_sec)))
+            .unwrap_or_else(|| "-".to_string()),
+        "THR" => process.threads.to_string(),
+        "FDS" => process.open_fds.map_or_else(|| "-".to_string(), |fds| fds.to_string()),
+        "PGID" => process.pgid.map_or_else(|| "-".to_string(), |pgid| pgid.to_string()),
+        "SID" => process.session_id.map_or_else(|| "-".to_string(), |sid| sid.to_string()),
+        "TTY" => process.tty.clone().unwrap_or_else(|| "?".to_string()),
+        "STATE" => format!("{:?}", process.state),
+        "WCHAN" => process.wchan.clone().unwrap_or_else(|| "-".to_string()),
+        "RST" => process.restarts.to_string(),
+        "BUSY" => busy_summary(process).unwrap_or_else(|| "-".to_string()),
+        "TIME" => format_cpu_time(process.cpu_time_total),
+        "AGE" => format_process_age(SystemTime::now().duration_since(process.start_time).unwrap_or_default()),
+        "START" => kacemon_core::export::short_timestamp(process.start_time, SystemTime::now()),
+        _ => String::new(),
+    }
+}
+
+/// One-line strip of per-category CPU and memory totals above the process table
+pub struct CategoryBar;
+
+impl CategoryBar {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        usage: &[CategoryUsage],
+        active: Option<&str>,  // category the table is filtered to
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height == 0 {
+            return Ok(());
+        }
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        let mut remaining = area.width as usize;
+        for entry in usage {
+            // Name badge, then a small CPU bar and the totals
+            let badge = format!(" {} ", entry.name);
+            let bar_width = 8;
+            let filled = ((entry.cpu_percent.clamp(0.0, 100.0) / 100.0) * bar_width as f32).round() as usize;
+            let totals = format!(
+                " {:.0}% {} ({}) ",
+                entry.cpu_percent,
+                format_bytes(entry.memory_rss),
+                entry.processes
+            );
+            let width = badge.chars().count() + bar_width + totals.len() + 1;
+            if width > remaining {
+                break;
+            }
+            remaining -= width;
+
+            let color = colors.category_color(&entry.name);
+            if active == Some(entry.name.as_str()) {
+                writer.queue(SetBackgroundColor(color))?;
+                writer.queue(SetForegroundColor(colors.background))?;
+            } else {
+                writer.queue(SetForegroundColor(color))?;
+            }
+            writer.queue(Print(&badge))?;
+            writer.queue(SetBackgroundColor(colors.background))?;
+            writer.queue(SetForegroundColor(color))?;
+            writer.queue(Print("█".repeat(filled)))?;
+            writer.queue(SetForegroundColor(colors.gauge_bg))?;
+            writer.queue(Print("░".repeat(bar_width - filled)))?;
+            writer.queue(SetForegroundColor(colors.foreground))?;
+            writer.queue(Print(format!("{} ", totals)))?;
+        }
+        writer.queue(Print(" ".repeat(remaining)))?;
+
+        Ok(())
+    }
+}
+
+/// One-line key to the process table's colors and marks, for whoever hasn't learned them yet
+pub struct Legend;
+
+impl Legend {
+    pub fn render<W: Write>(&self, writer: &mut W, area: Rect, cpu: GaugeMarks, memory: GaugeMarks, colors: &ColorScheme) -> io::Result<()> {
+        if area.height == 0 {
+            return Ok(());
+        }
+
+        let percent = |mark: Option<f32>| mark.map_or_else(|| "-".to_string(), |mark| format!("{:.0}%", mark));
+        let states = [
+            ProcessState::Running,
+            ProcessState::Sleeping,
+            ProcessState::Waiting,
+            ProcessState::DiskSleep,
+            ProcessState::Zombie,
+            ProcessState::Stopped,
+        ];
+        let mut entries: Vec<(Color, String)> = vec![(colors.muted, " STATE".to_string())];
+        entries.extend(states.iter().map(|state| (colors.process_state_color(state), format!("● {:?}", state))));
+        entries.extend([
+            (colors.muted, "│".to_string()),
+            (colors.warning, "CPU%/MEM% over 50%".to_string()),
+            (colors.error, "alerting process".to_string()),
+            (colors.foreground, "◉ followed ◎ watched".to_string()),
+            (colors.muted, "│".to_string()),
+            (colors.warning, format!("gauges warn at CPU {} MEM {}", percent(cpu.warning), percent(memory.warning))),
+            (colors.error, format!("critical at CPU {} MEM {}", percent(cpu.critical), percent(memory.critical))),
+        ]);
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        let mut remaining = area.width as usize;
+        for (color, text) in entries {
+            let width = text.chars().count() + 1;
+            if width > remaining {
+                break;
+            }
+            remaining -= width;
+            writer.queue(SetForegroundColor(color))?;
+            writer.queue(Print(format!("{} ", text)))?;
+        }
+        writer.queue(Print(" ".repeat(remaining)))?;
+        writer.queue(SetForegroundColor(colors.foreground))?;
+
+        Ok(())
+    }
+}
+
 /// Footer widget for keybind hints
 pub struct Footer;
 
@@ -348,6 +998,8 @@ impl Footer {
         &self,
         writer: &mut W,
         area: Rect,
+        status: Option<(&str, bool)>,  // (message, is_error)
+        badge: Option<&str>,  // refresh rate, or the frame of a file being viewed
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height == 0 {
@@ -358,14 +1010,54 @@ impl Footer {
         writer.queue(SetForegroundColor(colors.muted))?;
         writer.queue(SetBackgroundColor(colors.background))?;
 
-        let keybinds = "q:quit ↑↓:navigate s:sort /:filter c:columns r:refresh ?:help k:kill";
-        let truncated = if keybinds.len() > area.width as usize {
-            format!("{}...", &keybinds[..area.width as usize - 3])
-        } else {
-            format!("{:width$}", keybinds, width = area.width as usize)
-        };
+        // A pending status message replaces the keybind hints
+        if let Some((message, is_error)) = status {
+            writer.queue(SetForegroundColor(if is_error { colors.error } else { colors.success }))?;
+            writer.queue(Print(pad_or_truncate(message, area.width as usize)))?;
+            return Ok(());
+        }
 
-        writer.queue(Print(truncated))?;
+        // Current refresh rate sits at the right edge
+        let rate = badge.map(|badge| format!(" {}", badge)).unwrap_or_default();
+        let rate_width = rate.chars().count();
+        let keybinds_width = (area.width as usize).saturating_sub(rate_width);
+
+        let keybinds = "q:quit ↑↓:navigate s:sort /:filter n:sockets Tab:panel c:columns r/+/-:refresh ?:help K:kill v:copy";
+        writer.queue(Print(pad_or_truncate(keybinds, keybinds_width)))?;
+        if rate_width <= area.width as usize {
+            writer.queue(SetForegroundColor(colors.accent))?;
+            writer.queue(Print(rate))?;
+        }
+        Ok(())
+    }
+}
+
+/// One line over a panel whose collector is failing, saying why and how old the data
+/// still shown under it is
+pub struct FailureNote;
+
+impl FailureNote {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        failure: &CollectorFailure,
+        now: SystemTime,  // the snapshot's time, so a viewed file reads as it did then
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height == 0 {
+            return Ok(());
+        }
+
+        let since = match failure.last_success {
+            Some(at) => format!("last good {}", crate::ui::fleet::format_age(now.duration_since(at).unwrap_or_default())),
+            None => "no data yet".to_string(),
+        };
+        let text = format!("⚠ {} failing: {} · {}", failure.collector.replace('_', " "), failure.error, since);
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.error))?;
+        writer.queue(Print(pad_or_truncate(&text, area.width as usize)))?;
         Ok(())
     }
 }
@@ -374,11 +1066,15 @@ impl Footer {
 pub struct NetworkGauges;
 
 impl NetworkGauges {
+    #[allow(clippy::too_many_arguments)]
     pub fn render<W: Write>(
         &self,
         writer: &mut W,
         area: Rect,
         networks: &[NetworkInfo],
+        selected: Option<&str>,
+        throughput_history: &[f64],
+        include_virtual: bool,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height < 2 {
@@ -386,17 +1082,7 @@ impl NetworkGauges {
         }
 
         // Calculate aggregate network stats
-        let (total_rx_rate, total_tx_rate, _total_rx, _total_tx) = networks.iter().fold(
-            (0u64, 0u64, 0u64, 0u64),
-            |(rx_rate, tx_rate, rx_total, tx_total), net| {
-                (
-                    rx_rate + net.rx_bytes_delta,
-                    tx_rate + net.tx_bytes_delta,
-                    rx_total + net.rx_bytes,
-                    tx_total + net.tx_bytes,
-                )
-            },
-        );
+        let (total_rx_rate, total_tx_rate) = NetworkInfo::total_deltas(networks, include_virtual);
 
         // Draw creative title with network activity indicator
         writer.queue(cursor::MoveTo(area.x, area.y))?;
@@ -417,8 +1103,12 @@ impl NetworkGauges {
         
         writer.queue(SetForegroundColor(activity_color))?;
         writer.queue(Print(activity_indicator))?;
-        writer.queue(SetForegroundColor(colors.table_header))?;
-        let title = format!(" NETWORK ({} interfaces)", networks.len());
+        writer.queue(SetForegroundColor(if selected.is_some() { colors.highlight } else { colors.table_header }))?;
+        let title = format!(
+            " NETWORK ({} interfaces){}",
+            networks.len(),
+            if selected.is_some() { " - Enter: details" } else { "" }
+        );
         let full_title = format!("{}{}", activity_indicator, title);
         
         // Ensure we clear the full width
@@ -430,6 +1120,7 @@ impl NetworkGauges {
             let truncated = format!("{}...", &title[..area.width as usize - 7]);
             writer.queue(Print(truncated))?;
         }
+        title_sparkline(writer, area, full_title.chars().count(), throughput_history, None, colors.accent, colors)?;
 
         if area.height >= 2 {
             // Draw decorative border
@@ -487,18 +1178,7 @@ impl NetworkGauges {
             if area.height > 3 && !networks.is_empty() {
                 let available_lines = (area.height as usize).saturating_sub(3);
                 
-                // Sort interfaces: active first, then by name, limit to max 6
-                let mut sorted_networks: Vec<_> = networks.iter().collect();
-                sorted_networks.sort_by(|a, b| {
-                    let a_active = a.rx_bytes_delta > 0 || a.tx_bytes_delta > 0;
-                    let b_active = b.rx_bytes_delta > 0 || b.tx_bytes_delta > 0;
-                    
-                    match (a_active, b_active) {
-                        (true, false) => std::cmp::Ordering::Less,  // Active interfaces first
-                        (false, true) => std::cmp::Ordering::Greater,
-                        _ => a.interface_name.cmp(&b.interface_name), // Then alphabetical
-                    }
-                });
+                let sorted_networks = sorted_interfaces(networks);
                 
                 // Show all interfaces that fit in available space
                 let interfaces_to_show = sorted_networks.len().min(available_lines);
@@ -506,6 +1186,10 @@ impl NetworkGauges {
                 for (i, net) in sorted_networks.iter().take(interfaces_to_show).enumerate() {
                     let y_pos = area.y + 3 + i as u16;
                     writer.queue(cursor::MoveTo(area.x, y_pos))?;
+                    let is_selected = selected == Some(net.interface_name.as_str());
+                    if is_selected {
+                        writer.queue(SetBackgroundColor(colors.table_row_alt))?;
+                    }
                     
                     // Interface status icon with activity level
                     let (status_icon, status_color) = if net.rx_bytes_delta > 0 || net.tx_bytes_delta > 0 {
@@ -596,6 +1280,9 @@ impl NetworkGauges {
                         let clear_space = " ".repeat(area.width as usize - used_width);
                         writer.queue(Print(clear_space))?;
                     }
+                    if is_selected {
+                        writer.queue(SetBackgroundColor(colors.background))?;
+                    }
                 }
                 
                 // Show summary if there are more interfaces than can fit
@@ -621,8 +1308,327 @@ impl NetworkGauges {
     }
 }
 
+/// Interfaces in display order: active first, then alphabetical
+pub(crate) fn sorted_interfaces(networks: &[NetworkInfo]) -> Vec<&NetworkInfo> {
+    let mut sorted: Vec<_> = networks.iter().collect();
+    sorted.sort_by(|a, b| {
+        let a_active = a.rx_bytes_delta > 0 || a.tx_bytes_delta > 0;
+        let b_active = b.rx_bytes_delta > 0 || b.tx_bytes_delta > 0;
+        
+        match (a_active, b_active) {
+            (true, false) => std::cmp::Ordering::Less,  // Active interfaces first
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => a.interface_name.cmp(&b.interface_name), // Then alphabetical
+        }
+    });
+    sorted
+}
+
+/// Socket list widget (ss/netstat style)
+pub struct ConnectionsPanel;
+
+impl ConnectionsPanel {
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        title: &str,
+        connections: &[&ConnectionInfo],
+        processes: &[ProcessInfo],
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        let process_names: HashMap<u32, &str> = processes.iter().map(|p| (p.pid, p.name.as_str())).collect();
+
+        // Title line
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let title_text = format!("─ {} ({} sockets) ", title, connections.len());
+        writer.queue(Print(pad_or_truncate(&title_text, width)))?;
+
+        // Column header
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.table_header))?;
+        let header = format!(
+            "{:<6}{:<28}{:<28}{:<12}{:<8}{}",
+            "PROTO", "LOCAL", "REMOTE", "STATE", "PID", "PROCESS"
+        );
+        writer.queue(Print(pad_or_truncate(&header, width)))?;
+
+        let visible_rows = (area.height as usize).saturating_sub(2);
+        for (i, connection) in connections.iter().take(visible_rows).enumerate() {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + i as u16))?;
+
+            let state_color = match connection.state {
+                SocketState::Established => colors.success,
+                SocketState::Listen => colors.accent,
+                SocketState::TimeWait | SocketState::CloseWait | SocketState::FinWait1 | SocketState::FinWait2 => colors.warning,
+                _ => colors.foreground,
+            };
+            writer.queue(SetForegroundColor(state_color))?;
+
+            let pid = connection.pid.map(|pid| pid.to_string()).unwrap_or_else(|| "-".to_string());
+            let name = connection.pid
+                .and_then(|pid| process_names.get(&pid).copied())
+                .unwrap_or("-");
+            let row = format!(
+                "{:<6}{:<28}{:<28}{:<12}{:<8}{}",
+                connection.protocol.as_str(),
+                connection.local_address.to_string(),
+                connection.remote_address.to_string(),
+                connection.state.as_str(),
+                pid,
+                name
+            );
+            writer.queue(Print(pad_or_truncate(&row, width)))?;
+        }
+
+        // Clear rows left over from a longer list
+        writer.queue(SetForegroundColor(colors.foreground))?;
+        for i in connections.len().min(visible_rows)..visible_rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + i as u16))?;
+            writer.queue(Print(" ".repeat(width)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Block-character history chart; newest sample at the right edge
+pub struct HistoryChart;
+
+impl HistoryChart {
+    const LEVELS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    /// Render `values` scaled against `max` (or the series maximum if None)
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        values: &[f64],
+        max: Option<f64>,
+        color: crossterm::style::Color,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.is_empty() {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+        let height = area.height as usize;
+        let visible = &values[values.len().saturating_sub(width)..];
+        let max = max
+            .unwrap_or_else(|| visible.iter().cloned().fold(0.0, f64::max))
+            .max(f64::EPSILON);
+
+        // Total eighth-blocks for each column
+        let levels: Vec<usize> = visible
+            .iter()
+            .map(|v| ((v / max).clamp(0.0, 1.0) * (height * 8) as f64).round() as usize)
+            .collect();
+        let padding = width - levels.len();
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(color))?;
+        for row in 0..height {
+            writer.queue(cursor::MoveTo(area.x, area.y + row as u16))?;
+            let floor = (height - 1 - row) * 8;
+            let line: String = std::iter::repeat(' ')
+                .take(padding)
+                .chain(levels.iter().map(|&level| Self::LEVELS[level.saturating_sub(floor).min(8)]))
+                .collect();
+            writer.queue(Print(line))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Draw a one-row sparkline right-aligned on a widget's title line
+fn title_sparkline<W: Write>(
+    writer: &mut W,
+    area: Rect,
+    title_width: usize,
+    values: &[f64],
+    max: Option<f64>,
+    color: crossterm::style::Color,
+    colors: &ColorScheme,
+) -> io::Result<()> {
+    let width = (area.width as usize).saturating_sub(title_width + 2).min(60);
+    if values.is_empty() || width < 4 {
+        return Ok(());
+    }
+
+    let spark_area = Rect::new(area.right() - width as u16, area.y, width as u16, 1);
+    HistoryChart.render(writer, spark_area, values, max, color, colors)
+}
+
+/// Mounted filesystem list with usage and I/O rates
+pub struct DiskPanel;
+
+impl DiskPanel {
+    #[allow(clippy::too_many_arguments)]
+    pub fn render<W: Write>(
+        &self,
+        writer: &mut W,
+        area: Rect,
+        disks: &[DiskInfo],
+        selected: Option<usize>,
+        interval_secs: f64,
+        marks: GaugeMarks,
+        alert: bool,
+        colors: &ColorScheme,
+    ) -> io::Result<()> {
+        if area.height < 2 {
+            return Ok(());
+        }
+
+        let width = area.width as usize;
+
+        writer.queue(cursor::MoveTo(area.x, area.y))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+        writer.queue(SetForegroundColor(if selected.is_some() { colors.highlight } else { colors.table_header }))?;
+        if alert {
+            writer.queue(SetForegroundColor(colors.background))?;
+            writer.queue(SetBackgroundColor(colors.error))?;
+        }
+        let title = format!("DISKS ({} mounts){}", disks.len(), if selected.is_some() { " - Enter: details" } else { "" });
+        writer.queue(Print(pad_or_truncate(&title, width)))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
+
+        writer.queue(cursor::MoveTo(area.x, area.y + 1))?;
+        writer.queue(SetForegroundColor(colors.muted))?;
+        writer.queue(Print("─".repeat(width)))?;
+
+        let rows = (area.height as usize).saturating_sub(2);
+        let selected_index = selected.unwrap_or(0);
+        let start = selected_index.saturating_sub(rows.saturating_sub(1));
+
+        for row in 0..rows {
+            writer.queue(cursor::MoveTo(area.x, area.y + 2 + row as u16))?;
+            let Some(disk) = disks.get(start + row) else {
+                writer.queue(SetBackgroundColor(colors.background))?;
+                writer.queue(Print(" ".repeat(width)))?;
+                continue;
+            };
+
+            let usage = if disk.total_space > 0 {
+                disk.used_space as f32 / disk.total_space as f32 * 100.0
+            } else {
+                0.0
+            };
+            let per_sec = |bytes: u64| (bytes as f64 / interval_secs.max(0.001)) as u64;
+            let mount = format!("{:<12} ", disk.mount_point);
+            let text = format!(
+                "{:3.0}% R:{:>7}/s W:{:>7}/s",
+                usage,
+                format_rate(per_sec(disk.read_bytes_delta)),
+                format_rate(per_sec(disk.write_bytes_delta))
+            );
+
+            let selected_row = selected == Some(start + row);
+            let paint = |writer: &mut W| -> io::Result<()> {
+                if selected_row {
+                    writer.queue(SetBackgroundColor(colors.table_selected))?;
+                    writer.queue(SetForegroundColor(colors.background))?;
+                } else {
+                    writer.queue(SetBackgroundColor(colors.background))?;
+                    writer.queue(SetForegroundColor(colors.memory_usage_color(usage)))?;
+                }
+                Ok(())
+            };
+            paint(writer)?;
+            // A usage bar between mount point and numbers when the row has room for one
+            let bar_width = width.saturating_sub(mount.chars().count() + text.chars().count() + 1).min(12);
+            if bar_width >= 6 {
+                writer.queue(Print(&mount))?;
+                gauge_bar(writer, bar_width, usage, marks, colors)?;
+                paint(writer)?;
+                writer.queue(Print(" "))?;
+                writer.queue(Print(pad_or_truncate(&text, width - mount.chars().count() - bar_width - 1)))?;
+            } else {
+                writer.queue(Print(pad_or_truncate(&format!("{}{}", mount, text), width)))?;
+            }
+        }
+
+        writer.queue(SetBackgroundColor(colors.background))?;
+        Ok(())
+    }
+}
+
+/// Pad text to exactly `width` columns, truncating with an ellipsis if needed
+pub(crate) fn pad_or_truncate(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len > width {
+        let kept: String = text.chars().take(width.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    } else {
+        format!("{}{}", text, " ".repeat(width - len))
+    }
+}
+
+/// A Celsius reading in the unit every panel shows, Fahrenheit: "176°F"
+pub(crate) fn format_temperature(celsius: f32) -> String {
+    format!("{:.0}°F", celsius * 9.0 / 5.0 + 32.0)
+}
+
+/// CPU time as top shows it: "4:05.32" under an hour, "12:04:05" past it
+pub(crate) fn format_cpu_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    if secs < 3600 {
+        format!("{}:{:02}.{:02}", secs / 60, secs % 60, time.subsec_millis() / 10)
+    } else {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    }
+}
+
+/// Time since a process started in its two largest units: "3d4h", "5h12m", "7m30s", "42s"
+pub(crate) fn format_process_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m{}s", secs / 60, secs % 60),
+        3600..=86399 => format!("{}h{}m", secs / 3600, secs / 60 % 60),
+        _ => format!("{}d{}h", secs / 86400, secs / 3600 % 24),
+    }
+}
+
+/// Format a refresh interval compactly ("250ms", "1s", "1.5s")
+pub(crate) fn format_interval(interval: Duration) -> String {
+    let ms = interval.as_millis();
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms >= 60_000 && ms % 60_000 == 0 {
+        format!("{}min", ms / 60_000)
+    } else if ms % 1000 == 0 {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
+
+/// "950", "1.2k", "12k", "1.2M": event counts and rates, in thousands and millions. The unit
+/// is picked after rounding, so 9,999.6 is "10k" like 10,000 rather than "10.0k".
+pub(crate) fn format_count(count: f64) -> String {
+    let thousands = count / 1e3;
+    if count.round() < 1e3 {
+        format!("{:.0}", count)
+    } else if (thousands * 10.0).round() < 100.0 {
+        format!("{:.1}k", thousands)
+    } else if thousands.round() < 1e3 {
+        format!("{:.0}k", thousands)
+    } else {
+        format!("{:.1}M", count / 1e6)
+    }
+}
+
 /// Format bytes with rate suffix (no extra "/s" since we add it in display)
-fn format_rate(bytes_per_sec: u64) -> String {
+pub(crate) fn format_rate(bytes_per_sec: u64) -> String {
     if bytes_per_sec == 0 {
         return "0B".to_string();
     }
@@ -652,6 +1658,8 @@ impl TemperatureGauge {
         writer: &mut W,
         area: Rect,
         temperatures: &[TemperatureInfo],
+        priority: &[String],  // labels listed before the hottest sensors
+        alert: bool,
         colors: &ColorScheme,
     ) -> io::Result<()> {
         if area.height < 2 {
@@ -688,19 +1696,24 @@ impl TemperatureGauge {
                 colors.success // Green for cool
             };
 
-            let temp_f = temp * 9.0 / 5.0 + 32.0;
-            (format!("{} TEMPERATURE ({:.0}°F)", status_icon, temp_f), color)
+            (format!("{} TEMPERATURE ({})", status_icon, format_temperature(temp)), color)
         } else {
             ("🌡️ TEMPERATURE (No sensors)".to_string(), colors.muted)
         };
 
-        writer.queue(SetForegroundColor(title_color))?;
+        if alert {
+            writer.queue(SetForegroundColor(colors.background))?;
+            writer.queue(SetBackgroundColor(colors.error))?;
+        } else {
+            writer.queue(SetForegroundColor(title_color))?;
+        }
         let title_truncated = if title_text.len() > area.width as usize {
             format!("{}...", &title_text[..area.width as usize - 3])
         } else {
             format!("{:width$}", title_text, width = area.width as usize)
         };
         writer.queue(Print(title_truncated))?;
+        writer.queue(SetBackgroundColor(colors.background))?;
 
         if area.height >= 2 {
             // Draw decorative border
@@ -710,8 +1723,7 @@ impl TemperatureGauge {
             writer.queue(Print(border))?;
         }
 
-        if area.height >= 4 && max_temp_info.is_some() {
-            let temp_info = max_temp_info.unwrap();
+        if let Some(temp_info) = max_temp_info.filter(|_| area.height >= 4) {
             let temp = temp_info.temperature;
             
             // Draw large temperature gauge
@@ -720,7 +1732,7 @@ impl TemperatureGauge {
             // Calculate gauge parameters
             let gauge_width = (area.width as usize).saturating_sub(2).min(30);
             let max_temp = temp_info.critical.or(temp_info.max).unwrap_or(100.0);
-            let percentage = ((temp / max_temp) * 100.0).min(100.0).max(0.0) as usize;
+            let percentage = ((temp / max_temp) * 100.0).clamp(0.0, 100.0) as usize;
             let fill_width = (gauge_width * percentage) / 100;
             
             // Temperature gauge with gradient effect
@@ -730,7 +1742,9 @@ impl TemperatureGauge {
             // Create the gauge bar with color gradient
             for i in 0..gauge_width {
                 let pos_percentage = (i * 100) / gauge_width;
-                let gauge_color = if pos_percentage >= 80 {
+                let gauge_color = if colors.truecolor {
+                    colors.gradient(pos_percentage as f32)
+                } else if pos_percentage >= 80 {
                     colors.error // Red zone
                 } else if pos_percentage >= 60 {
                     colors.warning // Yellow zone
@@ -756,18 +1770,17 @@ impl TemperatureGauge {
             if area.height >= 5 {
                 writer.queue(cursor::MoveTo(area.x, area.y + 3))?;
                 writer.queue(SetForegroundColor(colors.foreground))?;
-                let temp_f = temp * 9.0 / 5.0 + 32.0;
-                let temp_text = format!("{:.0}°F ({:.0}%)", temp_f, percentage);
+                let temp_text = format!("{} ({:.0}%)", format_temperature(temp), percentage);
                 let centered_x = area.x + (area.width.saturating_sub(temp_text.len() as u16)) / 2;
                 writer.queue(cursor::MoveTo(centered_x, area.y + 3))?;
                 writer.queue(Print(temp_text))?;
             }
 
-            // Show individual sensor readings if space allows
+            // Show individual sensor readings if space allows, as many as fit
             if area.height >= 6 && temperatures.len() > 1 {
-                let sensors_to_show = (area.height as usize - 5).min(temperatures.len()).min(4);
+                let sensors_to_show = area.height as usize - 5;
                 
-                for (i, temp_info) in temperatures.iter().take(sensors_to_show).enumerate() {
+                for (i, temp_info) in ranked_sensors(temperatures, priority).into_iter().take(sensors_to_show).enumerate() {
                     writer.queue(cursor::MoveTo(area.x, area.y + 4 + i as u16))?;
                     
                     let sensor_color = if temp_info.temperature >= 80.0 {  // 176°F
@@ -779,18 +1792,13 @@ impl TemperatureGauge {
                     };
                     
                     writer.queue(SetForegroundColor(sensor_color))?;
-                    let temp_f = temp_info.temperature * 9.0 / 5.0 + 32.0;
-                    let sensor_text = format!("{}: {:.0}°F", 
-                        temp_info.label.chars().take(8).collect::<String>(),
-                        temp_f
+                    // Labels get what the reading leaves, so "Package id 0" isn't cut to "Package "
+                    let label_width = (area.width as usize).saturating_sub(8).max(8);
+                    let sensor_text = format!("{}: {}", 
+                        temp_info.label.chars().take(label_width).collect::<String>(),
+                        format_temperature(temp_info.temperature)
                     );
-                    
-                    let truncated = if sensor_text.len() > area.width as usize {
-                        format!("{}...", &sensor_text[..area.width as usize - 3])
-                    } else {
-                        format!("{:width$}", sensor_text, width = area.width as usize)
-                    };
-                    writer.queue(Print(truncated))?;
+                    writer.queue(Print(pad_or_truncate(&sensor_text, area.width as usize)))?;
                 }
             }
         } else if area.height >= 3 {
@@ -807,8 +1815,51 @@ impl TemperatureGauge {
     }
 }
 
+/// Sensors in listing order: those matching a `priority` label first, in the order given,
+/// then the rest hottest first. Labels match case-insensitively, in part or whole.
+pub(crate) fn ranked_sensors<'a>(temperatures: &'a [TemperatureInfo], priority: &[String]) -> Vec<&'a TemperatureInfo> {
+    let priority: Vec<String> = priority.iter().map(|label| label.to_lowercase()).collect();
+    let rank = |sensor: &TemperatureInfo| {
+        let label = sensor.label.to_lowercase();
+        priority.iter().position(|wanted| label.contains(wanted.as_str())).unwrap_or(priority.len())
+    };
+    
+    let mut sensors: Vec<&TemperatureInfo> = temperatures.iter().collect();
+    sensors.sort_by(|a, b| rank(a).cmp(&rank(b)).then(b.temperature.total_cmp(&a.temperature)));
+    sensors
+}
+
+/// Format a scheduling delay: "850µs", "3.4ms"
+pub(crate) fn format_micros(micros: f32) -> String {
+    if micros < 1000.0 {
+        format!("{:.0}µs", micros)
+    } else {
+        format!("{:.1}ms", micros / 1000.0)
+    }
+}
+
+/// "98% for 14m" while a process stays over the hog threshold
+pub(crate) fn busy_summary(process: &ProcessInfo) -> Option<String> {
+    let busy = std::time::SystemTime::now().duration_since(process.busy_since?).unwrap_or_default().as_secs();
+    let duration = match busy {
+        0..=59 => format!("{}s", busy),
+        60..=3599 => format!("{}m", busy / 60),
+        _ => format!("{}h{:02}m", busy / 3600, busy / 60 % 60),
+    };
+    Some(format!("{:.0}% for {}", process.cpu_percent, duration))
+}
+
+/// "+12.0MB/min over 30min"
+pub(crate) fn leak_summary(leak: &LeakSuspect) -> String {
+    format!(
+        "+{}/min over {}min",
+        format_bytes(leak.growth_per_min as u64),
+        leak.span.as_secs() / 60
+    )
+}
+
 /// Format bytes in human-readable format
-fn format_bytes(bytes: u64) -> String {
+pub(crate) fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
@@ -0,0 +1,199 @@
+use crossterm::event::{self, Event};
+use kacemon_core::{
+    metrics::{scan::deep_scan, services::control_unit},
+    mock::FakeCollector,
+    Alert, CoreError, DeepScan, DiskInfo, KillSignal, MetricsCollector, RemoteHost, ServiceAction, SystemSnapshot,
+};
+use std::{
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Messages delivered to the UI thread
+pub enum AppMessage {
+    /// Raw terminal event, mapped by the UI's `InputHandler`
+    Input(Event),
+    Snapshot(Box<SystemSnapshot>),
+    CollectorError(String),
+    KillResult {
+        pid: u32,
+        name: String,
+        signal: KillSignal,
+        result: Result<(), String>,
+    },
+    /// Whether systemd took a start/stop/restart job for the unit
+    ServiceResult {
+        unit: String,
+        action: ServiceAction,
+        result: Result<(), String>,
+    },
+    /// Latest poll of the configured remote host at `index`
+    Remote {
+        index: usize,
+        result: Result<Box<(SystemSnapshot, Vec<Alert>)>, String>,
+    },
+    /// What a running deep scan is looking at
+    ScanProgress(String),
+    ScanDone(Box<DeepScan>),
+}
+
+/// Requests from the UI thread to the collector thread
+pub enum CollectorCommand {
+    SetInterval(Duration),
+    SetConnectionsEnabled(bool),
+    SetProcessNetworkEnabled(bool),
+    SetMemoryBreakdownEnabled(bool),
+    SetServicesEnabled(bool),
+    SetLogFeedEnabled(bool),
+    /// Collect right away instead of waiting for the interval
+    RefreshNow,
+    Kill {
+        pid: u32,
+        name: String,
+        signal: KillSignal,
+    },
+    ControlService {
+        unit: String,
+        action: ServiceAction,
+    },
+}
+
+/// Forward terminal events so keypresses are seen even while a collection is running
+pub fn spawn_input_thread(tx: Sender<AppMessage>) {
+    thread::spawn(move || {
+        // Stops once reading fails or the UI side hangs up
+        while let Ok(event) = event::read() {
+            if tx.send(AppMessage::Input(event)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Run collection on its own thread, returning the channel used to steer it
+pub fn spawn_collector_thread(
+    mut collector: MetricsCollector,
+    interval: Duration,
+    tx: Sender<AppMessage>,
+) -> Sender<CollectorCommand> {
+    let (command_tx, command_rx) = mpsc::channel();
+    thread::spawn(move || collector_loop(&mut collector, interval, &command_rx, &tx));
+    command_tx
+}
+
+/// Feed made-up snapshots on the same schedule a real collector would, for `--demo`
+pub fn spawn_demo_thread(mut collector: FakeCollector, interval: Duration, tx: Sender<AppMessage>) -> Sender<CollectorCommand> {
+    let (command_tx, command_rx) = mpsc::channel();
+    thread::spawn(move || demo_loop(&mut collector, interval, &command_rx, &tx));
+    command_tx
+}
+
+/// Poll each remote host on a thread of its own, so a slow one can't hold up the rest
+pub fn spawn_remote_threads(hosts: &[RemoteHost], interval: Duration, tx: &Sender<AppMessage>) {
+    for (index, host) in hosts.iter().enumerate() {
+        let host = host.clone();
+        let tx = tx.clone();
+        thread::spawn(move || loop {
+            let started = Instant::now();
+            let result = host.fetch().map(Box::new).map_err(|e| match e {
+                CoreError::Remote(message) => message,
+                e => e.to_string(),
+            });
+            if tx.send(AppMessage::Remote { index, result }).is_err() {
+                return;
+            }
+            thread::sleep(interval.saturating_sub(started.elapsed()));
+        });
+    }
+}
+
+/// Run a deep scan on a thread of its own, so neither drawing nor collection waits for it
+pub fn spawn_deep_scan(disks: Vec<DiskInfo>, mount_point: Option<String>, tx: Sender<AppMessage>) {
+    thread::spawn(move || {
+        let scan = deep_scan(&disks, mount_point.as_deref(), |step| {
+            let _ = tx.send(AppMessage::ScanProgress(step));
+        });
+        let _ = tx.send(AppMessage::ScanDone(Box::new(scan)));
+    });
+}
+
+fn collector_loop(
+    collector: &mut MetricsCollector,
+    mut interval: Duration,
+    commands: &Receiver<CollectorCommand>,
+    tx: &Sender<AppMessage>,
+) {
+    loop {
+        let started = Instant::now();
+        let message = match collector.collect() {
+            Ok(snapshot) => AppMessage::Snapshot(Box::new(snapshot)),
+            Err(e) => AppMessage::CollectorError(e.to_string()),
+        };
+        if tx.send(message).is_err() {
+            return;
+        }
+
+        // Serve commands until the next collection is due
+        loop {
+            let timeout = (started + interval).saturating_duration_since(Instant::now());
+            match commands.recv_timeout(timeout) {
+                Ok(CollectorCommand::SetInterval(new_interval)) => interval = new_interval,
+                Ok(CollectorCommand::SetConnectionsEnabled(enabled)) => collector.set_connections_enabled(enabled),
+                Ok(CollectorCommand::SetProcessNetworkEnabled(enabled)) => collector.set_process_network_enabled(enabled),
+                Ok(CollectorCommand::SetMemoryBreakdownEnabled(enabled)) => collector.set_memory_breakdown_enabled(enabled),
+                Ok(CollectorCommand::SetServicesEnabled(enabled)) => collector.set_services_enabled(enabled),
+                Ok(CollectorCommand::SetLogFeedEnabled(enabled)) => collector.set_log_feed_enabled(enabled),
+                Ok(CollectorCommand::RefreshNow) => {
+                    collector.expire_slow_metrics();
+                    break;
+                }
+                Ok(CollectorCommand::Kill { pid, name, signal }) => {
+                    let result = collector.kill_process(pid, signal).map_err(|e| e.to_string());
+                    if tx.send(AppMessage::KillResult { pid, name, signal, result }).is_err() {
+                        return;
+                    }
+                }
+                Ok(CollectorCommand::ControlService { unit, action }) => {
+                    // Only queues the job, so this doesn't hold up collection for long
+                    let result = control_unit(&unit, action).map_err(|e| e.to_string());
+                    if tx.send(AppMessage::ServiceResult { unit, action, result }).is_err() {
+                        return;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+fn demo_loop(
+    collector: &mut FakeCollector,
+    mut interval: Duration,
+    commands: &Receiver<CollectorCommand>,
+    tx: &Sender<AppMessage>,
+) {
+    loop {
+        let started = Instant::now();
+        if tx.send(AppMessage::Snapshot(Box::new(collector.collect()))).is_err() {
+            return;
+        }
+
+        loop {
+            let timeout = (started + interval).saturating_duration_since(Instant::now());
+            match commands.recv_timeout(timeout) {
+                Ok(CollectorCommand::SetInterval(new_interval)) => {
+                    interval = new_interval;
+                    collector.set_interval(new_interval);
+                }
+                Ok(CollectorCommand::RefreshNow) => break,
+                // The UI doesn't offer kills or unit control in demo mode, and the optional
+                // collectors have nothing to add to made-up data
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use clap::{Arg, Command};
-use kacemon_core::Config;
-use std::{io::stdout, path::PathBuf, process};
+use clap::{Arg, ArgMatches, Command};
+use kacemon_core::{kill_refusal, report, telegraf, Classifier, Config, KillSignal, MetricsCollector, OutputFormat, ProcessFilter, TelegrafFormat, WatchCondition};
+use std::{io::{stdout, BufRead, IsTerminal, Write}, path::{Path, PathBuf}, process, time::{Duration, Instant}};
 
 fn main() {
     if let Err(e) = run() {
@@ -9,24 +9,36 @@ fn main() {
     }
 }
 
-fn run() -> anyhow::Result<()> {
-    // Parse command line arguments
-    let matches = Command::new("kacemon")
+/// Command line arguments and subcommands
+fn cli() -> Command {
+    Command::new("kacemon")
         .version("0.3.0")
         .about("System Resource Monitor - A cross-platform TUI system monitor")
         .arg(
             Arg::new("refresh")
                 .long("refresh")
                 .value_name("MS")
-                .help("Refresh interval in milliseconds")
+                .help("Refresh interval in milliseconds: 100ms-10s in the TUI (1h with --slow-poll), 50ms-1h headless; clamped with a warning")
                 .value_parser(clap::value_parser!(u64))
         )
+        .arg(
+            Arg::new("slow-poll")
+                .long("slow-poll")
+                .help("Allow refresh intervals past 10s in the TUI and offer 10s-5min presets")
+                .action(clap::ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("theme")
                 .long("theme")
                 .value_name("THEME")
-                .help("UI theme")
-                .value_parser(["dark", "light"])
+                .help("UI theme: dark, light or a theme defined in the config file")
+        )
+        .arg(
+            Arg::new("max-fps")
+                .long("max-fps")
+                .value_name("FPS")
+                .help("Maximum screen redraws per second (1-240)")
+                .value_parser(clap::value_parser!(u32))
         )
         .arg(
             Arg::new("no-color")
@@ -34,7 +46,45 @@ fn run() -> anyhow::Result<()> {
                 .help("Disable colors")
                 .action(clap::ArgAction::SetTrue)
         )
-
+        .arg(
+            Arg::new("ascii")
+                .long("ascii")
+                .help("Draw with plain ASCII characters only")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("daemon")
+                .long("daemon")
+                .help("Run headless: collect on the refresh interval and write snapshots to the log")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("log")
+                .long("log")
+                .value_name("PATH")
+                .help("Snapshot log for --daemon, rotated by size")
+                .value_parser(clap::value_parser!(PathBuf))
+        )
+        .arg(
+            Arg::new("log-format")
+                .long("log-format")
+                .value_name("FORMAT")
+                .help("What each log line holds: summary (default) or full")
+                .value_parser(|s: &str| s.parse::<kacemon_core::daemon::LogFormat>())
+        )
+        .arg(
+            Arg::new("csv")
+                .long("csv")
+                .value_name("PATH")
+                .help("Append one row of system metrics per refresh to a CSV file (with the TUI or --daemon)")
+                .value_parser(clap::value_parser!(PathBuf))
+        )
+        .arg(
+            Arg::new("serve")
+                .long("serve")
+                .value_name("ADDR")
+                .help("Run headless and serve snapshots as JSON over HTTP, e.g. 127.0.0.1:9100 (no auth)")
+        )
         .arg(
             Arg::new("json-config")
                 .long("json-config")
@@ -42,31 +92,464 @@ fn run() -> anyhow::Result<()> {
                 .help("Path to JSON configuration file")
                 .value_parser(clap::value_parser!(PathBuf))
         )
-        .get_matches();
+        .arg(
+            Arg::new("demo")
+                .long("demo")
+                .help("Animate made-up data instead of this machine's, the same every run (screenshots, docs, UI work)")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .subcommand(
+            Command::new("check-config")
+                .about("Validate a config file (default: --json-config or the first one found) and exit")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .value_parser(clap::value_parser!(PathBuf))
+                )
+        )
+        .subcommand(
+            Command::new("kill")
+                .about("Signal every process matching a filter query, listing them first (a safer pkill)")
+                .arg(
+                    Arg::new("pattern")
+                        .value_name("PATTERN")
+                        .help("Filter query as typed after / in the TUI, e.g. nginx or 'name:sleep cpu>50'; a bare word matches the name only, or a PID exactly, and cmd~ reaches the command line")
+                        .required(true)
+                        .num_args(1..)
+                )
+                .arg(
+                    Arg::new("signal")
+                        .long("signal")
+                        .short('s')
+                        .value_name("SIG")
+                        .help("Signal to send: TERM (default), KILL, HUP, INT, STOP, CONT, USR1 or USR2")
+                        .default_value("TERM")
+                        .value_parser(|s: &str| s.parse::<KillSignal>())
+                )
+                .arg(
+                    Arg::new("user")
+                        .long("user")
+                        .short('u')
+                        .value_name("USER")
+                        .help("Only processes owned by this user")
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .short('n')
+                        .help("List the matching processes without signalling them")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("yes")
+                        .long("yes")
+                        .short('y')
+                        .help("Signal without asking first; needed when stdin isn't a terminal")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .help("Signal even when more than 20 processes, PID 1 or kacemon itself match")
+                        .action(clap::ArgAction::SetTrue)
+                )
+                .arg(format_arg())
+        )
+        .subcommand(
+            Command::new("status")
+                .about("Print headline numbers and firing alerts once; exits 2 if any alert rule is firing")
+                .arg(format_arg())
+        )
+        .subcommand(
+            Command::new("telegraf")
+                .about("Print metrics for Telegraf's exec input, or with --execd keep running for its execd input")
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .short('f')
+                        .value_name("FORMAT")
+                        .help("influx (line protocol, default) or json")
+                        .default_value("influx")
+                        .value_parser(|s: &str| s.parse::<TelegrafFormat>())
+                )
+                .arg(
+                    Arg::new("execd")
+                        .long("execd")
+                        .help("Stay running and print a pass for every line read on stdin (execd's signal = \"STDIN\"), until stdin closes")
+                        .action(clap::ArgAction::SetTrue)
+                )
+        )
+        .subcommand(
+            Command::new("watch")
+                .about("Wait until a condition holds, e.g. 'memory available < 1GB', then exit with status 2")
+                .arg(
+                    Arg::new("condition")
+                        .value_name("CONDITION")
+                        .help("<metric> <op> <value> or [no] process <filter>, joined with and/or; see the README")
+                        .required(true)
+                        .num_args(1..)
+                )
+                .arg(
+                    Arg::new("exec")
+                        .long("exec")
+                        .short('e')
+                        .value_name("COMMAND")
+                        .help("Run COMMAND through sh -c each time the condition starts to hold, and keep watching")
+                )
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("SECS")
+                        .help("Give up after SECS, exiting 0")
+                        .value_parser(clap::value_parser!(u64))
+                )
+        )
+        .subcommand(
+            Command::new("view")
+                .about("Open a .kmon bundle, a /snapshot JSON file or a --log-format full log read-only")
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf))
+                )
+        )
+}
 
-    // Build CLI configuration
-    let cli_config = kacemon_core::config::CliConfig {
-        refresh_ms: matches.get_one::<u64>("refresh").copied(),
-        theme: matches.get_one::<String>("theme").map(|t| match t.as_str() {
-            "light" => kacemon_core::Theme::Light,
-            _ => kacemon_core::Theme::Dark,
-        }),
-        no_color: matches.get_flag("no-color"),
-    };
+fn run() -> anyhow::Result<()> {
+    let matches = cli().get_matches();
+    
+    if let Some(check) = matches.subcommand_matches("check-config") {
+        let path = check.get_one::<PathBuf>("path").or(matches.get_one::<PathBuf>("json-config"));
+        return check_config(path);
+    }
+
+    let cli_config = cli_config(&matches);
 
     // Load configuration
     let json_config_path = matches.get_one::<PathBuf>("json-config");
-    let config = Config::load(Some(&cli_config), json_config_path)?;
+    let (config, warnings) = Config::load_with_warnings(Some(&cli_config), json_config_path)?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+
+    if let Some(kill) = matches.subcommand_matches("kill") {
+        let pattern: Vec<&str> = kill.get_many::<String>("pattern").expect("pattern is required").map(String::as_str).collect();
+        return run_kill(
+            &config,
+            &pattern.join(" "),
+            *kill.get_one::<KillSignal>("signal").expect("signal has a default"),
+            kill.get_one::<String>("user").map(String::as_str),
+            KillMode { dry_run: kill.get_flag("dry-run"), yes: kill.get_flag("yes"), force: kill.get_flag("force") },
+            *kill.get_one::<OutputFormat>("format").expect("format has a default"),
+        );
+    }
+
+    if let Some(status) = matches.subcommand_matches("status") {
+        return run_status(&config, *status.get_one::<OutputFormat>("format").expect("format has a default"));
+    }
+
+    if let Some(telegraf) = matches.subcommand_matches("telegraf") {
+        return run_telegraf(
+            &config,
+            *telegraf.get_one::<TelegrafFormat>("format").expect("format has a default"),
+            telegraf.get_flag("execd"),
+        );
+    }
+
+    if let Some(watch) = matches.subcommand_matches("watch") {
+        let words: Vec<&str> = watch.get_many::<String>("condition").expect("condition is required").map(String::as_str).collect();
+        let condition: WatchCondition = words.join(" ").parse().map_err(|e| anyhow::anyhow!("Bad condition: {}", e))?;
+        return run_watch(
+            &config,
+            &condition,
+            watch.get_one::<String>("exec").map(String::as_str),
+            watch.get_one::<u64>("timeout").map(|secs| Duration::from_secs(*secs)),
+        );
+    }
 
-    // Validate refresh rate
-    if config.refresh_ms < 100 {
-        eprintln!("Warning: Refresh rate too low, using 100ms minimum");
+    if let Some(view) = matches.subcommand_matches("view") {
+        let path = view.get_one::<PathBuf>("path").expect("path is required");
+        return run_view(config, path);
+    }
+
+    if matches.get_flag("demo") {
+        return run_demo(config);
+    }
+
+    if cli_config.headless {
+        kacemon_core::daemon::run_daemon(&config)?;
+        return Ok(());
     }
 
     // Run TUI
     run_tui_only(config)
 }
 
+/// `--format` of the one-shot subcommands
+fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .short('f')
+        .value_name("FORMAT")
+        .help("Output as table (default), json or csv")
+        .default_value("table")
+        .value_parser(|s: &str| s.parse::<OutputFormat>())
+}
+
+/// CLI overrides for the config. Only the TUI (no subcommand, or `view`) has a screen to keep
+/// up to date; --daemon, --serve and the one-shot subcommands take the headless refresh bounds.
+fn cli_config(matches: &ArgMatches) -> kacemon_core::config::CliConfig {
+    let tui = matches.subcommand_name().map_or(true, |name| name == "view");
+    let headless = !tui || matches.get_flag("daemon") || matches.contains_id("serve");
+    
+    kacemon_core::config::CliConfig {
+        refresh_ms: matches.get_one::<u64>("refresh").copied(),
+        theme: matches.get_one::<String>("theme").cloned().map(kacemon_core::Theme::from),
+        no_color: matches.get_flag("no-color"),
+        max_fps: matches.get_one::<u32>("max-fps").copied(),
+        ascii: matches.get_flag("ascii"),
+        slow_poll: matches.get_flag("slow-poll"),
+        headless,
+        log_path: matches.get_one::<PathBuf>("log").cloned(),
+        log_format: matches.get_one::<kacemon_core::daemon::LogFormat>("log-format").copied(),
+        csv_path: matches.get_one::<PathBuf>("csv").cloned(),
+        serve_addr: matches.get_one::<String>("serve").cloned(),
+    }
+}
+
+/// A collector for the one-shot subcommands, which read once and leave. CPU percentages
+/// need two readings apart, so queries like cpu>50 mean something.
+fn one_shot_snapshot(config: &Config, collector: &mut MetricsCollector) -> anyhow::Result<kacemon_core::SystemSnapshot> {
+    collector.init()?;
+    std::thread::sleep(Duration::from_millis(250));
+    collector.expire_slow_metrics();
+    let mut snapshot = collector.collect()?;
+    Classifier::new(&config.categories)?.tag(&mut snapshot.processes);
+    Ok(snapshot)
+}
+
+/// Print the current state once, exiting with status 2 when any alert rule is firing so
+/// health checks can act on it
+fn run_status(config: &Config, format: OutputFormat) -> anyhow::Result<()> {
+    let mut collector = MetricsCollector::builder()
+        .networks(false)
+        .gpus(false)
+        .run_queue(false)
+        .cgroups(false)
+        .build()?;
+    let snapshot = one_shot_snapshot(config, &mut collector)?;
+    let alerts = config.alerts.evaluate(&snapshot);
+    print!("{}", report::status_report(&snapshot, &alerts, format));
+    if !alerts.is_empty() {
+        process::exit(2);
+    }
+    Ok(())
+}
+
+/// Metrics in a format Telegraf parses as is. Once for its exec input; with `execd`, a pass
+/// per line on stdin for as long as Telegraf keeps the pipe open, so counters and CPU
+/// usage are measured between its collection intervals rather than over a fresh 250ms.
+fn run_telegraf(config: &Config, format: TelegrafFormat, execd: bool) -> anyhow::Result<()> {
+    let mut collector = MetricsCollector::builder().run_queue(false).cgroups(false).build()?;
+    let mut out = stdout();
+    if !execd {
+        let snapshot = one_shot_snapshot(config, &mut collector)?;
+        out.write_all(telegraf::telegraf_metrics(&snapshot, format, config.gpu_export).as_bytes())?;
+        return Ok(());
+    }
+
+    collector.init()?;
+    for line in std::io::stdin().lock().lines() {
+        line?;
+        let snapshot = collector.collect()?;
+        out.write_all(telegraf::telegraf_metrics(&snapshot, format, config.gpu_export).as_bytes())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Report whether a config file loads and validates, exiting non-zero when it doesn't
+fn check_config(path: Option<&PathBuf>) -> anyhow::Result<()> {
+    match Config::check_file(path.map(PathBuf::as_path)) {
+        Ok((path, warnings)) => {
+            for warning in &warnings {
+                println!("warning: {}", warning);
+            }
+            println!("{}: OK", path.display());
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// How far `kacemon kill` goes on its own
+struct KillMode {
+    /// Only list the matches
+    dry_run: bool,
+    /// Don't ask before signalling
+    yes: bool,
+    /// Signal even matches `kill_refusal` objects to
+    force: bool,
+}
+
+/// Send `signal` to every process the query and user pick out, after listing them and
+/// asking. Exits non-zero when nothing matches or a signal couldn't be sent, as pkill does.
+fn run_kill(
+    config: &Config,
+    pattern: &str,
+    signal: KillSignal,
+    user: Option<&str>,
+    mode: KillMode,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let filter = pattern
+        .parse::<ProcessFilter>()
+        .map_err(|e| anyhow::anyhow!("Bad filter '{}': {}", pattern, e))?
+        .pkill_words();
+    // An empty query matches everything, which is never what a kill means
+    if filter.is_empty() {
+        anyhow::bail!("Empty filter; give a name, a PID or a query such as 'name:sleep'");
+    }
+    let mut collector = MetricsCollector::builder()
+        .disks(false)
+        .networks(false)
+        .pressure(false)
+        .temperatures(false)
+        .gpus(false)
+        .run_queue(false)
+        .cgroups(false)
+        .build()?;
+    let snapshot = one_shot_snapshot(config, &mut collector)?;
+
+    // Never this process or the shells it runs under, whose command lines hold the pattern
+    // too, nor kernel threads, which ignore signals anyway
+    let ancestors = snapshot.lineage(process::id());
+    let matches_self = snapshot.processes.iter().any(|p| p.pid == process::id() && filter.matches(p));
+    let mut targets: Vec<_> = snapshot
+        .processes
+        .iter()
+        .filter(|p| !ancestors.contains(&p.pid) && !p.is_kernel_thread)
+        .filter(|p| user.map_or(true, |user| p.user == user))
+        .filter(|p| filter.matches(p))
+        .collect();
+    targets.sort_by_key(|p| p.pid);
+
+    if targets.is_empty() {
+        eprintln!("No processes match '{}'", pattern);
+        process::exit(1);
+    }
+    print!("{}", report::process_list(&targets, format));
+    // Keep stdout to the listing when a script is reading it
+    let note = |line: String| if format == OutputFormat::Table { println!("{}", line) } else { eprintln!("{}", line) };
+    let plural = if targets.len() == 1 { "" } else { "es" };
+    // Filters like '!name:x' or 're:.' get past the empty check but still take in everything
+    if let Some(reason) = kill_refusal(&targets, matches_self).filter(|_| !mode.force) {
+        anyhow::bail!("Not signalling: {}; narrow the filter or pass --force", reason);
+    }
+    if mode.dry_run {
+        note(format!("Would send {} to {} process{}", signal.name(), targets.len(), plural));
+        return Ok(());
+    }
+    if !mode.yes {
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("No terminal to confirm on; pass --yes to signal without asking");
+        }
+        eprint!("Send {} to {} process{}? [y/N] ", signal.name(), targets.len(), plural);
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+            note("Nothing sent".to_string());
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for process in &targets {
+        if let Err(e) = collector.kill_process(process.pid, signal) {
+            eprintln!("{}", e);
+            failed += 1;
+        }
+    }
+    note(format!("Sent {} to {} of {} process{}", signal.name(), targets.len() - failed, targets.len(), plural));
+    if failed > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Collect on the refresh interval until `condition` holds, print what it read and exit with
+/// status 2, so scripts can tell it from an error (1) or a `timeout` running out (0). With
+/// `exec`, run that instead each time the condition goes from false to true and keep going.
+fn run_watch(config: &Config, condition: &WatchCondition, exec: Option<&str>, timeout: Option<Duration>) -> anyhow::Result<()> {
+    let mut collector = MetricsCollector::builder()
+        .networks(false)
+        .gpus(false)
+        .run_queue(false)
+        .cgroups(false)
+        .build()?;
+    collector.init()?;
+    let classifier = Classifier::new(&config.categories)?;
+    let interval = config.refresh_interval();
+    let started = Instant::now();
+    // The first reading of CPU usage needs a baseline some time back, as in `one_shot_snapshot`
+    std::thread::sleep(Duration::from_millis(250));
+
+    let mut held = false;
+    loop {
+        let pass_started = Instant::now();
+        let mut snapshot = collector.collect()?;
+        classifier.tag(&mut snapshot.processes);
+        let holds = condition.matches(&snapshot);
+        if holds && !held {
+            for line in condition.describe(&snapshot) {
+                println!("{}", line);
+            }
+            let Some(command) = exec else {
+                process::exit(2);
+            };
+            let status = process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .env("KACEMON_WATCH_CONDITION", condition.to_string())
+                .env("KACEMON_HOSTNAME", &snapshot.system.hostname)
+                .status();
+            match status {
+                Ok(status) if !status.success() => eprintln!("kacemon: '{}' exited with {}", command, status),
+                Err(e) => eprintln!("kacemon: couldn't run '{}': {}", command, e),
+                Ok(_) => {},
+            }
+        }
+        held = holds;
+
+        if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+            return Ok(());
+        }
+        std::thread::sleep(interval.saturating_sub(pass_started.elapsed()));
+    }
+}
+
+/// Explore a saved file in the TUI with collection off
+fn run_view(config: Config, path: &Path) -> anyhow::Result<()> {
+    let frames = kacemon_core::export::read_recording(path)?;
+    let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+    let mut app = kacemon_tui::App::view(config, frames, name)?;
+    let mut stdout = stdout();
+    app.run(&mut stdout)?;
+    Ok(())
+}
+
+/// Animate the fake machine in the TUI
+fn run_demo(config: Config) -> anyhow::Result<()> {
+    let mut app = kacemon_tui::App::demo(config)?;
+    let mut stdout = stdout();
+    app.run(&mut stdout)?;
+    Ok(())
+}
+
 /// Run in TUI-only mode
 fn run_tui_only(config: Config) -> anyhow::Result<()> {
     let mut app = kacemon_tui::App::new(config)?;
@@ -74,3 +557,26 @@ fn run_tui_only(config: Config) -> anyhow::Result<()> {
     app.run(&mut stdout)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_shot_subcommands_take_headless_refresh_bounds() {
+        let path = std::env::temp_dir().join(format!("kacemon-cli-refresh-{}.json", process::id()));
+        std::fs::write(&path, r#"{ "refresh_ms": 60000 }"#).unwrap();
+        let load = |args: &[&str]| {
+            let matches = cli().get_matches_from([&["kacemon", "--json-config", path.to_str().unwrap()], args].concat());
+            Config::load(Some(&cli_config(&matches)), Some(&path))
+        };
+        
+        // A minute is too slow to redraw a screen by, fine for anything without one
+        for args in [&["status"][..], &["telegraf"], &["watch", "load > 1"], &["kill", "sleep"], &["--daemon"]] {
+            assert_eq!(load(args).unwrap().refresh_ms, 60_000, "{:?}", args);
+        }
+        assert!(load(&[]).is_err());
+        
+        std::fs::remove_file(&path).unwrap();
+    }
+}